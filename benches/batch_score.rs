@@ -0,0 +1,42 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use fzgrep::{batch_score, BatchMatchOptions};
+
+const QUERIES: usize = 100;
+const LINES: usize = 10_000;
+
+const WORDS: [&str; 16] = [
+    "fuzzy", "match", "score", "query", "target", "line", "file", "context", "result", "option",
+    "reader", "output", "format", "search", "filter", "config",
+];
+
+/// Deterministic pseudo-random words, so that every run measures the same input.
+///
+fn words(seed: usize, count: usize) -> impl Iterator<Item = &'static str> {
+    (0..count).map(move |i| WORDS[(seed * 31 + i * 17 + seed * i) % WORDS.len()])
+}
+
+fn batch_score_benchmark(c: &mut Criterion) {
+    let queries = (0..QUERIES)
+        .map(|seed| words(seed, 2).map(|w| &w[..3]).collect::<String>())
+        .collect::<Vec<_>>();
+    let queries = queries.iter().map(String::as_str).collect::<Vec<_>>();
+    let lines = (0..LINES)
+        .map(|seed| words(seed, 8).collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>();
+    let options = BatchMatchOptions::default();
+
+    let mut group = c.benchmark_group("batch_score");
+    group.throughput(Throughput::Elements((QUERIES * LINES) as u64));
+    group.sample_size(10);
+    group.bench_function("100 queries x 10000 lines", |b| {
+        b.iter(|| {
+            for line in &lines {
+                black_box(batch_score(black_box(&queries), line, &options));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, batch_score_benchmark);
+criterion_main!(benches);
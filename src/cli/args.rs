@@ -1,22 +1,52 @@
 use crate::{
     cli::{
-        error::ColorOverrideParsingError,
-        formatting::{Formatting, FormattingOptions},
-        sgr_sequence,
+        config::{self, Config},
+        error::{
+            ByteRangeParsingError, ColorOverrideParsingError, FieldOrderParsingError,
+            FileSizeParsingError, PatternListError, UnknownEncodingError,
+        },
+        formatting::{ColorGradient, Formatting, FormattingOptions, Theme},
+        output::template::{Field, Template},
+        pager, sgr_sequence,
     },
-    core::request::{
-        ContextSize, Lines, MatchCollectionStrategy, MatchOptions, OutputBehavior, Request, Targets,
+    core::{
+        filter::Filter,
+        request::{
+            CaseSensitivity, ContextSize, Lines, MatchMode, OutputBehavior, OutputField,
+            OutputFormat, Request, ScoreFormat, SortOrder, Targets,
+        },
+        request_builder::RequestBuilder,
+        scoring_config::ScoringConfig,
     },
 };
 use atty::Stream;
-use clap::{parser::ValuesRef, value_parser, Arg, ArgAction, ArgMatches, Command};
+use clap::{
+    builder::{PossibleValuesParser, RangedU64ValueParser, TypedValueParser},
+    error::ErrorKind,
+    parser::{ValueSource, ValuesRef},
+    value_parser, Arg, ArgAction, ArgMatches, Command,
+};
+use clap_complete::Shell;
+use encoding_rs::Encoding;
 use log::LevelFilter;
-use std::{env, path::PathBuf};
+use std::{
+    env,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    iter,
+    ops::Range,
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
 
 /// Sets up a [`Request`] struct based on the program command line arguments
 ///
 /// `args` can technically be any [`String`] iterator but in practice it is expected to be used only with [`std::env::args`].
 ///
+/// Only the config file given with `--config` is read and the environment is not looked at;
+/// see [`make_request_with`] for the config file at the default location and `FZGREP_DEFAULT_OPTIONS`.
+///
 /// # Errors:
 ///
 /// If `args` do not satisfy internal invariant (e.g. there are too few arguments),
@@ -29,11 +59,11 @@ use std::{env, path::PathBuf};
 /// // basic usage
 /// use atty::{self, Stream};
 /// use fzgrep::cli::{args, formatting::{Formatting, FormattingOptions}};
-/// use fzgrep::{ContextSize, Lines, MatchCollectionStrategy, MatchOptions, OutputBehavior, Request, Targets};
+/// use fzgrep::{CaseSensitivity, ContextChars, ContextSize, Filter, Lines, LossyEncoding, MatchCollectionStrategy, MatchMode, MatchOptions, OutputBehavior, OutputFormat, Request, ScoringConfig, SortOrder, SourceRegistry, Targets};
 /// use log::LevelFilter;
 /// use std::path::PathBuf;
 ///
-/// let args = ["fzgrep", "query", "file"];
+/// let args = ["fzgrep", "--no-pager", "query", "file"];
 /// let request = args::make_request(args.into_iter().map(String::from));
 /// assert_eq!(
 ///     request,
@@ -41,14 +71,48 @@ use std::{env, path::PathBuf};
 ///         query: String::from("query"),
 ///         targets: Targets::Files(vec![PathBuf::from("file")]),
 ///         strategy: MatchCollectionStrategy::CollectAll,
+///         max_time: None,
 ///         match_options: MatchOptions {
 ///             track_line_numbers: false,
+///             track_column_numbers: false,
 ///             track_file_names: false,
 ///             context_size: ContextSize {
 ///                 before: Lines(0),
 ///                 after: Lines(0),
 ///             },
+///             context_chars: ContextChars {
+///                 chars_before: 0,
+///                 chars_after: 0,
+///             },
+///             skip_bytes: None,
+///             byte_range: None,
+///             decompress: false,
+///             search_zip: false,
+///             follow_links: false,
+///             dedup_inodes: false,
+///             filter: Filter::default(),
+///             skip_hidden: false,
+///             word_boundary_check: false,
+///             word_chars: None,
+///             whole_line: false,
+///             case_sensitivity: CaseSensitivity::Insensitive,
+///             match_mode: MatchMode::Fuzzy,
+///             path_aware: false,
+///             acronym_bonus: None,
+///             print_unmatched: false,
+///             unicode_normalization: false,
+///             ignore_diacritics: false,
+///             scoring: ScoringConfig::default(),
+///             lossy_encoding: LossyEncoding(false),
+///             encoding: None,
+///             sources: SourceRegistry::default(),
+///             null_data: false,
+///             max_line_length: None,
+///             truncate_long_lines: false,
 ///         },
+///         sort_order: SortOrder::Score,
+///         unique: false,
+///         relative_paths_root: None,
 ///         output_behavior: OutputBehavior::Normal(
 ///             if atty::is(Stream::Stdout) {
 ///                 Formatting::On(FormattingOptions::default())
@@ -56,7 +120,32 @@ use std::{env, path::PathBuf};
 ///                 Formatting::Off
 ///             }
 ///         ),
+///         dry_run: false,
+///         count_lines: false,
+///         field_separator: String::from(":"),
+///         output_format: OutputFormat::Text,
+///         print_context_count: false,
+///         print_match_positions: false,
+///         score_format: None,
+///         show_caret: false,
+///         print_file_headers: false,
+///         unmatched_marker: String::from("  "),
+///         highlight_query: false,
+///         field_order: None,
+///         line_number_width: 0,
+///         auto_align: false,
+///         context_separator: Some(String::from("--")),
+///         output_template: None,
+///         output_file: None,
+///         line_buffered: false,
+///         zero_terminated: false,
+///         pager: None,
 ///         log_verbosity: LevelFilter::Error,
+///         print_stats: false,
+///         show_progress: false,
+///         suppress_errors: false,
+///         report_errors_as_matches: false,
+///         notices: Vec::new(),
 ///     }
 /// );
 /// ```
@@ -75,7 +164,7 @@ use std::{env, path::PathBuf};
 /// // no input files and `--recursive` flag - use current directory///
 /// use fzgrep::cli::args;
 /// use fzgrep::Targets;
-/// use std::{env, path::PathBuf};
+/// use std::env;
 ///
 /// let args = ["fzgrep", "--recursive", "query"];
 /// let request = args::make_request(args.into_iter().map(String::from));
@@ -140,7 +229,7 @@ use std::{env, path::PathBuf};
 /// ```
 /// // symmetric context
 /// use fzgrep::cli::args;
-/// use fzgrep::{ContextSize, Lines};
+/// use fzgrep::{CaseSensitivity, ContextSize, Lines};
 ///
 /// let args = ["fzgrep", "--context", "2", "query", "file"];
 /// let request = args::make_request(args.into_iter().map(String::from));
@@ -156,7 +245,7 @@ use std::{env, path::PathBuf};
 /// ```
 /// // asymmetric context
 /// use fzgrep::cli::args;
-/// use fzgrep::{ContextSize, Lines};
+/// use fzgrep::{CaseSensitivity, ContextSize, Lines};
 ///
 /// let args = ["fzgrep", "--before-context", "1", "--after-context", "2", "query", "file"];
 /// let request = args::make_request(args.into_iter().map(String::from));
@@ -232,30 +321,319 @@ use std::{env, path::PathBuf};
 /// ```
 ///
 pub fn make_request(args: impl Iterator<Item = String>) -> Request {
-    let matches = match_command_line(args);
+    make_request_with(args, None, None)
+}
+
+/// Same as [`make_request`], except that the default option values are read from the config file `config`
+/// unless `--config` or `--no-config` is given (nothing is read if `config` does not exist),
+/// and from `default_options`, which are whitespace-separated options (the content of [`DEFAULT_OPTIONS_VAR`]).
+/// The default options take precedence over the config file, the options in `args` take precedence over both.
+/// If `default_options` contain unrecognized options or positional arguments, a warning is printed
+/// and they are ignored altogether.
+///
+/// [`make_request`] reads neither of them on its own, so the application passes the config file
+/// at the default location (see [`default_config_path`]) and the value of [`DEFAULT_OPTIONS_VAR`].
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::{cli::args, MatchCollectionStrategy};
+/// use std::path::Path;
+///
+/// // a config file that does not exist is skipped
+/// let args = ["fzgrep", "query", "file"].map(String::from);
+/// let config = Path::new("no/such/config.toml");
+/// let request = args::make_request_with(args.into_iter(), Some(config), None);
+/// assert_eq!(request.strategy, MatchCollectionStrategy::CollectAll);
+///
+/// // the options given explicitly take precedence over the default ones
+/// let args = ["fzgrep", "--top", "10", "query", "file"].map(String::from);
+/// let request = args::make_request_with(args.into_iter(), None, Some("--line-number --top 5"));
+/// assert!(request.match_options.track_line_numbers);
+/// assert_eq!(request.strategy, MatchCollectionStrategy::CollectTop(10));
+/// ```
+///
+pub fn make_request_with(
+    args: impl Iterator<Item = String>,
+    config: Option<&Path>,
+    default_options: Option<&str>,
+) -> Request {
+    request_from(args, config, default_options)
+}
+
+/// The environment variable the `fzgrep` binary reads the default options from (see [`make_request_with`]).
+///
+pub const DEFAULT_OPTIONS_VAR: &str = "FZGREP_DEFAULT_OPTIONS";
+
+/// Returns the default location of the config file:
+/// `$XDG_CONFIG_HOME/fzgrep/config.toml` or, if `XDG_CONFIG_HOME` is not set, `~/.config/fzgrep/config.toml`.
+/// Returns [`None`] if neither `XDG_CONFIG_HOME` nor `HOME` is set.
+///
+pub fn default_config_path() -> Option<PathBuf> {
+    config::default_config_path()
+}
+
+/// Same as [`make_request_with`], except that the invalid arguments (e.g. an unknown option or a missing query)
+/// result in an error instead of the usage message and the exit.
+/// The help and the version are still printed, followed by the exit, as are the usage errors
+/// that can only be detected later on (e.g. an invalid config file).
+///
+pub(crate) fn try_make_request(
+    args: impl Iterator<Item = String>,
+    config: Option<&Path>,
+    default_options: Option<&str>,
+) -> Result<Request, clap::Error> {
+    let args = args.collect::<Vec<_>>();
+    match make_command().try_get_matches_from(&args) {
+        // `--help` and `--version` are "errors" printed to the standard output
+        Err(e) if !e.use_stderr() => e.exit(),
+        Err(e) => Err(e),
+        Ok(_) => Ok(request_from(args.into_iter(), config, default_options)),
+    }
+}
+
+fn request_from(
+    args: impl Iterator<Item = String>,
+    config: Option<&Path>,
+    default_options: Option<&str>,
+) -> Request {
+    let matches = match_command_line(args, config, default_options);
+    // a column without a line is of little use
+    let line_number_implied = matches.get_flag("column_number") && !matches.get_flag("line_number");
+
+    let ContextSize {
+        before: Lines(before),
+        after: Lines(after),
+    } = context_size_from(&matches);
+    let mut builder = RequestBuilder::new(query_from(&matches), targets_from(&matches))
+        .before_context(before)
+        .after_context(after)
+        .context_chars(
+            matches
+                .get_one::<usize>("context_chars")
+                .copied()
+                .unwrap_or(0),
+        )
+        .line_numbers(
+            matches.get_flag("line_number")
+                || line_number_implied
+                || template_uses(&matches, Field::Line)
+                || field_order_uses(&matches, OutputField::Line),
+        )
+        .column_numbers(
+            matches.get_flag("column_number")
+                || template_uses(&matches, Field::Column)
+                || field_order_uses(&matches, OutputField::Column),
+        )
+        .file_names(
+            track_file_name_from(&matches)
+                || template_uses(&matches, Field::File)
+                || field_order_uses(&matches, OutputField::File),
+        )
+        .word_boundary_check(matches.get_flag("word_regexp"))
+        .whole_line(matches.get_flag("whole_line"))
+        .decompress(matches.get_flag("decompress"))
+        .search_zip(matches.get_flag("search_zip"))
+        .follow_links(matches.get_flag("dereference"))
+        .dedup_inodes(matches.get_flag("dereference") || matches.get_flag("no_dereference_unique"))
+        .filter(filter_from(&matches))
+        .skip_hidden(matches.get_flag("no_hidden"))
+        .case_sensitivity(case_sensitivity_from(&matches))
+        .match_mode(match_mode_from(&matches))
+        .path_aware(matches.get_flag("path_aware"))
+        .unicode_normalization(matches.get_flag("normalize_unicode"))
+        .ignore_diacritics(matches.get_flag("ignore_diacritics"))
+        .lossy_encoding(matches.get_flag("lossy_encoding"))
+        .null_data(matches.get_flag("null_data"))
+        .truncate_long_lines(matches.get_flag("truncate_long_lines"))
+        .scoring(
+            matches
+                .get_one::<ScoringConfig>("scoring_config")
+                .copied()
+                .unwrap_or_default(),
+        )
+        .sort_order(sort_order_from(&matches))
+        .unique(matches.get_flag("unique"))
+        .output_behavior(output_behavior_from(&matches))
+        .dry_run(matches.get_flag("dry_run") || matches.get_flag("list_files"))
+        .count_lines(matches.get_flag("list_files"))
+        .field_separator(field_separator_from(&matches))
+        .output_format(output_format_from(&matches))
+        .print_context_count(matches.get_flag("print_context_count"))
+        .print_match_positions(matches.get_flag("print_match_positions"))
+        .show_caret(show_caret_from(&matches))
+        .print_file_headers(matches.get_flag("print_file_headers"))
+        .print_unmatched(matches.get_flag("print_unmatched"))
+        .highlight_query(matches.get_flag("highlight_query"))
+        .auto_align(matches.get_flag("auto_align"))
+        .context_separator(context_separator_from(&matches))
+        .line_buffered(matches.get_flag("line_buffered"))
+        .zero_terminated(matches.get_flag("zero_terminated"))
+        .log_verbosity(log_verbosity_from(&matches))
+        .print_stats(matches.get_flag("stats") && !is_quiet(&matches))
+        .show_progress(matches.get_flag("progress") && atty::is(Stream::Stderr))
+        .suppress_errors(matches.get_flag("no_messages"))
+        .report_errors_as_matches(matches.get_flag("report_errors_as_matches"));
+    if line_number_implied {
+        builder = builder.notice("--column-number implies --line-number");
+    }
+    if let Some(marker) = matches.get_one::<String>("unmatched_marker") {
+        builder = builder.unmatched_marker(marker);
+    }
+    if let Some(&bonus) = matches.get_one::<u32>("acronym_bonus") {
+        builder = builder.acronym_bonus(bonus);
+    }
+    if let Some(word_chars) = matches.get_one::<String>("word_chars") {
+        builder = builder.word_chars(word_chars.chars());
+    }
+    if let Some(encoding) = matches.get_one::<&'static Encoding>("encoding") {
+        builder = builder.encoding(encoding);
+    }
+    if let Some(secs) = matches.get_one::<u64>("max_time") {
+        builder = builder.max_time(Duration::from_secs(*secs));
+    }
+    if let Some(n) = matches.get_one::<usize>("max_results") {
+        // `--top` may still have a value coming from the config file
+        builder = builder.max_results(*n);
+    } else if let Some(top) = matches.get_one::<usize>("top") {
+        builder = builder.top(*top);
+    }
+    if let Some(bytes) = matches.get_one::<u64>("max_filesize") {
+        builder = builder.max_file_size(*bytes);
+    }
+    if let Some(range) = matches.get_one::<Range<u64>>("byte_range") {
+        builder = builder.byte_range(range.clone());
+    }
+    if let Some(bytes) = matches.get_one::<usize>("max_line_length") {
+        builder = builder.max_line_length(*bytes);
+    }
+    if let Some(root) = relative_paths_root_from(&matches) {
+        builder = builder.relative_paths_root(root);
+    }
+    if matches.get_flag("show_score") || field_order_uses(&matches, OutputField::Score) {
+        builder = builder.score_format(
+            matches
+                .get_one::<ScoreFormat>("score_format")
+                .copied()
+                .unwrap_or(ScoreFormat::Decimal),
+        );
+    }
+    if let Some(template) = matches.get_one::<String>("format") {
+        builder = builder.output_template(template);
+    }
+    if let Some(order) = matches.get_one::<Vec<OutputField>>("field_order") {
+        builder = builder.field_order(order.clone());
+    }
+    if let Some(width) = matches.get_one::<usize>("line_number_width") {
+        builder = builder.line_number_width(*width);
+    }
+    if let Some(file) = matches.get_one::<PathBuf>("output") {
+        builder = builder.output_file(file);
+    }
+    if let Some(pager) = pager_from(&matches, atty::is(Stream::Stdout)) {
+        builder = builder.pager(pager);
+    }
+    builder
+        .build()
+        .expect("Conflicting options are resolved when the arguments are parsed")
+}
+
+/// Parses `value` (see [`DEFAULT_OPTIONS_VAR`]) on its own, or returns [`None`] with a warning
+/// if it contains anything but valid options.
+///
+fn parse_default_options(value: &str) -> Option<ArgMatches> {
+    // The value is split as is, without any kind of variable expansion, so a reference
+    // to the variable itself (or any other one) cannot cause recursive expansion.
+    // Such a reference would be a positional argument and is rejected below.
+    // If the value contains positional arguments, the placeholder pattern becomes a target.
+    // The logger is not set up yet at this point, hence plain `eprintln!`.
+    let args = iter::once("fzgrep")
+        .chain(value.split_whitespace())
+        .chain(["--", "pattern"]);
+    match make_command().try_get_matches_from(args) {
+        Ok(matches) if matches.contains_id("target") => {
+            eprintln!(
+                "warning: {DEFAULT_OPTIONS_VAR} may contain only options, but '{value}' contains positional arguments; ignoring it"
+            );
+            None
+        }
+        Ok(matches) => Some(matches),
+        Err(_) => {
+            eprintln!(
+                "warning: {DEFAULT_OPTIONS_VAR} contains unrecognized or invalid options ('{value}'); ignoring it"
+            );
+            None
+        }
+    }
+}
+
+/// Installs the values of the options given in `default_options` (see [`parse_default_options`])
+/// as default values of the corresponding arguments of `command`, the same way as [`Config::apply`] does.
+///
+fn apply_default_options(mut command: Command, default_options: &ArgMatches) -> Command {
+    for id in default_options.ids() {
+        let id = id.as_str();
+        if id == "pattern" || !is_from_command_line(default_options, id) {
+            continue;
+        }
+        let values = default_options
+            .get_raw(id)
+            .into_iter()
+            .flatten()
+            .map(|value| value.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        command = command.mut_arg(id, |arg| arg.default_values(values));
+    }
+    command
+}
 
-    Request {
-        query: query_from(&matches),
-        targets: targets_from(&matches),
-        strategy: strategy_from(&matches),
-        match_options: match_options_from(&matches),
-        output_behavior: output_behavior_from(&matches),
-        log_verbosity: log_verbosity_from(&matches),
+fn match_command_line(
+    args: impl Iterator<Item = String>,
+    config: Option<&Path>,
+    default_options: Option<&str>,
+) -> ArgMatches {
+    let args = args.collect::<Vec<_>>();
+    let default_options = default_options.and_then(parse_default_options);
+    let with_default_options = |command| match &default_options {
+        Some(default_options) => apply_default_options(command, default_options),
+        None => command,
+    };
+    let matches = with_default_options(make_command()).get_matches_from(&args);
+    if let Some(shell) = matches.get_one::<Shell>("generate_completions").copied() {
+        print_completions(shell);
+    }
+    if matches.get_flag("print_json_schema") {
+        print_json_schema();
+    }
+    let matches = match config_from(&matches, config) {
+        // Parse once again, this time with the config values installed as the defaults
+        // (the default options are installed on top of them, as they take precedence).
+        Some(config) => with_default_options(config.apply(make_command())).get_matches_from(args),
+        None => matches,
+    };
+    if matches.get_flag("dump_config") {
+        print_config(&matches);
     }
+    matches
 }
 
-fn match_command_line(args: impl Iterator<Item = String>) -> ArgMatches {
+fn make_command() -> Command {
     Command::new(option_env!("CARGO_NAME").unwrap_or("fzgrep"))
         .version(option_env!("CARGO_PKG_VERSION").unwrap_or("unknown"))
         .author(option_env!("CARGO_EMAIL").unwrap_or("Andrii Semkiv <semkiv@gmail.com>"))
         .after_help(
             "With more than one FILEs assume -f.\n\
-            Exit status is 0 if any match is found, 1 otherwise; if any error(s) occur, the exit status is 2."
+            Exit status is 0 if any match is found, 1 otherwise; if any error(s) occur, the exit status is 2.\n\
+            Default options can be put into the FZGREP_DEFAULT_OPTIONS environment variable."
         )
         .arg(
             Arg::new("pattern")
                 .value_name("PATTERN")
-                .required(true)
+                .required_unless_present_any([
+                    "generate_completions",
+                    "dump_config",
+                    "print_json_schema",
+                ])
                 .help("Pattern to match"),
         )
         .arg(
@@ -268,6 +646,37 @@ fn match_command_line(args: impl Iterator<Item = String>) -> ArgMatches {
                     and the standard input otherwise"
                 ),
         )
+        .arg(
+            Arg::new("files_from")
+                .short('T')
+                .long("files-from")
+                .value_name("FILE")
+                .help(
+                    "Read the targets to search in from FILE, one per line ('-' for the standard input).\n\
+                    The targets are searched in addition to the ones given on the command line\n\
+                    and file names are printed as if multiple targets were given."
+                ),
+        )
+        .arg(
+            Arg::new("null_input")
+                .long("null-input")
+                .action(ArgAction::SetTrue)
+                .requires("files_from")
+                .help("Targets read with '--files-from' are separated by NUL characters instead of newlines"),
+        )
+        .arg(
+            Arg::new("target_glob")
+                .long("target-glob")
+                .value_name("GLOB")
+                .action(ArgAction::Append)
+                .value_parser(glob::Pattern::new)
+                .conflicts_with_all(["target", "files_from"])
+                .help(
+                    "Search the files matching GLOB (e.g. 'src/**/*.rs') instead of the targets.\n\
+                    The pattern is expanded by fzgrep, so it should be quoted to keep the shell from expanding it.\n\
+                    Can be given multiple times; file names are printed as if multiple targets were given"
+                )
+        )
         .arg(
             Arg::new("recursive")
                 .short('r')
@@ -275,6 +684,292 @@ fn match_command_line(args: impl Iterator<Item = String>) -> ArgMatches {
                 .action(ArgAction::SetTrue)
                 .help("Recurse directories")
         )
+        .arg(
+            Arg::new("dereference")
+                .long("dereference")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Follow symbolic links when recursing directories.\n\
+                    Files reachable under multiple names are searched only once (see '--no-dereference-unique')"
+                )
+        )
+        .arg(
+            Arg::new("no_dereference_unique")
+                .long("no-dereference-unique")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Without following symbolic links, search files reachable under multiple names\n\
+                    (e.g. hard links or overlapping targets) only once when recursing directories.\n\
+                    Files are identified by their device and inode numbers, which is supported only on Unix"
+                )
+        )
+        .arg(
+            Arg::new("hidden")
+                .long("hidden")
+                .action(ArgAction::SetTrue)
+                .overrides_with("no_hidden")
+                .help(
+                    "Search hidden files and directories (the ones whose names start with '.')\n\
+                    when recursing directories. This is the default for now"
+                )
+        )
+        .arg(
+            Arg::new("no_hidden")
+                .long("no-hidden")
+                .action(ArgAction::SetTrue)
+                .overrides_with("hidden")
+                .help(
+                    "Skip hidden files and directories (the ones whose names start with '.')\n\
+                    when recursing directories. The targets given explicitly are never skipped.\n\
+                    This is going to become the default in a future version"
+                )
+        )
+        .arg(
+            Arg::new("include")
+                .long("include")
+                .value_name("GLOB")
+                .action(ArgAction::Append)
+                .value_parser(glob::Pattern::new)
+                .help(
+                    "When recursing directories, search only files whose name or path matches GLOB.\n\
+                    Can be given multiple times (together with '--include-from' and '--include-regex'),\n\
+                    a file is searched if any of them matches"
+                )
+        )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .value_name("GLOB")
+                .action(ArgAction::Append)
+                .value_parser(glob::Pattern::new)
+                .help(
+                    "When recursing directories, skip files whose name or path matches GLOB.\n\
+                    Can be given multiple times (together with '--exclude-from' and '--exclude-regex'),\n\
+                    a file is skipped if any of them matches"
+                )
+        )
+        .arg(
+            Arg::new("include_extension")
+                .long("include-extension")
+                .value_name("EXT")
+                .action(ArgAction::Append)
+                .value_parser(extension_glob_parser)
+                .help(
+                    "A shorthand for '--include *.EXT' (the leading dot of EXT is optional,\n\
+                    e.g. '--include-extension .rs' and '--include-extension rs' are the same).\n\
+                    Can be given multiple times, just like '--include'"
+                )
+        )
+        .arg(
+            Arg::new("exclude_extension")
+                .long("exclude-extension")
+                .value_name("EXT")
+                .action(ArgAction::Append)
+                .value_parser(extension_glob_parser)
+                .help(
+                    "A shorthand for '--exclude *.EXT' (the leading dot of EXT is optional,\n\
+                    e.g. '--exclude-extension .min.js' and '--exclude-extension min.js' are the same).\n\
+                    Can be given multiple times, just like '--exclude'"
+                )
+        )
+        .arg(
+            Arg::new("include_from")
+                .long("include-from")
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(PathBuf))
+                .help(
+                    "Read '--include' patterns from FILE, one per line.\n\
+                    Blank lines and lines starting with '#' are ignored. Can be given multiple times"
+                )
+        )
+        .arg(
+            Arg::new("exclude_from")
+                .long("exclude-from")
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(PathBuf))
+                .help(
+                    "Read '--exclude' patterns from FILE, one per line.\n\
+                    Blank lines and lines starting with '#' are ignored. Can be given multiple times"
+                )
+        )
+        .arg(
+            Arg::new("include_regex")
+                .long("include-regex")
+                .value_name("RE")
+                .action(ArgAction::Append)
+                .value_parser(regex_parser)
+                .help(
+                    "When recursing directories, search only files whose path matches RE.\n\
+                    Can be given multiple times, a file is searched if any of them matches.\n\
+                    Requires fzgrep to be built with the 'regex' feature"
+                )
+        )
+        .arg(
+            Arg::new("exclude_regex")
+                .long("exclude-regex")
+                .value_name("RE")
+                .action(ArgAction::Append)
+                .value_parser(regex_parser)
+                .help(
+                    "When recursing directories, skip files whose path matches RE.\n\
+                    Can be given multiple times, a file is skipped if any of them matches.\n\
+                    Requires fzgrep to be built with the 'regex' feature"
+                )
+        )
+        .arg(
+            Arg::new("decompress")
+                .short('z')
+                .long("decompress")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Transparently decompress gzip (.gz) and zstd (.zst) files.\n\
+                    Requires fzgrep to be built with the corresponding features ('gzip' and 'zstd')"
+                )
+        )
+        .arg(
+            Arg::new("search_zip")
+                .long("search-zip")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "In the recursive mode, search the entries of zip (.zip) archives as if the archives were directories;\n\
+                    the matches are reported as 'archive.zip:path/in/archive.txt'. The include/exclude patterns\n\
+                    apply to the entry names. Requires fzgrep to be built with the 'zip-archives' feature"
+                )
+        )
+        .arg(
+            Arg::new("word_regexp")
+                .short('w')
+                .long("word-regexp")
+                .action(ArgAction::SetTrue)
+                .help("Select only lines where the match starts and ends at word boundaries")
+        )
+        .arg(
+            Arg::new("word_chars")
+                .long("word-chars")
+                .value_name("CHARS")
+                .requires("word_regexp")
+                .help(
+                    "Consider only CHARS word characters for '--word-regexp', every other character being\n\
+                    a word boundary, e.g. '--word-chars abc...xyz-' for Lisp identifiers.\n\
+                    By default word characters are the alphanumeric characters and underscores"
+                )
+        )
+        .arg(
+            Arg::new("whole_line")
+                .short('x')
+                .long("whole-line")
+                .action(ArgAction::SetTrue)
+                .help("Select only lines where every character is matched, i.e. lines roughly equal to PATTERN")
+        )
+        .arg(
+            Arg::new("smart_case")
+                .short('S')
+                .long("smart-case")
+                .action(ArgAction::SetTrue)
+                .help("Match case-sensitively if PATTERN contains uppercase letters, case-insensitively otherwise")
+        )
+        .arg(
+            Arg::new("acronym")
+                .long("acronym")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Match the characters of PATTERN only against the initial characters of words\n\
+                    (e.g. 'fb' matches 'FooBar' and 'foo_bar', but not 'foobar')"
+                )
+        )
+        .arg(
+            Arg::new("fixed_string")
+                .long("fixed-string")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("acronym")
+                .help(
+                    "Match PATTERN only as a literal substring of the line instead of fuzzily\n\
+                    (e.g. 'ab' matches 'xaby', but not 'axby'); the letter case options still apply"
+                )
+        )
+        .arg(
+            Arg::new("path_aware")
+                .long("path-aware")
+                .visible_alias("path-query")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["acronym", "fixed_string"])
+                .help(
+                    "If PATTERN looks like a path (contains '/' or '\\'), match its components one by one:\n\
+                    every component has to match within a single path component of the line, in the same order\n\
+                    (e.g. 'src/lib' matches 'src/core/lib.rs', but not 'lib/src.rs' or 's/rc/lib.rs')"
+                )
+        )
+        .arg(
+            Arg::new("acronym_bonus")
+                .long("acronym-bonus")
+                .value_name("N")
+                .value_parser(value_parser!(u32))
+                .help(
+                    "Add N points to the score of a match for every matched character that starts a word,\n\
+                    favoring acronym-like matches (e.g. 'fb' in 'foo_bar') without switching to '--acronym'"
+                )
+        )
+        .arg(
+            Arg::new("normalize_unicode")
+                .long("normalize-unicode")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Bring PATTERN and the lines to Unicode Normalization Form C (NFC) before matching,\n\
+                    so that precomposed and decomposed forms of the same characters match each other"
+                )
+        )
+        .arg(
+            Arg::new("ignore_diacritics")
+                .long("ignore-diacritics")
+                .action(ArgAction::SetTrue)
+                .help("Ignore diacritical marks when matching (e.g. 'cafe' matches 'café' and vice versa)")
+        )
+        .arg(
+            Arg::new("lossy_encoding")
+                .short('U')
+                .long("lossy-encoding")
+                .action(ArgAction::SetTrue)
+                .help("Replace invalid UTF-8 sequences in the input with U+FFFD instead of failing")
+        )
+        .arg(
+            Arg::new("encoding")
+                .long("encoding")
+                .value_name("ENC")
+                .value_parser(encoding_parser)
+                .help(
+                    "Decode the input from the character encoding ENC into UTF-8 before matching\n\
+                    (malformed sequences are replaced with U+FFFD; a byte order mark takes precedence).\n\
+                    Supported encodings (case-insensitive; the WHATWG labels are accepted as well, e.g. 'latin1'):\n\
+                    UTF-8, UTF-16LE, UTF-16BE, IBM866, ISO-8859-2..8, ISO-8859-8-I, ISO-8859-10, ISO-8859-13..16,\n\
+                    KOI8-R, KOI8-U, macintosh, windows-874, windows-1250..1258, x-mac-cyrillic,\n\
+                    GBK, gb18030, Big5, EUC-JP, ISO-2022-JP, Shift_JIS and EUC-KR.\n\
+                    Note that, as in web browsers, 'latin1' and 'ISO-8859-1' stand for windows-1252 (its superset).\n\
+                    The default is UTF-8"
+                )
+        )
+        .arg(
+            Arg::new("null_data")
+                .long("null-data")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Treat the input as lines terminated by NUL characters instead of newlines\n\
+                    (e.g. the output of 'find -print0'), like 'grep -z'. The output is still newline-terminated"
+                )
+        )
+        .arg(
+            Arg::new("relative_paths")
+                .long("relative-paths")
+                .value_name("ROOT")
+                .value_parser(value_parser!(PathBuf))
+                .num_args(0..=1)
+                .require_equals(true)
+                .help(
+                    "Print file names relative to ROOT (the current working directory if ROOT is omitted).\n\
+                    File names that are not under ROOT are printed in full."
+                )
+        )
         .arg(
             Arg::new("line_number")
                 .short('n')
@@ -282,6 +977,35 @@ fn match_command_line(args: impl Iterator<Item = String>) -> ArgMatches {
                 .action(ArgAction::SetTrue)
                 .help("Print line number with matching lines"),
         )
+        .arg(
+            Arg::new("line_number_width")
+                .long("line-number-width")
+                .value_name("N")
+                .value_parser(value_parser!(usize))
+                .help("Right-align line numbers to at least N digits"),
+        )
+        .arg(
+            Arg::new("auto_align")
+                .long("auto-align")
+                .action(ArgAction::SetTrue)
+                .help("Right-align line numbers to the widest one printed"),
+        )
+        .arg(
+            Arg::new("column_number")
+                .short('k')
+                .long("column-number")
+                .action(ArgAction::SetTrue)
+                .help("Print the column of the first matched character with matching lines"),
+        )
+        .arg(
+            Arg::new("show_caret")
+                .long("show-caret")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Together with '--column-number', print a line with a caret ('^') under the first matched\n\
+                    character after each matching line (only when printing to a terminal)"
+                ),
+        )
         .arg(
             Arg::new("with_filename")
                 .short('f')
@@ -298,6 +1022,44 @@ fn match_command_line(args: impl Iterator<Item = String>) -> ArgMatches {
                 .conflicts_with("with_filename")
                 .help("Suppress the file name prefix on output"),
         )
+        .arg(
+            Arg::new("highlight_query")
+                .long("highlight-query")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Also highlight the characters matching the query in the context lines\n\
+                    (colored with the 'cq=' capability, see '--color-overrides')"
+                ),
+        )
+        .arg(
+            Arg::new("print_file_headers")
+                .long("print-file-headers")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("no_filename")
+                .help(
+                    "Print a '==> FILE <==' header before the matches from each file\n\
+                    instead of the file name on every line"
+                ),
+        )
+        .arg(
+            Arg::new("print_unmatched")
+                .long("print-unmatched")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["sort", "reverse_sort", "top", "unique"])
+                .help(
+                    "Print every line of the files with matches: the matching lines start with '> ',\n\
+                    the rest of them with the marker set by '--unmatched-marker'.\n\
+                    The lines are printed in the file order, the context options do not apply"
+                ),
+        )
+        .arg(
+            Arg::new("unmatched_marker")
+                .long("unmatched-marker")
+                .value_name("MARKER")
+                .default_value("  ")
+                .requires("print_unmatched")
+                .help("Start the lines that do not match with MARKER when printing every line"),
+        )
         .arg(
             Arg::new("context")
                 .short('C')
@@ -326,38 +1088,379 @@ fn match_command_line(args: impl Iterator<Item = String>) -> ArgMatches {
                 .help("Print NUM lines of trailing context")
         )
         .arg(
-            Arg::new("top")
-                .long("top")
-                .value_name("N")
+            Arg::new("context_chars")
+                .long("context-chars")
+                .value_name("NUM")
                 .value_parser(value_parser!(usize))
-                .help("Fetch only top N results")
+                .help(
+                    "Print whole lines of surrounding context until there are at least NUM characters\n\
+                    of it on either side; combined with the line-based context, the larger one wins",
+                ),
         )
         .arg(
-            Arg::new("quiet")
-                .short('q')
-                .long("quiet")
-                .visible_alias("silent")
+            Arg::new("context_separator")
+                .long("context-separator")
+                .value_name("STR")
+                .overrides_with("no_context_separator")
+                .help(
+                    "Print STR on a line of its own between the groups of lines (matches with their context)\n\
+                    that are not adjacent, when any context is printed; the default is '--'.\n\
+                    {source} and {line} are replaced with the file name and the line number of the following match"
+                )
+        )
+        .arg(
+            Arg::new("no_context_separator")
+                .long("no-context-separator")
                 .action(ArgAction::SetTrue)
-                .conflicts_with("verbose")
-                .help("Suppress all output")
+                .overrides_with("context_separator")
+                .help("Do not print anything between the groups of lines that are not adjacent")
         )
         .arg(
-            Arg::new("verbose")
-                .short('v')
-                .long("verbose")
-                .action(ArgAction::Count)
-                .conflicts_with("quiet")
+            Arg::new("scoring_config")
+                .long("scoring-config")
+                .value_name("JSON")
+                .value_parser(scoring_config_parser)
                 .help(
-                    "Verbose output. Specify multiple times to increase verbosity.\n\
-                    Without the switch only errors are reported (unless '-q' is specified);\n\
-                    \t'-v' additionally enables warning messages;\n\
-                    \t'-vv' additionally enables info messages;\n\
-                    \t'-vvv' additionally enables debug messages;\n\
-                    \tand '-vvvv' additionally enables trace messages."
+                    "Weights of the fuzzy scoring as a JSON object, e.g. '{\"consecutive_match\": 3}'.\n\
+                    Supported keys: 'regular', 'consecutive_match', 'exact_match', 'word_start',\n\
+                    'after_separator', 'after_path_separator' and 'camel_case'; the omitted ones keep their defaults.\n\
+                    The matched characters are still chosen with the default weights, the custom ones only rescore the match."
                 )
         )
         .arg(
-            Arg::new("color")
+            Arg::new("min_query_length")
+                .long("min-query-length")
+                .value_name("NUM")
+                .value_parser(value_parser!(usize))
+                .help(
+                    "Refuse to search for queries shorter than NUM characters,\n\
+                    which are likely to match almost every line"
+                )
+        )
+        .arg(
+            Arg::new("top")
+                .long("top")
+                .value_name("N")
+                .value_parser(RangedU64ValueParser::<usize>::new().range(1..))
+                .help("Fetch only top N results")
+        )
+        .arg(
+            Arg::new("max_results")
+                .long("max-results")
+                .value_name("N")
+                .value_parser(value_parser!(usize))
+                .conflicts_with("top")
+                .help(
+                    "Stop searching after the first N matches (in the order they are found, across all files),\n\
+                    regardless of their scores"
+                )
+        )
+        .arg(
+            Arg::new("max_time")
+                .long("max-time")
+                .value_name("SECS")
+                .value_parser(value_parser!(u64))
+                .help(
+                    "Stop searching after SECS seconds and output the matches found so far.\n\
+                    The results may be incomplete then, so a warning is printed and the exit status is 2.\n\
+                    The time limit is checked between the files, a file being searched is searched to the end,\n\
+                    so a single file or the standard input is never interrupted."
+                )
+        )
+        .arg(
+            Arg::new("sort")
+                .long("sort")
+                .value_name("KEY")
+                .value_parser(
+                    PossibleValuesParser::new([
+                        "score",
+                        "score-asc",
+                        "filename",
+                        "filename-desc",
+                        "line",
+                        "line-desc",
+                        "none",
+                    ])
+                    .map(|key| sort_order_from_key(&key)),
+                )
+                .help(
+                    "Order of the results in the output; by default the best matches come first.\n\
+                    'filename' and 'line' require file names and line numbers to be tracked (see '-f' and '-n'),\n\
+                    'none' preserves the order in which the matches are found."
+                )
+        )
+        .arg(
+            Arg::new("reverse_sort")
+                .long("reverse-sort")
+                .action(ArgAction::SetTrue)
+                .help("Invert the order of the results given by '--sort'")
+        )
+        .arg(
+            Arg::new("unique")
+                .short('u')
+                .long("unique")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Print only the best match among the ones with identical lines\n\
+                    (e.g. the same copyright header in many files)"
+                )
+        )
+        .arg(
+            Arg::new("max_filesize")
+                .long("max-filesize")
+                .value_name("BYTES")
+                .value_parser(file_size_parser)
+                .help(
+                    "Skip files larger than BYTES.\n\
+                    Suffixes 'K', 'M' and 'G' can be used for kibibytes, mebibytes and gibibytes respectively"
+                )
+        )
+        .arg(
+            Arg::new("byte_range")
+                .long("byte-range")
+                .value_name("START-END")
+                .value_parser(byte_range_parser)
+                .help(
+                    "Search only the lines that start between the bytes START (inclusive) and END (exclusive) of every file.\n\
+                    Line numbers are still counted from the beginning of the file"
+                )
+        )
+        .arg(
+            Arg::new("max_line_length")
+                .long("max-line-length")
+                .value_name("BYTES")
+                .value_parser(value_parser!(usize))
+                .help("Skip lines longer than BYTES (e.g. minified code or encoded blobs)")
+        )
+        .arg(
+            Arg::new("truncate_long_lines")
+                .long("truncate-long-lines")
+                .action(ArgAction::SetTrue)
+                .requires("max_line_length")
+                .help("Truncate lines longer than '--max-line-length' (marking them with '…') instead of skipping them")
+        )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .visible_alias("silent")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("verbose")
+                .help("Suppress all output")
+        )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Print the names of the files that would be searched, one per line, without searching them.\n\
+                    The path and color options apply to the names the same way as to the results"
+                )
+        )
+        .arg(
+            Arg::new("list_files")
+                .long("list-files")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Same as '--dry-run', but print the line count of each file after its name,\n\
+                    e.g. 'path/to/file.rs (342 lines)'"
+                )
+        )
+        .arg(
+            Arg::new("only_filenames_with_score")
+                .long("only-filenames-with-score")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("format")
+                .help(
+                    "Instead of the matches, print every file that has any along with the best score found in it,\n\
+                    e.g. 'path/to/file.rs:87', the best-matching files first. With '--top NUM' print NUM files"
+                )
+        )
+        .arg(
+            Arg::new("field_separator")
+                .long("field-separator")
+                .value_name("SEP")
+                .default_value(":")
+                .help("Separate the file name, the line number, etc. and the line itself with SEP")
+        )
+        .arg(
+            Arg::new("print_context_count")
+                .long("print-context-count")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Print the numbers of leading and trailing context lines actually collected\n\
+                    after the line number of each matching line, e.g. 'file:42[2+1]:line'"
+                )
+        )
+        .arg(
+            Arg::new("print_match_positions")
+                .long("print-match-positions")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Print the positions (0-based character indices) of the matched characters\n\
+                    on a separate line after each matching line, e.g. '[0,3,5,7]' (never colored)"
+                )
+        )
+        .arg(
+            Arg::new("show_score")
+                .long("show-score")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Print the score of each match as a field of its own before the matching line,\n\
+                    e.g. 'file:42:187:line' (see '--score-format')"
+                )
+        )
+        .arg(
+            Arg::new("score_format")
+                .long("score-format")
+                .value_name("FORMAT")
+                .value_parser(
+                    PossibleValuesParser::new(["decimal", "hex", "percent"])
+                        .map(|format| score_format_from_name(&format)),
+                )
+                .requires("show_score")
+                .help(
+                    "How '--show-score' prints the scores: 'decimal' (the raw score, the default),\n\
+                    'hex' (the raw score in lowercase hexadecimal) or 'percent' (0 to 100,\n\
+                    relative to the best possible score for the query)"
+                )
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("TEMPLATE")
+                .help(
+                    "Print every match according to TEMPLATE instead of the regular layout, e.g. '{file}:{line}:{match}'.\n\
+                    Placeholders: {file}, {line}, {col}, {score}, {match}, {before} and {after}\n\
+                    (the context lines are separated by newlines). Using {file}, {line} or {col} implies\n\
+                    the corresponding option. Unknown placeholders are printed as is"
+                )
+        )
+        .arg(
+            Arg::new("field_order")
+                .long("field-order")
+                .value_name("SPEC")
+                .value_parser(field_order_parser)
+                .conflicts_with("format")
+                .help(
+                    "Print only the given fields of every line, in the given order, e.g. 'line,match,file'.\n\
+                    SPEC is a comma-separated list of 'file', 'line', 'col', 'score' and 'match';\n\
+                    listing a field implies the corresponding option. The default is 'file,line,col,score,match'\n\
+                    (the fields that are not enabled are not printed)"
+                )
+        )
+        .arg(
+            Arg::new("output_format")
+                .long("output-format")
+                .value_name("FORMAT")
+                .value_parser(PossibleValuesParser::new(["tsv", "ndjson"]))
+                .conflicts_with("field_separator")
+                .help(
+                    "Output format. 'tsv' is a shorthand for a tab field separator.\n\
+                    'ndjson' prints every match as a JSON object on a line of its own (context lines included),\n\
+                    ignoring the formatting options; with '--sort none' the matches are printed as soon as they are found.\n\
+                    'ndjson' requires the 'serde' feature"
+                )
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .help(
+                    "Write the results to FILE instead of the standard output.\n\
+                    The output is not colored unless '--color always' is specified."
+                )
+        )
+        .arg(
+            Arg::new("line_buffered")
+                .long("line-buffered")
+                .action(ArgAction::SetTrue)
+                .help("Flush the output after every line")
+        )
+        .arg(
+            Arg::new("zero_terminated")
+                .short('0')
+                .long("zero-terminated")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Terminate every output line with a NUL character instead of a newline\n\
+                    (e.g. for 'xargs -0'). Implies '--no-pager'"
+                )
+        )
+        .arg(
+            Arg::new("pager")
+                .long("pager")
+                .value_name("CMD")
+                .help(
+                    "Pipe the output through CMD when it does not fit on the terminal\n\
+                    (defaults to $PAGER, or 'less -R' if it is not set).\n\
+                    Paging only happens when the standard output is a terminal and '--line-buffered' is not given,\n\
+                    so the output is colored by default"
+                )
+        )
+        .arg(
+            Arg::new("no_pager")
+                .long("no-pager")
+                .action(ArgAction::SetTrue)
+                .overrides_with("pager")
+                .help("Never pipe the output through a pager")
+        )
+        .arg(
+            Arg::new("stats")
+                .long("stats")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Print search statistics to the standard error after the results.\n\
+                    The statistics are printed as a single line of 'key=value' pairs:\n\
+                    'files', 'lines', 'matches' and 'elapsed' (in seconds)."
+                )
+        )
+        .arg(
+            Arg::new("progress")
+                .long("progress")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Show the number of files searched and matches found so far on the standard error while searching.\n\
+                    Ignored if the standard error is not a terminal."
+                )
+        )
+        .arg(
+            Arg::new("no_messages")
+                .short('s')
+                .long("no-messages")
+                .action(ArgAction::SetTrue)
+                .help("Suppress error messages (the exit status still reflects the errors)")
+        )
+        .arg(
+            Arg::new("report_errors_as_matches")
+                .long("report-errors-as-matches")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Let the exit status depend only on whether any matches were found:\n\
+                    an error while searching results in 0 or 1 (no matches) instead of 2,\n\
+                    and the files that cannot be read are skipped.\n\
+                    The errors are still reported unless '--no-messages' is given"
+                )
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .action(ArgAction::Count)
+                .conflicts_with("quiet")
+                .help(
+                    "Verbose output. Specify multiple times to increase verbosity.\n\
+                    Without the switch only errors are reported (unless '-q' is specified);\n\
+                    \t'-v' additionally enables warning messages;\n\
+                    \t'-vv' additionally enables info messages;\n\
+                    \t'-vvv' additionally enables debug messages;\n\
+                    \tand '-vvvv' additionally enables trace messages."
+                )
+        )
+        .arg(
+            Arg::new("color")
                 .long("color")
                 .visible_alias("colour")
                 .value_name("WHEN")
@@ -368,6 +1471,28 @@ fn match_command_line(args: impl Iterator<Item = String>) -> ArgMatches {
                     With 'auto' the output is colored only when the standard input is connected to a terminal."
                 )
         )
+        .arg(
+            Arg::new("color_theme")
+                .long("color-theme")
+                .visible_alias("colour-theme")
+                .value_name("THEME")
+                .value_parser(
+                    PossibleValuesParser::new([
+                        "default",
+                        "dark",
+                        "light",
+                        "solarized-dark",
+                        "solarized-light",
+                        "none",
+                    ])
+                    .map(|theme| theme_from(&theme)),
+                )
+                .help(
+                    "Color theme to use when the output is colored.\n\
+                    The theme is applied first, so '--color-overrides' can be used to adjust individual colors.\n\
+                    'default' follows 'grep' colors, 'none' leaves all the text unstyled."
+                )
+        )
         .arg(
             Arg::new("color_overrides")
                 .long("color-overrides")
@@ -383,22 +1508,176 @@ fn match_command_line(args: impl Iterator<Item = String>) -> ArgMatches {
                     \t'fn=' color for file names\n\
                     \t'se=' color for separators\n\
                     \t'sl=' color for the whole selected line (the non-matching part)\n\
-                    \t'cx=' color for the surrounding context\n\
+                    \t'cx=' color for the surrounding context (both leading and trailing)\n\
+                    \t'bc=' color for the leading context\n\
+                    \t'ac=' color for the trailing context\n\
+                    \t'ck=' color for the context line counts (see '--print-context-count')\n\
+                    \t'sc=' color for the scores (see '--show-score')\n\
+                    \t'cq=' color for the text matching the query in the context (see '--highlight-query')\n\
                     Note that some of `grep` capabilities (e.g. 'rv', 'ne', 'mt=', 'bn=') are not available\n\
                     The default behavior is equivalent to '--color-overrides ms=01;31:mc=01;31:sl=:cx=:fn=35:ln=32:se=36'.\n\
                     For more information see 'grep' documentation: https://man7.org/linux/man-pages/man1/grep.1.html#ENVIRONMENT\n\
                     and/or ASCII escape codes: https://en.wikipedia.org/wiki/ANSI_escape_code."
                 )
         )
+        .arg(
+            Arg::new("color_reset")
+                .long("color-reset")
+                .visible_alias("colour-reset")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "End every colored output line with an explicit reset sequence ('\\e[0m'),\n\
+                    for the terminals and pagers that carry the colors over to the next line"
+                )
+        )
+        .arg(
+            Arg::new("gradient_colors")
+                .long("gradient-colors")
+                .visible_alias("gradient-colours")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Color the matches by their scores, from red for the lowest ones, through yellow,\n\
+                    to green for the highest ones. Overrides the 'ms=' color."
+                )
+        )
+        .arg(
+            Arg::new("hyperlinks")
+                .long("hyperlinks")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Turn file names and line numbers into terminal hyperlinks (OSC 8) to the matching line.\n\
+                    Only applies when both the file name and the line number are printed and the output is colored."
+                )
+        )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .conflicts_with("no_config")
+                .help(
+                    "Read default option values from FILE.\n\
+                    By default '$XDG_CONFIG_HOME/fzgrep/config.toml' (or '~/.config/fzgrep/config.toml') is used if it exists.\n\
+                    Options given on the command line take precedence over the ones from the config file"
+                )
+        )
+        .arg(
+            Arg::new("no_config")
+                .long("no-config")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("config")
+                .help("Do not read the config file")
+        )
+        .arg(
+            Arg::new("generate_completions")
+                .long("generate-completions")
+                .value_name("SHELL")
+                .value_parser(value_parser!(Shell))
+                .exclusive(true)
+                .help("Print the completion script for SHELL to the standard output and exit")
+        )
+        .arg(
+            Arg::new("print_json_schema")
+                .long("print-json-schema")
+                .action(ArgAction::SetTrue)
+                .exclusive(true)
+                .help(
+                    "Print the JSON Schema (draft-07) of the serialized matching results to the standard output and exit\n\
+                    (requires the `json-schema` feature)"
+                )
+        )
+        .arg(
+            Arg::new("dump_config")
+                .long("dump-config")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Print the resolved values of the options that can be set in the config file\n\
+                    (coming from the command line, FZGREP_DEFAULT_OPTIONS, the config file and the defaults)\n\
+                    to the standard output as TOML and exit. The output is a valid config file"
+                )
+        )
         .next_line_help(true)
-        .get_matches_from(args)
 }
 
-fn color_overrides_parser(
+fn print_completions(shell: Shell) -> ! {
+    let mut command = make_command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, &mut io::stdout());
+    process::exit(0);
+}
+
+#[cfg(feature = "json-schema")]
+fn print_json_schema() -> ! {
+    match serde_json::to_string_pretty(&schemars::schema_for!(crate::MatchingResult)) {
+        Ok(schema) => {
+            println!("{schema}");
+            process::exit(0);
+        }
+        Err(e) => make_command()
+            .error(ErrorKind::Io, format!("cannot serialize the schema: {e}"))
+            .exit(),
+    }
+}
+
+#[cfg(not(feature = "json-schema"))]
+fn print_json_schema() -> ! {
+    make_command()
+        .error(
+            ErrorKind::InvalidValue,
+            "JSON schema support is not enabled (the `json-schema` feature)",
+        )
+        .exit()
+}
+
+fn print_config(matches: &ArgMatches) -> ! {
+    match toml::to_string(&Config::from_matches(matches)) {
+        Ok(config) => {
+            print!("{config}");
+            process::exit(0);
+        }
+        Err(e) => make_command()
+            .error(ErrorKind::Io, format!("cannot serialize the config: {e}"))
+            .exit(),
+    }
+}
+
+/// Loads the config file given with `--config` or, unless `--no-config` is given, `default` if it exists.
+///
+fn config_from(matches: &ArgMatches, default: Option<&Path>) -> Option<Config> {
+    if matches.get_flag("no_config") {
+        return None;
+    }
+
+    let path = match matches.get_one::<PathBuf>("config") {
+        Some(path) => path.as_path(),
+        None => default.filter(|path| path.is_file())?,
+    };
+
+    match config::load_config(path) {
+        Ok(config) => Some(config),
+        Err(e) => make_command()
+            .error(ErrorKind::Io, format!("'{}': {e}", path.display()))
+            .exit(),
+    }
+}
+
+/// Returns whether the argument has been explicitly specified on the command line
+/// (as opposed to having a default value, possibly coming from the config file).
+///
+fn is_from_command_line(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+fn color_overrides_parser(grep_sequence: &str) -> Result<String, ColorOverrideParsingError> {
+    // The overrides are applied on top of the color theme later on, here they are only validated.
+    apply_color_overrides(FormattingOptions::default(), grep_sequence)?;
+    Ok(grep_sequence.to_string())
+}
+
+fn apply_color_overrides(
+    mut options: FormattingOptions,
     grep_sequence: &str,
 ) -> Result<FormattingOptions, ColorOverrideParsingError> {
-    let mut options = FormattingOptions::default();
-
     for token in grep_sequence.split(':') {
         if let Some((cap, sgr)) = token.split_once('=') {
             match cap {
@@ -423,7 +1702,29 @@ fn color_overrides_parser(
                         .map_err(ColorOverrideParsingError::BadStyleSequence)?
                 }
                 "cx" => {
-                    options.context = sgr_sequence::style_from(sgr)
+                    let style = sgr_sequence::style_from(sgr)
+                        .map_err(ColorOverrideParsingError::BadStyleSequence)?;
+                    options.before_context = style;
+                    options.after_context = style;
+                }
+                "bc" => {
+                    options.before_context = sgr_sequence::style_from(sgr)
+                        .map_err(ColorOverrideParsingError::BadStyleSequence)?
+                }
+                "ac" => {
+                    options.after_context = sgr_sequence::style_from(sgr)
+                        .map_err(ColorOverrideParsingError::BadStyleSequence)?
+                }
+                "ck" => {
+                    options.context_count = sgr_sequence::style_from(sgr)
+                        .map_err(ColorOverrideParsingError::BadStyleSequence)?
+                }
+                "sc" => {
+                    options.score = sgr_sequence::style_from(sgr)
+                        .map_err(ColorOverrideParsingError::BadStyleSequence)?
+                }
+                "cq" => {
+                    options.context_match = sgr_sequence::style_from(sgr)
                         .map_err(ColorOverrideParsingError::BadStyleSequence)?
                 }
                 "bn" | "mt" => {
@@ -443,50 +1744,335 @@ fn color_overrides_parser(
     Ok(options)
 }
 
-fn query_from(matches: &ArgMatches) -> String {
-    let query = matches
-        .get_one::<String>("pattern")
-        .expect("QUERY argument is required, it cannot be empty");
-    query.clone()
+fn sort_order_from_key(key: &str) -> SortOrder {
+    match key {
+        "score" => SortOrder::Score,
+        "score-asc" => SortOrder::ScoreAsc,
+        "filename" => SortOrder::FileName,
+        "filename-desc" => SortOrder::FileNameDesc,
+        "line" => SortOrder::Line,
+        "line-desc" => SortOrder::LineDesc,
+        "none" => SortOrder::None,
+        _ => unreachable!(),
+    }
 }
 
-fn targets_from(matches: &ArgMatches) -> Targets {
-    match matches.get_many::<String>("target") {
-        Some(targets) => {
-            let targets = targets.map(PathBuf::from).collect::<Vec<_>>();
-            if matches.get_flag("recursive") {
-                Targets::RecursiveEntries(targets)
-            } else {
-                Targets::Files(targets)
-            }
-        }
-        None => {
-            if matches.get_flag("recursive") {
-                Targets::RecursiveEntries(vec![env::current_dir().unwrap_or(PathBuf::from("."))])
-            } else {
-                Targets::Stdin
-            }
-        }
+fn score_format_from_name(name: &str) -> ScoreFormat {
+    match name {
+        "decimal" => ScoreFormat::Decimal,
+        "hex" => ScoreFormat::Hex,
+        "percent" => ScoreFormat::Percent,
+        _ => unreachable!(),
     }
 }
 
-fn strategy_from(matches: &ArgMatches) -> MatchCollectionStrategy {
-    match matches.get_one::<usize>("top") {
-        Some(cap) => MatchCollectionStrategy::CollectTop(*cap),
-        None => MatchCollectionStrategy::CollectAll,
+fn theme_from(name: &str) -> Theme {
+    match name {
+        "default" => Theme::Default,
+        "dark" => Theme::Dark,
+        "light" => Theme::Light,
+        "solarized-dark" => Theme::SolarizedDark,
+        "solarized-light" => Theme::SolarizedLight,
+        "none" => Theme::None,
+        _ => unreachable!(),
     }
 }
 
-fn match_options_from(matches: &ArgMatches) -> MatchOptions {
-    MatchOptions {
-        track_line_numbers: matches.get_flag("line_number"),
-        track_file_names: track_file_name_from(matches),
-        context_size: context_size_from(matches),
-    }
+fn scoring_config_parser(config: &str) -> Result<ScoringConfig, serde_json::Error> {
+    serde_json::from_str(config)
 }
 
-fn track_file_name_from(matches: &ArgMatches) -> bool {
-    // `--with-filename` flag has been specified -> file names *should* be tracked
+#[cfg(feature = "regex")]
+fn regex_parser(re: &str) -> Result<regex::Regex, regex::Error> {
+    regex::Regex::new(re)
+}
+
+#[cfg(not(feature = "regex"))]
+fn regex_parser(_re: &str) -> Result<String, &'static str> {
+    Err("regex support is not enabled (the `regex` feature)")
+}
+
+fn byte_range_parser(range: &str) -> Result<Range<u64>, ByteRangeParsingError> {
+    let (start, end) = range
+        .split_once('-')
+        .ok_or_else(|| ByteRangeParsingError::NotARange(range.to_string()))?;
+    let parse = |offset: &str| {
+        offset
+            .parse::<u64>()
+            .map_err(|e| ByteRangeParsingError::NotANumber(offset.to_string(), e))
+    };
+    let (start, end) = (parse(start)?, parse(end)?);
+    if end < start {
+        return Err(ByteRangeParsingError::Reversed(range.to_string()));
+    }
+    Ok(start..end)
+}
+
+fn file_size_parser(size: &str) -> Result<u64, FileSizeParsingError> {
+    let (number, multiplier) = match size.chars().last() {
+        Some('K') => (&size[..size.len() - 1], 1 << 10),
+        Some('M') => (&size[..size.len() - 1], 1 << 20),
+        Some('G') => (&size[..size.len() - 1], 1 << 30),
+        _ => (size, 1),
+    };
+
+    number
+        .parse::<u64>()
+        .map_err(|e| FileSizeParsingError::NotANumber(number.to_string(), e))?
+        .checked_mul(multiplier)
+        .ok_or_else(|| FileSizeParsingError::TooLarge(size.to_string()))
+}
+
+fn encoding_parser(label: &str) -> Result<&'static Encoding, UnknownEncodingError> {
+    // the replacement encoding only exists to prevent decoding some dangerous legacy encodings
+    Encoding::for_label(label.as_bytes())
+        .filter(|&encoding| encoding != encoding_rs::REPLACEMENT)
+        .ok_or_else(|| UnknownEncodingError(label.to_string()))
+}
+
+fn field_order_parser(spec: &str) -> Result<Vec<OutputField>, FieldOrderParsingError> {
+    let mut order = Vec::new();
+    for name in spec.split(',').map(str::trim) {
+        let field = match name {
+            "file" => OutputField::File,
+            "line" => OutputField::Line,
+            "col" => OutputField::Column,
+            "score" => OutputField::Score,
+            "match" => OutputField::Match,
+            _ => return Err(FieldOrderParsingError::UnknownField(name.to_string())),
+        };
+        if order.contains(&field) {
+            return Err(FieldOrderParsingError::RepeatedField(name.to_string()));
+        }
+        order.push(field);
+    }
+    Ok(order)
+}
+
+fn query_from(matches: &ArgMatches) -> String {
+    let query = matches
+        .get_one::<String>("pattern")
+        .expect("QUERY argument is required, it cannot be empty");
+    if let Some(&min_length) = matches.get_one::<usize>("min_query_length") {
+        let length = query.chars().count();
+        if length < min_length {
+            make_command()
+                .error(
+                    ErrorKind::ValueValidation,
+                    format!(
+                        "the query '{query}' is {length} characters long, \
+                        shorter than the minimum of {min_length} (see '--min-query-length')"
+                    ),
+                )
+                .exit();
+        }
+    }
+    query.clone()
+}
+
+fn targets_from(matches: &ArgMatches) -> Targets {
+    if let Some(patterns) = matches.get_many::<glob::Pattern>("target_glob") {
+        return Targets::Glob(patterns.cloned().collect());
+    }
+    let listed = matches
+        .get_one::<String>("files_from")
+        .map(|list| files_from(list, matches.get_flag("null_input")));
+    match (matches.get_many::<String>("target"), listed) {
+        (None, None) => {
+            if matches.get_flag("recursive") {
+                Targets::RecursiveEntries(vec![env::current_dir().unwrap_or(PathBuf::from("."))])
+            } else {
+                Targets::Stdin
+            }
+        }
+        (targets, listed) => {
+            let targets = targets
+                .into_iter()
+                .flatten()
+                .map(PathBuf::from)
+                .chain(listed.into_iter().flatten())
+                .collect::<Vec<_>>();
+            if matches.get_flag("recursive") {
+                Targets::RecursiveEntries(targets)
+            } else {
+                Targets::Files(targets)
+            }
+        }
+    }
+}
+
+/// Reads the list of targets given with `--files-from` (`-` stands for the standard input).
+/// The program exits with an error message if the list cannot be read.
+///
+fn files_from(list: &str, null_separated: bool) -> Vec<PathBuf> {
+    let targets = if list == "-" {
+        read_targets(io::stdin().lock(), null_separated)
+    } else {
+        File::open(list).and_then(|file| read_targets(BufReader::new(file), null_separated))
+    };
+    match targets {
+        Ok(targets) => targets,
+        Err(e) => make_command()
+            .error(ErrorKind::Io, format!("'{list}': {e}"))
+            .exit(),
+    }
+}
+
+fn read_targets(reader: impl BufRead, null_separated: bool) -> Result<Vec<PathBuf>, io::Error> {
+    let separator = if null_separated { b'\0' } else { b'\n' };
+    let mut targets = Vec::new();
+    for entry in reader.split(separator) {
+        let entry =
+            String::from_utf8(entry?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        // tolerate CRLF line endings and skip blank lines
+        let entry = if null_separated {
+            entry.as_str()
+        } else {
+            entry.trim_end_matches('\r')
+        };
+        if !entry.is_empty() {
+            targets.push(PathBuf::from(entry));
+        }
+    }
+    Ok(targets)
+}
+
+fn relative_paths_root_from(matches: &ArgMatches) -> Option<PathBuf> {
+    matches.contains_id("relative_paths").then(|| {
+        matches
+            .get_one::<PathBuf>("relative_paths")
+            .cloned()
+            .unwrap_or_else(|| env::current_dir().unwrap_or(PathBuf::from(".")))
+    })
+}
+
+fn sort_order_from(matches: &ArgMatches) -> SortOrder {
+    let order = matches
+        .get_one::<SortOrder>("sort")
+        .copied()
+        .unwrap_or(SortOrder::Score);
+    if matches.get_flag("reverse_sort") {
+        order.reversed()
+    } else {
+        order
+    }
+}
+
+fn filter_from(matches: &ArgMatches) -> Filter {
+    Filter {
+        include_globs: globs_from(matches, ["include", "include_extension"], "include_from"),
+        exclude_globs: globs_from(matches, ["exclude", "exclude_extension"], "exclude_from"),
+        #[cfg(feature = "regex")]
+        include_regexes: regexes_from(matches, "include_regex"),
+        #[cfg(feature = "regex")]
+        exclude_regexes: regexes_from(matches, "exclude_regex"),
+    }
+}
+
+/// Collects the glob patterns given inline (`ids`, e.g. the globs and the extensions) followed by the ones
+/// read from the lists (`lists_id`).
+///
+fn globs_from(matches: &ArgMatches, ids: [&str; 2], lists_id: &str) -> Vec<glob::Pattern> {
+    let inline = ids
+        .into_iter()
+        .flat_map(|id| matches.get_many::<glob::Pattern>(id).into_iter().flatten())
+        .cloned();
+    let listed = matches
+        .get_many::<PathBuf>(lists_id)
+        .into_iter()
+        .flatten()
+        .flat_map(|list| patterns_from(list));
+    inline.chain(listed).collect()
+}
+
+/// Turns a file extension (with or without the leading dot) into a glob pattern matching the files having it,
+/// e.g. `min.js` -> `*.min.js`. The extension is matched literally, i.e. any glob metacharacters in it are escaped.
+///
+fn extension_glob_parser(extension: &str) -> Result<glob::Pattern, glob::PatternError> {
+    let extension = extension.strip_prefix('.').unwrap_or(extension);
+    glob::Pattern::new(&format!("*.{}", glob::Pattern::escape(extension)))
+}
+
+/// Reads the list of glob patterns given with `--include-from` or `--exclude-from`.
+/// The program exits with an error message if the list cannot be read or contains an invalid pattern.
+///
+fn patterns_from(list: &Path) -> Vec<glob::Pattern> {
+    let patterns = File::open(list)
+        .map_err(PatternListError::Io)
+        .and_then(|file| read_patterns(BufReader::new(file)));
+    match patterns {
+        Ok(patterns) => patterns,
+        Err(e) => {
+            let kind = match e {
+                PatternListError::Io(_) => ErrorKind::Io,
+                PatternListError::BadPattern(..) => ErrorKind::ValueValidation,
+            };
+            make_command()
+                .error(kind, format!("'{}': {e}", list.display()))
+                .exit()
+        }
+    }
+}
+
+fn read_patterns(reader: impl BufRead) -> Result<Vec<glob::Pattern>, PatternListError> {
+    let mut patterns = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(PatternListError::Io)?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let pattern =
+            glob::Pattern::new(line).map_err(|e| PatternListError::BadPattern(index + 1, e))?;
+        patterns.push(pattern);
+    }
+    Ok(patterns)
+}
+
+#[cfg(feature = "regex")]
+fn regexes_from(matches: &ArgMatches, id: &str) -> Vec<regex::Regex> {
+    matches
+        .get_many::<regex::Regex>(id)
+        .map(|regexes| regexes.cloned().collect())
+        .unwrap_or_default()
+}
+
+fn template_uses(matches: &ArgMatches, field: Field) -> bool {
+    matches
+        .get_one::<String>("format")
+        .is_some_and(|template| Template::parse(template).uses(field))
+}
+
+fn field_order_uses(matches: &ArgMatches, field: OutputField) -> bool {
+    matches
+        .get_one::<Vec<OutputField>>("field_order")
+        .is_some_and(|order| order.contains(&field))
+}
+
+fn case_sensitivity_from(matches: &ArgMatches) -> CaseSensitivity {
+    if matches.get_flag("smart_case") {
+        CaseSensitivity::Smart
+    } else {
+        CaseSensitivity::Insensitive
+    }
+}
+
+fn match_mode_from(matches: &ArgMatches) -> MatchMode {
+    if matches.get_flag("acronym") {
+        MatchMode::Acronym
+    } else if matches.get_flag("fixed_string") {
+        MatchMode::FixedString
+    } else {
+        MatchMode::Fuzzy
+    }
+}
+
+fn track_file_name_from(matches: &ArgMatches) -> bool {
+    // `--no-filename` flag has been specified on the command line -> it overrides `with-filename` from the config
+    if is_from_command_line(matches, "no_filename") {
+        return false;
+    }
+    // `--with-filename` flag has been specified -> file names *should* be tracked
     if matches.get_flag("with_filename") {
         return true;
     }
@@ -494,10 +2080,12 @@ fn track_file_name_from(matches: &ArgMatches) -> bool {
     if matches.get_flag("no_filename") {
         return false;
     }
-    // no flags specified, but there are multiple input files -> file names *should* be tracked
+    // no flags specified, but there are multiple input files (or a list of them) -> file names *should* be tracked
     if matches
         .get_many("target")
         .is_some_and(|fs: ValuesRef<'_, String>| fs.len() > 1)
+        || matches.contains_id("files_from")
+        || matches.contains_id("target_glob")
     {
         return true;
     }
@@ -506,7 +2094,12 @@ fn track_file_name_from(matches: &ArgMatches) -> bool {
 }
 
 fn context_size_from(matches: &ArgMatches) -> ContextSize {
-    if let Some(num) = matches.get_one::<usize>("context").copied() {
+    // leading/trailing context specified on the command line overrides `context` from the config
+    let context = matches.get_one::<usize>("context").copied().filter(|_| {
+        !is_from_command_line(matches, "before_context")
+            && !is_from_command_line(matches, "after_context")
+    });
+    if let Some(num) = context {
         ContextSize {
             before: Lines(num),
             after: Lines(num),
@@ -532,32 +2125,131 @@ fn context_size_from(matches: &ArgMatches) -> ContextSize {
 fn formatting_from(matches: &ArgMatches) -> Formatting {
     if let Some(behavior) = matches.get_one::<String>("color") {
         let behavior = behavior.as_str();
-        if behavior == "always" || (behavior == "auto" && atty::is(Stream::Stdout)) {
-            let formatting_options = matches
-                .get_one::<FormattingOptions>("color_overrides")
-                .cloned()
+        // the output file (if any) is never considered a terminal
+        let to_terminal = !matches.contains_id("output") && atty::is(Stream::Stdout);
+        if behavior == "always" || (behavior == "auto" && to_terminal) {
+            let theme_options = matches
+                .get_one::<Theme>("color_theme")
+                .copied()
+                .map(FormattingOptions::from)
                 .unwrap_or_default();
+            let mut formatting_options = match matches.get_one::<String>("color_overrides") {
+                Some(overrides) => apply_color_overrides(theme_options, overrides)
+                    .expect("Color overrides are validated when the arguments are parsed"),
+                None => theme_options,
+            };
+            formatting_options.hyperlinks = matches.get_flag("hyperlinks");
+            formatting_options.color_reset = matches.get_flag("color_reset");
+            formatting_options.gradient = gradient_from(matches);
             Formatting::On(formatting_options)
-        } else if behavior == "never" || (behavior == "auto" && atty::isnt(Stream::Stdout)) {
+        } else if behavior == "never" || (behavior == "auto" && !to_terminal) {
             Formatting::Off
         } else {
             unreachable!();
         }
     } else {
-        Formatting::On(FormattingOptions::default())
+        Formatting::On(FormattingOptions {
+            hyperlinks: matches.get_flag("hyperlinks"),
+            color_reset: matches.get_flag("color_reset"),
+            gradient: gradient_from(matches),
+            ..Default::default()
+        })
+    }
+}
+
+fn gradient_from(matches: &ArgMatches) -> Option<ColorGradient> {
+    matches
+        .get_flag("gradient_colors")
+        .then(ColorGradient::default)
+}
+
+fn output_format_from(matches: &ArgMatches) -> OutputFormat {
+    match matches
+        .get_one::<String>("output_format")
+        .map(String::as_str)
+    {
+        Some("ndjson") if cfg!(feature = "serde") => OutputFormat::Ndjson,
+        Some("ndjson") => make_command()
+            .error(
+                ErrorKind::InvalidValue,
+                "NDJSON output is not enabled (the `serde` feature)",
+            )
+            .exit(),
+        _ => OutputFormat::Text,
+    }
+}
+
+fn field_separator_from(matches: &ArgMatches) -> String {
+    match matches
+        .get_one::<String>("output_format")
+        .map(String::as_str)
+    {
+        Some("tsv") => String::from("\t"),
+        _ => matches
+            .get_one::<String>("field_separator")
+            .cloned()
+            .unwrap_or(String::from(":")),
     }
 }
 
 fn output_behavior_from(matches: &ArgMatches) -> OutputBehavior {
-    if matches.get_flag("quiet") {
+    if is_quiet(matches) {
         return OutputBehavior::Quiet;
     }
 
+    if matches.get_flag("only_filenames_with_score") {
+        return OutputBehavior::FileScores(formatting_from(matches));
+    }
+
     OutputBehavior::Normal(formatting_from(matches))
 }
 
+fn pager_from(matches: &ArgMatches, stdout_is_terminal: bool) -> Option<String> {
+    // a pager only makes sense for the results going to a terminal as soon as they are all ready
+    let to_terminal = !matches.contains_id("output") && stdout_is_terminal;
+    if matches.get_flag("no_pager")
+        || !to_terminal
+        || matches.get_flag("line_buffered")
+        || matches.get_flag("zero_terminated")
+        || is_quiet(matches)
+    {
+        return None;
+    }
+    Some(
+        matches
+            .get_one::<String>("pager")
+            .cloned()
+            .unwrap_or_else(pager::default_pager),
+    )
+}
+
+fn context_separator_from(matches: &ArgMatches) -> Option<String> {
+    if matches.get_flag("no_context_separator") {
+        return None;
+    }
+    Some(
+        matches
+            .get_one::<String>("context_separator")
+            .cloned()
+            .unwrap_or(String::from("--")),
+    )
+}
+
+fn show_caret_from(matches: &ArgMatches) -> bool {
+    // the caret is aligned by the column of the match, which is only shown with '--column-number',
+    // and is of no use anywhere but on a terminal
+    matches.get_flag("show_caret")
+        && matches.get_flag("column_number")
+        && terminal_size::terminal_size().is_some()
+}
+
+fn is_quiet(matches: &ArgMatches) -> bool {
+    // `--verbose` specified on the command line overrides `quiet` from the config
+    matches.get_flag("quiet") && !is_from_command_line(matches, "verbose")
+}
+
 fn log_verbosity_from(matches: &ArgMatches) -> LevelFilter {
-    if matches.get_flag("quiet") {
+    if is_quiet(matches) {
         return LevelFilter::Off;
     }
 
@@ -573,12 +2265,17 @@ fn log_verbosity_from(matches: &ArgMatches) -> LevelFilter {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::request::Lines;
+    use crate::core::request::{
+        ContextChars, Lines, LossyEncoding, MatchCollectionStrategy, MatchMode, MatchOptions,
+    };
+    use crate::core::source_plugin::SourceRegistry;
+    use std::{collections::BTreeSet, io::Write};
+    use tempfile::NamedTempFile;
     use yansi::Style;
 
     #[test]
     fn make_request_no_targets() {
-        let args = ["fzgrep", "query"];
+        let args = ["fzgrep", "--no-pager", "query"];
         let request = make_request(args.into_iter().map(String::from));
         assert_eq!(
             request,
@@ -586,27 +2283,86 @@ mod tests {
                 query: String::from("query"),
                 targets: Targets::Stdin,
                 strategy: MatchCollectionStrategy::CollectAll,
+                max_time: None,
                 match_options: MatchOptions {
                     track_line_numbers: false,
+                    track_column_numbers: false,
                     track_file_names: false,
                     context_size: ContextSize {
                         before: Lines(0),
                         after: Lines(0),
                     },
+                    context_chars: ContextChars {
+                        chars_before: 0,
+                        chars_after: 0,
+                    },
+                    skip_bytes: None,
+                    byte_range: None,
+                    decompress: false,
+                    search_zip: false,
+                    follow_links: false,
+                    dedup_inodes: false,
+                    filter: Filter::default(),
+                    skip_hidden: false,
+                    word_boundary_check: false,
+                    word_chars: None,
+                    whole_line: false,
+                    case_sensitivity: CaseSensitivity::Insensitive,
+                    match_mode: MatchMode::Fuzzy,
+                    path_aware: false,
+                    acronym_bonus: None,
+                    print_unmatched: false,
+                    unicode_normalization: false,
+                    ignore_diacritics: false,
+                    scoring: ScoringConfig::default(),
+                    lossy_encoding: LossyEncoding(false),
+                    encoding: None,
+                    sources: SourceRegistry::default(),
+                    null_data: false,
+                    max_line_length: None,
+                    truncate_long_lines: false,
                 },
+                sort_order: SortOrder::Score,
+                unique: false,
+                relative_paths_root: None,
                 output_behavior: OutputBehavior::Normal(if atty::is(Stream::Stdout) {
                     Formatting::On(FormattingOptions::default())
                 } else {
                     Formatting::Off
                 }),
+                dry_run: false,
+                count_lines: false,
+                field_separator: String::from(":"),
+                output_format: OutputFormat::Text,
+                print_context_count: false,
+                print_match_positions: false,
+                score_format: None,
+                show_caret: false,
+                print_file_headers: false,
+                unmatched_marker: String::from("  "),
+                highlight_query: false,
+                field_order: None,
+                line_number_width: 0,
+                auto_align: false,
+                context_separator: Some(String::from("--")),
+                output_template: None,
+                output_file: None,
+                line_buffered: false,
+                zero_terminated: false,
+                pager: None,
                 log_verbosity: LevelFilter::Error,
+                print_stats: false,
+                show_progress: false,
+                suppress_errors: false,
+                report_errors_as_matches: false,
+                notices: Vec::new(),
             }
         );
     }
 
     #[test]
     fn make_request_no_targets_recursive() {
-        let args = ["fzgrep", "--recursive", "query"];
+        let args = ["fzgrep", "--no-pager", "--recursive", "query"];
         let request = make_request(args.into_iter().map(String::from));
         assert_eq!(
             request,
@@ -614,27 +2370,86 @@ mod tests {
                 query: String::from("query"),
                 targets: Targets::RecursiveEntries(vec![env::current_dir().unwrap()]),
                 strategy: MatchCollectionStrategy::CollectAll,
+                max_time: None,
                 match_options: MatchOptions {
                     track_line_numbers: false,
+                    track_column_numbers: false,
                     track_file_names: false,
                     context_size: ContextSize {
                         before: Lines(0),
                         after: Lines(0),
                     },
+                    context_chars: ContextChars {
+                        chars_before: 0,
+                        chars_after: 0,
+                    },
+                    skip_bytes: None,
+                    byte_range: None,
+                    decompress: false,
+                    search_zip: false,
+                    follow_links: false,
+                    dedup_inodes: false,
+                    filter: Filter::default(),
+                    skip_hidden: false,
+                    word_boundary_check: false,
+                    word_chars: None,
+                    whole_line: false,
+                    case_sensitivity: CaseSensitivity::Insensitive,
+                    match_mode: MatchMode::Fuzzy,
+                    path_aware: false,
+                    acronym_bonus: None,
+                    print_unmatched: false,
+                    unicode_normalization: false,
+                    ignore_diacritics: false,
+                    scoring: ScoringConfig::default(),
+                    lossy_encoding: LossyEncoding(false),
+                    encoding: None,
+                    sources: SourceRegistry::default(),
+                    null_data: false,
+                    max_line_length: None,
+                    truncate_long_lines: false,
                 },
+                sort_order: SortOrder::Score,
+                unique: false,
+                relative_paths_root: None,
                 output_behavior: OutputBehavior::Normal(if atty::is(Stream::Stdout) {
                     Formatting::On(FormattingOptions::default())
                 } else {
                     Formatting::Off
                 }),
+                dry_run: false,
+                count_lines: false,
+                field_separator: String::from(":"),
+                output_format: OutputFormat::Text,
+                print_context_count: false,
+                print_match_positions: false,
+                score_format: None,
+                show_caret: false,
+                print_file_headers: false,
+                unmatched_marker: String::from("  "),
+                highlight_query: false,
+                field_order: None,
+                line_number_width: 0,
+                auto_align: false,
+                context_separator: Some(String::from("--")),
+                output_template: None,
+                output_file: None,
+                line_buffered: false,
+                zero_terminated: false,
+                pager: None,
                 log_verbosity: LevelFilter::Error,
+                print_stats: false,
+                show_progress: false,
+                suppress_errors: false,
+                report_errors_as_matches: false,
+                notices: Vec::new(),
             }
         );
     }
 
     #[test]
     fn make_request_single_target() {
-        let args = ["fzgrep", "query", "file"];
+        let args = ["fzgrep", "--no-pager", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
         assert_eq!(
             request,
@@ -642,20 +2457,79 @@ mod tests {
                 query: String::from("query"),
                 targets: Targets::Files(vec![PathBuf::from("file")]),
                 strategy: MatchCollectionStrategy::CollectAll,
+                max_time: None,
                 match_options: MatchOptions {
                     track_line_numbers: false,
+                    track_column_numbers: false,
                     track_file_names: false,
                     context_size: ContextSize {
                         before: Lines(0),
                         after: Lines(0),
                     },
+                    context_chars: ContextChars {
+                        chars_before: 0,
+                        chars_after: 0,
+                    },
+                    skip_bytes: None,
+                    byte_range: None,
+                    decompress: false,
+                    search_zip: false,
+                    follow_links: false,
+                    dedup_inodes: false,
+                    filter: Filter::default(),
+                    skip_hidden: false,
+                    word_boundary_check: false,
+                    word_chars: None,
+                    whole_line: false,
+                    case_sensitivity: CaseSensitivity::Insensitive,
+                    match_mode: MatchMode::Fuzzy,
+                    path_aware: false,
+                    acronym_bonus: None,
+                    print_unmatched: false,
+                    unicode_normalization: false,
+                    ignore_diacritics: false,
+                    scoring: ScoringConfig::default(),
+                    lossy_encoding: LossyEncoding(false),
+                    encoding: None,
+                    sources: SourceRegistry::default(),
+                    null_data: false,
+                    max_line_length: None,
+                    truncate_long_lines: false,
                 },
+                sort_order: SortOrder::Score,
+                unique: false,
+                relative_paths_root: None,
                 output_behavior: OutputBehavior::Normal(if atty::is(Stream::Stdout) {
                     Formatting::On(FormattingOptions::default())
                 } else {
                     Formatting::Off
                 }),
+                dry_run: false,
+                count_lines: false,
+                field_separator: String::from(":"),
+                output_format: OutputFormat::Text,
+                print_context_count: false,
+                print_match_positions: false,
+                score_format: None,
+                show_caret: false,
+                print_file_headers: false,
+                unmatched_marker: String::from("  "),
+                highlight_query: false,
+                field_order: None,
+                line_number_width: 0,
+                auto_align: false,
+                context_separator: Some(String::from("--")),
+                output_template: None,
+                output_file: None,
+                line_buffered: false,
+                zero_terminated: false,
+                pager: None,
                 log_verbosity: LevelFilter::Error,
+                print_stats: false,
+                show_progress: false,
+                suppress_errors: false,
+                report_errors_as_matches: false,
+                notices: Vec::new(),
             }
         );
     }
@@ -687,222 +2561,1715 @@ mod tests {
             "file3",
         ];
         let request = make_request(args.into_iter().map(String::from));
-        assert!(!request.match_options.track_file_names);
+        assert!(!request.match_options.track_file_names);
+    }
+
+    #[test]
+    fn make_request_non_ascii_emoji() {
+        let args = ["fzgrep", "🐣🦀", "file1", "👨‍🔬.txt", "file3"];
+        let request = make_request(args.into_iter().map(String::from));
+
+        assert_eq!(request.query, "🐣🦀");
+        assert_eq!(
+            request.targets,
+            Targets::Files(vec![
+                PathBuf::from("file1"),
+                PathBuf::from("👨‍🔬.txt"),
+                PathBuf::from("file3")
+            ])
+        );
+    }
+
+    #[test]
+    fn make_request_non_ascii_cyrillic() {
+        let args = ["fzgrep", "тест", "file1", "тест.txt", "file3"];
+        let request = make_request(args.into_iter().map(String::from));
+
+        assert_eq!(request.query, "тест");
+        assert_eq!(
+            request.targets,
+            Targets::Files(vec![
+                PathBuf::from("file1"),
+                PathBuf::from("тест.txt"),
+                PathBuf::from("file3")
+            ])
+        );
+    }
+
+    #[test]
+    fn make_request_non_ascii_chinese() {
+        let args = ["fzgrep", "打电", "file1", "测试.txt", "file3"];
+        let request = make_request(args.into_iter().map(String::from));
+
+        assert_eq!(request.query, "打电");
+        assert_eq!(
+            request.targets,
+            Targets::Files(vec![
+                PathBuf::from("file1"),
+                PathBuf::from("测试.txt"),
+                PathBuf::from("file3")
+            ])
+        );
+    }
+
+    #[test]
+    fn make_request_recursive_short() {
+        let args = ["fzgrep", "-r", "query", "dir"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.targets,
+            Targets::RecursiveEntries(vec![PathBuf::from("dir")])
+        );
+    }
+
+    #[test]
+    fn make_request_recursive_long() {
+        let args = ["fzgrep", "--recursive", "query", "dir"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.targets,
+            Targets::RecursiveEntries(vec![PathBuf::from("dir")])
+        );
+    }
+
+    #[test]
+    fn make_request_with_file_name_short() {
+        let args = ["fzgrep", "-f", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.track_file_names);
+    }
+
+    #[test]
+    fn make_request_with_file_name_long() {
+        let args = ["fzgrep", "--with-filename", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.track_file_names);
+    }
+
+    #[test]
+    fn make_request_no_file_name_short() {
+        let args = ["fzgrep", "-F", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.track_file_names);
+    }
+
+    #[test]
+    fn make_request_no_file_name_long() {
+        let args = ["fzgrep", "--no-filename", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.track_file_names);
+    }
+
+    #[test]
+    fn make_request_context_short() {
+        let args = ["fzgrep", "-C", "2", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.context_size,
+            ContextSize {
+                before: Lines(2),
+                after: Lines(2),
+            }
+        );
+    }
+
+    #[test]
+    fn make_request_context_long() {
+        let args = ["fzgrep", "--context", "2", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.context_size,
+            ContextSize {
+                before: Lines(2),
+                after: Lines(2),
+            }
+        );
+    }
+
+    #[test]
+    fn make_request_context_before_short() {
+        let args = ["fzgrep", "-B", "2", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.context_size,
+            ContextSize {
+                before: Lines(2),
+                after: Lines(0),
+            }
+        );
+    }
+
+    #[test]
+    fn make_request_context_before_long() {
+        let args = ["fzgrep", "--before-context", "2", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.context_size,
+            ContextSize {
+                before: Lines(2),
+                after: Lines(0),
+            }
+        );
+    }
+
+    #[test]
+    fn make_request_context_after_short() {
+        let args = ["fzgrep", "-A", "2", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.context_size,
+            ContextSize {
+                before: Lines(0),
+                after: Lines(2),
+            }
+        );
+    }
+
+    #[test]
+    fn make_request_context_after_long() {
+        let args = ["fzgrep", "--after-context", "2", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.context_size,
+            ContextSize {
+                before: Lines(0),
+                after: Lines(2),
+            }
+        );
+    }
+
+    #[test]
+    fn make_request_context_before_after_short() {
+        let args = ["fzgrep", "-B", "1", "-A", "2", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.context_size,
+            ContextSize {
+                before: Lines(1),
+                after: Lines(2),
+            }
+        );
+    }
+
+    #[test]
+    fn make_request_context_before_after_long() {
+        let args = [
+            "fzgrep",
+            "--before-context",
+            "1",
+            "--after-context",
+            "2",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.context_size,
+            ContextSize {
+                before: Lines(1),
+                after: Lines(2),
+            }
+        );
+    }
+
+    #[test]
+    fn make_request_context_chars() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.context_chars, ContextChars::default());
+
+        let args = [
+            "fzgrep",
+            "-C",
+            "1",
+            "--context-chars",
+            "40",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.context_chars,
+            ContextChars {
+                chars_before: 40,
+                chars_after: 40,
+            }
+        );
+        assert_eq!(
+            request.match_options.context_size,
+            ContextSize {
+                before: Lines(1),
+                after: Lines(1),
+            }
+        );
+    }
+
+    #[test]
+    fn make_request_top() {
+        let args = ["fzgrep", "--top", "10", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.strategy, MatchCollectionStrategy::CollectTop(10));
+
+        let args = ["fzgrep", "--top", "0", "query", "file"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::ValueValidation
+        );
+    }
+
+    #[test]
+    fn make_request_format() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.output_template, None);
+
+        let args = [
+            "fzgrep",
+            "--format",
+            "{line}:{col}:{match}",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.output_template.as_deref(),
+            Some("{line}:{col}:{match}")
+        );
+        assert!(request.match_options.track_line_numbers);
+        assert!(request.match_options.track_column_numbers);
+        assert!(!request.match_options.track_file_names);
+
+        let args = ["fzgrep", "--format", "{file}: {match}", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.track_line_numbers);
+        assert!(request.match_options.track_file_names);
+    }
+
+    #[test]
+    fn make_request_max_results() {
+        let args = ["fzgrep", "--max-results", "10", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.strategy, MatchCollectionStrategy::CollectFirstN(10));
+
+        let args = [
+            "fzgrep",
+            "--max-results",
+            "10",
+            "--top",
+            "5",
+            "query",
+            "file",
+        ];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::ArgumentConflict
+        );
+    }
+
+    #[test]
+    fn make_request_max_time() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.max_time, None);
+
+        let args = ["fzgrep", "--max-time", "5", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.max_time, Some(Duration::from_secs(5)));
+
+        let args = ["fzgrep", "--max-time", "1.5", "query", "file"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::ValueValidation
+        );
+    }
+
+    #[test]
+    fn make_request_max_results_overrides_config_top() {
+        let mut config = NamedTempFile::new().unwrap();
+        writeln!(config, "top = 5").unwrap();
+        let config_path = config.path().to_string_lossy().into_owned();
+        let args = [
+            "fzgrep",
+            "--config",
+            &config_path,
+            "--max-results",
+            "10",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.strategy, MatchCollectionStrategy::CollectFirstN(10));
+    }
+
+    #[test]
+    fn make_request_word_regexp() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.word_boundary_check);
+
+        let args = ["fzgrep", "-w", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.word_boundary_check);
+
+        let args = ["fzgrep", "--word-regexp", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.word_boundary_check);
+        assert_eq!(request.match_options.word_chars, None);
+    }
+
+    #[test]
+    fn make_request_word_chars() {
+        let args = ["fzgrep", "-w", "--word-chars", "ab-a", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.word_chars,
+            Some(BTreeSet::from(['a', 'b', '-']))
+        );
+
+        let args = ["fzgrep", "-w", "--word-chars", "", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.word_chars, Some(BTreeSet::new()));
+    }
+
+    #[test]
+    fn word_chars_requires_word_regexp() {
+        let args = ["fzgrep", "--word-chars", "ab", "query", "file"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingRequiredArgument
+        );
+    }
+
+    #[test]
+    fn make_request_decompress() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.decompress);
+
+        let args = ["fzgrep", "-z", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.decompress);
+
+        let args = ["fzgrep", "--decompress", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.decompress);
+    }
+
+    #[test]
+    fn make_request_files_from() {
+        let mut list = NamedTempFile::new().unwrap();
+        write!(list, "first\nsecond\r\n\nthird").unwrap();
+        let list_path = list.path().to_str().unwrap();
+
+        let args = ["fzgrep", "query", "--files-from", list_path];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.targets,
+            Targets::Files(vec![
+                PathBuf::from("first"),
+                PathBuf::from("second"),
+                PathBuf::from("third")
+            ])
+        );
+        assert!(request.match_options.track_file_names);
+
+        let args = ["fzgrep", "query", "file", "-T", list_path];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.targets,
+            Targets::Files(vec![
+                PathBuf::from("file"),
+                PathBuf::from("first"),
+                PathBuf::from("second"),
+                PathBuf::from("third")
+            ])
+        );
+
+        let args = ["fzgrep", "-r", "query", "-T", list_path];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.targets,
+            Targets::RecursiveEntries(vec![
+                PathBuf::from("first"),
+                PathBuf::from("second"),
+                PathBuf::from("third")
+            ])
+        );
+    }
+
+    #[test]
+    fn make_request_files_from_null_input() {
+        let mut list = NamedTempFile::new().unwrap();
+        write!(list, "first\0with\nnewline\0").unwrap();
+        let list_path = list.path().to_str().unwrap();
+
+        let args = ["fzgrep", "query", "--null-input", "-T", list_path];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.targets,
+            Targets::Files(vec![PathBuf::from("first"), PathBuf::from("with\nnewline")])
+        );
+    }
+
+    #[test]
+    fn make_request_target_glob() {
+        let args = ["fzgrep", "query", "--target-glob", "src/**/*.rs"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.targets,
+            Targets::Glob(vec![glob::Pattern::new("src/**/*.rs").unwrap()])
+        );
+        assert!(request.match_options.track_file_names);
+
+        let args = [
+            "fzgrep",
+            "-r",
+            "query",
+            "--target-glob",
+            "*.rs",
+            "--target-glob",
+            "*.toml",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.targets,
+            Targets::Glob(vec![
+                glob::Pattern::new("*.rs").unwrap(),
+                glob::Pattern::new("*.toml").unwrap()
+            ])
+        );
+    }
+
+    #[test]
+    fn target_glob_invalid() {
+        let args = ["fzgrep", "query", "--target-glob", "[a"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::ValueValidation
+        );
+    }
+
+    #[test]
+    fn target_glob_conflicts_with_targets() {
+        let args = ["fzgrep", "query", "file", "--target-glob", "*.rs"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::ArgumentConflict
+        );
+    }
+
+    #[test]
+    fn make_request_show_score() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.score_format, None);
+
+        let args = ["fzgrep", "--show-score", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.score_format, Some(ScoreFormat::Decimal));
+
+        for (name, format) in [
+            ("decimal", ScoreFormat::Decimal),
+            ("hex", ScoreFormat::Hex),
+            ("percent", ScoreFormat::Percent),
+        ] {
+            let args = ["fzgrep", "--show-score", "--score-format", name, "query"];
+            let request = make_request(args.into_iter().map(String::from));
+            assert_eq!(request.score_format, Some(format));
+        }
+    }
+
+    #[test]
+    fn score_format_requires_show_score() {
+        let args = ["fzgrep", "--score-format", "hex", "query", "file"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingRequiredArgument
+        );
+    }
+
+    #[test]
+    fn null_input_requires_files_from() {
+        let args = ["fzgrep", "--null-input", "query", "file"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingRequiredArgument
+        );
+    }
+
+    #[test]
+    fn read_targets_newline_separated() {
+        let targets = read_targets(io::Cursor::new("a\nb c\r\n\n\nd\n"), false).unwrap();
+        assert_eq!(
+            targets,
+            [PathBuf::from("a"), PathBuf::from("b c"), PathBuf::from("d")]
+        );
+    }
+
+    #[test]
+    fn read_targets_null_separated() {
+        let targets = read_targets(io::Cursor::new("a\0b\nc\0\0"), true).unwrap();
+        assert_eq!(targets, [PathBuf::from("a"), PathBuf::from("b\nc")]);
+    }
+
+    #[test]
+    fn read_targets_invalid_utf8() {
+        let error = read_targets(io::Cursor::new(b"a\n\xff\n"), false).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn make_request_max_line_length() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.max_line_length, None);
+        assert!(!request.match_options.truncate_long_lines);
+
+        let args = ["fzgrep", "--max-line-length", "100", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.max_line_length, Some(100));
+        assert!(!request.match_options.truncate_long_lines);
+
+        let args = [
+            "fzgrep",
+            "--max-line-length",
+            "100",
+            "--truncate-long-lines",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.max_line_length, Some(100));
+        assert!(request.match_options.truncate_long_lines);
+    }
+
+    #[test]
+    fn truncate_long_lines_requires_max_line_length() {
+        let args = ["fzgrep", "--truncate-long-lines", "query", "file"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingRequiredArgument
+        );
+    }
+
+    #[test]
+    fn make_request_whole_line() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.whole_line);
+
+        let args = ["fzgrep", "-x", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.whole_line);
+
+        let args = ["fzgrep", "--whole-line", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.whole_line);
+    }
+
+    #[test]
+    fn make_request_acronym() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.match_mode, MatchMode::Fuzzy);
+
+        let args = ["fzgrep", "--acronym", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.match_mode, MatchMode::Acronym);
+    }
+
+    #[test]
+    fn make_request_fixed_string() {
+        let args = ["fzgrep", "--fixed-string", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.match_mode, MatchMode::FixedString);
+
+        let args = ["fzgrep", "--fixed-string", "--acronym", "query", "file"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::ArgumentConflict
+        );
+    }
+
+    #[test]
+    fn make_request_normalize_unicode() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.unicode_normalization);
+
+        let args = ["fzgrep", "--normalize-unicode", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.unicode_normalization);
+    }
+
+    #[test]
+    fn make_request_ignore_diacritics() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.ignore_diacritics);
+
+        let args = ["fzgrep", "--ignore-diacritics", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.ignore_diacritics);
+    }
+
+    #[test]
+    fn make_request_dereference() {
+        let args = ["fzgrep", "-r", "query", "dir"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.follow_links);
+        assert!(!request.match_options.dedup_inodes);
+
+        let args = ["fzgrep", "-r", "--dereference", "query", "dir"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.follow_links);
+        assert!(request.match_options.dedup_inodes);
+
+        let args = ["fzgrep", "-r", "--no-dereference-unique", "query", "dir"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.follow_links);
+        assert!(request.match_options.dedup_inodes);
+    }
+
+    #[test]
+    fn make_request_hidden() {
+        let args = ["fzgrep", "-r", "query", "dir"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.skip_hidden);
+
+        let args = ["fzgrep", "-r", "--no-hidden", "query", "dir"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.skip_hidden);
+
+        let args = ["fzgrep", "-r", "--no-hidden", "--hidden", "query", "dir"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.skip_hidden);
+
+        let args = ["fzgrep", "-r", "--hidden", "--no-hidden", "query", "dir"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.skip_hidden);
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn make_request_filter() {
+        let args = ["fzgrep", "-r", "query", "dir"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.filter, Filter::default());
+
+        let args = [
+            "fzgrep",
+            "-r",
+            "--include-regex",
+            r"\.rs$",
+            "--include-regex",
+            r"\.toml$",
+            "--exclude-regex",
+            "^target/",
+            "query",
+            "dir",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.filter,
+            Filter {
+                include_regexes: vec![
+                    regex::Regex::new(r"\.rs$").unwrap(),
+                    regex::Regex::new(r"\.toml$").unwrap()
+                ],
+                exclude_regexes: vec![regex::Regex::new("^target/").unwrap()],
+                ..Default::default()
+            }
+        );
+
+        let args = ["fzgrep", "-r", "--include-regex", "(", "query", "dir"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::ValueValidation
+        );
+    }
+
+    #[test]
+    fn make_request_glob_filter() {
+        let mut list = NamedTempFile::new().unwrap();
+        write!(list, "# sources\n*.rs\n\n  *.toml  \n").unwrap();
+        let list_path = list.path().to_str().unwrap();
+
+        let args = [
+            "fzgrep",
+            "-r",
+            "--include",
+            "*.md",
+            "--include-from",
+            list_path,
+            "--exclude",
+            "*/target/*",
+            "query",
+            "dir",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.filter.include_globs,
+            [
+                glob::Pattern::new("*.md").unwrap(),
+                glob::Pattern::new("*.rs").unwrap(),
+                glob::Pattern::new("*.toml").unwrap()
+            ]
+        );
+        assert_eq!(
+            request.match_options.filter.exclude_globs,
+            [glob::Pattern::new("*/target/*").unwrap()]
+        );
+
+        let args = ["fzgrep", "-r", "--exclude-from", list_path, "query", "dir"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.filter,
+            Filter {
+                exclude_globs: vec![
+                    glob::Pattern::new("*.rs").unwrap(),
+                    glob::Pattern::new("*.toml").unwrap()
+                ],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn make_request_extension_filter() {
+        let args = [
+            "fzgrep",
+            "-r",
+            "--include",
+            "*.md",
+            "--include-extension",
+            "rs",
+            "--exclude-extension",
+            ".min.js",
+            "--exclude-extension",
+            ".lock",
+            "query",
+            "dir",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.filter.include_globs,
+            [
+                glob::Pattern::new("*.md").unwrap(),
+                glob::Pattern::new("*.rs").unwrap()
+            ]
+        );
+        assert_eq!(
+            request.match_options.filter.exclude_globs,
+            [
+                glob::Pattern::new("*.min.js").unwrap(),
+                glob::Pattern::new("*.lock").unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn extension_glob_parser_escapes() {
+        assert_eq!(
+            extension_glob_parser(".txt").unwrap(),
+            glob::Pattern::new("*.txt").unwrap()
+        );
+        assert_eq!(
+            extension_glob_parser("txt").unwrap(),
+            glob::Pattern::new("*.txt").unwrap()
+        );
+        let pattern = extension_glob_parser("[ch]").unwrap();
+        assert!(pattern.matches("file.[ch]"));
+        assert!(!pattern.matches("file.c"));
+    }
+
+    #[test]
+    fn read_patterns_invalid() {
+        let error = read_patterns("*.rs\n\n[a\n".as_bytes()).unwrap_err();
+        assert!(matches!(error, PatternListError::BadPattern(3, _)));
+        assert!(error.to_string().contains("line 3"));
+    }
+
+    #[test]
+    fn include_invalid_glob() {
+        let args = ["fzgrep", "-r", "--include", "[a", "query", "dir"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::ValueValidation
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "regex"))]
+    fn make_request_filter_unsupported() {
+        let args = ["fzgrep", "-r", "--include-regex", "rs$", "query", "dir"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::ValueValidation
+        );
+    }
+
+    #[test]
+    fn dump_config_round_trip() {
+        let matches = make_command()
+            .try_get_matches_from([
+                "fzgrep",
+                "-rn",
+                "--context",
+                "2",
+                "--max-filesize",
+                "5K",
+                "-vv",
+                "--color-theme",
+                "dark",
+                "query",
+            ])
+            .unwrap();
+        let config = Config::from_matches(&matches);
+        assert_eq!(
+            config,
+            Config {
+                recursive: Some(true),
+                line_number: Some(true),
+                with_filename: Some(false),
+                no_filename: Some(false),
+                context: Some(2),
+                before_context: None,
+                after_context: None,
+                top: None,
+                max_filesize: Some(String::from("5K")),
+                quiet: Some(false),
+                verbose: Some(2),
+                color: Some(String::from("auto")),
+                color_theme: Some(String::from("dark")),
+                color_overrides: None,
+            }
+        );
+
+        let mut tmp = NamedTempFile::new().unwrap();
+        write!(tmp, "{}", toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(config::load_config(tmp.path()).unwrap(), config);
+    }
+
+    #[test]
+    fn make_request_dry_run() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.dry_run);
+
+        let args = ["fzgrep", "--dry-run", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.dry_run);
+        assert!(!request.count_lines);
+    }
+
+    #[test]
+    fn make_request_list_files() {
+        let args = ["fzgrep", "--list-files", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.dry_run);
+        assert!(request.count_lines);
+    }
+
+    #[test]
+    fn make_request_print_unmatched() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.print_unmatched);
+        assert_eq!(request.unmatched_marker, "  ");
+
+        let args = ["fzgrep", "--print-unmatched", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.print_unmatched);
+        assert_eq!(request.unmatched_marker, "  ");
+        assert_eq!(request.sort_order, SortOrder::None);
+
+        let args = [
+            "fzgrep",
+            "--print-unmatched",
+            "--unmatched-marker",
+            "| ",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.unmatched_marker, "| ");
+    }
+
+    #[test]
+    fn print_unmatched_conflicts() {
+        for conflicting in [
+            ["--sort", "line"].as_slice(),
+            &["--reverse-sort"],
+            &["--top", "3"],
+            &["--unique"],
+        ] {
+            let args = ["fzgrep", "--print-unmatched"]
+                .into_iter()
+                .chain(conflicting.iter().copied())
+                .chain(["query", "file"]);
+            assert_eq!(
+                make_command()
+                    .try_get_matches_from(args)
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::ArgumentConflict,
+                "{conflicting:?}"
+            );
+        }
+        let args = ["fzgrep", "--unmatched-marker", "| ", "query", "file"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingRequiredArgument
+        );
+    }
+
+    #[test]
+    fn make_request_acronym_bonus() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.acronym_bonus, None);
+
+        let args = ["fzgrep", "--acronym-bonus", "7", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.acronym_bonus, Some(7));
+
+        let args = ["fzgrep", "--acronym-bonus", "-1", "query", "file"];
+        assert!(make_command().try_get_matches_from(args).is_err());
+    }
+
+    #[test]
+    fn make_request_path_aware() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.path_aware);
+
+        let args = ["fzgrep", "--path-aware", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.path_aware);
+
+        let args = ["fzgrep", "--path-query", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.path_aware);
+
+        let args = ["fzgrep", "--path-aware", "--acronym", "query", "file"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::ArgumentConflict
+        );
+    }
+
+    #[test]
+    fn make_request_field_order() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.field_order, None);
+
+        let args = [
+            "fzgrep",
+            "--field-order",
+            "score,line,match",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.field_order,
+            Some(vec![
+                OutputField::Score,
+                OutputField::Line,
+                OutputField::Match
+            ])
+        );
+        // the listed fields are enabled
+        assert!(request.match_options.track_line_numbers);
+        assert!(!request.match_options.track_column_numbers);
+        assert_eq!(request.score_format, Some(ScoreFormat::Decimal));
+
+        let args = ["fzgrep", "--field-order", "line,bogus", "query", "file"];
+        assert_eq!(
+            make_command()
+                .try_get_matches_from(args)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::ValueValidation
+        );
+    }
+
+    #[test]
+    fn make_request_line_number_width() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.line_number_width, 0);
+        assert!(!request.auto_align);
+
+        let args = ["fzgrep", "--line-number-width", "5", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.line_number_width, 5);
+
+        let args = ["fzgrep", "--auto-align", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.auto_align);
+    }
+
+    #[test]
+    fn make_request_context_separator() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.context_separator.as_deref(), Some("--"));
+
+        let args = [
+            "fzgrep",
+            "--context-separator",
+            "~~ {line}",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.context_separator.as_deref(), Some("~~ {line}"));
+
+        let args = ["fzgrep", "--no-context-separator", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.context_separator, None);
+
+        // the last one wins
+        let args = [
+            "fzgrep",
+            "--no-context-separator",
+            "--context-separator",
+            "..",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.context_separator.as_deref(), Some(".."));
+    }
+
+    #[test]
+    fn make_request_show_caret() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.show_caret);
+
+        // never without the column number
+        let args = ["fzgrep", "--show-caret", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.show_caret);
+
+        let args = ["fzgrep", "--show-caret", "--column-number", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.show_caret, terminal_size::terminal_size().is_some());
+    }
+
+    #[test]
+    fn make_request_column_number() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.track_column_numbers);
+
+        let args = ["fzgrep", "-k", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.track_column_numbers);
+
+        let args = ["fzgrep", "--column-number", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.track_column_numbers);
+    }
+
+    #[test]
+    fn make_request_column_number_implies_line_number() {
+        let args = ["fzgrep", "--column-number", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.track_line_numbers);
+        assert_eq!(
+            request.notices,
+            vec![String::from("--column-number implies --line-number")]
+        );
+
+        let args = ["fzgrep", "-k", "-n", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.track_line_numbers);
+        assert!(request.notices.is_empty());
+
+        // a field order (or a template) picks the fields to print on its own
+        let args = ["fzgrep", "--field-order", "col,match", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.track_column_numbers);
+        assert!(!request.match_options.track_line_numbers);
+    }
+
+    #[test]
+    fn make_request_smart_case() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.case_sensitivity,
+            CaseSensitivity::Insensitive
+        );
+
+        let args = ["fzgrep", "-S", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.case_sensitivity,
+            CaseSensitivity::Smart
+        );
+
+        let args = ["fzgrep", "--smart-case", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.match_options.case_sensitivity,
+            CaseSensitivity::Smart
+        );
+    }
+
+    #[test]
+    fn make_request_null_data() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.match_options.null_data);
+
+        let args = ["fzgrep", "--null-data", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.null_data);
+    }
+
+    #[test]
+    fn make_request_lossy_encoding() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.lossy_encoding, LossyEncoding(false));
+
+        let args = ["fzgrep", "-U", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.lossy_encoding, LossyEncoding(true));
+
+        let args = ["fzgrep", "--lossy-encoding", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.lossy_encoding, LossyEncoding(true));
+    }
+
+    #[test]
+    fn make_request_sort() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.sort_order, SortOrder::Score);
+
+        for (key, order) in [
+            ("score", SortOrder::Score),
+            ("score-asc", SortOrder::ScoreAsc),
+            ("filename", SortOrder::FileName),
+            ("filename-desc", SortOrder::FileNameDesc),
+            ("line", SortOrder::Line),
+            ("line-desc", SortOrder::LineDesc),
+            ("none", SortOrder::None),
+        ] {
+            let args = ["fzgrep", "--sort", key, "query", "file"];
+            let request = make_request(args.into_iter().map(String::from));
+            assert_eq!(request.sort_order, order);
+        }
+    }
+
+    #[test]
+    fn make_request_reverse_sort() {
+        let args = ["fzgrep", "--reverse-sort", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.sort_order, SortOrder::ScoreAsc);
+
+        let args = [
+            "fzgrep",
+            "--sort",
+            "line",
+            "--reverse-sort",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.sort_order, SortOrder::LineDesc);
+    }
+
+    #[test]
+    fn make_request_sort_invalid_key() {
+        let args = ["fzgrep", "--sort", "size", "query", "file"];
+        assert!(make_command().try_get_matches_from(args).is_err());
+    }
+
+    #[test]
+    fn make_request_relative_paths() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.relative_paths_root, None);
+
+        let args = ["fzgrep", "--relative-paths", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.query, "query");
+        assert_eq!(
+            request.relative_paths_root,
+            Some(env::current_dir().unwrap())
+        );
+
+        let args = ["fzgrep", "--relative-paths=some/dir", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.relative_paths_root, Some(PathBuf::from("some/dir")));
+    }
+
+    #[test]
+    fn make_request_report_errors_as_matches() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.report_errors_as_matches);
+
+        let args = ["fzgrep", "--report-errors-as-matches", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.report_errors_as_matches);
+        assert!(!request.suppress_errors);
+    }
+
+    #[test]
+    fn make_request_no_messages() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.suppress_errors);
+
+        let args = ["fzgrep", "-s", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.suppress_errors);
+        assert_ne!(request.output_behavior, OutputBehavior::Quiet);
+
+        let args = ["fzgrep", "--no-messages", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.suppress_errors);
+    }
+
+    #[test]
+    fn make_request_line_buffered() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.line_buffered);
+
+        let args = ["fzgrep", "--line-buffered", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.line_buffered);
+    }
+
+    #[test]
+    fn make_request_zero_terminated() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.zero_terminated);
+
+        let args = ["fzgrep", "-0", "--pager", "more", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.zero_terminated);
+        assert_eq!(request.pager, None);
+
+        let args = ["fzgrep", "--zero-terminated", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.zero_terminated);
+    }
+
+    #[test]
+    fn make_request_no_pager() {
+        let args = ["fzgrep", "--no-pager", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.pager, None);
+
+        let args = ["fzgrep", "--pager", "more", "--no-pager", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.pager, None);
+    }
+
+    #[test]
+    fn make_request_pager_not_to_terminal() {
+        let args = ["fzgrep", "--pager", "more", "-o", "out", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.pager, None);
+
+        let args = ["fzgrep", "--pager", "more", "--line-buffered", "query"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.pager, None);
+    }
+
+    #[test]
+    fn make_request_pager() {
+        let args = ["fzgrep", "--pager", "more", "query", "file"];
+        let matches = match_command_line(args.into_iter().map(String::from), None, None);
+        assert_eq!(pager_from(&matches, true).as_deref(), Some("more"));
+        assert_eq!(pager_from(&matches, false), None);
+    }
+
+    #[test]
+    fn make_request_pager_with_arguments() {
+        let args = ["fzgrep", "--pager", "less -SR", "query", "file"];
+        let matches = match_command_line(args.into_iter().map(String::from), None, None);
+        assert_eq!(pager_from(&matches, true).as_deref(), Some("less -SR"));
+    }
+
+    #[test]
+    fn make_request_pager_overridden() {
+        let args = ["fzgrep", "--no-pager", "--pager", "more", "query", "file"];
+        let matches = match_command_line(args.into_iter().map(String::from), None, None);
+        assert_eq!(pager_from(&matches, true).as_deref(), Some("more"));
+
+        let args = ["fzgrep", "--pager", "more", "-o", "out", "query", "file"];
+        let matches = match_command_line(args.into_iter().map(String::from), None, None);
+        assert_eq!(pager_from(&matches, true), None);
+    }
+
+    #[test]
+    fn make_request_output() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.output_file, None);
+
+        let args = ["fzgrep", "-o", "out.txt", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.output_file, Some(PathBuf::from("out.txt")));
+        assert_eq!(
+            request.output_behavior,
+            OutputBehavior::Normal(Formatting::Off)
+        );
+
+        let args = [
+            "fzgrep", "--output", "out.txt", "--color", "always", "query", "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.output_file, Some(PathBuf::from("out.txt")));
+        assert_eq!(
+            request.output_behavior,
+            OutputBehavior::Normal(Formatting::On(FormattingOptions::default()))
+        );
+    }
+
+    #[test]
+    fn make_request_field_separator() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.field_separator, ":");
+
+        let args = ["fzgrep", "--field-separator", "|", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.field_separator, "|");
+
+        let args = ["fzgrep", "--output-format", "tsv", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.field_separator, "\t");
     }
 
     #[test]
-    fn make_request_non_ascii_emoji() {
-        let args = ["fzgrep", "🐣🦀", "file1", "👨‍🔬.txt", "file3"];
+    fn make_request_print_match_positions() {
+        let args = ["fzgrep", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.print_match_positions);
 
-        assert_eq!(request.query, "🐣🦀");
-        assert_eq!(
-            request.targets,
-            Targets::Files(vec![
-                PathBuf::from("file1"),
-                PathBuf::from("👨‍🔬.txt"),
-                PathBuf::from("file3")
-            ])
-        );
+        let args = ["fzgrep", "--print-match-positions", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.print_match_positions);
     }
 
     #[test]
-    fn make_request_non_ascii_cyrillic() {
-        let args = ["fzgrep", "тест", "file1", "тест.txt", "file3"];
+    fn make_request_print_context_count() {
+        let args = ["fzgrep", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
+        assert!(!request.print_context_count);
 
-        assert_eq!(request.query, "тест");
-        assert_eq!(
-            request.targets,
-            Targets::Files(vec![
-                PathBuf::from("file1"),
-                PathBuf::from("тест.txt"),
-                PathBuf::from("file3")
-            ])
-        );
+        let args = ["fzgrep", "--print-context-count", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.print_context_count);
     }
 
     #[test]
-    fn make_request_non_ascii_chinese() {
-        let args = ["fzgrep", "打电", "file1", "测试.txt", "file3"];
+    fn make_request_output_format() {
+        let args = ["fzgrep", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.output_format, OutputFormat::Text);
 
-        assert_eq!(request.query, "打电");
-        assert_eq!(
-            request.targets,
-            Targets::Files(vec![
-                PathBuf::from("file1"),
-                PathBuf::from("测试.txt"),
-                PathBuf::from("file3")
-            ])
-        );
+        let args = ["fzgrep", "--output-format", "tsv", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.output_format, OutputFormat::Text);
     }
 
+    #[cfg(feature = "serde")]
     #[test]
-    fn make_request_recursive_short() {
-        let args = ["fzgrep", "-r", "query", "dir"];
+    fn make_request_output_format_ndjson() {
+        let args = ["fzgrep", "--output-format", "ndjson", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
-        assert_eq!(
-            request.targets,
-            Targets::RecursiveEntries(vec![PathBuf::from("dir")])
-        );
+        assert_eq!(request.output_format, OutputFormat::Ndjson);
+        assert_eq!(request.field_separator, ":");
     }
 
     #[test]
-    fn make_request_recursive_long() {
-        let args = ["fzgrep", "--recursive", "query", "dir"];
-        let request = make_request(args.into_iter().map(String::from));
+    fn make_request_field_separator_conflicts_with_output_format() {
+        let args = [
+            "fzgrep",
+            "--field-separator",
+            "|",
+            "--output-format",
+            "tsv",
+            "query",
+            "file",
+        ];
+        let command = make_command();
         assert_eq!(
-            request.targets,
-            Targets::RecursiveEntries(vec![PathBuf::from("dir")])
+            command.try_get_matches_from(args).unwrap_err().kind(),
+            ErrorKind::ArgumentConflict
         );
     }
 
     #[test]
-    fn make_request_with_file_name_short() {
-        let args = ["fzgrep", "-f", "query", "file"];
+    fn make_request_stats() {
+        let args = ["fzgrep", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
-        assert!(request.match_options.track_file_names);
-    }
+        assert!(!request.print_stats);
 
-    #[test]
-    fn make_request_with_file_name_long() {
-        let args = ["fzgrep", "--with-filename", "query", "file"];
+        let args = ["fzgrep", "--stats", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
-        assert!(request.match_options.track_file_names);
-    }
+        assert!(request.print_stats);
 
-    #[test]
-    fn make_request_no_file_name_short() {
-        let args = ["fzgrep", "-F", "query", "file"];
+        let args = ["fzgrep", "--stats", "--quiet", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
-        assert!(!request.match_options.track_file_names);
+        assert!(!request.print_stats);
     }
 
     #[test]
-    fn make_request_no_file_name_long() {
-        let args = ["fzgrep", "--no-filename", "query", "file"];
+    fn make_request_progress() {
+        let args = ["fzgrep", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
-        assert!(!request.match_options.track_file_names);
+        assert!(!request.show_progress);
+
+        let args = ["fzgrep", "--progress", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.show_progress, atty::is(Stream::Stderr));
     }
 
     #[test]
-    fn make_request_context_short() {
-        let args = ["fzgrep", "-C", "2", "query", "file"];
+    fn make_request_scoring_config() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.scoring, ScoringConfig::default());
+
+        let args = [
+            "fzgrep",
+            "--scoring-config",
+            r#"{"consecutive_match": 3, "camel_case": 0}"#,
+            "query",
+            "file",
+        ];
         let request = make_request(args.into_iter().map(String::from));
         assert_eq!(
-            request.match_options.context_size,
-            ContextSize {
-                before: Lines(2),
-                after: Lines(2),
+            request.match_options.scoring,
+            ScoringConfig {
+                consecutive_match: 3,
+                camel_case: 0,
+                ..Default::default()
             }
         );
     }
 
     #[test]
-    fn make_request_context_long() {
-        let args = ["fzgrep", "--context", "2", "query", "file"];
-        let request = make_request(args.into_iter().map(String::from));
-        assert_eq!(
-            request.match_options.context_size,
-            ContextSize {
-                before: Lines(2),
-                after: Lines(2),
-            }
-        );
+    fn scoring_config_parser_errors() {
+        assert!(scoring_config_parser("not json").is_err());
+        assert!(scoring_config_parser(r#"{"no_such_weight": 1}"#).is_err());
+        assert!(scoring_config_parser(r#"{"regular": "one"}"#).is_err());
     }
 
     #[test]
-    fn make_request_context_before_short() {
-        let args = ["fzgrep", "-B", "2", "query", "file"];
+    fn make_request_max_filesize() {
+        let args = ["fzgrep", "--max-filesize", "100", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
-        assert_eq!(
-            request.match_options.context_size,
-            ContextSize {
-                before: Lines(2),
-                after: Lines(0),
-            }
-        );
+        assert_eq!(request.match_options.skip_bytes, Some(100));
     }
 
     #[test]
-    fn make_request_context_before_long() {
-        let args = ["fzgrep", "--before-context", "2", "query", "file"];
+    fn make_request_max_filesize_suffixes() {
+        let args = ["fzgrep", "--max-filesize", "2K", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.skip_bytes, Some(2 * 1024));
+
+        let args = ["fzgrep", "--max-filesize", "3M", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.skip_bytes, Some(3 * 1024 * 1024));
+
+        let args = ["fzgrep", "--max-filesize", "4G", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
         assert_eq!(
-            request.match_options.context_size,
-            ContextSize {
-                before: Lines(2),
-                after: Lines(0),
-            }
+            request.match_options.skip_bytes,
+            Some(4 * 1024 * 1024 * 1024)
         );
     }
 
     #[test]
-    fn make_request_context_after_short() {
-        let args = ["fzgrep", "-A", "2", "query", "file"];
+    fn make_request_encoding() {
+        let args = ["fzgrep", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.encoding, None);
+
+        let args = ["fzgrep", "--encoding", "Shift_JIS", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.match_options.encoding, Some(encoding_rs::SHIFT_JIS));
+    }
+
+    #[test]
+    fn encoding_parser_labels() {
+        assert_eq!(encoding_parser("utf-8").unwrap(), encoding_rs::UTF_8);
+        assert_eq!(encoding_parser("UTF-16LE").unwrap(), encoding_rs::UTF_16LE);
         assert_eq!(
-            request.match_options.context_size,
-            ContextSize {
-                before: Lines(0),
-                after: Lines(2),
-            }
+            encoding_parser("latin1").unwrap(),
+            encoding_rs::WINDOWS_1252
         );
+        assert_eq!(encoding_parser(" koi8-r ").unwrap(), encoding_rs::KOI8_R);
+        assert!(matches!(
+            encoding_parser("utf-9"),
+            Err(UnknownEncodingError(name)) if name == "utf-9"
+        ));
+        assert!(encoding_parser("iso-2022-kr").is_err());
     }
 
     #[test]
-    fn make_request_context_after_long() {
-        let args = ["fzgrep", "--after-context", "2", "query", "file"];
-        let request = make_request(args.into_iter().map(String::from));
+    fn field_order_parser_valid() {
         assert_eq!(
-            request.match_options.context_size,
-            ContextSize {
-                before: Lines(0),
-                after: Lines(2),
-            }
+            field_order_parser("line,match").unwrap(),
+            [OutputField::Line, OutputField::Match]
+        );
+        assert_eq!(
+            field_order_parser("match, score,col,file,line").unwrap(),
+            [
+                OutputField::Match,
+                OutputField::Score,
+                OutputField::Column,
+                OutputField::File,
+                OutputField::Line
+            ]
         );
     }
 
     #[test]
-    fn make_request_context_before_after_short() {
-        let args = ["fzgrep", "-B", "1", "-A", "2", "query", "file"];
+    fn field_order_parser_errors() {
+        assert!(matches!(
+            field_order_parser("line,column"),
+            Err(FieldOrderParsingError::UnknownField(name)) if name == "column"
+        ));
+        assert!(matches!(
+            field_order_parser("line,"),
+            Err(FieldOrderParsingError::UnknownField(name)) if name.is_empty()
+        ));
+        assert!(matches!(
+            field_order_parser("line,match,line"),
+            Err(FieldOrderParsingError::RepeatedField(name)) if name == "line"
+        ));
+    }
+
+    #[test]
+    fn file_size_parser_errors() {
+        assert!(matches!(
+            file_size_parser("abc"),
+            Err(FileSizeParsingError::NotANumber(_, _))
+        ));
+        assert!(matches!(
+            file_size_parser("10X"),
+            Err(FileSizeParsingError::NotANumber(_, _))
+        ));
+        assert!(matches!(
+            file_size_parser("18446744073709551615G"),
+            Err(FileSizeParsingError::TooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn make_request_highlight_query() {
+        let args = ["fzgrep", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
-        assert_eq!(
-            request.match_options.context_size,
-            ContextSize {
-                before: Lines(1),
-                after: Lines(2),
-            }
-        );
+        assert!(!request.highlight_query);
+
+        let args = ["fzgrep", "--highlight-query", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.highlight_query);
     }
 
     #[test]
-    fn make_request_context_before_after_long() {
-        let args = [
-            "fzgrep",
-            "--before-context",
-            "1",
-            "--after-context",
-            "2",
-            "query",
-            "file",
-        ];
+    fn make_request_unique() {
+        let args = ["fzgrep", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
-        assert_eq!(
-            request.match_options.context_size,
-            ContextSize {
-                before: Lines(1),
-                after: Lines(2),
-            }
-        );
+        assert!(!request.unique);
+
+        let args = ["fzgrep", "--unique", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.unique);
+
+        let args = ["fzgrep", "-u", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.unique);
     }
 
     #[test]
-    fn make_request_top() {
-        let args = ["fzgrep", "--top", "10", "query", "file"];
+    fn make_request_byte_range() {
+        let args = ["fzgrep", "--byte-range", "100-2000", "query", "file"];
         let request = make_request(args.into_iter().map(String::from));
-        assert_eq!(request.strategy, MatchCollectionStrategy::CollectTop(10));
+        assert_eq!(request.match_options.byte_range, Some(100..2000));
+    }
+
+    #[test]
+    fn byte_range_parser_errors() {
+        assert!(matches!(
+            byte_range_parser("100"),
+            Err(ByteRangeParsingError::NotARange(_))
+        ));
+        assert!(matches!(
+            byte_range_parser("a-100"),
+            Err(ByteRangeParsingError::NotANumber(_, _))
+        ));
+        assert!(matches!(
+            byte_range_parser("100-"),
+            Err(ByteRangeParsingError::NotANumber(_, _))
+        ));
+        assert!(matches!(
+            byte_range_parser("200-100"),
+            Err(ByteRangeParsingError::Reversed(_))
+        ));
     }
 
     #[test]
@@ -929,6 +4296,34 @@ mod tests {
         assert_eq!(request.log_verbosity, LevelFilter::Off);
     }
 
+    #[test]
+    fn make_request_only_filenames_with_score() {
+        let args = [
+            "fzgrep",
+            "--color",
+            "never",
+            "--only-filenames-with-score",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.output_behavior,
+            OutputBehavior::FileScores(Formatting::Off)
+        );
+        assert!(request.match_options.track_file_names);
+
+        let args = [
+            "fzgrep",
+            "--quiet",
+            "--only-filenames-with-score",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.output_behavior, OutputBehavior::Quiet);
+    }
+
     #[test]
     fn make_request_verbose_short() {
         let args = ["fzgrep", "-v", "query", "file"];
@@ -1048,41 +4443,113 @@ mod tests {
     }
 
     #[test]
-    fn make_request_color_never() {
-        let args = ["fzgrep", "--color", "never", "query", "file"];
+    fn make_request_color_never() {
+        let args = ["fzgrep", "--color", "never", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.output_behavior,
+            OutputBehavior::Normal(Formatting::Off)
+        );
+    }
+
+    #[test]
+    fn make_request_color_never_with_color_overrides() {
+        let args = [
+            "fzgrep",
+            "--color",
+            "never",
+            "--color-overrides",
+            "ms=1;33",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.output_behavior,
+            OutputBehavior::Normal(Formatting::Off)
+        );
+    }
+
+    #[test]
+    fn make_request_color_overrides_selected_match() {
+        let args = [
+            "fzgrep",
+            "--color",
+            "always",
+            "--color-overrides",
+            "ms=1;32;43",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request
+                .output_behavior
+                .formatting()
+                .unwrap()
+                .options()
+                .unwrap()
+                .selected_match,
+            Style::new().green().on_yellow().bold(),
+        );
+    }
+
+    #[test]
+    fn make_request_color_overrides_line_number() {
+        let args = [
+            "fzgrep",
+            "--color",
+            "always",
+            "--color-overrides",
+            "ln=1;32;43",
+            "query",
+            "file",
+        ];
         let request = make_request(args.into_iter().map(String::from));
         assert_eq!(
-            request.output_behavior,
-            OutputBehavior::Normal(Formatting::Off)
+            request
+                .output_behavior
+                .formatting()
+                .unwrap()
+                .options()
+                .unwrap()
+                .line_number,
+            Style::new().green().on_yellow().bold(),
         );
     }
 
     #[test]
-    fn make_request_color_never_with_color_overrides() {
+    fn make_request_color_overrides_context_count() {
         let args = [
             "fzgrep",
             "--color",
-            "never",
+            "always",
             "--color-overrides",
-            "ms=1;33",
+            "ck=1;33",
             "query",
             "file",
         ];
         let request = make_request(args.into_iter().map(String::from));
         assert_eq!(
-            request.output_behavior,
-            OutputBehavior::Normal(Formatting::Off)
+            request
+                .output_behavior
+                .formatting()
+                .unwrap()
+                .options()
+                .unwrap()
+                .context_count,
+            Style::new().yellow().bold(),
         );
     }
 
     #[test]
-    fn make_request_color_overrides_selected_match() {
+    fn make_request_color_overrides_context_match() {
         let args = [
             "fzgrep",
             "--color",
             "always",
             "--color-overrides",
-            "ms=1;32;43",
+            "cq=2;32",
             "query",
             "file",
         ];
@@ -1094,19 +4561,19 @@ mod tests {
                 .unwrap()
                 .options()
                 .unwrap()
-                .selected_match,
-            Style::new().green().on_yellow().bold(),
+                .context_match,
+            Style::new().green().dim(),
         );
     }
 
     #[test]
-    fn make_request_color_overrides_line_number() {
+    fn make_request_color_overrides_score() {
         let args = [
             "fzgrep",
             "--color",
             "always",
             "--color-overrides",
-            "ln=1;32;43",
+            "sc=4;35",
             "query",
             "file",
         ];
@@ -1118,8 +4585,8 @@ mod tests {
                 .unwrap()
                 .options()
                 .unwrap()
-                .line_number,
-            Style::new().green().on_yellow().bold(),
+                .score,
+            Style::new().magenta().underline(),
         );
     }
 
@@ -1207,18 +4674,192 @@ mod tests {
             "file",
         ];
         let request = make_request(args.into_iter().map(String::from));
+        let options = request
+            .output_behavior
+            .formatting()
+            .unwrap()
+            .options()
+            .unwrap();
         assert_eq!(
-            request
-                .output_behavior
-                .formatting()
-                .unwrap()
-                .options()
-                .unwrap()
-                .context,
+            options.before_context,
+            Style::new().green().on_yellow().bold(),
+        );
+        assert_eq!(
+            options.after_context,
+            Style::new().green().on_yellow().bold(),
+        );
+    }
+
+    #[test]
+    fn make_request_color_overrides_before_context() {
+        let args = [
+            "fzgrep",
+            "--color",
+            "always",
+            "--color-overrides",
+            "bc=1;32;43",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        let options = request
+            .output_behavior
+            .formatting()
+            .unwrap()
+            .options()
+            .unwrap();
+        assert_eq!(
+            options.before_context,
+            Style::new().green().on_yellow().bold(),
+        );
+        assert_eq!(options.after_context, Style::new());
+    }
+
+    #[test]
+    fn make_request_color_overrides_after_context() {
+        let args = [
+            "fzgrep",
+            "--color",
+            "always",
+            "--color-overrides",
+            "cx=2:ac=1;32;43",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        let options = request
+            .output_behavior
+            .formatting()
+            .unwrap()
+            .options()
+            .unwrap();
+        assert_eq!(options.before_context, Style::new().dim());
+        assert_eq!(
+            options.after_context,
             Style::new().green().on_yellow().bold(),
         );
     }
 
+    #[test]
+    fn make_request_color_theme() {
+        let args = [
+            "fzgrep",
+            "--color",
+            "always",
+            "--color-theme",
+            "solarized-dark",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.output_behavior,
+            OutputBehavior::Normal(Formatting::On(FormattingOptions::from(
+                Theme::SolarizedDark
+            )))
+        );
+    }
+
+    #[test]
+    fn make_request_color_theme_with_color_overrides() {
+        let args = [
+            "fzgrep",
+            "--color",
+            "always",
+            "--color-overrides",
+            "ms=1;32;43",
+            "--color-theme",
+            "dark",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.output_behavior,
+            OutputBehavior::Normal(Formatting::On(FormattingOptions {
+                selected_match: Style::new().green().on_yellow().bold(),
+                ..FormattingOptions::from(Theme::Dark)
+            }))
+        );
+    }
+
+    #[test]
+    fn make_request_hyperlinks() {
+        let args = [
+            "fzgrep",
+            "--color",
+            "always",
+            "--hyperlinks",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.output_behavior,
+            OutputBehavior::Normal(Formatting::On(FormattingOptions {
+                hyperlinks: true,
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn make_request_color_reset() {
+        let args = [
+            "fzgrep",
+            "--color",
+            "always",
+            "--color-reset",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.output_behavior,
+            OutputBehavior::Normal(Formatting::On(FormattingOptions {
+                color_reset: true,
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn make_request_gradient_colors() {
+        let args = [
+            "fzgrep",
+            "--color",
+            "always",
+            "--gradient-colors",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.output_behavior,
+            OutputBehavior::Normal(Formatting::On(FormattingOptions {
+                gradient: Some(ColorGradient::default()),
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn make_request_hyperlinks_color_never() {
+        let args = [
+            "fzgrep",
+            "--color",
+            "never",
+            "--hyperlinks",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(
+            request.output_behavior,
+            OutputBehavior::Normal(Formatting::Off)
+        );
+    }
+
     #[test]
     fn make_request_color_overrides_multiple_capabilities() {
         let args = [
@@ -1257,7 +4898,14 @@ mod tests {
             OutputBehavior::Normal(Formatting::On(FormattingOptions {
                 selected_match: Style::new().blue().on_yellow().bold(),
                 selected_line: Style::new().white().dim(),
-                context: Style::new().white().dim(),
+                before_context: Style::new().white().dim(),
+                after_context: Style::new().white().dim(),
+                context_count: Style::new().green(),
+                score: Style::new().yellow(),
+                context_match: Style::new().red(),
+                hyperlinks: false,
+                color_reset: false,
+                gradient: None,
                 file_name: Style::new().fixed(51).underline(),
                 line_number: Style::new().rgb(127, 127, 127).italic().underline(),
                 separator: Style::new().magenta().on_rgb(0, 192, 0)
@@ -1265,9 +4913,140 @@ mod tests {
         );
     }
 
+    #[test]
+    fn make_request_config() {
+        let mut config = NamedTempFile::new().unwrap();
+        write!(
+            config,
+            "line-number = true\n\
+            context = 2\n\
+            top = 5\n"
+        )
+        .unwrap();
+        let config_path = config.path().to_string_lossy().into_owned();
+        let args = ["fzgrep", "--config", &config_path, "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert!(request.match_options.track_line_numbers);
+        assert_eq!(
+            request.match_options.context_size,
+            ContextSize {
+                before: Lines(2),
+                after: Lines(2),
+            }
+        );
+        assert_eq!(request.strategy, MatchCollectionStrategy::CollectTop(5));
+    }
+
+    #[test]
+    fn make_request_config_overridden_by_command_line() {
+        let mut config = NamedTempFile::new().unwrap();
+        write!(
+            config,
+            "with-filename = true\n\
+            context = 2\n\
+            top = 5\n\
+            quiet = true\n"
+        )
+        .unwrap();
+        let config_path = config.path().to_string_lossy().into_owned();
+        let args = [
+            "fzgrep",
+            "--config",
+            &config_path,
+            "--top",
+            "10",
+            "--no-filename",
+            "--before-context",
+            "1",
+            "--verbose",
+            "query",
+            "file",
+        ];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.strategy, MatchCollectionStrategy::CollectTop(10));
+        assert!(!request.match_options.track_file_names);
+        assert_eq!(
+            request.match_options.context_size,
+            ContextSize {
+                before: Lines(1),
+                after: Lines(0),
+            }
+        );
+        assert_ne!(request.output_behavior, OutputBehavior::Quiet);
+        assert_eq!(request.log_verbosity, LevelFilter::Warn);
+    }
+
+    #[test]
+    fn make_request_no_config() {
+        let args = ["fzgrep", "--no-config", "query", "file"];
+        let request = make_request(args.into_iter().map(String::from));
+        assert_eq!(request.strategy, MatchCollectionStrategy::CollectAll);
+    }
+
+    #[test]
+    fn make_request_with_default_config() {
+        let mut config = NamedTempFile::new().unwrap();
+        writeln!(config, "top = 5").unwrap();
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request_with(
+            args.into_iter().map(String::from),
+            Some(config.path()),
+            None,
+        );
+        assert_eq!(request.strategy, MatchCollectionStrategy::CollectTop(5));
+    }
+
+    #[test]
+    fn make_request_with_default_config_disabled() {
+        let mut config = NamedTempFile::new().unwrap();
+        writeln!(config, "top = 5").unwrap();
+        let args = ["fzgrep", "--no-config", "query", "file"];
+        let request = make_request_with(
+            args.into_iter().map(String::from),
+            Some(config.path()),
+            None,
+        );
+        assert_eq!(request.strategy, MatchCollectionStrategy::CollectAll);
+    }
+
+    #[test]
+    fn make_request_with_default_config_overridden() {
+        let mut default = NamedTempFile::new().unwrap();
+        writeln!(default, "top = 5").unwrap();
+        let mut config = NamedTempFile::new().unwrap();
+        writeln!(config, "top = 7").unwrap();
+        let config_path = config.path().to_string_lossy().into_owned();
+        let args = ["fzgrep", "--config", &config_path, "query", "file"];
+        let request = make_request_with(
+            args.into_iter().map(String::from),
+            Some(default.path()),
+            None,
+        );
+        assert_eq!(request.strategy, MatchCollectionStrategy::CollectTop(7));
+    }
+
+    #[test]
+    fn make_request_with_missing_default_config() {
+        let args = ["fzgrep", "query", "file"];
+        let request = make_request_with(
+            args.into_iter().map(String::from),
+            Some(Path::new("no/such/config.toml")),
+            None,
+        );
+        assert_eq!(request.strategy, MatchCollectionStrategy::CollectAll);
+    }
+
     #[test]
     fn make_request_all_options_short() {
-        let args = ["fzgrep", "-rnfv", "-B1", "-A2", "query", "file"];
+        let args = [
+            "fzgrep",
+            "-rnfv",
+            "-B1",
+            "-A2",
+            "--no-pager",
+            "query",
+            "file",
+        ];
         let request = make_request(args.into_iter().map(String::from));
         assert_eq!(
             request,
@@ -1275,6 +5054,7 @@ mod tests {
                 query: String::from("query"),
                 targets: Targets::RecursiveEntries(vec![PathBuf::from("file")]),
                 strategy: MatchCollectionStrategy::CollectAll,
+                max_time: None,
                 output_behavior: OutputBehavior::Normal(if atty::is(Stream::Stdout) {
                     Formatting::On(FormattingOptions::default())
                 } else {
@@ -1282,13 +5062,71 @@ mod tests {
                 }),
                 match_options: MatchOptions {
                     track_line_numbers: true,
+                    track_column_numbers: false,
                     track_file_names: true,
                     context_size: ContextSize {
                         before: Lines(1),
                         after: Lines(2)
                     },
+                    context_chars: ContextChars {
+                        chars_before: 0,
+                        chars_after: 0,
+                    },
+                    skip_bytes: None,
+                    byte_range: None,
+                    decompress: false,
+                    search_zip: false,
+                    follow_links: false,
+                    dedup_inodes: false,
+                    filter: Filter::default(),
+                    skip_hidden: false,
+                    word_boundary_check: false,
+                    word_chars: None,
+                    whole_line: false,
+                    case_sensitivity: CaseSensitivity::Insensitive,
+                    match_mode: MatchMode::Fuzzy,
+                    path_aware: false,
+                    acronym_bonus: None,
+                    print_unmatched: false,
+                    unicode_normalization: false,
+                    ignore_diacritics: false,
+                    scoring: ScoringConfig::default(),
+                    lossy_encoding: LossyEncoding(false),
+                    encoding: None,
+                    sources: SourceRegistry::default(),
+                    null_data: false,
+                    max_line_length: None,
+                    truncate_long_lines: false,
                 },
+                sort_order: SortOrder::Score,
+                unique: false,
+                relative_paths_root: None,
+                dry_run: false,
+                count_lines: false,
+                field_separator: String::from(":"),
+                output_format: OutputFormat::Text,
+                print_context_count: false,
+                print_match_positions: false,
+                score_format: None,
+                show_caret: false,
+                print_file_headers: false,
+                unmatched_marker: String::from("  "),
+                highlight_query: false,
+                field_order: None,
+                line_number_width: 0,
+                auto_align: false,
+                context_separator: Some(String::from("--")),
+                output_template: None,
+                output_file: None,
+                line_buffered: false,
+                zero_terminated: false,
+                pager: None,
                 log_verbosity: LevelFilter::Warn,
+                print_stats: false,
+                show_progress: false,
+                suppress_errors: false,
+                report_errors_as_matches: false,
+                notices: Vec::new(),
             }
         );
     }
@@ -1311,6 +5149,7 @@ mod tests {
             "always",
             "--color-overrides",
             "ms=05;34",
+            "--no-pager",
             "query",
             "file",
         ];
@@ -1321,19 +5160,78 @@ mod tests {
                 query: String::from("query"),
                 targets: Targets::RecursiveEntries(vec![PathBuf::from("file")]),
                 strategy: MatchCollectionStrategy::CollectTop(10),
+                max_time: None,
                 output_behavior: OutputBehavior::Normal(Formatting::On(FormattingOptions {
                     selected_match: Style::new().blue().blink(),
                     ..Default::default()
                 })),
                 match_options: MatchOptions {
                     track_line_numbers: true,
+                    track_column_numbers: false,
                     track_file_names: true,
                     context_size: ContextSize {
                         before: Lines(1),
                         after: Lines(2)
                     },
+                    context_chars: ContextChars {
+                        chars_before: 0,
+                        chars_after: 0,
+                    },
+                    skip_bytes: None,
+                    byte_range: None,
+                    decompress: false,
+                    search_zip: false,
+                    follow_links: false,
+                    dedup_inodes: false,
+                    filter: Filter::default(),
+                    skip_hidden: false,
+                    word_boundary_check: false,
+                    word_chars: None,
+                    whole_line: false,
+                    case_sensitivity: CaseSensitivity::Insensitive,
+                    match_mode: MatchMode::Fuzzy,
+                    path_aware: false,
+                    acronym_bonus: None,
+                    print_unmatched: false,
+                    unicode_normalization: false,
+                    ignore_diacritics: false,
+                    scoring: ScoringConfig::default(),
+                    lossy_encoding: LossyEncoding(false),
+                    encoding: None,
+                    sources: SourceRegistry::default(),
+                    null_data: false,
+                    max_line_length: None,
+                    truncate_long_lines: false,
                 },
+                sort_order: SortOrder::Score,
+                unique: false,
+                relative_paths_root: None,
+                dry_run: false,
+                count_lines: false,
+                field_separator: String::from(":"),
+                output_format: OutputFormat::Text,
+                print_context_count: false,
+                print_match_positions: false,
+                score_format: None,
+                show_caret: false,
+                print_file_headers: false,
+                unmatched_marker: String::from("  "),
+                highlight_query: false,
+                field_order: None,
+                line_number_width: 0,
+                auto_align: false,
+                context_separator: Some(String::from("--")),
+                output_template: None,
+                output_file: None,
+                line_buffered: false,
+                zero_terminated: false,
+                pager: None,
                 log_verbosity: LevelFilter::Warn,
+                print_stats: false,
+                show_progress: false,
+                suppress_errors: false,
+                report_errors_as_matches: false,
+                notices: Vec::new(),
             }
         );
     }
@@ -0,0 +1,244 @@
+use crate::cli::error::ConfigError;
+use clap::{ArgMatches, Command};
+use serde::{Deserialize, Serialize};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// Default values for the command line options read from a config file.
+///
+/// The keys are the long names of the corresponding command line options, e.g.
+/// ```toml
+/// line-number = true
+/// context = 2
+/// max-filesize = "10M"
+/// color-overrides = "ms=01;34:fn=35"
+/// ```
+///
+/// Options given on the command line always take precedence over the config file.
+///
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct Config {
+    /// Default for `--recursive`.
+    ///
+    pub(crate) recursive: Option<bool>,
+
+    /// Default for `--line-number`.
+    ///
+    pub(crate) line_number: Option<bool>,
+
+    /// Default for `--with-filename`.
+    ///
+    pub(crate) with_filename: Option<bool>,
+
+    /// Default for `--no-filename`.
+    ///
+    pub(crate) no_filename: Option<bool>,
+
+    /// Default for `--context`.
+    ///
+    pub(crate) context: Option<usize>,
+
+    /// Default for `--before-context`.
+    ///
+    pub(crate) before_context: Option<usize>,
+
+    /// Default for `--after-context`.
+    ///
+    pub(crate) after_context: Option<usize>,
+
+    /// Default for `--top`.
+    ///
+    pub(crate) top: Option<usize>,
+
+    /// Default for `--max-filesize` (suffixes are supported the same way as on the command line).
+    ///
+    pub(crate) max_filesize: Option<String>,
+
+    /// Default for `--quiet`.
+    ///
+    pub(crate) quiet: Option<bool>,
+
+    /// Default verbosity level (the number of times `--verbose` is specified).
+    ///
+    pub(crate) verbose: Option<u8>,
+
+    /// Default for `--color`.
+    ///
+    pub(crate) color: Option<String>,
+
+    /// Default for `--color-theme`.
+    ///
+    pub(crate) color_theme: Option<String>,
+
+    /// Default for `--color-overrides`.
+    ///
+    pub(crate) color_overrides: Option<String>,
+}
+
+impl Config {
+    /// Installs the values from the config as default values of the corresponding arguments of `command`.
+    /// This way the values specified on the command line naturally override the ones from the config.
+    ///
+    pub(crate) fn apply(&self, command: Command) -> Command {
+        let command = with_default(command, "recursive", self.recursive);
+        let command = with_default(command, "line_number", self.line_number);
+        let command = with_default(command, "with_filename", self.with_filename);
+        let command = with_default(command, "no_filename", self.no_filename);
+        let command = with_default(command, "context", self.context);
+        let command = with_default(command, "before_context", self.before_context);
+        let command = with_default(command, "after_context", self.after_context);
+        let command = with_default(command, "top", self.top);
+        let command = with_default(command, "max_filesize", self.max_filesize.as_ref());
+        let command = with_default(command, "quiet", self.quiet);
+        let command = with_default(command, "verbose", self.verbose);
+        let command = with_default(command, "color", self.color.as_ref());
+        let command = with_default(command, "color_theme", self.color_theme.as_ref());
+        with_default(command, "color_overrides", self.color_overrides.as_ref())
+    }
+
+    /// Collects the resolved values of the options that can be put into the config file from `matches`,
+    /// i.e. the values coming from the command line, the config file, the environment and the built-in defaults.
+    /// Options that have no value (e.g. no `--top`) are left unset.
+    ///
+    pub(crate) fn from_matches(matches: &ArgMatches) -> Self {
+        Self {
+            recursive: Some(matches.get_flag("recursive")),
+            line_number: Some(matches.get_flag("line_number")),
+            with_filename: Some(matches.get_flag("with_filename")),
+            no_filename: Some(matches.get_flag("no_filename")),
+            context: matches.get_one("context").copied(),
+            before_context: matches.get_one("before_context").copied(),
+            after_context: matches.get_one("after_context").copied(),
+            top: matches.get_one("top").copied(),
+            max_filesize: raw_value(matches, "max_filesize"),
+            quiet: Some(matches.get_flag("quiet")),
+            verbose: Some(matches.get_count("verbose")),
+            color: raw_value(matches, "color"),
+            color_theme: raw_value(matches, "color_theme"),
+            color_overrides: raw_value(matches, "color_overrides"),
+        }
+    }
+}
+
+/// Returns the default location of the config file:
+/// `$XDG_CONFIG_HOME/fzgrep/config.toml` or, if `XDG_CONFIG_HOME` is not set, `~/.config/fzgrep/config.toml`.
+/// Returns [`None`] if neither `XDG_CONFIG_HOME` nor `HOME` is set.
+///
+pub(crate) fn default_config_path() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("fzgrep").join("config.toml"))
+}
+
+/// Reads and parses the config file at `path`.
+///
+/// # Errors
+///
+///   * [`ConfigError::Io`] if the file cannot be read.
+///   * [`ConfigError::BadToml`] if the file is not a valid config.
+///
+pub(crate) fn load_config(path: &Path) -> Result<Config, ConfigError> {
+    let content = fs::read_to_string(path).map_err(ConfigError::Io)?;
+    toml::from_str(&content).map_err(ConfigError::BadToml)
+}
+
+/// Returns the value of the argument `id` as it was given (i.e. before being parsed).
+///
+fn raw_value(matches: &ArgMatches, id: &str) -> Option<String> {
+    matches
+        .get_raw(id)
+        .and_then(|mut values| values.next())
+        .map(|value| value.to_string_lossy().into_owned())
+}
+
+fn with_default(command: Command, id: &str, value: Option<impl ToString>) -> Command {
+    match value {
+        Some(value) => command.mut_arg(id, |arg| arg.default_value(value.to_string())),
+        None => command,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn load_config_full() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        write!(
+            tmp,
+            "recursive = true\n\
+            line-number = true\n\
+            with-filename = true\n\
+            no-filename = false\n\
+            context = 1\n\
+            before-context = 2\n\
+            after-context = 3\n\
+            top = 4\n\
+            max-filesize = \"5K\"\n\
+            quiet = false\n\
+            verbose = 2\n\
+            color = \"always\"\n\
+            color-theme = \"dark\"\n\
+            color-overrides = \"ms=01;34\"\n"
+        )
+        .unwrap();
+        assert_eq!(
+            load_config(tmp.path()).unwrap(),
+            Config {
+                recursive: Some(true),
+                line_number: Some(true),
+                with_filename: Some(true),
+                no_filename: Some(false),
+                context: Some(1),
+                before_context: Some(2),
+                after_context: Some(3),
+                top: Some(4),
+                max_filesize: Some(String::from("5K")),
+                quiet: Some(false),
+                verbose: Some(2),
+                color: Some(String::from("always")),
+                color_theme: Some(String::from("dark")),
+                color_overrides: Some(String::from("ms=01;34")),
+            }
+        );
+    }
+
+    #[test]
+    fn load_config_partial() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        writeln!(tmp, "top = 4").unwrap();
+        assert_eq!(
+            load_config(tmp.path()).unwrap(),
+            Config {
+                top: Some(4),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn load_config_unknown_key() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        writeln!(tmp, "no-such-option = 4").unwrap();
+        assert!(matches!(
+            load_config(tmp.path()),
+            Err(ConfigError::BadToml(_))
+        ));
+    }
+
+    #[test]
+    fn load_config_missing_file() {
+        let tmp = NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        drop(tmp);
+        assert!(matches!(load_config(&path), Err(ConfigError::Io(_))));
+    }
+}
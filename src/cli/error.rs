@@ -1,4 +1,4 @@
-use std::{error::Error, fmt::Display, num::ParseIntError};
+use std::{error::Error, fmt::Display, io, num::ParseIntError};
 
 /// Errors that can occur when parsing `grep` formatting sequences.
 /// (see [`grep` documentation](https://man7.org/linux/man-pages/man1/grep.1.html#ENVIRONMENT) for more information)
@@ -97,6 +97,108 @@ pub(crate) enum ColorSequenceParsingError {
     BadTrueColor,
 }
 
+/// Errors that might occur when parsing file sizes (e.g. `--max-filesize` values).
+#[derive(Debug)]
+pub(crate) enum FileSizeParsingError {
+    /// Raised if the numeric part of the size cannot be parsed as an unsigned integer.
+    ///
+    /// # Fields
+    ///   * a [`String`] containing the offending numeric part
+    ///   * a [`ParseIntError`] containing exact error why parsing failed
+    ///
+    NotANumber(String, ParseIntError),
+    /// Raised if the size (with the suffix applied) does not fit into 64 bits.
+    ///
+    /// # Fields
+    ///   * a [`String`] containing the offending size
+    ///
+    TooLarge(String),
+}
+
+/// Errors that might occur when parsing byte ranges (`--byte-range` values).
+#[derive(Debug)]
+pub(crate) enum ByteRangeParsingError {
+    /// Raised if the given string is not a '<start>-<end>' pair.
+    ///
+    /// # Fields
+    ///   * a [`String`] containing the offending string
+    ///
+    NotARange(String),
+    /// Raised if either end of the range cannot be parsed as an unsigned integer.
+    ///
+    /// # Fields
+    ///   * a [`String`] containing the offending offset
+    ///   * a [`ParseIntError`] containing exact error why parsing failed
+    ///
+    NotANumber(String, ParseIntError),
+    /// Raised if the end of the range precedes its start.
+    ///
+    /// # Fields
+    ///   * a [`String`] containing the offending range
+    ///
+    Reversed(String),
+}
+
+/// Errors that might occur when parsing field orders (`--field-order` values).
+#[derive(Debug)]
+pub(crate) enum FieldOrderParsingError {
+    /// Raised if the name of a field is not known.
+    ///
+    /// # Fields
+    ///   * a [`String`] containing the offending name
+    ///
+    UnknownField(String),
+    /// Raised if a field is listed more than once.
+    ///
+    /// # Fields
+    ///   * a [`String`] containing the name of the repeated field
+    ///
+    RepeatedField(String),
+}
+
+/// An error raised if the name of a character encoding (see `--encoding`) is not recognized.
+///
+/// # Fields
+///   * a [`String`] containing the offending name
+///
+#[derive(Debug)]
+pub(crate) struct UnknownEncodingError(pub(crate) String);
+
+/// Errors that might occur when reading pattern lists (e.g. `--include-from` files).
+#[derive(Debug)]
+pub(crate) enum PatternListError {
+    /// Raised if the list cannot be read.
+    ///
+    /// # Fields
+    ///   * an [`io::Error`] with the underlying I/O error
+    ///
+    Io(io::Error),
+    /// Raised if a line of the list is not a valid glob pattern.
+    ///
+    /// # Fields
+    ///   * a [`usize`] with the (1-based) number of the offending line
+    ///   * a [`glob::PatternError`] with a more detailed error
+    ///
+    BadPattern(usize, glob::PatternError),
+}
+
+/// Errors that might occur when loading the config file.
+#[derive(Debug)]
+pub(crate) enum ConfigError {
+    /// Raised if the config file cannot be read.
+    ///
+    /// # Fields
+    ///   * an [`io::Error`] with the underlying I/O error
+    ///
+    Io(io::Error),
+    /// Raised if the config file is not a valid TOML or contains unknown/malformed options.
+    ///
+    /// # Fields
+    ///   * a [`toml::de::Error`] with a more detailed error
+    ///
+    BadToml(toml::de::Error),
+}
+
 impl Display for ColorOverrideParsingError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -139,3 +241,70 @@ impl Display for ColorSequenceParsingError {
 }
 
 impl Error for ColorSequenceParsingError {}
+
+impl Display for FileSizeParsingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotANumber(s, e) => write!(f, "'{s}' is not a valid size: {e}"),
+            Self::TooLarge(s) => write!(f, "Size '{s}' is too large"),
+        }
+    }
+}
+
+impl Error for FileSizeParsingError {}
+
+impl Display for ByteRangeParsingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotARange(s) => write!(f, "'{s}' is not a valid range (expected 'START-END')"),
+            Self::NotANumber(s, e) => write!(f, "'{s}' is not a valid offset: {e}"),
+            Self::Reversed(s) => write!(f, "Range '{s}' ends before it starts"),
+        }
+    }
+}
+
+impl Error for ByteRangeParsingError {}
+
+impl Display for FieldOrderParsingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownField(name) => write!(
+                f,
+                "Unknown field '{name}' (expected 'file', 'line', 'col', 'score' or 'match')"
+            ),
+            Self::RepeatedField(name) => write!(f, "Field '{name}' is listed more than once"),
+        }
+    }
+}
+
+impl Error for FieldOrderParsingError {}
+
+impl Display for UnknownEncodingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown encoding '{}'", self.0)
+    }
+}
+
+impl Error for UnknownEncodingError {}
+
+impl Display for PatternListError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Cannot read the pattern list: {e}"),
+            Self::BadPattern(line, e) => write!(f, "Invalid pattern on line {line}: {e}"),
+        }
+    }
+}
+
+impl Error for PatternListError {}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Cannot read the config file: {e}"),
+            Self::BadToml(e) => write!(f, "Invalid config file: {e}"),
+        }
+    }
+}
+
+impl Error for ConfigError {}
@@ -1,7 +1,9 @@
-use yansi::Style;
+use yansi::{Color, Style};
 
 /// Controls output formatting.
 ///
+// the options are a handful of styles that are copied once per run, boxing them is not worth it
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Formatting {
     /// Request the output text to be formatted according to the supplied options.
@@ -37,9 +39,83 @@ pub struct FormattingOptions {
     ///
     pub selected_line: Style,
 
-    /// Style of the surrounding context
+    /// Style of the context preceding the selected line
     ///
-    pub context: Style,
+    pub before_context: Style,
+
+    /// Style of the context following the selected line
+    ///
+    pub after_context: Style,
+
+    /// Style of the numbers of context lines printed with `--print-context-count`
+    ///
+    pub context_count: Style,
+
+    /// Style of the scores printed with `--show-score`
+    ///
+    pub score: Style,
+
+    /// Style of the characters of the context lines matching the query, highlighted with `--highlight-query`
+    ///
+    pub context_match: Style,
+
+    /// Whether file names and line numbers should be turned into terminal hyperlinks (OSC 8)
+    /// pointing to the corresponding line of the file.
+    ///
+    pub hyperlinks: bool,
+
+    /// Whether every line should end with an explicit reset sequence (`\x1b[0m`),
+    /// for the terminals and pagers that carry the styling over to the next line otherwise.
+    ///
+    pub color_reset: bool,
+
+    /// If set, the selected matches are colored according to their scores (see [`ColorGradient`])
+    /// instead of being styled with [`FormattingOptions::selected_match`].
+    ///
+    pub gradient: Option<ColorGradient>,
+}
+
+/// A gradient between two colors that the selected matches are colored along, by their normalized scores
+/// (see [`crate::MatchingResult::normalized_score`]): the lowest scores get the `low` color, the highest ones the `high` one.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorGradient {
+    /// Color of the lowest scores.
+    ///
+    pub low: Color,
+
+    /// Color of the highest scores.
+    ///
+    pub high: Color,
+}
+
+/// Predefined color themes.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Theme {
+    /// Same as [`FormattingOptions::default`], i.e. `grep`'s default colors.
+    ///
+    Default,
+
+    /// Brighter colors that stand out on dark backgrounds.
+    ///
+    Dark,
+
+    /// Darker colors that stay readable on light backgrounds.
+    ///
+    Light,
+
+    /// [Solarized](https://ethanschoonover.com/solarized/) palette for dark backgrounds.
+    ///
+    SolarizedDark,
+
+    /// [Solarized](https://ethanschoonover.com/solarized/) palette for light backgrounds.
+    ///
+    SolarizedLight,
+
+    /// No styling at all.
+    ///
+    None,
 }
 
 impl Formatting {
@@ -54,6 +130,114 @@ impl Formatting {
     }
 }
 
+impl ColorGradient {
+    /// Returns the style of a match with the normalized `score` (clamped to `0.0..=1.0`),
+    /// with the color linearly interpolated between `low` and `high` in the RGB space.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fzgrep::cli::formatting::ColorGradient;
+    /// use yansi::{Color, Style};
+    ///
+    /// let gradient = ColorGradient {
+    ///     low: Color::Rgb(255, 0, 0),
+    ///     high: Color::Rgb(0, 255, 0),
+    /// };
+    /// assert_eq!(gradient.interpolate(0.0), Style::new().rgb(255, 0, 0));
+    /// assert_eq!(gradient.interpolate(0.5), Style::new().rgb(128, 128, 0));
+    /// assert_eq!(gradient.interpolate(1.0), Style::new().rgb(0, 255, 0));
+    /// ```
+    ///
+    pub fn interpolate(&self, score: f64) -> Style {
+        let score = if score.is_nan() {
+            0.0
+        } else {
+            score.clamp(0.0, 1.0)
+        };
+        let (low, high) = (rgb(self.low), rgb(self.high));
+        let channel = |low: u8, high: u8| {
+            // the result lies between the two channels, so it always fits
+            (f64::from(low) + (f64::from(high) - f64::from(low)) * score).round() as u8
+        };
+        Style::new().rgb(
+            channel(low.0, high.0),
+            channel(low.1, high.1),
+            channel(low.2, high.2),
+        )
+    }
+}
+
+impl Default for ColorGradient {
+    /// Red for the low scores, through yellow, to green for the high ones.
+    ///
+    fn default() -> Self {
+        Self {
+            low: Color::Rgb(255, 0, 0),
+            high: Color::Rgb(0, 255, 0),
+        }
+    }
+}
+
+/// Approximates `color` with RGB components. The named and the fixed colors are looked up
+/// in the `xterm` palette, the terminal default one is taken for white.
+///
+fn rgb(color: Color) -> (u8, u8, u8) {
+    const BASIC: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Fixed(n @ 0..=15) => BASIC[usize::from(n)],
+        Color::Fixed(n @ 16..=231) => {
+            let level = |component: u8| {
+                if component == 0 {
+                    0
+                } else {
+                    55 + component * 40
+                }
+            };
+            let n = n - 16;
+            (level(n / 36), level(n / 6 % 6), level(n % 6))
+        }
+        Color::Fixed(n) => {
+            let gray = 8 + (n - 232) * 10;
+            (gray, gray, gray)
+        }
+        Color::Black => BASIC[0],
+        Color::Red => BASIC[1],
+        Color::Green => BASIC[2],
+        Color::Yellow => BASIC[3],
+        Color::Blue => BASIC[4],
+        Color::Magenta => BASIC[5],
+        Color::Cyan => BASIC[6],
+        Color::White | Color::Primary => BASIC[7],
+        Color::BrightBlack => BASIC[8],
+        Color::BrightRed => BASIC[9],
+        Color::BrightGreen => BASIC[10],
+        Color::BrightYellow => BASIC[11],
+        Color::BrightBlue => BASIC[12],
+        Color::BrightMagenta => BASIC[13],
+        Color::BrightCyan => BASIC[14],
+        Color::BrightWhite => BASIC[15],
+    }
+}
+
 impl Default for FormattingOptions {
     /// Default formatting options that correspond to `grep`'s defaults.
     /// More info, see [`grep` source code](https://git.savannah.gnu.org/cgit/grep.git/tree/src/grep.c?id=102be2bfa571355ff44db39348438a0def1ab382#n299).
@@ -75,7 +259,97 @@ impl Default for FormattingOptions {
             file_name: Style::new().magenta(),
             separator: Style::new().cyan(),
             selected_line: Style::new(),
-            context: Style::new(),
+            before_context: Style::new(),
+            after_context: Style::new(),
+            context_count: Style::new().green(),
+            score: Style::new().yellow(),
+            context_match: Style::new().red(),
+            hyperlinks: false,
+            color_reset: false,
+            gradient: None,
+        }
+    }
+}
+
+impl From<Theme> for FormattingOptions {
+    fn from(theme: Theme) -> Self {
+        match theme {
+            Theme::Default => Self::default(),
+            Theme::Dark => Self {
+                selected_match: Style::new().bright_red().bold(),
+                line_number: Style::new().bright_green(),
+                file_name: Style::new().bright_magenta(),
+                separator: Style::new().bright_cyan(),
+                selected_line: Style::new().bright_white(),
+                before_context: Style::new().white().dim(),
+                after_context: Style::new().white().dim(),
+                context_count: Style::new().bright_green(),
+                score: Style::new().bright_yellow(),
+                context_match: Style::new().bright_red(),
+                hyperlinks: false,
+                color_reset: false,
+                gradient: None,
+            },
+            Theme::Light => Self {
+                selected_match: Style::new().red().bold().underline(),
+                line_number: Style::new().blue(),
+                file_name: Style::new().magenta().bold(),
+                separator: Style::new().black(),
+                selected_line: Style::new().black(),
+                before_context: Style::new().bright_black(),
+                after_context: Style::new().bright_black(),
+                context_count: Style::new().blue(),
+                score: Style::new().cyan(),
+                context_match: Style::new().red().underline(),
+                hyperlinks: false,
+                color_reset: false,
+                gradient: None,
+            },
+            Theme::SolarizedDark => Self {
+                selected_match: Style::new().rgb(0xcb, 0x4b, 0x16).bold(),
+                line_number: Style::new().rgb(0x85, 0x99, 0x00),
+                file_name: Style::new().rgb(0x6c, 0x71, 0xc4),
+                separator: Style::new().rgb(0x2a, 0xa1, 0x98),
+                selected_line: Style::new().rgb(0x93, 0xa1, 0xa1),
+                before_context: Style::new().rgb(0x58, 0x6e, 0x75),
+                after_context: Style::new().rgb(0x58, 0x6e, 0x75),
+                context_count: Style::new().rgb(0x85, 0x99, 0x00),
+                score: Style::new().rgb(0xb5, 0x89, 0x00),
+                context_match: Style::new().rgb(0xcb, 0x4b, 0x16),
+                hyperlinks: false,
+                color_reset: false,
+                gradient: None,
+            },
+            Theme::SolarizedLight => Self {
+                selected_match: Style::new().rgb(0xdc, 0x32, 0x2f).bold(),
+                line_number: Style::new().rgb(0x85, 0x99, 0x00),
+                file_name: Style::new().rgb(0x26, 0x8b, 0xd2),
+                separator: Style::new().rgb(0x2a, 0xa1, 0x98),
+                selected_line: Style::new().rgb(0x58, 0x6e, 0x75),
+                before_context: Style::new().rgb(0x93, 0xa1, 0xa1),
+                after_context: Style::new().rgb(0x93, 0xa1, 0xa1),
+                context_count: Style::new().rgb(0x85, 0x99, 0x00),
+                score: Style::new().rgb(0xb5, 0x89, 0x00),
+                context_match: Style::new().rgb(0xdc, 0x32, 0x2f),
+                hyperlinks: false,
+                color_reset: false,
+                gradient: None,
+            },
+            Theme::None => Self {
+                selected_match: Style::new(),
+                line_number: Style::new(),
+                file_name: Style::new(),
+                separator: Style::new(),
+                selected_line: Style::new(),
+                before_context: Style::new(),
+                after_context: Style::new(),
+                context_count: Style::new(),
+                score: Style::new(),
+                context_match: Style::new(),
+                hyperlinks: false,
+                color_reset: false,
+                gradient: None,
+            },
         }
     }
 }
@@ -92,7 +366,88 @@ mod test {
         assert_eq!(default.file_name, Style::new().magenta());
         assert_eq!(default.separator, Style::new().cyan());
         assert_eq!(default.selected_line, Style::new());
-        assert_eq!(default.context, Style::new());
+        assert_eq!(default.before_context, Style::new());
+        assert_eq!(default.after_context, Style::new());
+        assert_eq!(default.context_count, Style::new().green());
+        assert_eq!(default.score, Style::new().yellow());
+        assert_eq!(default.context_match, Style::new().red());
+        assert!(!default.hyperlinks);
+        assert!(!default.color_reset);
+        assert_eq!(default.gradient, None);
+    }
+
+    #[test]
+    fn theme_default() {
+        assert_eq!(
+            FormattingOptions::from(Theme::Default),
+            FormattingOptions::default()
+        );
+    }
+
+    #[test]
+    fn themes_non_default() {
+        for theme in [
+            Theme::Dark,
+            Theme::Light,
+            Theme::SolarizedDark,
+            Theme::SolarizedLight,
+        ] {
+            assert_ne!(
+                FormattingOptions::from(theme),
+                FormattingOptions::default(),
+                "{theme:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn theme_none() {
+        let options = FormattingOptions::from(Theme::None);
+        assert_ne!(options, FormattingOptions::default());
+        assert_eq!(options.selected_match, Style::new());
+        assert_eq!(options.line_number, Style::new());
+        assert_eq!(options.file_name, Style::new());
+        assert_eq!(options.separator, Style::new());
+        assert_eq!(options.selected_line, Style::new());
+        assert_eq!(options.before_context, Style::new());
+        assert_eq!(options.after_context, Style::new());
+        assert_eq!(options.context_count, Style::new());
+        assert_eq!(options.score, Style::new());
+    }
+
+    #[test]
+    fn gradient_interpolate() {
+        let gradient = ColorGradient {
+            low: Color::Rgb(0, 100, 200),
+            high: Color::Rgb(200, 100, 0),
+        };
+        assert_eq!(gradient.interpolate(0.0), Style::new().rgb(0, 100, 200));
+        assert_eq!(gradient.interpolate(0.25), Style::new().rgb(50, 100, 150));
+        assert_eq!(gradient.interpolate(1.0), Style::new().rgb(200, 100, 0));
+    }
+
+    #[test]
+    fn gradient_interpolate_out_of_range() {
+        let gradient = ColorGradient::default();
+        assert_eq!(gradient.interpolate(-1.0), gradient.interpolate(0.0));
+        assert_eq!(gradient.interpolate(2.0), gradient.interpolate(1.0));
+        assert_eq!(gradient.interpolate(f64::NAN), gradient.interpolate(0.0));
+    }
+
+    #[test]
+    fn gradient_interpolate_palette_colors() {
+        let gradient = ColorGradient {
+            low: Color::Black,
+            high: Color::Fixed(231),
+        };
+        assert_eq!(gradient.interpolate(0.0), Style::new().rgb(0, 0, 0));
+        assert_eq!(gradient.interpolate(1.0), Style::new().rgb(255, 255, 255));
+        let gradient = ColorGradient {
+            low: Color::Fixed(232),
+            high: Color::Fixed(16),
+        };
+        assert_eq!(gradient.interpolate(0.0), Style::new().rgb(8, 8, 8));
+        assert_eq!(gradient.interpolate(1.0), Style::new().rgb(0, 0, 0));
     }
 
     #[test]
@@ -0,0 +1,92 @@
+use std::io::{self, Write};
+
+/// A thin wrapper around a [`Write`] that flushes the inner writer after every write containing a newline.
+///
+/// Useful when the output is piped into another program that should receive the lines as soon as they are ready.
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::cli::line_buffered_writer::LineBufferedWriter;
+/// use std::io::Write;
+///
+/// let mut writer = LineBufferedWriter::new(Vec::new());
+/// writeln!(writer, "line").unwrap();
+/// assert_eq!(writer.into_inner(), b"line\n");
+/// ```
+///
+pub struct LineBufferedWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> LineBufferedWriter<W> {
+    /// Wraps `inner` into a line buffered writer.
+    ///
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Unwraps the writer, returning the underlying one.
+    ///
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for LineBufferedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if buf[..written].contains(&b'\n') {
+            self.inner.flush()?;
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct FlushCounter {
+        data: Vec<u8>,
+        flushes: usize,
+    }
+
+    impl Write for FlushCounter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.data.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.flushes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn flush_on_newline() {
+        let mut writer = LineBufferedWriter::new(FlushCounter::default());
+        write!(writer, "no newline").unwrap();
+        assert_eq!(writer.inner.flushes, 0);
+        writeln!(writer, ", now a newline").unwrap();
+        assert_eq!(writer.inner.flushes, 1);
+        write!(writer, "first\nsecond\n").unwrap();
+        assert_eq!(writer.inner.flushes, 2);
+        assert_eq!(
+            writer.into_inner().data,
+            b"no newline, now a newline\nfirst\nsecond\n"
+        );
+    }
+
+    #[test]
+    fn explicit_flush() {
+        let mut writer = LineBufferedWriter::new(FlushCounter::default());
+        writer.flush().unwrap();
+        assert_eq!(writer.inner.flushes, 1);
+    }
+}
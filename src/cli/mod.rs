@@ -1,5 +1,10 @@
 pub mod args;
+pub(crate) mod config;
 pub(crate) mod error;
 pub mod formatting;
+pub mod line_buffered_writer;
+pub mod nul_terminated_writer;
 pub(crate) mod output;
+pub mod pager;
+pub(crate) mod progress;
 pub(crate) mod sgr_sequence;
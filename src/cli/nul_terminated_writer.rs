@@ -0,0 +1,85 @@
+use std::io::{self, Write};
+
+/// A thin wrapper around a [`Write`] that replaces every newline written to it with a NUL character,
+/// so that the lines become NUL-terminated.
+///
+/// Useful when the output is piped into a program expecting NUL-separated records (e.g. `xargs -0`).
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::cli::nul_terminated_writer::NulTerminatedWriter;
+/// use std::io::Write;
+///
+/// let mut writer = NulTerminatedWriter::new(Vec::new());
+/// writeln!(writer, "line").unwrap();
+/// assert_eq!(writer.into_inner(), b"line\0");
+/// ```
+///
+pub struct NulTerminatedWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> NulTerminatedWriter<W> {
+    /// Wraps `inner` into a NUL-terminated writer.
+    ///
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Unwraps the writer, returning the underlying one.
+    ///
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for NulTerminatedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let replaced = buf
+            .iter()
+            .map(|&byte| if byte == b'\n' { b'\0' } else { byte })
+            .collect::<Vec<_>>();
+        // the replacement keeps the length, so the number of bytes written is the same for `buf`
+        self.inner.write(&replaced)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newlines_replaced() {
+        let mut writer = NulTerminatedWriter::new(Vec::new());
+        write!(writer, "no newline").unwrap();
+        writeln!(writer, ", now a newline").unwrap();
+        write!(writer, "first\nsecond\n").unwrap();
+        assert_eq!(
+            writer.into_inner(),
+            b"no newline, now a newline\0first\0second\0"
+        );
+    }
+
+    #[test]
+    fn split_back() {
+        let lines = ["file with spaces.txt:1:line", "", "тест.txt:2:текст"];
+        let mut writer = NulTerminatedWriter::new(Vec::new());
+        for line in lines {
+            writeln!(writer, "{line}").unwrap();
+        }
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(
+            output
+                .strip_suffix('\0')
+                .unwrap()
+                .split('\0')
+                .collect::<Vec<_>>(),
+            lines
+        );
+    }
+}
@@ -1,108 +1,548 @@
 use crate::{
-    cli::formatting::Formatting,
-    matching_results::result::{Context, MatchingResult},
+    cli::formatting::{Formatting, FormattingOptions},
+    core::request::{OutputField, ScoreFormat},
+    matching_results::result::{self, Context, MatchingResult},
 };
 use log::debug;
-use std::ops::Range;
+use std::{
+    borrow::Cow,
+    cmp,
+    collections::BTreeMap,
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
 use vscode_fuzzy_score_rs::FuzzyMatch;
 use yansi::{Paint, Style};
 
-/// Formats supplied `matches` into a rich text string.
+pub(crate) mod ndjson;
+pub(crate) mod template;
+
+/// Optional parts of the output produced by [`format_results`].
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct OutputOptions<'a> {
+    /// Print the numbers of context lines actually collected with every matching line.
+    ///
+    pub(crate) context_count: bool,
+
+    /// Print the positions of the matched characters after every matching line.
+    ///
+    pub(crate) match_positions: bool,
+
+    /// Print the score of every match in this format.
+    ///
+    pub(crate) score_format: Option<ScoreFormat>,
+
+    /// Print a caret under the column of every match (provided the column is tracked).
+    ///
+    pub(crate) caret: bool,
+
+    /// Print a `==> <file name> <==` header before the matches from every file instead of the file name on every line.
+    ///
+    pub(crate) file_headers: bool,
+
+    /// Highlight the characters of the context lines that fuzzy-match this query (when formatting is on).
+    ///
+    pub(crate) highlighted_query: Option<&'a str>,
+
+    /// Print the fields of every line in this order ([`OutputField::DEFAULT_ORDER`] if not set).
+    ///
+    pub(crate) field_order: Option<&'a [OutputField]>,
+
+    /// Right-align the line numbers to at least this many digits.
+    ///
+    pub(crate) line_number_width: usize,
+
+    /// Right-align the line numbers to the widest one among the printed lines.
+    ///
+    pub(crate) auto_align: bool,
+
+    /// Print this line between the groups of lines (matches with their context) that are not adjacent.
+    /// `{source}` and `{line}` are replaced with the file name and the line number of the following match.
+    ///
+    pub(crate) context_separator: Option<&'a str>,
+
+    /// Start the matching lines with [`MATCH_MARKER`] and every other line with this marker
+    /// (when every line of the files is printed, see [`crate::MatchOptions::print_unmatched`]).
+    ///
+    pub(crate) unmatched_marker: Option<&'a str>,
+
+    /// Print the file names relative to this directory (see [`make_file_names_relative`]).
+    /// Only the printed names are affected, the hyperlinks still point to the files as found.
+    ///
+    pub(crate) relative_paths_root: Option<&'a Path>,
+}
+
+/// The marker the matching lines start with when every line is printed.
+///
+pub(crate) const MATCH_MARKER: &str = "> ";
+
+/// Formats supplied `matches` as rich text, writing them to `dest` as they are formatted.
 ///
 /// When grepping files the format is as follows:
 /// ```text
-/// <filename>:<line-number>:<colored-matching-line>
+/// <filename>:<line-number>[<before>+<after>]:<column-number>:<score>:<colored-matching-line>
 /// ```
-/// where `colored-matching-line` is a matching line with matching characters painted blue.
-/// Whether `<filename>` and `<line-number>` are printed depends on `options`.
+/// where `:` is actually `field_separator`,
+/// `colored-matching-line` is a matching line with matching characters painted blue.
+/// Whether `<filename>`, `<line-number>` and `<column-number>` are printed depends on the matches themselves.
+/// If `output_options.field_order` is set, only the fields listed there are printed, in that order
+/// (e.g. `<line-number>:<colored-matching-line>:<filename>`).
+/// The line numbers are right-aligned to `output_options.line_number_width` digits
+/// (or more if `output_options.auto_align` is set and there are wider ones).
+/// If `output_options.context_separator` is set, it is printed (colored as a separator) on a line of its own
+/// between the matches whose lines (including the context) do not follow each other, e.g. `--`.
+/// The numbers of context lines (`[<before>+<after>]`) are printed only if `output_options.context_count` is set.
+/// The score is printed only if `output_options.score_format` is set, in that format.
+/// The column number, the numbers of context lines and the score are printed only for matching lines,
+/// not for the context.
+/// If `output_options.caret` is set, every matching line with a column number is followed by a line
+/// with a `^` under the first matched character (colored as a separator).
+/// If `output_options.match_positions` is set, every matching line (and its caret) is followed by a line
+/// with the positions of the matched characters, e.g. `[0,3,5,7]` (never colored).
+/// If `output_options.unmatched_marker` is set, the matching lines start with [`MATCH_MARKER`]
+/// and the rest of the lines (except for the separators and the headers) with the marker.
+/// If the formatting options ask for it ([`FormattingOptions::color_reset`]), every line ends with a reset
+/// sequence (`\x1b[0m`).
+/// If `output_options.highlighted_query` is set, the characters of the context lines that match it
+/// are colored as context matches (otherwise the context lines are colored as a whole).
+/// If `output_options.file_headers` is set, `<filename>` is left out of the lines and printed
+/// (colored as a file name) in a `==> <filename> <==` header instead, whenever it differs from the one
+/// of the previous match. So the matches from the same file are expected to be next to each other
+/// (otherwise a file gets more than one header).
+/// If `output_options.relative_paths_root` is set, the file names are printed relative to it.
+///
+/// Nothing is accumulated in memory, so the output of arbitrarily large result sets costs no extra memory.
+///
+/// # Errors
+///
+///   * [`io::Error`] if writing to `dest` fails.
 ///
-pub(crate) fn format_results(matches: &[MatchingResult], formatting: &Formatting) -> String {
-    let mut ret = String::new();
+pub(crate) fn format_results(
+    dest: &mut impl Write,
+    matches: &[MatchingResult],
+    formatting: &Formatting,
+    field_separator: &str,
+    output_options: OutputOptions,
+) -> io::Result<()> {
+    let field_order = output_options
+        .field_order
+        .unwrap_or(&OutputField::DEFAULT_ORDER);
+    let line_number_width = if output_options.auto_align {
+        output_options
+            .line_number_width
+            .max(max_line_number_width(matches))
+    } else {
+        output_options.line_number_width
+    };
+    // the reset is written after the whole line, so that no styling can leak into the next one
+    let line_end = if formatting.options().is_some_and(|o| o.color_reset) {
+        "\x1b[0m\n"
+    } else {
+        "\n"
+    };
+    let (match_marker, unmatched_marker) = output_options
+        .unmatched_marker
+        .map_or(("", ""), |marker| (MATCH_MARKER, marker));
+    let root = output_options.relative_paths_root.and_then(absolute_root);
+    let mut previous = None;
     for m in matches.iter() {
+        // the results keep the file names as found, only the printed ones are made relative
+        let displayed_file_name = m.file_name.as_deref().map(|file_name| {
+            root.as_deref()
+                .and_then(|root| relative_file_name(file_name, root))
+                .map_or(Cow::Borrowed(file_name), Cow::Owned)
+        });
+        if let (Some(separator), Some(previous)) = (output_options.context_separator, previous) {
+            if !are_adjacent(previous, m) {
+                write!(
+                    dest,
+                    "{}{line_end}",
+                    format_one_piece(
+                        &fill_context_separator(separator, displayed_file_name.as_deref(), m),
+                        formatting.options().map(|o| o.separator)
+                    )
+                )?;
+            }
+        }
+        let is_new_file = previous.is_none_or(|p: &MatchingResult| p.file_name != m.file_name);
+        if let (true, true, Some(file_name)) = (
+            output_options.file_headers,
+            is_new_file,
+            &displayed_file_name,
+        ) {
+            write!(
+                dest,
+                "{}{line_end}",
+                format_one_piece(
+                    &format!("==> {file_name} <=="),
+                    formatting.options().map(|o| o.file_name)
+                )
+            )?;
+        }
+        previous = Some(m);
+
         let MatchingResult {
             matching_line,
             fuzzy_match,
+            score_bonus: _,
             file_name,
             line_number,
+            column_number,
             context:
                 Context {
                     before: context_before,
                     after: context_after,
                 },
         } = m;
+        let path = file_name
+            .as_deref()
+            .filter(|_| !output_options.file_headers);
+        let file_name = displayed_file_name
+            .as_deref()
+            .filter(|_| !output_options.file_headers);
 
         for (index, context_line) in context_before.iter().enumerate() {
-            let line_number = line_number.and_then(|l| Some(l - matches.len() + index + 1));
-            ret.push_str(&format_context_line(
-                context_line,
+            let fields = LineFields {
                 file_name,
-                &line_number,
-                formatting,
-            ));
-            ret.push('\n');
+                path,
+                line_number: line_number.map(|l| l - context_before.len() + index),
+                line_number_width,
+                ..Default::default()
+            };
+            write!(
+                dest,
+                "{unmatched_marker}{}{line_end}",
+                fields.format_line(
+                    &format_context_line(
+                        context_line,
+                        output_options.highlighted_query,
+                        formatting.options().map(|o| o.before_context),
+                        formatting
+                    ),
+                    field_order,
+                    formatting,
+                    field_separator
+                )
+            )?;
         }
 
-        ret.push_str(&format_selected_line(
-            matching_line,
-            fuzzy_match,
+        let context_count = output_options
+            .context_count
+            .then_some((context_before.len(), context_after.len()));
+        let score = output_options
+            .score_format
+            .map(|format| format_score(m, format));
+        let fields = LineFields {
             file_name,
-            line_number,
-            formatting,
-        ));
-        ret.push('\n');
+            path,
+            line_number: *line_number,
+            line_number_width,
+            column_number: *column_number,
+            context_count,
+            score: score.as_deref(),
+        };
+        write!(
+            dest,
+            "{match_marker}{}{line_end}",
+            fields.format_line(
+                &format_selected_line(m, formatting),
+                field_order,
+                formatting,
+                field_separator
+            )
+        )?;
+
+        if let (true, Some(column_number)) = (output_options.caret, column_number) {
+            // the fields are measured without the styling, which takes no room on the screen
+            let (plain_prefix, _) =
+                fields.format_fields(field_order, &Formatting::Off, field_separator);
+            // without the line itself there is nothing to point at
+            if let Some(plain_prefix) = plain_prefix {
+                write!(
+                    dest,
+                    "{}{line_end}",
+                    format_caret(
+                        &format!("{match_marker}{plain_prefix}"),
+                        matching_line,
+                        *column_number,
+                        formatting.options()
+                    )
+                )?;
+            }
+        }
+
+        if output_options.match_positions {
+            write!(
+                dest,
+                "{unmatched_marker}{}{line_end}",
+                format_match_positions(fuzzy_match)
+            )?;
+        }
 
         for (index, context_line) in context_after.iter().enumerate() {
-            let line_number = line_number.and_then(|l| Some(l + index + 1));
-            ret.push_str(&format_context_line(
-                context_line,
+            let fields = LineFields {
                 file_name,
-                &line_number,
-                formatting,
-            ));
-            ret.push('\n');
+                path,
+                line_number: line_number.and_then(|l| Some(l + index + 1)),
+                line_number_width,
+                ..Default::default()
+            };
+            write!(
+                dest,
+                "{unmatched_marker}{}{line_end}",
+                fields.format_line(
+                    &format_context_line(
+                        context_line,
+                        output_options.highlighted_query,
+                        formatting.options().map(|o| o.after_context),
+                        formatting
+                    ),
+                    field_order,
+                    formatting,
+                    field_separator
+                )
+            )?;
         }
     }
 
-    ret
+    Ok(())
 }
 
-fn format_context_line(
-    content: &str,
-    file_name: &Option<String>,
-    line_number: &Option<usize>,
-    formatting: &Formatting,
+/// Checks whether the lines of `next` (including the context) directly follow the ones of `previous` in the same file.
+/// Without the line numbers this cannot be known, so the matches are never considered adjacent.
+///
+fn are_adjacent(previous: &MatchingResult, next: &MatchingResult) -> bool {
+    if previous.file_name != next.file_name {
+        return false;
+    }
+    match (previous.line_number, next.line_number) {
+        (Some(previous_line), Some(next_line)) => {
+            let previous_first = previous_line - previous.context.before.len();
+            let previous_last = previous_line + previous.context.after.len();
+            let next_first = next_line - next.context.before.len();
+            previous_first < next_first && next_first <= previous_last + 1
+        }
+        _ => false,
+    }
+}
+
+/// Replaces the `{source}` and `{line}` placeholders in `separator` with `file_name` and the line number of `next`
+/// (empty if not tracked).
+///
+fn fill_context_separator(
+    separator: &str,
+    file_name: Option<&str>,
+    next: &MatchingResult,
 ) -> String {
-    let mut result = String::new();
+    separator
+        .replace("{source}", file_name.unwrap_or_default())
+        .replace(
+            "{line}",
+            &next.line_number.map(|l| l.to_string()).unwrap_or_default(),
+        )
+}
+
+/// Returns the number of digits of the greatest line number among supplied `matches` and their context lines
+/// (0 if the line numbers are not tracked).
+///
+fn max_line_number_width(matches: &[MatchingResult]) -> usize {
+    matches
+        .iter()
+        .filter_map(|m| m.line_number.map(|l| l + m.context.after.len()))
+        .max()
+        .map_or(0, |l| l.to_string().len())
+}
+
+/// Makes the file names of supplied `matches` relative to `root`.
+/// File names that cannot be made relative to `root` (e.g. the ones that are outside of it) are left as is.
+///
+pub(crate) fn make_file_names_relative(matches: &mut [MatchingResult], root: &Path) {
+    make_paths_relative(
+        matches.iter_mut().filter_map(|m| m.file_name.as_mut()),
+        root,
+    );
+}
 
-    if let Some(prefix) = format_line_prefix(file_name, line_number, formatting) {
-        result.push_str(&prefix);
+/// Makes supplied `file_names` relative to `root`.
+/// File names that cannot be made relative to `root` (e.g. the ones that are outside of it) are left as is.
+///
+pub(crate) fn make_paths_relative<'a>(
+    file_names: impl Iterator<Item = &'a mut String>,
+    root: &Path,
+) {
+    let Some(root) = absolute_root(root) else {
+        return;
+    };
+    for file_name in file_names {
+        if let Some(relative) = relative_file_name(file_name, &root) {
+            *file_name = relative;
+        }
     }
+}
 
-    result.push_str(&format_one_piece(
-        content,
-        formatting.options().map(|o| o.context),
-    ));
+/// Returns `matches` with the file names made relative to `root` (see [`make_file_names_relative`]),
+/// leaving `matches` themselves intact.
+///
+pub(crate) fn with_relative_file_names<'a>(
+    matches: &'a [MatchingResult],
+    root: Option<&Path>,
+) -> Cow<'a, [MatchingResult]> {
+    match root {
+        Some(root) => {
+            let mut matches = matches.to_vec();
+            make_file_names_relative(&mut matches, root);
+            Cow::Owned(matches)
+        }
+        None => Cow::Borrowed(matches),
+    }
+}
 
-    result
+/// Formats supplied `files` (the names of the files that would be searched), one per line.
+/// The names are followed by the line counts of the files (if present), e.g. `file.txt (42 lines)`.
+///
+pub(crate) fn format_file_names(
+    files: &[(String, Option<usize>)],
+    formatting: &Formatting,
+) -> String {
+    files
+        .iter()
+        .map(|(file_name, line_count)| {
+            let file_name = format_one_piece(file_name, formatting.options().map(|o| o.file_name));
+            match line_count {
+                Some(1) => format!("{file_name} (1 line)\n"),
+                Some(count) => format!("{file_name} ({count} lines)\n"),
+                None => file_name + "\n",
+            }
+        })
+        .collect()
 }
 
-fn format_selected_line(
-    content: &str,
-    fuzzy_match: &FuzzyMatch,
-    file_name: &Option<String>,
-    line_number: &Option<usize>,
+/// Formats the best score found in every file that has any of `results`, one file per line
+/// (e.g. `file.txt:87`, where `:` is actually `field_separator`).
+/// The files are ordered by the score, the highest first, the ones with equal scores by name.
+/// If `limit` is set, only that many files are listed.
+///
+pub(crate) fn format_file_scores(
+    results: &[MatchingResult],
     formatting: &Formatting,
+    field_separator: &str,
+    limit: Option<usize>,
 ) -> String {
-    let mut result = String::new();
+    let mut best_scores = BTreeMap::new();
+    for result in results {
+        let score = result.score();
+        best_scores
+            .entry(result.file_name.as_deref().unwrap_or_default())
+            .and_modify(|best| *best = cmp::max(*best, score))
+            .or_insert(score);
+    }
+    let mut files = best_scores.into_iter().collect::<Vec<_>>();
+    // the sort is stable, so the files with equal scores stay sorted by name
+    files.sort_by_key(|&(_, score)| cmp::Reverse(score));
+    files
+        .into_iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|(file_name, score)| {
+            format!(
+                "{}{}{}\n",
+                format_one_piece(file_name, formatting.options().map(|o| o.file_name)),
+                format_one_piece(field_separator, formatting.options().map(|o| o.separator)),
+                format_one_piece(&score.to_string(), formatting.options().map(|o| o.score))
+            )
+        })
+        .collect()
+}
+
+fn relative_file_name(file_name: &str, root: &Path) -> Option<String> {
+    let relative = absolute_path(Path::new(file_name))
+        .as_deref()
+        .and_then(|path| path.strip_prefix(root).ok())
+        .map(|path| path.display().to_string());
+    if relative.is_none() {
+        debug!(
+            "{file_name} cannot be made relative to {}, using the full path.",
+            root.display()
+        );
+    }
+    relative
+}
 
-    if let Some(prefix) = format_line_prefix(file_name, line_number, formatting) {
-        result.push_str(&prefix);
+fn absolute_root(root: &Path) -> Option<PathBuf> {
+    let absolute = absolute_path(root);
+    if absolute.is_none() {
+        debug!(
+            "Cannot determine the absolute path of {}, keeping file names as is.",
+            root.display()
+        );
     }
+    absolute
+}
+
+fn absolute_path(path: &Path) -> Option<PathBuf> {
+    if path.is_absolute() {
+        Some(path.to_path_buf())
+    } else {
+        env::current_dir().ok().map(|dir| dir.join(path))
+    }
+}
 
+/// Formats the matching line of `m`, coloring the matched characters
+/// (along the gradient by [`MatchingResult::normalized_score`], if there is one).
+///
+fn format_selected_line(m: &MatchingResult, formatting: &Formatting) -> String {
     let options = formatting.options();
+    let match_style = options.map(|o| match o.gradient {
+        // the same score as the printed one, the bonus included
+        Some(gradient) => gradient.interpolate(m.normalized_score()),
+        None => o.selected_match,
+    });
+    format_matched_chars(
+        &m.matching_line,
+        m.fuzzy_match.positions(),
+        options.map(|o| o.selected_line),
+        match_style,
+    )
+}
+
+/// Formats a context line with `line_style`, except for the characters matching `query` (if any)
+/// which are formatted as context matches. Without formatting there is nothing to highlight,
+/// so the query is not even matched then.
+///
+fn format_context_line(
+    content: &str,
+    query: Option<&str>,
+    line_style: Option<Style>,
+    formatting: &Formatting,
+) -> String {
+    let fuzzy_match = query
+        .filter(|_| formatting.options().is_some())
+        .and_then(|query| vscode_fuzzy_score_rs::fuzzy_match(query, content));
+    match fuzzy_match {
+        Some(fuzzy_match) => format_matched_chars(
+            content,
+            fuzzy_match.positions(),
+            line_style,
+            formatting.options().map(|o| o.context_match),
+        ),
+        None => format_one_piece(content, line_style),
+    }
+}
+
+/// Formats the characters of `content` at `positions` with `match_style` and the rest with `line_style`.
+///
+fn format_matched_chars(
+    content: &str,
+    positions: &[usize],
+    line_style: Option<Style>,
+    match_style: Option<Style>,
+) -> String {
+    let mut result = String::new();
+
     let mut str_itr = content.chars();
     let mut previous_range_end = 0;
-    for range in group_indices(fuzzy_match.positions()) {
+    for range in result::group_indices(positions) {
         let preceding_non_match = str_itr
             .by_ref()
             .take(range.start - previous_range_end)
@@ -111,20 +551,14 @@ fn format_selected_line(
         // Visually it makes no difference, but there are extra characters in the output,
         // making it harder to validate and compare results.
         if !preceding_non_match.is_empty() {
-            result.push_str(&format_one_piece(
-                &preceding_non_match,
-                options.map(|o| o.selected_line),
-            ))
+            result.push_str(&format_one_piece(&preceding_non_match, line_style))
         }
 
         let matching_part = str_itr
             .by_ref()
             .take(range.end - range.start)
             .collect::<String>();
-        result.push_str(&format_one_piece(
-            &matching_part,
-            options.map(|o| o.selected_match),
-        ));
+        result.push_str(&format_one_piece(&matching_part, match_style));
 
         previous_range_end = range.end;
     }
@@ -134,120 +568,257 @@ fn format_selected_line(
     // Visually it makes no difference, but there are extra characters in the output,
     // making it harder to validate and compare results.
     if !remaining_non_match.is_empty() {
-        result.push_str(&format_one_piece(
-            &remaining_non_match,
-            options.map(|o| o.selected_line),
-        ));
+        result.push_str(&format_one_piece(&remaining_non_match, line_style));
     }
 
     result
 }
 
-fn format_line_prefix(
-    file_name: &Option<String>,
-    line_number: &Option<usize>,
-    formatting: &Formatting,
-) -> Option<String> {
-    let mut result = None;
-    let options = formatting.options();
+/// Makes the line pointing at `column_number` (1-based) of `line` printed after `prefix`,
+/// e.g. `    ^` for the column 3 after `1:`.
+/// Tabs are kept as they are, so that the caret is aligned however wide they are displayed.
+///
+fn format_caret(
+    prefix: &str,
+    line: &str,
+    column_number: usize,
+    options: Option<FormattingOptions>,
+) -> String {
+    let indent = prefix
+        .chars()
+        .chain(line.chars().take(column_number - 1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+    indent + &format_one_piece("^", options.map(|o| o.separator))
+}
 
-    if let Some(file_name) = file_name {
-        let result = result.get_or_insert(String::new());
-        result.push_str(&format_one_piece(file_name, options.map(|o| o.file_name)));
-        result.push_str(&format_one_piece(":", options.map(|o| o.separator)));
-    }
+/// Lists the positions of the matched characters, e.g. `[0,3,5,7]`.
+///
+fn format_match_positions(fuzzy_match: &FuzzyMatch) -> String {
+    let positions = fuzzy_match
+        .positions()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    format!("[{}]", positions.join(","))
+}
 
-    if let Some(line_number) = line_number {
-        let result = result.get_or_insert(String::new());
-        result.push_str(&format_one_piece(
-            &line_number.to_string(),
-            options.map(|o| o.line_number),
-        ));
-        result.push_str(&format_one_piece(":", options.map(|o| o.separator)));
+/// The fields printed around a line in the regular layout, the absent ones are skipped.
+///
+#[derive(Default)]
+struct LineFields<'a> {
+    file_name: Option<&'a str>,
+    /// The file name as found (rather than as printed), the hyperlinks point to it.
+    path: Option<&'a str>,
+    line_number: Option<usize>,
+    /// The minimum width of the line number (it is right-aligned).
+    line_number_width: usize,
+    column_number: Option<usize>,
+    context_count: Option<(usize, usize)>,
+    score: Option<&'a str>,
+}
+
+impl LineFields<'_> {
+    /// Formats `line` (already styled) together with the fields in `field_order`, separated by `field_separator`.
+    /// `line` itself is printed only if [`OutputField::Match`] is in `field_order`.
+    ///
+    fn format_line(
+        &self,
+        line: &str,
+        field_order: &[OutputField],
+        formatting: &Formatting,
+        field_separator: &str,
+    ) -> String {
+        match self.format_fields(field_order, formatting, field_separator) {
+            (Some(before), after) => before + line + &after,
+            (None, fields) => fields,
+        }
     }
 
-    result
-}
+    /// Formats the fields in `field_order`, returning the ones preceding the line (each followed by `field_separator`)
+    /// and the ones following it (each preceded by `field_separator`).
+    /// If the line is not in `field_order`, the former is [`None`] and the latter are all the fields separated
+    /// by `field_separator`.
+    ///
+    fn format_fields(
+        &self,
+        field_order: &[OutputField],
+        formatting: &Formatting,
+        field_separator: &str,
+    ) -> (Option<String>, String) {
+        let options = formatting.options();
+        let separator = format_one_piece(field_separator, options.map(|o| o.separator));
 
-fn format_one_piece(s: &str, style: Option<Style>) -> String {
-    match style {
-        Some(style) => s.paint(style).to_string(),
-        None => s.to_string(),
+        let mut before = Vec::new();
+        let mut after = None;
+        for field in field_order {
+            let piece = match field {
+                OutputField::File => self
+                    .file_name
+                    .map(|file_name| format_one_piece(file_name, options.map(|o| o.file_name))),
+                OutputField::Line => {
+                    let line_number = self.line_number.map(|line_number| {
+                        format_one_piece(
+                            &format!("{line_number:>width$}", width = self.line_number_width),
+                            options.map(|o| o.line_number),
+                        )
+                    });
+                    // without line numbers the count makes up a field of its own
+                    let context_count = self
+                        .context_count
+                        .map(|context_count| format_context_count(context_count, options));
+                    match (line_number, context_count) {
+                        (None, None) => None,
+                        (line_number, context_count) => Some(
+                            line_number.unwrap_or_default() + &context_count.unwrap_or_default(),
+                        ),
+                    }
+                }
+                // columns are styled the same way as line numbers
+                OutputField::Column => self.column_number.map(|column_number| {
+                    format_one_piece(&column_number.to_string(), options.map(|o| o.line_number))
+                }),
+                OutputField::Score => self
+                    .score
+                    .map(|score| format_one_piece(score, options.map(|o| o.score))),
+                OutputField::Match => {
+                    after.get_or_insert_with(Vec::new);
+                    continue;
+                }
+            };
+            if let Some(piece) = piece {
+                after.as_mut().unwrap_or(&mut before).push(piece);
+            }
+        }
+
+        let (before, after): (Option<String>, String) = match after {
+            Some(after) => (
+                Some(
+                    before
+                        .iter()
+                        .map(|piece| format!("{piece}{separator}"))
+                        .collect(),
+                ),
+                after
+                    .iter()
+                    .map(|piece| format!("{separator}{piece}"))
+                    .collect(),
+            ),
+            None => (None, before.join(&separator)),
+        };
+
+        if options.is_some_and(|o| o.hyperlinks) {
+            if let Some(url) = self
+                .path
+                .zip(self.line_number)
+                .and_then(|(file_name, line_number)| file_url(file_name, line_number))
+            {
+                // the link is put on the fields preceding the line, unless there are none
+                return match before {
+                    Some(before) if !before.is_empty() => (Some(hyperlink(&url, &before)), after),
+                    before if !after.is_empty() => (before, hyperlink(&url, &after)),
+                    before => (before, after),
+                };
+            }
+        }
+
+        (before, after)
     }
 }
 
-fn group_indices(indices: &[usize]) -> Vec<Range<usize>> {
-    if indices.is_empty() {
-        return Vec::new();
-    }
+fn format_context_count(
+    (before, after): (usize, usize),
+    options: Option<FormattingOptions>,
+) -> String {
+    format_one_piece(
+        &format!("[{before}+{after}]"),
+        options.map(|o| o.context_count),
+    )
+}
 
-    let mut ret = Vec::new();
-    let mut itr = indices.iter();
-    // we've already handled the case of an empty input, it is safe to unwrap
-    let mut start = *itr.next().unwrap();
+/// Renders the score of `result` according to `format`.
+/// The percentage is [`MatchingResult::normalized_score`], so a score bonus counts there too (up to 100).
+///
+fn format_score(result: &MatchingResult, format: ScoreFormat) -> String {
+    match format {
+        ScoreFormat::Decimal => result.score().to_string(),
+        ScoreFormat::Hex => format!("{:x}", result.score()),
+        ScoreFormat::Percent => format!("{:.0}", result.normalized_score() * 100.0),
+    }
+}
 
-    for (i, x) in itr.enumerate() {
-        if x - indices[i] != 1 {
-            let end = indices[i];
-            ret.push(Range {
-                start,
-                end: end + 1,
-            });
-            start = *x;
+/// Makes a `file://` URL pointing to the given line of the given file.
+/// Returns [`None`] if the absolute path of the file cannot be determined.
+///
+fn file_url(file_name: &str, line_number: usize) -> Option<String> {
+    let path = fs::canonicalize(file_name).ok()?;
+    let mut url = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            url.push(char::from(byte));
+        } else {
+            url.push_str(&format!("%{byte:02X}"));
         }
     }
-    // again, the case of an empty input is already handled so it is safe to unwrap here too
-    ret.push(Range {
-        start,
-        end: indices.last().unwrap() + 1,
-    });
+    url.push_str(&format!("#{line_number}"));
+    Some(url)
+}
 
-    debug!("Match indices {:?} -> ranges {:?}", indices, ret);
+/// Wraps `text` into an OSC 8 terminal hyperlink pointing to `url`.
+///
+fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
 
-    ret
+fn format_one_piece(s: &str, style: Option<Style>) -> String {
+    match style {
+        Some(style) => s.paint(style).to_string(),
+        None => s.to_string(),
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::cli::formatting::FormattingOptions;
+    use crate::cli::formatting::ColorGradient;
+    use crate::matching_results::result::MatchingResultBuilder;
+    use yansi::Color;
+
+    fn formatted(
+        matches: &[MatchingResult],
+        formatting: &Formatting,
+        field_separator: &str,
+        print_context_count: bool,
+    ) -> String {
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            matches,
+            formatting,
+            field_separator,
+            OutputOptions {
+                context_count: print_context_count,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        String::from_utf8(dest).unwrap()
+    }
 
     #[test]
     fn results_output_selected_match_default() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
+            MatchingResultBuilder::new("te", "test").build(),
+            MatchingResultBuilder::new("t", "test").build(),
+            MatchingResultBuilder::new("tet", "test").build(),
         ];
         assert_eq!(
-            format_results(&results, &Formatting::On(FormattingOptions::default())),
+            formatted(
+                &results,
+                &Formatting::On(FormattingOptions::default()),
+                ":",
+                false
+            ),
             format!(
                 "{}st\n\
                 tes{}\n\
@@ -263,242 +834,883 @@ mod test {
     #[test]
     fn results_output_selected_match_off() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
+            MatchingResultBuilder::new("te", "test").build(),
+            MatchingResultBuilder::new("t", "test").build(),
+            MatchingResultBuilder::new("tet", "test").build(),
+        ];
+        assert_eq!(
+            formatted(&results, &Formatting::Off, ":", false),
+            "test\n\
+            test\n\
+            test\n"
+        )
+    }
+
+    #[test]
+    fn results_output_selected_match_custom() {
+        let results = vec![
+            MatchingResultBuilder::new("te", "test").build(),
+            MatchingResultBuilder::new("t", "test").build(),
+            MatchingResultBuilder::new("tet", "test").build(),
+        ];
+        assert_eq!(
+            formatted(
+                &results,
+                &Formatting::On(FormattingOptions {
+                    selected_match: Style::new().yellow(),
+                    ..Default::default()
+                }),
+                ":",
+                false
+            ),
+            format!(
+                "{}st\n\
+                tes{}\n\
+                {}s{}\n",
+                "te".yellow(),
+                't'.yellow(),
+                "te".yellow(),
+                't'.yellow(),
+            )
+        )
+    }
+
+    #[test]
+    fn results_output_selected_line_default() {
+        let results = vec![
+            MatchingResultBuilder::new("te", "test").build(),
+            MatchingResultBuilder::new("t", "test").build(),
+            MatchingResultBuilder::new("tet", "test").build(),
+        ];
+        assert_eq!(
+            formatted(
+                &results,
+                &Formatting::On(FormattingOptions::default()),
+                ":",
+                false
+            ),
+            format!(
+                "{}st\n\
+                tes{}\n\
+                {}s{}\n",
+                "te".red().bold(),
+                't'.red().bold(),
+                "te".red().bold(),
+                't'.red().bold(),
+            )
+        )
+    }
+
+    #[test]
+    fn results_output_selected_line_off() {
+        let results = vec![
+            MatchingResultBuilder::new("te", "test").build(),
+            MatchingResultBuilder::new("t", "test").build(),
+            MatchingResultBuilder::new("tet", "test").build(),
+        ];
+        assert_eq!(
+            formatted(&results, &Formatting::Off, ":", false),
+            "test\n\
+            test\n\
+            test\n"
+        )
+    }
+
+    #[test]
+    fn results_output_selected_line_custom() {
+        let results = vec![
+            MatchingResultBuilder::new("te", "test").build(),
+            MatchingResultBuilder::new("t", "test").build(),
+            MatchingResultBuilder::new("tet", "test").build(),
+        ];
+        assert_eq!(
+            formatted(
+                &results,
+                &Formatting::On(FormattingOptions {
+                    selected_line: Style::new().yellow(),
+                    ..Default::default()
+                }),
+                ":",
+                false
+            ),
+            format!(
+                "{}{}\n\
+                {}{}\n\
+                {}{}{}\n",
+                "te".red().bold(),
+                "st".yellow(),
+                "tes".yellow(),
+                't'.red().bold(),
+                "te".red().bold(),
+                's'.yellow(),
+                't'.red().bold(),
+            )
+        )
+    }
+
+    #[test]
+    fn results_output_column_number_default() {
+        let results = vec![MatchingResultBuilder::new("st", "test")
+            .line_number(42)
+            .column_number(3)
+            .build()];
+        assert_eq!(
+            formatted(
+                &results,
+                &Formatting::On(FormattingOptions::default()),
+                ":",
+                false
+            ),
+            format!(
+                "{}{}{}{}te{}\n",
+                "42".green(),
+                ':'.cyan(),
+                "3".green(),
+                ':'.cyan(),
+                "st".red().bold(),
+            )
+        )
+    }
+
+    #[test]
+    fn results_output_column_number_off() {
+        let results = vec![
+            MatchingResultBuilder::new("st", "test")
+                .file_name("test.txt")
+                .line_number(42)
+                .column_number(3)
+                .before_context(&["before"])
+                .after_context(&["after"])
+                .build(),
+            MatchingResultBuilder::new("t", "test")
+                .column_number(1)
+                .build(),
+        ];
+        assert_eq!(
+            formatted(&results, &Formatting::Off, ":", false),
+            "test.txt:41:before\n\
+            test.txt:42:3:test\n\
+            test.txt:43:after\n\
+            1:test\n"
+        )
+    }
+
+    #[test]
+    fn results_output_field_separator_off() {
+        let results = vec![MatchingResultBuilder::new("t", "test")
+            .file_name("test.txt")
+            .line_number(42)
+            .column_number(1)
+            .after_context(&["after"])
+            .build()];
+        assert_eq!(
+            formatted(&results, &Formatting::Off, "\t", false),
+            "test.txt\t42\t1\ttest\n\
+            test.txt\t43\tafter\n"
+        )
+    }
+
+    #[test]
+    fn results_output_field_separator_default() {
+        let results = vec![MatchingResultBuilder::new("t", "test")
+            .line_number(42)
+            .build()];
+        assert_eq!(
+            formatted(
+                &results,
+                &Formatting::On(FormattingOptions::default()),
+                " | ",
+                false
+            ),
+            format!("{}{}tes{}\n", "42".green(), " | ".cyan(), 't'.red().bold())
+        )
+    }
+
+    #[test]
+    fn results_output_context_count_off() {
+        let results = vec![
+            MatchingResultBuilder::new("t", "test")
+                .file_name("test.txt")
+                .line_number(1)
+                .after_context(&["after"])
+                .build(),
+            MatchingResultBuilder::new("t", "test").build(),
+        ];
+        assert_eq!(
+            formatted(&results, &Formatting::Off, ":", true),
+            "test.txt:1[0+1]:test\n\
+            test.txt:2:after\n\
+            [0+0]:test\n"
+        )
+    }
+
+    #[test]
+    fn results_output_match_positions() {
+        let results = vec![
+            MatchingResultBuilder::new("tt", "test")
+                .line_number(1)
+                .after_context(&["after"])
+                .build(),
+            MatchingResultBuilder::new("es", "test").build(),
+        ];
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::On(FormattingOptions::default()),
+            ":",
+            OutputOptions {
+                match_positions: true,
+                ..Default::default()
             },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(dest).unwrap(),
+            format!(
+                "{}{}{}es{}\n\
+                [0,3]\n\
+                {}{}after\n\
+                t{}t\n\
+                [1,2]\n",
+                '1'.green(),
+                ':'.cyan(),
+                't'.red().bold(),
+                't'.red().bold(),
+                '2'.green(),
+                ':'.cyan(),
+                "es".red().bold(),
+            )
+        )
+    }
+
+    #[test]
+    fn results_output_score() {
+        let results = vec![MatchingResultBuilder::new("te", "test")
+            .line_number(1)
+            .after_context(&["after"])
+            .build()];
+        let score = results[0].fuzzy_match.score();
+        for (format, expected) in [
+            (ScoreFormat::Decimal, score.to_string()),
+            (ScoreFormat::Hex, format!("{score:x}")),
+            (ScoreFormat::Percent, String::from("100")),
+        ] {
+            let mut dest = Vec::new();
+            format_results(
+                &mut dest,
+                &results,
+                &Formatting::Off,
+                ":",
+                OutputOptions {
+                    score_format: Some(format),
+                    ..Default::default()
                 },
+            )
+            .unwrap();
+            assert_eq!(
+                String::from_utf8(dest).unwrap(),
+                format!("1:{expected}:test\n2:after\n"),
+                "{format:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn results_output_unmatched_markers() {
+        let results = vec![
+            MatchingResultBuilder::new("te", "test")
+                .line_number(2)
+                .before_context(&["first"])
+                .build(),
+            MatchingResultBuilder::new("te", "a test")
+                .line_number(4)
+                .before_context(&["third"])
+                .after_context(&["fifth", "sixth"])
+                .build(),
+        ];
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::Off,
+            ":",
+            OutputOptions {
+                unmatched_marker: Some("| "),
+                ..Default::default()
             },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(dest).unwrap(),
+            "| 1:first\n\
+            > 2:test\n\
+            | 3:third\n\
+            > 4:a test\n\
+            | 5:fifth\n\
+            | 6:sixth\n"
+        );
+    }
+
+    #[test]
+    fn results_output_unmatched_markers_caret() {
+        let results = vec![MatchingResultBuilder::new("te", "a test")
+            .line_number(4)
+            .column_number(3)
+            .build()];
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::Off,
+            ":",
+            OutputOptions {
+                caret: true,
+                unmatched_marker: Some("  "),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let output = String::from_utf8(dest).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "> 4:3:a test");
+        // the caret stays under the first matched character
+        assert_eq!(lines[1].find('^'), lines[0].find('t'));
+    }
+
+    #[test]
+    fn results_output_score_with_bonus() {
+        let results = vec![MatchingResultBuilder::new("te", "test")
+            .score_bonus(20)
+            .build()];
+        let score = results[0].fuzzy_match.score() + 20;
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::Off,
+            ":",
+            OutputOptions {
+                score_format: Some(ScoreFormat::Decimal),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(dest).unwrap(), format!("{score}:test\n"));
+    }
+
+    #[test]
+    fn results_output_score_formatted() {
+        let results = vec![MatchingResultBuilder::new("es", "test").build()];
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::On(FormattingOptions {
+                score: Style::new().blue(),
+                ..Default::default()
+            }),
+            ":",
+            OutputOptions {
+                score_format: Some(ScoreFormat::Decimal),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(dest).unwrap(),
+            format!(
+                "{}{}t{}t\n",
+                results[0].fuzzy_match.score().to_string().blue(),
+                ':'.cyan(),
+                "es".red().bold(),
+            )
+        );
+    }
+
+    #[test]
+    fn results_output_caret() {
+        let results = vec![MatchingResultBuilder::new("es", "a\ttest")
+            .line_number(1)
+            .column_number(4)
+            .after_context(&["after"])
+            .build()];
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::Off,
+            ":",
+            OutputOptions {
+                caret: true,
+                match_positions: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(dest).unwrap(),
+            "1:4:a\ttest\n     \t ^\n[3,4]\n2:after\n"
+        );
+    }
+
+    #[test]
+    fn results_output_caret_formatted() {
+        let results = vec![MatchingResultBuilder::new("es", "test")
+            .column_number(2)
+            .build()];
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::On(FormattingOptions::default()),
+            ":",
+            OutputOptions {
+                caret: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(dest).unwrap(),
+            format!(
+                "{}{}t{}t\n   {}\n",
+                '2'.green(),
+                ':'.cyan(),
+                "es".red().bold(),
+                '^'.cyan(),
+            )
+        );
+    }
+
+    #[test]
+    fn results_output_caret_no_column() {
+        let results = vec![MatchingResultBuilder::new("es", "test").build()];
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::Off,
+            ":",
+            OutputOptions {
+                caret: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(dest).unwrap(), "test\n");
+    }
+
+    fn with_field_order(
+        matches: &[MatchingResult],
+        formatting: &Formatting,
+        field_order: &[OutputField],
+    ) -> String {
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            matches,
+            formatting,
+            ":",
+            OutputOptions {
+                field_order: Some(field_order),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        String::from_utf8(dest).unwrap()
+    }
+
+    #[test]
+    fn results_output_field_order_reordered() {
+        let results = vec![MatchingResultBuilder::new("es", "test")
+            .file_name("file.txt")
+            .line_number(3)
+            .before_context(&["before"])
+            .build()];
+        assert_eq!(
+            with_field_order(
+                &results,
+                &Formatting::Off,
+                &[OutputField::Line, OutputField::Match, OutputField::File]
+            ),
+            "2:before:file.txt\n3:test:file.txt\n"
+        );
+    }
+
+    #[test]
+    fn results_output_field_order_omitted() {
+        let results = vec![MatchingResultBuilder::new("es", "test")
+            .file_name("file.txt")
+            .line_number(3)
+            .column_number(2)
+            .build()];
+        assert_eq!(
+            with_field_order(
+                &results,
+                &Formatting::Off,
+                &[OutputField::Line, OutputField::Match]
+            ),
+            "3:test\n"
+        );
+        assert_eq!(
+            with_field_order(
+                &results,
+                &Formatting::Off,
+                &[OutputField::File, OutputField::Column]
+            ),
+            "file.txt:2\n"
+        );
+        // the absent fields are skipped as well
+        assert_eq!(
+            with_field_order(
+                &results,
+                &Formatting::Off,
+                &[OutputField::Score, OutputField::Match, OutputField::Line]
+            ),
+            "test:3\n"
+        );
+    }
+
+    #[test]
+    fn results_output_field_order_formatted() {
+        let results = vec![MatchingResultBuilder::new("es", "test")
+            .line_number(3)
+            .build()];
+        assert_eq!(
+            with_field_order(
+                &results,
+                &Formatting::On(FormattingOptions::default()),
+                &[OutputField::Match, OutputField::Line]
+            ),
+            format!("t{}t{}{}\n", "es".red().bold(), ':'.cyan(), "3".green())
+        );
+    }
+
+    #[test]
+    fn results_output_field_order_caret() {
+        let results = vec![MatchingResultBuilder::new("es", "test")
+            .line_number(3)
+            .column_number(2)
+            .build()];
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::Off,
+            ":",
+            OutputOptions {
+                caret: true,
+                field_order: Some(&[OutputField::Column, OutputField::Match, OutputField::Line]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(dest).unwrap(), "2:test:3\n   ^\n");
+    }
+
+    #[test]
+    fn results_output_highlighted_query() {
+        let results = vec![MatchingResultBuilder::new("es", "test")
+            .before_context(&["tests"])
+            .after_context(&["none"])
+            .build()];
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::On(FormattingOptions::default()),
+            ":",
+            OutputOptions {
+                highlighted_query: Some("es"),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(dest).unwrap(),
+            format!(
+                "{}{}{}\n{}{}{}\n{}\n",
+                "t".paint(Style::new()),
+                "es".paint(Style::new().red()),
+                "ts".paint(Style::new()),
+                "t".paint(Style::new()),
+                "es".paint(Style::new().red().bold()),
+                "t".paint(Style::new()),
+                "none".paint(Style::new()),
+            )
+        );
+    }
+
+    #[test]
+    fn results_output_highlighted_query_off() {
+        let results = vec![MatchingResultBuilder::new("es", "test")
+            .before_context(&["tests"])
+            .build()];
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::Off,
+            ":",
+            OutputOptions {
+                highlighted_query: Some("es"),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(dest).unwrap(), "tests\ntest\n");
+    }
+
+    #[test]
+    fn results_output_file_headers() {
+        let results = vec![
+            MatchingResultBuilder::new("es", "test")
+                .file_name("first.txt")
+                .line_number(3)
+                .build(),
+            MatchingResultBuilder::new("es", "best")
+                .file_name("first.txt")
+                .line_number(5)
+                .after_context(&["after"])
+                .build(),
+            MatchingResultBuilder::new("es", "rest")
+                .file_name("second.txt")
+                .line_number(1)
+                .build(),
+        ];
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::Off,
+            ":",
+            OutputOptions {
+                file_headers: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(dest).unwrap(),
+            "==> first.txt <==\n3:test\n5:best\n6:after\n==> second.txt <==\n1:rest\n"
+        );
+    }
+
+    #[test]
+    fn results_output_file_headers_without_file_names() {
+        let results = vec![MatchingResultBuilder::new("es", "test").build()];
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::Off,
+            ":",
+            OutputOptions {
+                file_headers: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(dest).unwrap(), "test\n");
+    }
+
+    #[test]
+    fn results_output_line_number_width() {
+        let results = vec![
+            MatchingResultBuilder::new("es", "test")
+                .line_number(7)
+                .before_context(&["before"])
+                .build(),
+            MatchingResultBuilder::new("es", "test")
+                .line_number(42)
+                .build(),
+        ];
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::Off,
+            ":",
+            OutputOptions {
+                line_number_width: 3,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(dest).unwrap(),
+            "  6:before\n  7:test\n 42:test\n"
+        );
+    }
+
+    #[test]
+    fn results_output_auto_align() {
+        let results = vec![
+            MatchingResultBuilder::new("es", "test")
+                .line_number(7)
+                .build(),
+            MatchingResultBuilder::new("es", "test")
+                .line_number(98)
+                .after_context(&["99", "100"])
+                .build(),
+        ];
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            &results,
+            &Formatting::Off,
+            ":",
+            OutputOptions {
+                line_number_width: 2,
+                auto_align: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(dest).unwrap(),
+            "  7:test\n 98:test\n 99:99\n100:100\n"
+        );
+    }
+
+    #[test]
+    fn max_line_number_width_no_line_numbers() {
+        let results = vec![MatchingResultBuilder::new("es", "test").build()];
+        assert_eq!(max_line_number_width(&results), 0);
+        assert_eq!(max_line_number_width(&[]), 0);
+    }
+
+    fn with_context_separator(
+        matches: &[MatchingResult],
+        formatting: &Formatting,
+        separator: &str,
+    ) -> String {
+        let mut dest = Vec::new();
+        format_results(
+            &mut dest,
+            matches,
+            formatting,
+            ":",
+            OutputOptions {
+                context_separator: Some(separator),
+                ..Default::default()
             },
-        ];
-        assert_eq!(
-            format_results(&results, &Formatting::Off),
-            "test\n\
-            test\n\
-            test\n"
         )
+        .unwrap();
+        String::from_utf8(dest).unwrap()
     }
 
     #[test]
-    fn results_output_selected_match_custom() {
+    fn results_output_context_separator_adjacent() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
+            MatchingResultBuilder::new("es", "test")
+                .line_number(2)
+                .after_context(&["three"])
+                .build(),
+            MatchingResultBuilder::new("es", "test")
+                .line_number(5)
+                .before_context(&["four"])
+                .build(),
         ];
         assert_eq!(
-            format_results(
-                &results,
-                &Formatting::On(FormattingOptions {
-                    selected_match: Style::new().yellow(),
-                    ..Default::default()
-                })
-            ),
-            format!(
-                "{}st\n\
-                tes{}\n\
-                {}s{}\n",
-                "te".yellow(),
-                't'.yellow(),
-                "te".yellow(),
-                't'.yellow(),
-            )
-        )
+            with_context_separator(&results, &Formatting::Off, "--"),
+            "2:test\n3:three\n4:four\n5:test\n"
+        );
     }
 
     #[test]
-    fn results_output_selected_line_default() {
+    fn results_output_context_separator_gap() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
+            MatchingResultBuilder::new("es", "test")
+                .file_name("file.txt")
+                .line_number(2)
+                .after_context(&["three"])
+                .build(),
+            MatchingResultBuilder::new("es", "test")
+                .file_name("file.txt")
+                .line_number(6)
+                .before_context(&["five"])
+                .build(),
+            // earlier in the file, so not adjacent either
+            MatchingResultBuilder::new("es", "test")
+                .file_name("file.txt")
+                .line_number(1)
+                .build(),
         ];
         assert_eq!(
-            format_results(&results, &Formatting::On(FormattingOptions::default())),
-            format!(
-                "{}st\n\
-                tes{}\n\
-                {}s{}\n",
-                "te".red().bold(),
-                't'.red().bold(),
-                "te".red().bold(),
-                't'.red().bold(),
-            )
-        )
+            with_context_separator(&results, &Formatting::Off, "--"),
+            "file.txt:2:test\nfile.txt:3:three\n--\nfile.txt:5:five\nfile.txt:6:test\n--\nfile.txt:1:test\n"
+        );
     }
 
     #[test]
-    fn results_output_selected_line_off() {
+    fn results_output_context_separator_files() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
+            MatchingResultBuilder::new("es", "test")
+                .file_name("a.txt")
+                .line_number(1)
+                .build(),
+            MatchingResultBuilder::new("es", "test")
+                .file_name("b.txt")
+                .line_number(2)
+                .build(),
         ];
         assert_eq!(
-            format_results(&results, &Formatting::Off),
-            "test\n\
-            test\n\
-            test\n"
-        )
+            with_context_separator(&results, &Formatting::Off, "== {source}:{line} =="),
+            "a.txt:1:test\n== b.txt:2 ==\nb.txt:2:test\n"
+        );
     }
 
     #[test]
-    fn results_output_selected_line_custom() {
+    fn results_output_context_separator_formatted() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
+            MatchingResultBuilder::new("es", "test").build(),
+            MatchingResultBuilder::new("es", "test").build(),
         ];
         assert_eq!(
-            format_results(
+            with_context_separator(
+                &results,
+                &Formatting::On(FormattingOptions::default()),
+                "{source}{line}--"
+            ),
+            format!(
+                "t{}t\n{}\nt{}t\n",
+                "es".red().bold(),
+                "--".cyan(),
+                "es".red().bold()
+            )
+        );
+    }
+
+    #[test]
+    fn results_output_context_count_default() {
+        let results = vec![MatchingResultBuilder::new("t", "test")
+            .line_number(42)
+            .after_context(&["four", "five"])
+            .build()];
+        assert_eq!(
+            formatted(
                 &results,
                 &Formatting::On(FormattingOptions {
-                    selected_line: Style::new().yellow(),
+                    context_count: Style::new().yellow(),
                     ..Default::default()
-                })
+                }),
+                ":",
+                true
             ),
             format!(
-                "{}{}\n\
-                {}{}\n\
-                {}{}{}\n",
-                "te".red().bold(),
-                "st".yellow(),
-                "tes".yellow(),
-                't'.red().bold(),
-                "te".red().bold(),
-                's'.yellow(),
+                "{}{}{}tes{}\n\
+                {}{}four\n\
+                {}{}five\n",
+                "42".green(),
+                "[0+2]".yellow(),
+                ':'.cyan(),
                 't'.red().bold(),
+                "43".green(),
+                ':'.cyan(),
+                "44".green(),
+                ':'.cyan(),
             )
         )
     }
@@ -506,39 +1718,23 @@ mod test {
     #[test]
     fn results_output_line_number_default() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: None,
-                line_number: Some(42),
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: None,
-                line_number: Some(100500),
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: None,
-                line_number: Some(13),
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
+            MatchingResultBuilder::new("te", "test")
+                .line_number(42)
+                .build(),
+            MatchingResultBuilder::new("t", "test")
+                .line_number(100500)
+                .build(),
+            MatchingResultBuilder::new("tet", "test")
+                .line_number(13)
+                .build(),
         ];
         assert_eq!(
-            format_results(&results, &Formatting::On(FormattingOptions::default())),
+            formatted(
+                &results,
+                &Formatting::On(FormattingOptions::default()),
+                ":",
+                false
+            ),
             format!(
                 "{}{}{}st\n\
                 {}{}tes{}\n\
@@ -560,39 +1756,18 @@ mod test {
     #[test]
     fn results_output_line_number_off() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: None,
-                line_number: Some(42),
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: None,
-                line_number: Some(100500),
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: None,
-                line_number: Some(13),
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
+            MatchingResultBuilder::new("te", "test")
+                .line_number(42)
+                .build(),
+            MatchingResultBuilder::new("t", "test")
+                .line_number(100500)
+                .build(),
+            MatchingResultBuilder::new("tet", "test")
+                .line_number(13)
+                .build(),
         ];
         assert_eq!(
-            format_results(&results, &Formatting::Off),
+            formatted(&results, &Formatting::Off, ":", false),
             "42:test\n\
             100500:test\n\
             13:test\n"
@@ -602,44 +1777,25 @@ mod test {
     #[test]
     fn results_output_line_number_custom() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: None,
-                line_number: Some(42),
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: None,
-                line_number: Some(100500),
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: None,
-                line_number: Some(13),
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
+            MatchingResultBuilder::new("te", "test")
+                .line_number(42)
+                .build(),
+            MatchingResultBuilder::new("t", "test")
+                .line_number(100500)
+                .build(),
+            MatchingResultBuilder::new("tet", "test")
+                .line_number(13)
+                .build(),
         ];
         assert_eq!(
-            format_results(
+            formatted(
                 &results,
                 &Formatting::On(FormattingOptions {
                     line_number: Style::new().yellow(),
                     ..Default::default()
-                })
+                }),
+                ":",
+                false
             ),
             format!(
                 "{}{}{}st\n\
@@ -662,39 +1818,23 @@ mod test {
     #[test]
     fn results_output_file_name_default() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: Some(String::from("First")),
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: Some(String::from("Second")),
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: Some(String::from("Third")),
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
+            MatchingResultBuilder::new("te", "test")
+                .file_name("First")
+                .build(),
+            MatchingResultBuilder::new("t", "test")
+                .file_name("Second")
+                .build(),
+            MatchingResultBuilder::new("tet", "test")
+                .file_name("Third")
+                .build(),
         ];
         assert_eq!(
-            format_results(&results, &Formatting::On(FormattingOptions::default())),
+            formatted(
+                &results,
+                &Formatting::On(FormattingOptions::default()),
+                ":",
+                false
+            ),
             format!(
                 "{}{}{}st\n\
                 {}{}tes{}\n\
@@ -716,39 +1856,18 @@ mod test {
     #[test]
     fn results_output_file_name_off() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: Some(String::from("First")),
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: Some(String::from("Second")),
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: Some(String::from("Third")),
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
+            MatchingResultBuilder::new("te", "test")
+                .file_name("First")
+                .build(),
+            MatchingResultBuilder::new("t", "test")
+                .file_name("Second")
+                .build(),
+            MatchingResultBuilder::new("tet", "test")
+                .file_name("Third")
+                .build(),
         ];
         assert_eq!(
-            format_results(&results, &Formatting::Off),
+            formatted(&results, &Formatting::Off, ":", false),
             "First:test\n\
             Second:test\n\
             Third:test\n"
@@ -758,44 +1877,25 @@ mod test {
     #[test]
     fn results_output_file_name_custom() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: Some(String::from("First")),
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: Some(String::from("Second")),
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: Some(String::from("Third")),
-                line_number: None,
-                context: Context {
-                    before: vec![],
-                    after: vec![],
-                },
-            },
+            MatchingResultBuilder::new("te", "test")
+                .file_name("First")
+                .build(),
+            MatchingResultBuilder::new("t", "test")
+                .file_name("Second")
+                .build(),
+            MatchingResultBuilder::new("tet", "test")
+                .file_name("Third")
+                .build(),
         ];
         assert_eq!(
-            format_results(
+            formatted(
                 &results,
                 &Formatting::On(FormattingOptions {
                     file_name: Style::new().yellow(),
                     ..Default::default()
-                })
+                }),
+                ":",
+                false
             ),
             format!(
                 "{}{}{}st\n\
@@ -818,57 +1918,26 @@ mod test {
     #[test]
     fn results_output_context_default() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![
-                        String::from("first_before_one"),
-                        String::from("first_before_two"),
-                    ],
-                    after: vec![
-                        String::from("first_after_one"),
-                        String::from("first_after_two"),
-                    ],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![
-                        String::from("second_before_one"),
-                        String::from("second_before_two"),
-                    ],
-                    after: vec![
-                        String::from("second_after_one"),
-                        String::from("second_after_two"),
-                    ],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![
-                        String::from("third_before_one"),
-                        String::from("third_before_two"),
-                    ],
-                    after: vec![
-                        String::from("third_after_one"),
-                        String::from("third_after_two"),
-                    ],
-                },
-            },
+            MatchingResultBuilder::new("te", "test")
+                .before_context(&["first_before_one", "first_before_two"])
+                .after_context(&["first_after_one", "first_after_two"])
+                .build(),
+            MatchingResultBuilder::new("t", "test")
+                .before_context(&["second_before_one", "second_before_two"])
+                .after_context(&["second_after_one", "second_after_two"])
+                .build(),
+            MatchingResultBuilder::new("tet", "test")
+                .before_context(&["third_before_one", "third_before_two"])
+                .after_context(&["third_after_one", "third_after_two"])
+                .build(),
         ];
         assert_eq!(
-            format_results(&results, &Formatting::On(FormattingOptions::default())),
+            formatted(
+                &results,
+                &Formatting::On(FormattingOptions::default()),
+                ":",
+                false
+            ),
             format!(
                 "first_before_one\n\
                 first_before_two\n\
@@ -896,57 +1965,21 @@ mod test {
     #[test]
     fn results_output_context_off() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![
-                        String::from("first_before_one"),
-                        String::from("first_before_two"),
-                    ],
-                    after: vec![
-                        String::from("first_after_one"),
-                        String::from("first_after_two"),
-                    ],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![
-                        String::from("second_before_one"),
-                        String::from("second_before_two"),
-                    ],
-                    after: vec![
-                        String::from("second_after_one"),
-                        String::from("second_after_two"),
-                    ],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![
-                        String::from("third_before_one"),
-                        String::from("third_before_two"),
-                    ],
-                    after: vec![
-                        String::from("third_after_one"),
-                        String::from("third_after_two"),
-                    ],
-                },
-            },
+            MatchingResultBuilder::new("te", "test")
+                .before_context(&["first_before_one", "first_before_two"])
+                .after_context(&["first_after_one", "first_after_two"])
+                .build(),
+            MatchingResultBuilder::new("t", "test")
+                .before_context(&["second_before_one", "second_before_two"])
+                .after_context(&["second_after_one", "second_after_two"])
+                .build(),
+            MatchingResultBuilder::new("tet", "test")
+                .before_context(&["third_before_one", "third_before_two"])
+                .after_context(&["third_after_one", "third_after_two"])
+                .build(),
         ];
         assert_eq!(
-            format_results(&results, &Formatting::Off),
+            formatted(&results, &Formatting::Off, ":", false),
             "first_before_one\n\
             first_before_two\n\
             test\n\
@@ -968,62 +2001,30 @@ mod test {
     #[test]
     fn results_output_context_custom() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![
-                        String::from("first_before_one"),
-                        String::from("first_before_two"),
-                    ],
-                    after: vec![
-                        String::from("first_after_one"),
-                        String::from("first_after_two"),
-                    ],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![
-                        String::from("second_before_one"),
-                        String::from("second_before_two"),
-                    ],
-                    after: vec![
-                        String::from("second_after_one"),
-                        String::from("second_after_two"),
-                    ],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![
-                        String::from("third_before_one"),
-                        String::from("third_before_two"),
-                    ],
-                    after: vec![
-                        String::from("third_after_one"),
-                        String::from("third_after_two"),
-                    ],
-                },
-            },
+            MatchingResultBuilder::new("te", "test")
+                .before_context(&["first_before_one", "first_before_two"])
+                .after_context(&["first_after_one", "first_after_two"])
+                .build(),
+            MatchingResultBuilder::new("t", "test")
+                .before_context(&["second_before_one", "second_before_two"])
+                .after_context(&["second_after_one", "second_after_two"])
+                .build(),
+            MatchingResultBuilder::new("tet", "test")
+                .before_context(&["third_before_one", "third_before_two"])
+                .after_context(&["third_after_one", "third_after_two"])
+                .build(),
         ];
         assert_eq!(
-            format_results(
+            formatted(
                 &results,
                 &Formatting::On(FormattingOptions {
-                    context: Style::new().rgb(127, 127, 127).dim(),
+                    before_context: Style::new().rgb(127, 127, 127).dim(),
+                    after_context: Style::new().rgb(127, 127, 127).dim(),
+                    hyperlinks: false,
                     ..Default::default()
-                })
+                }),
+                ":",
+                false
             ),
             format!(
                 "{}\n\
@@ -1064,57 +2065,32 @@ mod test {
     #[test]
     fn results_output_all_default() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: Some(String::from("First")),
-                line_number: Some(42),
-                context: Context {
-                    before: vec![
-                        String::from("first_before_one"),
-                        String::from("first_before_two"),
-                    ],
-                    after: vec![
-                        String::from("first_after_one"),
-                        String::from("first_after_two"),
-                    ],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: Some(String::from("Second")),
-                line_number: Some(100500),
-                context: Context {
-                    before: vec![
-                        String::from("second_before_one"),
-                        String::from("second_before_two"),
-                    ],
-                    after: vec![
-                        String::from("second_after_one"),
-                        String::from("second_after_two"),
-                    ],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: Some(String::from("Third")),
-                line_number: Some(13),
-                context: Context {
-                    before: vec![
-                        String::from("third_before_one"),
-                        String::from("third_before_two"),
-                    ],
-                    after: vec![
-                        String::from("third_after_one"),
-                        String::from("third_after_two"),
-                    ],
-                },
-            },
+            MatchingResultBuilder::new("te", "test")
+                .file_name("First")
+                .line_number(42)
+                .before_context(&["first_before_one", "first_before_two"])
+                .after_context(&["first_after_one", "first_after_two"])
+                .build(),
+            MatchingResultBuilder::new("t", "test")
+                .file_name("Second")
+                .line_number(100500)
+                .before_context(&["second_before_one", "second_before_two"])
+                .after_context(&["second_after_one", "second_after_two"])
+                .build(),
+            MatchingResultBuilder::new("tet", "test")
+                .file_name("Third")
+                .line_number(13)
+                .before_context(&["third_before_one", "third_before_two"])
+                .after_context(&["third_after_one", "third_after_two"])
+                .build(),
         ];
         assert_eq!(
-            format_results(&results, &Formatting::On(FormattingOptions::default())),
+            formatted(
+                &results,
+                &Formatting::On(FormattingOptions::default()),
+                ":",
+                false
+            ),
             format!(
                 "{}{}{}{}first_before_one\n\
                 {}{}{}{}first_before_two\n\
@@ -1217,57 +2193,27 @@ mod test {
     #[test]
     fn results_output_all_off() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: Some(String::from("First")),
-                line_number: Some(42),
-                context: Context {
-                    before: vec![
-                        String::from("first_before_one"),
-                        String::from("first_before_two"),
-                    ],
-                    after: vec![
-                        String::from("first_after_one"),
-                        String::from("first_after_two"),
-                    ],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: Some(String::from("Second")),
-                line_number: Some(100500),
-                context: Context {
-                    before: vec![
-                        String::from("second_before_one"),
-                        String::from("second_before_two"),
-                    ],
-                    after: vec![
-                        String::from("second_after_one"),
-                        String::from("second_after_two"),
-                    ],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: Some(String::from("Third")),
-                line_number: Some(13),
-                context: Context {
-                    before: vec![
-                        String::from("third_before_one"),
-                        String::from("third_before_two"),
-                    ],
-                    after: vec![
-                        String::from("third_after_one"),
-                        String::from("third_after_two"),
-                    ],
-                },
-            },
+            MatchingResultBuilder::new("te", "test")
+                .file_name("First")
+                .line_number(42)
+                .before_context(&["first_before_one", "first_before_two"])
+                .after_context(&["first_after_one", "first_after_two"])
+                .build(),
+            MatchingResultBuilder::new("t", "test")
+                .file_name("Second")
+                .line_number(100500)
+                .before_context(&["second_before_one", "second_before_two"])
+                .after_context(&["second_after_one", "second_after_two"])
+                .build(),
+            MatchingResultBuilder::new("tet", "test")
+                .file_name("Third")
+                .line_number(13)
+                .before_context(&["third_before_one", "third_before_two"])
+                .after_context(&["third_after_one", "third_after_two"])
+                .build(),
         ];
         assert_eq!(
-            format_results(&results, &Formatting::Off),
+            formatted(&results, &Formatting::Off, ":", false),
             "First:40:first_before_one\n\
             First:41:first_before_two\n\
             First:42:test\n\
@@ -1289,57 +2235,27 @@ mod test {
     #[test]
     fn results_output_all_custom() {
         let results = vec![
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("te", "test").unwrap(),
-                file_name: Some(String::from("First")),
-                line_number: Some(42),
-                context: Context {
-                    before: vec![
-                        String::from("first_before_one"),
-                        String::from("first_before_two"),
-                    ],
-                    after: vec![
-                        String::from("first_after_one"),
-                        String::from("first_after_two"),
-                    ],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("t", "test").unwrap(),
-                file_name: Some(String::from("Second")),
-                line_number: Some(100500),
-                context: Context {
-                    before: vec![
-                        String::from("second_before_one"),
-                        String::from("second_before_two"),
-                    ],
-                    after: vec![
-                        String::from("second_after_one"),
-                        String::from("second_after_two"),
-                    ],
-                },
-            },
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tet", "test").unwrap(),
-                file_name: Some(String::from("Third")),
-                line_number: Some(13),
-                context: Context {
-                    before: vec![
-                        String::from("third_before_one"),
-                        String::from("third_before_two"),
-                    ],
-                    after: vec![
-                        String::from("third_after_one"),
-                        String::from("third_after_two"),
-                    ],
-                },
-            },
+            MatchingResultBuilder::new("te", "test")
+                .file_name("First")
+                .line_number(42)
+                .before_context(&["first_before_one", "first_before_two"])
+                .after_context(&["first_after_one", "first_after_two"])
+                .build(),
+            MatchingResultBuilder::new("t", "test")
+                .file_name("Second")
+                .line_number(100500)
+                .before_context(&["second_before_one", "second_before_two"])
+                .after_context(&["second_after_one", "second_after_two"])
+                .build(),
+            MatchingResultBuilder::new("tet", "test")
+                .file_name("Third")
+                .line_number(13)
+                .before_context(&["third_before_one", "third_before_two"])
+                .after_context(&["third_after_one", "third_after_two"])
+                .build(),
         ];
         assert_eq!(
-            format_results(
+            formatted(
                 &results,
                 &Formatting::On(FormattingOptions {
                     selected_match: Style::new().yellow().italic(),
@@ -1347,8 +2263,17 @@ mod test {
                     file_name: Style::new().cyan(),
                     separator: Style::new().fixed(50),
                     selected_line: Style::new().rgb(127, 127, 127).dim(),
-                    context: Style::new().rgb(127, 127, 127).dim(),
-                })
+                    before_context: Style::new().rgb(127, 127, 127).dim(),
+                    after_context: Style::new().rgb(127, 127, 127).dim(),
+                    context_count: Style::new().cyan(),
+                    score: Style::new().yellow(),
+                    context_match: Style::new().red(),
+                    hyperlinks: false,
+                    color_reset: false,
+                    gradient: None,
+                }),
+                ":",
+                false
             ),
             format!(
                 "{}{}{}{}{}\n\
@@ -1464,11 +2389,223 @@ mod test {
         )
     }
 
+    #[test]
+    fn results_output_hyperlinks() {
+        let results = vec![MatchingResultBuilder::new("te", "test")
+            .file_name("resources/tests/test.txt")
+            .line_number(42)
+            .build()];
+        let path = fs::canonicalize("resources/tests/test.txt").unwrap();
+        assert_eq!(
+            formatted(
+                &results,
+                &Formatting::On(FormattingOptions {
+                    hyperlinks: true,
+                    ..Default::default()
+                }),
+                ":",
+                false
+            ),
+            format!(
+                "\x1b]8;;file://{}#42\x1b\\{}{}{}{}\x1b]8;;\x1b\\{}st\n",
+                path.display(),
+                "resources/tests/test.txt".magenta(),
+                ':'.cyan(),
+                "42".green(),
+                ':'.cyan(),
+                "te".red().bold(),
+            )
+        )
+    }
+
+    #[test]
+    fn results_output_hyperlinks_no_line_number() {
+        let results = vec![MatchingResultBuilder::new("te", "test")
+            .file_name("resources/tests/test.txt")
+            .build()];
+        assert!(!formatted(
+            &results,
+            &Formatting::On(FormattingOptions {
+                hyperlinks: true,
+                ..Default::default()
+            }),
+            ":",
+            false
+        )
+        .contains("\x1b]8;;"));
+    }
+
+    #[test]
+    fn results_output_color_reset() {
+        let results = vec![
+            MatchingResultBuilder::new("te", "test")
+                .before_context(&["before"])
+                .build(),
+            MatchingResultBuilder::new("t", "test").build(),
+        ];
+        let formatting = Formatting::On(FormattingOptions {
+            color_reset: true,
+            ..Default::default()
+        });
+        let output = formatted(&results, &formatting, ":", false);
+        assert_eq!(output.lines().count(), 3);
+        assert!(output.lines().all(|line| line.ends_with("\x1b[0m")));
+        assert_eq!(
+            output.replace("\x1b[0m\n", "\n"),
+            formatted(
+                &results,
+                &Formatting::On(FormattingOptions::default()),
+                ":",
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn results_output_gradient() {
+        let results = vec![
+            MatchingResultBuilder::new("test", "test").build(),
+            MatchingResultBuilder::new("tt", "a long test").build(),
+        ];
+        let gradient = ColorGradient {
+            low: Color::Blue,
+            high: Color::Yellow,
+        };
+        let expected = results
+            .iter()
+            .map(|result| {
+                let score = result.normalized_score();
+                formatted(
+                    std::slice::from_ref(result),
+                    &Formatting::On(FormattingOptions {
+                        selected_match: gradient.interpolate(score),
+                        ..Default::default()
+                    }),
+                    ":",
+                    false,
+                )
+            })
+            .collect::<String>();
+        assert_eq!(
+            formatted(
+                &results,
+                &Formatting::On(FormattingOptions {
+                    gradient: Some(gradient),
+                    ..Default::default()
+                }),
+                ":",
+                false
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn file_scores() {
+        let results = [
+            MatchingResultBuilder::new("test", "a test")
+                .file_name("b.txt")
+                .build(),
+            MatchingResultBuilder::new("test", "test")
+                .file_name("b.txt")
+                .build(),
+            MatchingResultBuilder::new("test", "t e s t")
+                .file_name("a.txt")
+                .build(),
+            MatchingResultBuilder::new("test", "test")
+                .file_name("c.txt")
+                .build(),
+        ];
+        let exact = results[1].fuzzy_match.score();
+        let scattered = results[2].fuzzy_match.score();
+        assert!(scattered < exact);
+        assert_eq!(
+            format_file_scores(&results, &Formatting::Off, ":", None),
+            format!("b.txt:{exact}\nc.txt:{exact}\na.txt:{scattered}\n")
+        );
+        assert_eq!(
+            format_file_scores(&results, &Formatting::Off, "\t", Some(1)),
+            format!("b.txt\t{exact}\n")
+        );
+    }
+
+    #[test]
+    fn file_scores_formatted() {
+        let results = [MatchingResultBuilder::new("test", "test")
+            .file_name("file.txt")
+            .build()];
+        let score = results[0].fuzzy_match.score();
+        assert_eq!(
+            format_file_scores(
+                &results,
+                &Formatting::On(FormattingOptions::default()),
+                ":",
+                None
+            ),
+            format!(
+                "{}{}{}\n",
+                "file.txt".magenta(),
+                ":".cyan(),
+                score.to_string().paint(FormattingOptions::default().score)
+            )
+        );
+    }
+
+    #[test]
+    fn file_scores_no_results() {
+        assert!(format_file_scores(&[], &Formatting::Off, ":", None).is_empty());
+    }
+
+    #[test]
+    fn file_names_relative() {
+        let mut results = vec![MatchingResultBuilder::new("te", "test")
+            .file_name("resources/tests/nested/test.txt")
+            .build()];
+        make_file_names_relative(&mut results, Path::new("resources/tests"));
+        assert_eq!(results[0].file_name.as_deref(), Some("nested/test.txt"));
+    }
+
+    #[test]
+    fn file_names_relative_absolute_root() {
+        let mut results = vec![MatchingResultBuilder::new("te", "test")
+            .file_name("resources/tests/test.txt")
+            .build()];
+        make_file_names_relative(&mut results, &env::current_dir().unwrap());
+        assert_eq!(
+            results[0].file_name.as_deref(),
+            Some("resources/tests/test.txt")
+        );
+    }
+
+    #[test]
+    fn file_names_relative_outside_of_root() {
+        let mut results = vec![MatchingResultBuilder::new("te", "test")
+            .file_name("resources/tests/test.txt")
+            .build()];
+        make_file_names_relative(&mut results, Path::new("resources/tests/nested"));
+        assert_eq!(
+            results[0].file_name.as_deref(),
+            Some("resources/tests/test.txt")
+        );
+    }
+
+    #[test]
+    fn file_url_escaping() {
+        let url = file_url("resources/tests/name with spaces.txt", 3).unwrap();
+        assert!(url.starts_with("file:///"));
+        assert!(url.ends_with("/resources/tests/name%20with%20spaces.txt#3"));
+    }
+
     #[test]
     fn no_results_output_default() {
         let results = vec![];
         assert_eq!(
-            format_results(&results, &Formatting::On(FormattingOptions::default())),
+            formatted(
+                &results,
+                &Formatting::On(FormattingOptions::default()),
+                ":",
+                false
+            ),
             ""
         );
     }
@@ -1476,14 +2613,14 @@ mod test {
     #[test]
     fn no_results_output_off() {
         let results = vec![];
-        assert_eq!(format_results(&results, &Formatting::Off), "");
+        assert_eq!(formatted(&results, &Formatting::Off, ":", false), "");
     }
 
     #[test]
     fn no_results_output_custom() {
         let results = vec![];
         assert_eq!(
-            format_results(
+            formatted(
                 &results,
                 &Formatting::On(FormattingOptions {
                     selected_match: Style::new().green(),
@@ -1491,8 +2628,17 @@ mod test {
                     file_name: Style::new().cyan(),
                     separator: Style::new().fixed(50),
                     selected_line: Style::new().rgb(127, 127, 127).dim(),
-                    context: Style::new().rgb(127, 127, 127).dim(),
-                })
+                    before_context: Style::new().rgb(127, 127, 127).dim(),
+                    after_context: Style::new().rgb(127, 127, 127).dim(),
+                    context_count: Style::new().cyan(),
+                    score: Style::new().yellow(),
+                    context_match: Style::new().red(),
+                    hyperlinks: false,
+                    color_reset: false,
+                    gradient: None,
+                }),
+                ":",
+                false
             ),
             ""
         )
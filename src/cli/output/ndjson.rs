@@ -0,0 +1,155 @@
+use super::with_relative_file_names;
+use crate::matching_results::{result::MatchingResult, result_collection::ResultCollection};
+use std::{
+    io::{self, Write},
+    path::Path,
+    slice,
+};
+
+/// Writes `result` as a single line of JSON (see [`MatchingResult`] for the serialized form).
+///
+#[cfg(feature = "serde")]
+pub(crate) fn write_result(dest: &mut impl Write, result: &MatchingResult) -> io::Result<()> {
+    serde_json::to_writer(&mut *dest, result)?;
+    writeln!(dest)
+}
+
+#[cfg(not(feature = "serde"))]
+pub(crate) fn write_result(_dest: &mut impl Write, _result: &MatchingResult) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "NDJSON output is not enabled (the `serde` feature)",
+    ))
+}
+
+/// Writes every one of `results` as a single line of JSON.
+///
+pub(crate) fn write_results(dest: &mut impl Write, results: &[MatchingResult]) -> io::Result<()> {
+    for result in results {
+        write_result(dest, result)?;
+    }
+    Ok(())
+}
+
+/// A collection that writes every result pushed into it right away (as a single line of JSON),
+/// so that the results can be consumed while the search is still going on.
+/// The results are kept as well, but once writing fails the collection is full, which stops the search.
+///
+pub(crate) struct NdjsonStream<'a, W: Write> {
+    dest: &'a mut W,
+    relative_paths_root: Option<&'a Path>,
+    results: Vec<MatchingResult>,
+    error: Option<io::Error>,
+}
+
+impl<'a, W: Write> NdjsonStream<'a, W> {
+    /// Creates a collection writing to `dest`, the file names being made relative to `relative_paths_root` (if any).
+    ///
+    pub(crate) fn new(dest: &'a mut W, relative_paths_root: Option<&'a Path>) -> Self {
+        Self {
+            dest,
+            relative_paths_root,
+            results: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Returns the results written, or the error that stopped writing them.
+    ///
+    pub(crate) fn finish(self) -> io::Result<Vec<MatchingResult>> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.results),
+        }
+    }
+}
+
+impl<W: Write> ResultCollection for NdjsonStream<'_, W> {
+    fn push(&mut self, result: MatchingResult) {
+        if self.error.is_some() {
+            return;
+        }
+        // the kept results have the file names as found, only the written ones are made relative
+        let written = with_relative_file_names(slice::from_ref(&result), self.relative_paths_root);
+        // the result has to reach the consumer now rather than when the buffer fills up
+        match write_result(self.dest, &written[0]).and_then(|()| self.dest.flush()) {
+            Ok(()) => self.results.push(result),
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    fn stops_search(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::matching_results::result::MatchingResultBuilder;
+
+    #[test]
+    fn one_result_per_line() {
+        let results = [
+            MatchingResultBuilder::new("test", "test")
+                .line_number(1)
+                .build(),
+            MatchingResultBuilder::new("test", "a test")
+                .after_context(&["after"])
+                .build(),
+        ];
+        let mut buf = Vec::new();
+        write_results(&mut buf, &results).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["matching_line"], "test");
+        assert_eq!(first["line_number"], 1);
+        let second: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(second["matching_line"], "a test");
+        assert_eq!(second["context"]["after"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn stream() {
+        let mut buf = Vec::new();
+        let mut stream = NdjsonStream::new(&mut buf, Some(Path::new("dir")));
+        stream.push(
+            MatchingResultBuilder::new("test", "test")
+                .file_name("dir/file.txt")
+                .build(),
+        );
+        assert!(!stream.stops_search());
+        let results = stream.finish().unwrap();
+        assert_eq!(results.len(), 1);
+        // only the written file name is relative
+        assert_eq!(results[0].file_name.as_deref(), Some("dir/file.txt"));
+        let written: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(written["file_name"], "file.txt");
+    }
+
+    #[test]
+    fn stream_write_error() {
+        struct Failing;
+
+        impl Write for Failing {
+            fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut dest = Failing;
+        let mut stream = NdjsonStream::new(&mut dest, None);
+        stream.push(MatchingResultBuilder::new("test", "test").build());
+        assert!(stream.stops_search());
+        assert_eq!(
+            stream.finish().unwrap_err().kind(),
+            io::ErrorKind::BrokenPipe
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use super::{format_one_piece, format_selected_line};
+use crate::{cli::formatting::Formatting, matching_results::result::MatchingResult};
+use log::warn;
+use std::{
+    io::{self, Write},
+    mem,
+};
+
+/// A placeholder of an output template.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Field {
+    /// `{file}`: the file name (empty if file names are not tracked).
+    ///
+    File,
+
+    /// `{line}`: the line number (empty if line numbers are not tracked).
+    ///
+    Line,
+
+    /// `{col}`: the column of the first matched character (empty if column numbers are not tracked).
+    ///
+    Column,
+
+    /// `{score}`: the fuzzy score.
+    ///
+    Score,
+
+    /// `{match}`: the matching line with the matched characters highlighted.
+    ///
+    Match,
+
+    /// `{before}`: the leading context lines, separated by newlines.
+    ///
+    Before,
+
+    /// `{after}`: the trailing context lines, separated by newlines.
+    ///
+    After,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "file" => Some(Self::File),
+            "line" => Some(Self::Line),
+            "col" => Some(Self::Column),
+            "score" => Some(Self::Score),
+            "match" => Some(Self::Match),
+            "before" => Some(Self::Before),
+            "after" => Some(Self::After),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Piece {
+    Literal(String),
+    Field(Field),
+}
+
+/// A parsed output template (see `--format`), e.g. `{file}:{line}:{match}`.
+///
+#[derive(Debug, PartialEq)]
+pub(crate) struct Template {
+    pieces: Vec<Piece>,
+}
+
+impl Template {
+    /// Parses `template`. Unknown placeholders (as well as unbalanced braces) are kept as literal text
+    /// (with a warning for each unknown placeholder).
+    ///
+    pub(crate) fn parse(template: &str) -> Self {
+        let mut pieces = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            literal.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let end = rest.find('}');
+            let next_start = rest[1..].find('{').map(|index| index + 1);
+            match end {
+                Some(end) if next_start.is_none_or(|next_start| next_start > end) => {
+                    let name = &rest[1..end];
+                    match Field::from_name(name) {
+                        Some(field) => {
+                            if !literal.is_empty() {
+                                pieces.push(Piece::Literal(mem::take(&mut literal)));
+                            }
+                            pieces.push(Piece::Field(field));
+                        }
+                        None => {
+                            warn!("Unknown placeholder '{{{name}}}' in the output template, keeping it as is.");
+                            literal.push_str(&rest[..=end]);
+                        }
+                    }
+                    rest = &rest[end + 1..];
+                }
+                _ => {
+                    // an unclosed brace, it is just text up to the next opening one
+                    let literal_end = next_start.unwrap_or(rest.len());
+                    literal.push_str(&rest[..literal_end]);
+                    rest = &rest[literal_end..];
+                }
+            }
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            pieces.push(Piece::Literal(literal));
+        }
+        Self { pieces }
+    }
+
+    /// Checks whether the template contains `field`.
+    ///
+    pub(crate) fn uses(&self, field: Field) -> bool {
+        self.pieces.contains(&Piece::Field(field))
+    }
+
+    /// Writes supplied `matches` to `dest`, one filled-in template per match, each followed by a newline.
+    /// Every field is styled according to `formatting` (the score is never styled).
+    ///
+    /// # Errors
+    ///
+    ///   * [`io::Error`] if writing to `dest` fails.
+    ///
+    pub(crate) fn write_results(
+        &self,
+        dest: &mut impl Write,
+        matches: &[MatchingResult],
+        formatting: &Formatting,
+    ) -> io::Result<()> {
+        for m in matches {
+            for piece in &self.pieces {
+                match piece {
+                    Piece::Literal(text) => write!(dest, "{text}")?,
+                    Piece::Field(field) => write!(dest, "{}", format_field(*field, m, formatting))?,
+                }
+            }
+            writeln!(dest)?;
+        }
+        Ok(())
+    }
+}
+
+fn format_field(field: Field, m: &MatchingResult, formatting: &Formatting) -> String {
+    let options = formatting.options();
+    match field {
+        Field::File => m
+            .file_name
+            .as_deref()
+            .map_or_else(String::new, |file_name| {
+                format_one_piece(file_name, options.map(|o| o.file_name))
+            }),
+        Field::Line => m.line_number.map_or_else(String::new, |line_number| {
+            format_one_piece(&line_number.to_string(), options.map(|o| o.line_number))
+        }),
+        // columns are styled the same way as line numbers
+        Field::Column => m.column_number.map_or_else(String::new, |column_number| {
+            format_one_piece(&column_number.to_string(), options.map(|o| o.line_number))
+        }),
+        Field::Score => m.score().to_string(),
+        Field::Match => format_selected_line(m, formatting),
+        Field::Before => m
+            .context
+            .before
+            .iter()
+            .map(|line| format_one_piece(line, options.map(|o| o.before_context)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Field::After => m
+            .context
+            .after
+            .iter()
+            .map(|line| format_one_piece(line, options.map(|o| o.after_context)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cli::formatting::FormattingOptions, matching_results::result::MatchingResultBuilder,
+    };
+    use yansi::Paint;
+
+    fn literal(text: &str) -> Piece {
+        Piece::Literal(String::from(text))
+    }
+
+    #[test]
+    fn parse_fields() {
+        assert_eq!(
+            Template::parse("{file}:{line}:{match}").pieces,
+            [
+                Piece::Field(Field::File),
+                literal(":"),
+                Piece::Field(Field::Line),
+                literal(":"),
+                Piece::Field(Field::Match),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_unknown_placeholders() {
+        assert_eq!(
+            Template::parse("{nope} {score}").pieces,
+            [literal("{nope} "), Piece::Field(Field::Score)]
+        );
+    }
+
+    #[test]
+    fn parse_unbalanced_braces() {
+        assert_eq!(
+            Template::parse("{{col}} {").pieces,
+            [literal("{"), Piece::Field(Field::Column), literal("} {")]
+        );
+        assert_eq!(Template::parse("a}b").pieces, [literal("a}b")]);
+        assert!(Template::parse("").pieces.is_empty());
+    }
+
+    #[test]
+    fn uses() {
+        let template = Template::parse("{file}: {match}");
+        assert!(template.uses(Field::File));
+        assert!(template.uses(Field::Match));
+        assert!(!template.uses(Field::Line));
+    }
+
+    #[test]
+    fn write_results_plain() {
+        let results = vec![
+            MatchingResultBuilder::new("te", "test")
+                .file_name("a.txt")
+                .line_number(4)
+                .before_context(&["one", "two"])
+                .after_context(&["three"])
+                .build(),
+            MatchingResultBuilder::new("t", "test").build(),
+        ];
+        let mut dest = Vec::new();
+        Template::parse("{file}|{line}|{col}|{match}|{before}|{after}")
+            .write_results(&mut dest, &results, &Formatting::Off)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(dest).unwrap(),
+            "a.txt|4||test|one\ntwo|three\n\
+            |||test||\n"
+        );
+    }
+
+    #[test]
+    fn write_results_score() {
+        let results = vec![MatchingResultBuilder::new("te", "test").build()];
+        let mut dest = Vec::new();
+        Template::parse("{score}")
+            .write_results(&mut dest, &results, &Formatting::Off)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(dest).unwrap(),
+            format!("{}\n", results[0].fuzzy_match.score())
+        );
+    }
+
+    #[test]
+    fn write_results_formatted() {
+        let results = vec![MatchingResultBuilder::new("te", "test")
+            .file_name("a.txt")
+            .line_number(4)
+            .build()];
+        let mut dest = Vec::new();
+        Template::parse("{file}:{line}:{match}")
+            .write_results(
+                &mut dest,
+                &results,
+                &Formatting::On(FormattingOptions::default()),
+            )
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(dest).unwrap(),
+            format!(
+                "{}:{}:{}st\n",
+                "a.txt".magenta(),
+                '4'.green(),
+                "te".red().bold()
+            )
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use log::warn;
+use std::{
+    env,
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+use terminal_size::Height;
+
+/// The pager used when neither `--pager` nor the `PAGER` environment variable is given.
+/// `-R` makes `less` render the colors instead of printing the escape sequences.
+///
+const FALLBACK_PAGER: &str = "less -R";
+
+/// Returns the pager command to use by default: the content of the `PAGER` environment variable
+/// or `less -R` if it is not set (or empty).
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::cli::pager;
+///
+/// // `less -R` unless `PAGER` says otherwise
+/// assert!(!pager::default_pager().trim().is_empty());
+/// ```
+///
+pub fn default_pager() -> String {
+    env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| String::from(FALLBACK_PAGER))
+}
+
+/// Writes `output` to the standard output, piping it through `pager` (a command with whitespace-separated arguments)
+/// if it has more lines than fit on the terminal at once.
+/// If the terminal height cannot be determined or the pager cannot be started, `output` is written directly.
+///
+/// # Errors
+///
+///   * [`io::Error`] if writing to the standard output (or to the pager) fails.
+///
+pub fn page(output: &[u8], pager: &str) -> io::Result<()> {
+    let height = terminal_size::terminal_size().map(|(_, Height(height))| usize::from(height));
+    if height.is_some_and(|height| exceeds_height(output, height)) {
+        match pager_command(pager).map(|mut command| command.stdin(Stdio::piped()).spawn()) {
+            Some(Ok(mut child)) => {
+                if let Some(mut stdin) = child.stdin.take() {
+                    // the pager may be quit before it has read everything, which is not an error
+                    if let Err(e) = stdin.write_all(output) {
+                        if e.kind() != io::ErrorKind::BrokenPipe {
+                            return Err(e);
+                        }
+                    }
+                }
+                child.wait()?;
+                return Ok(());
+            }
+            Some(Err(e)) => {
+                warn!("Cannot start the pager '{pager}': {e}, printing the output as is.")
+            }
+            None => warn!("The pager command is empty, printing the output as is."),
+        }
+    }
+    io::stdout().write_all(output)
+}
+
+/// Checks whether `output` takes more lines than a terminal of the given `height` can show
+/// (keeping the last line for the prompt). Long lines wrapping around are not taken into account.
+///
+fn exceeds_height(output: &[u8], height: usize) -> bool {
+    output.iter().filter(|&&byte| byte == b'\n').count() >= height
+}
+
+/// Makes a [`Command`] running `pager`, split into the program and its arguments by whitespace.
+/// Returns [`None`] if `pager` is blank.
+///
+fn pager_command(pager: &str) -> Option<Command> {
+    let mut words = pager.split_whitespace();
+    let mut command = Command::new(words.next()?);
+    command.args(words);
+    Some(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn exceeds_height_by_lines() {
+        assert!(!exceeds_height(b"", 1));
+        assert!(!exceeds_height(b"one\ntwo\n", 3));
+        assert!(exceeds_height(b"one\ntwo\nthree\n", 3));
+        assert!(exceeds_height(b"one\ntwo\nthree\nfour\n", 3));
+    }
+
+    #[test]
+    fn pager_command_with_arguments() {
+        let command = pager_command("less  -R -F").unwrap();
+        assert_eq!(command.get_program(), "less");
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            [OsStr::new("-R"), OsStr::new("-F")]
+        );
+    }
+
+    #[test]
+    fn pager_command_blank() {
+        assert!(pager_command("").is_none());
+        assert!(pager_command("  ").is_none());
+    }
+}
@@ -0,0 +1,112 @@
+use std::{
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// How often the progress line is redrawn.
+///
+const REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The counters shown by [`Progress`], updated by the search as it goes.
+///
+#[derive(Debug, Default)]
+pub(crate) struct ProgressCounters {
+    files_searched: AtomicUsize,
+    matches_found: AtomicUsize,
+}
+
+impl ProgressCounters {
+    /// Records that one more file has been searched and that `matches_found` matches have been found so far.
+    ///
+    pub(crate) fn file_searched(&self, matches_found: usize) {
+        self.files_searched.fetch_add(1, Ordering::Relaxed);
+        self.matches_found.store(matches_found, Ordering::Relaxed);
+    }
+
+    fn render(&self) -> String {
+        format!(
+            "files: {} matches: {}",
+            self.files_searched.load(Ordering::Relaxed),
+            self.matches_found.load(Ordering::Relaxed)
+        )
+    }
+}
+
+/// A live progress line on the standard error, redrawn in place by a background thread
+/// (at most once per [`REFRESH_INTERVAL`]) until the value is dropped, which clears the line.
+///
+pub(crate) struct Progress {
+    counters: Arc<ProgressCounters>,
+    finished: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Progress {
+    /// Starts drawing the progress line.
+    ///
+    pub(crate) fn start() -> Self {
+        let counters = Arc::new(ProgressCounters::default());
+        let finished = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let counters = Arc::clone(&counters);
+            let finished = Arc::clone(&finished);
+            thread::spawn(move || {
+                while !finished.load(Ordering::Relaxed) {
+                    // the progress is only a hint, failing to draw it is not worth stopping the search
+                    let _ = draw(&counters.render());
+                    thread::park_timeout(REFRESH_INTERVAL);
+                }
+                let _ = draw("");
+            })
+        };
+        Self {
+            counters,
+            finished,
+            thread: Some(thread),
+        }
+    }
+
+    /// Returns the counters to be updated by the search.
+    ///
+    pub(crate) fn counters(&self) -> &ProgressCounters {
+        &self.counters
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finished.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Replaces the current line of the standard error with `line`:
+/// the carriage return moves the cursor to the start of the line and `ESC[K` erases the old content.
+///
+fn draw(line: &str) -> io::Result<()> {
+    let mut stderr = io::stderr().lock();
+    write!(stderr, "\r\x1b[K{line}")?;
+    stderr.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_render() {
+        let counters = ProgressCounters::default();
+        assert_eq!(counters.render(), "files: 0 matches: 0");
+        counters.file_searched(3);
+        counters.file_searched(5);
+        assert_eq!(counters.render(), "files: 2 matches: 5");
+    }
+}
@@ -0,0 +1,104 @@
+use vscode_fuzzy_score_rs::FuzzyMatch;
+
+/// Replaces the characters of the target that must not be matched (e.g. the ones not starting words in the acronym mode).
+/// It is never a part of a (reasonable) query, so the matcher is free to skip it but cannot match it.
+/// Unlike removing the characters, masking them keeps the match positions valid for the original target.
+///
+pub(crate) const MASK: char = '\0';
+
+/// Matches `query` against the initial characters of the words in `target`,
+/// e.g. `fb` matches `FooBar`, `foo_bar` or `foo bar`, but not `foobar`.
+///
+/// The characters that do not start a word are masked out before running the fuzzy matcher,
+/// so the positions of the returned match are char indices into the original `target`
+/// (i.e. they can be used for highlighting exactly as the ones of a regular fuzzy match).
+///
+pub(crate) fn acronym_match(query: &str, target: &str) -> Option<FuzzyMatch> {
+    if query.contains(MASK) {
+        return None;
+    }
+
+    let starts = word_starts(target);
+    let masked = target
+        .chars()
+        .enumerate()
+        .map(|(index, c)| {
+            if starts.binary_search(&index).is_ok() {
+                c
+            } else {
+                MASK
+            }
+        })
+        .collect::<String>();
+    vscode_fuzzy_score_rs::fuzzy_match(query, &masked)
+}
+
+/// Returns char indices of the characters starting a word in `target`, in ascending order.
+/// A word starts with an alphanumeric character that either follows a non-alphanumeric one
+/// (or the start of the line), or is an uppercase letter following a lowercase one (camelCase).
+///
+pub(crate) fn word_starts(target: &str) -> Vec<usize> {
+    let mut previous: Option<char> = None;
+    let mut starts = Vec::new();
+    for (index, c) in target.chars().enumerate() {
+        let is_start = c.is_alphanumeric()
+            && previous
+                .is_none_or(|p| !p.is_alphanumeric() || (c.is_uppercase() && p.is_lowercase()));
+        if is_start {
+            starts.push(index);
+        }
+        previous = Some(c);
+    }
+    starts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(query: &str, target: &str) -> Option<Vec<usize>> {
+        acronym_match(query, target).map(|m| m.positions().clone())
+    }
+
+    #[test]
+    fn word_starts_separators() {
+        assert_eq!(word_starts("foo bar_baz-qux.rs"), [0, 4, 8, 12, 16]);
+        assert_eq!(word_starts("  leading"), [2]);
+        assert!(word_starts("").is_empty());
+        assert!(word_starts("--").is_empty());
+    }
+
+    #[test]
+    fn word_starts_camel_case() {
+        assert_eq!(word_starts("fooBarBaz"), [0, 3, 6]);
+        assert_eq!(word_starts("FooBar"), [0, 3]);
+        assert_eq!(word_starts("HTTPServer"), [0]);
+        assert_eq!(word_starts("getHTTPResponse"), [0, 3]);
+    }
+
+    #[test]
+    fn acronym_camel_case() {
+        assert_eq!(positions("fb", "FooBar"), Some(vec![0, 3]));
+        assert_eq!(positions("xhr", "XmlHttpRequest"), Some(vec![0, 3, 7]));
+        assert_eq!(positions("fb", "foobar"), None);
+    }
+
+    #[test]
+    fn acronym_separators() {
+        assert_eq!(positions("fb", "foo_bar"), Some(vec![0, 4]));
+        assert_eq!(positions("gc", "git commit"), Some(vec![0, 4]));
+        assert_eq!(positions("rm", "src/read_me.txt"), Some(vec![4, 9]));
+        assert_eq!(positions("bf", "foo_bar"), None);
+    }
+
+    #[test]
+    fn acronym_unicode() {
+        assert_eq!(positions("тт", "тестування текст"), Some(vec![0, 11]));
+        assert_eq!(positions("зд", "завдання"), None);
+    }
+
+    #[test]
+    fn acronym_mask_in_query() {
+        assert_eq!(positions("f\0", "foo bar"), None);
+    }
+}
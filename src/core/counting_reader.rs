@@ -0,0 +1,120 @@
+use std::io::{self, Read};
+
+/// A thin wrapper around a [`Read`] that counts the lines passing through it without keeping them,
+/// i.e. it only looks for the `terminator` bytes (`\n` or `\0`).
+/// A last line lacking the terminator is counted as well (the same way it is searched).
+///
+pub(crate) struct CountingReader<R: Read> {
+    inner: R,
+    terminator: u8,
+    terminators: usize,
+    last_byte: Option<u8>,
+}
+
+impl<R: Read> CountingReader<R> {
+    /// Wraps `inner` into a reader counting the lines terminated by `terminator`.
+    ///
+    pub(crate) fn new(inner: R, terminator: u8) -> Self {
+        Self {
+            inner,
+            terminator,
+            terminators: 0,
+            last_byte: None,
+        }
+    }
+
+    /// Returns the number of lines read so far.
+    ///
+    pub(crate) fn lines(&self) -> usize {
+        match self.last_byte {
+            Some(byte) if byte != self.terminator => self.terminators + 1,
+            _ => self.terminators,
+        }
+    }
+
+    /// Reads `inner` to the end, returning the number of its lines.
+    ///
+    /// # Errors
+    ///
+    ///   * [`io::Error`] if reading fails.
+    ///
+    pub(crate) fn count_lines(mut self) -> io::Result<usize> {
+        io::copy(&mut self, &mut io::sink())?;
+        Ok(self.lines())
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        let bytes = &buf[..read];
+        self.terminators += bytes
+            .iter()
+            .filter(|&&byte| byte == self.terminator)
+            .count();
+        if let Some(&byte) = bytes.last() {
+            self.last_byte = Some(byte);
+        }
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_lines_terminated() {
+        assert_eq!(
+            CountingReader::new(&b""[..], b'\n').count_lines().unwrap(),
+            0
+        );
+        assert_eq!(
+            CountingReader::new(&b"\n"[..], b'\n')
+                .count_lines()
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            CountingReader::new(&b"one\ntwo\n"[..], b'\n')
+                .count_lines()
+                .unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn count_lines_unterminated_last() {
+        assert_eq!(
+            CountingReader::new(&b"one"[..], b'\n')
+                .count_lines()
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            CountingReader::new(&b"one\n\ntwo"[..], b'\n')
+                .count_lines()
+                .unwrap(),
+            3
+        );
+    }
+
+    #[test]
+    fn count_lines_nul_terminated() {
+        assert_eq!(
+            CountingReader::new(&b"one\ntwo\0three\0"[..], b'\0')
+                .count_lines()
+                .unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn read_passes_through() {
+        let mut reader = CountingReader::new(&b"one\ntwo"[..], b'\n');
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "one\ntwo");
+        assert_eq!(reader.lines(), 2);
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    error, fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+/// A flag raised by a background timer thread once the time limit of a search has passed.
+///
+pub(crate) struct Deadline {
+    expired: Arc<AtomicBool>,
+}
+
+impl Deadline {
+    /// Starts the timer expiring after `limit`.
+    /// A zero `limit` expires right away, without involving the timer thread.
+    ///
+    pub(crate) fn start(limit: Duration) -> Self {
+        let expired = Arc::new(AtomicBool::new(limit.is_zero()));
+        if !limit.is_zero() {
+            let expired = Arc::clone(&expired);
+            // the thread is not joined: if the search finishes first, it is simply left sleeping until the exit
+            thread::spawn(move || {
+                thread::sleep(limit);
+                expired.store(true, Ordering::Relaxed);
+            });
+        }
+        Self { expired }
+    }
+
+    /// Checks whether the time limit has passed.
+    ///
+    pub(crate) fn is_expired(&self) -> bool {
+        self.expired.load(Ordering::Relaxed)
+    }
+}
+
+/// An error signaling that the search was stopped by the time limit (see [`crate::Request::max_time`]).
+/// The matches found before that are still output, but they may be incomplete.
+///
+/// # Fields
+///   * a [`Duration`] containing the exceeded time limit
+///
+#[derive(Debug, PartialEq)]
+pub struct TimeoutError(pub Duration);
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the search was stopped after {} s, the results may be incomplete",
+            self.0.as_secs_f64()
+        )
+    }
+}
+
+impl error::Error for TimeoutError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_limit() {
+        assert!(Deadline::start(Duration::ZERO).is_expired());
+    }
+
+    #[test]
+    fn expires() {
+        let deadline = Deadline::start(Duration::from_millis(10));
+        while !deadline.is_expired() {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn long_limit() {
+        assert!(!Deadline::start(Duration::from_secs(3600)).is_expired());
+    }
+
+    #[test]
+    fn timeout_error_display() {
+        assert_eq!(
+            TimeoutError(Duration::from_secs(2)).to_string(),
+            "the search was stopped after 2 s, the results may be incomplete"
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use crate::core::acronym::MASK;
+use unicode_normalization::char::{decompose_canonical, is_combining_mark};
+
+/// Removes diacritical marks from `query`, e.g. `café` becomes `cafe`.
+/// Both the precomposed letters and the separate combining marks are handled.
+///
+pub(crate) fn strip_diacritics(query: &str) -> String {
+    query
+        .chars()
+        .filter(|&c| !is_combining_mark(c))
+        .map(base_char)
+        .collect()
+}
+
+/// Removes diacritical marks from `line` without changing the number of characters in it:
+/// the precomposed letters are replaced with their base letters and the combining marks are masked out.
+/// This way the positions of a match found in the result are valid for the original `line` as well.
+///
+pub(crate) fn mask_diacritics(line: &str) -> String {
+    line.chars()
+        .map(|c| {
+            if is_combining_mark(c) {
+                MASK
+            } else {
+                base_char(c)
+            }
+        })
+        .collect()
+}
+
+/// Returns the base letter of `c` if it is a letter with diacritical marks (e.g. `e` for `é`), `c` itself otherwise.
+/// Characters that decompose into anything but a letter followed by marks (e.g. Hangul syllables) are left as is.
+///
+fn base_char(c: char) -> char {
+    let mut decomposition = Vec::new();
+    decompose_canonical(c, |d| decomposition.push(d));
+    match decomposition.split_first() {
+        Some((&base, marks)) if marks.iter().all(|&m| is_combining_mark(m)) => base,
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_diacritics_precomposed() {
+        assert_eq!(strip_diacritics("élève"), "eleve");
+        assert_eq!(strip_diacritics("Müller"), "Muller");
+        assert_eq!(strip_diacritics("niño"), "nino");
+        assert_eq!(strip_diacritics("Ångström"), "Angstrom");
+    }
+
+    #[test]
+    fn strip_diacritics_decomposed() {
+        assert_eq!(strip_diacritics("cafe\u{301}"), "cafe");
+        assert_eq!(strip_diacritics("u\u{308}ber"), "uber");
+    }
+
+    #[test]
+    fn strip_diacritics_untouched() {
+        assert_eq!(strip_diacritics("plain ascii"), "plain ascii");
+        assert_eq!(strip_diacritics("Текст"), "Текст");
+        assert_eq!(strip_diacritics("한국어"), "한국어");
+        assert_eq!(strip_diacritics("straße"), "straße");
+    }
+
+    #[test]
+    fn mask_diacritics_keeps_positions() {
+        assert_eq!(mask_diacritics("crème brûlée"), "creme brulee");
+        assert_eq!(mask_diacritics("cafe\u{301} au lait"), "cafe\0 au lait");
+        assert_eq!(
+            mask_diacritics("cafe\u{301} au lait").chars().count(),
+            "cafe\u{301} au lait".chars().count()
+        );
+        assert_eq!(mask_diacritics("한국어"), "한국어");
+    }
+}
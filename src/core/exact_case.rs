@@ -0,0 +1,131 @@
+use crate::core::{acronym::MASK, explain, scoring_config::ScoringConfig};
+use std::iter;
+use vscode_fuzzy_score_rs::FuzzyMatch;
+
+/// Matches `query` against `target` with `fuzzy_match` (which ignores letter case), accepting only the matches
+/// whose characters have exactly the same case as the ones of the query, e.g. `Foo` matches `foo Foo`
+/// (at the second word), but not `foo`.
+///
+/// The matcher picks the best alignment regardless of the case, so if it picks one differing in case,
+/// the characters that cannot be matched case-exactly are masked out and the matcher is run again.
+/// If it still picks such an alignment (e.g. `aA` in `Aa aA`, where both characters are kept),
+/// the best of the case-exact alignments (see [`best_exact_alignment`]) is the only one left unmasked.
+/// The positions of the returned match are char indices into the original `target`.
+///
+pub(crate) fn exact_case_match(
+    fuzzy_match: fn(&str, &str) -> Option<FuzzyMatch>,
+    query: &str,
+    target: &str,
+) -> Option<FuzzyMatch> {
+    if query.contains(MASK) {
+        return None;
+    }
+
+    let m = fuzzy_match(query, target)?;
+    if is_exact_case(query, target, m.positions()) {
+        return Some(m);
+    }
+
+    let masked = target
+        .chars()
+        .map(|c| if query.contains(c) { c } else { MASK })
+        .collect::<String>();
+    if let Some(m) =
+        fuzzy_match(query, &masked).filter(|m| is_exact_case(query, target, m.positions()))
+    {
+        return Some(m);
+    }
+
+    let positions = best_exact_alignment(query, target)?;
+    let masked = target
+        .chars()
+        .enumerate()
+        .map(|(index, c)| {
+            if positions.binary_search(&index).is_ok() {
+                c
+            } else {
+                MASK
+            }
+        })
+        .collect::<String>();
+    fuzzy_match(query, &masked).filter(|m| is_exact_case(query, target, m.positions()))
+}
+
+/// Checks whether the characters of `target` at `positions` (char indices) are exactly the characters of `query`,
+/// case included.
+///
+fn is_exact_case(query: &str, target: &str, positions: &[usize]) -> bool {
+    let mut query_chars = query.chars();
+    let mut target_chars = target.chars().enumerate();
+    positions.iter().all(|&position| {
+        let target_char = target_chars.find_map(|(index, c)| (index == position).then_some(c));
+        target_char.is_some() && target_char == query_chars.next()
+    })
+}
+
+/// Returns char indices of the case-exact occurrence of the characters of `query` (in order) in `target`
+/// with the highest score, or [`None`] if there is none.
+/// Only the earliest occurrence starting at each of the characters equal to the first one of the query is considered.
+///
+fn best_exact_alignment(query: &str, target: &str) -> Option<Vec<usize>> {
+    let mut query_chars = query.chars();
+    let first = query_chars.next()?;
+    let rest = query_chars.collect::<Vec<_>>();
+    let target_chars = target.chars().collect::<Vec<_>>();
+    let score = |positions: &[usize]| {
+        explain::explain_positions(query, target, positions, &ScoringConfig::default())
+            .iter()
+            .map(|c| c.total)
+            .sum::<i32>()
+    };
+    (0..target_chars.len())
+        .filter(|&start| target_chars[start] == first)
+        .filter_map(|start| {
+            let mut candidates = start + 1..target_chars.len();
+            iter::once(Some(start))
+                .chain(
+                    rest.iter()
+                        .map(|&q| candidates.find(|&index| target_chars[index] == q)),
+                )
+                .collect::<Option<Vec<_>>>()
+        })
+        // the first of the equally scored occurrences wins
+        .rev()
+        .max_by_key(|positions| score(positions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(query: &str, target: &str) -> Option<Vec<usize>> {
+        exact_case_match(vscode_fuzzy_score_rs::fuzzy_match, query, target)
+            .map(|m| m.positions().clone())
+    }
+
+    #[test]
+    fn exact_case_first() {
+        assert_eq!(positions("Foo", "Foo foo"), Some(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn exact_case_after_different_case() {
+        assert_eq!(positions("Foo", "foo Foo"), Some(vec![4, 5, 6]));
+    }
+
+    #[test]
+    fn exact_case_ambiguous_characters() {
+        assert_eq!(positions("aA", "Aa aA"), Some(vec![3, 4]));
+    }
+
+    #[test]
+    fn no_exact_case() {
+        assert_eq!(positions("Foo", "foo FOO"), None);
+    }
+
+    #[test]
+    fn best_exact_alignment_found() {
+        assert_eq!(best_exact_alignment("aA", "Aa aA"), Some(vec![3, 4]));
+        assert_eq!(best_exact_alignment("aA", "Aa a"), None);
+    }
+}
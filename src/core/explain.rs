@@ -0,0 +1,289 @@
+use crate::core::scoring_config::ScoringConfig;
+use std::fmt;
+
+/// A breakdown of the score of a fuzzy match, one entry per query character (see [`explain_match`]).
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchExplanation {
+    /// The query characters in order, each with the target character it matched and the points it earned.
+    ///
+    pub chars: Vec<CharExplanation>,
+
+    /// The score reported by the matcher.
+    ///
+    pub score: i32,
+}
+
+/// How a single query character matched and what it earned.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct CharExplanation {
+    /// The character of the query.
+    ///
+    pub query_char: char,
+
+    /// The character of the target it matched (possibly of a different case).
+    ///
+    pub target_char: char,
+
+    /// The char (codepoint) index of [`CharExplanation::target_char`] in the target.
+    ///
+    pub target_index: usize,
+
+    /// The points every matched character earns ([`ScoringConfig::regular`]).
+    ///
+    pub base_score: i32,
+
+    /// The bonuses on top of [`CharExplanation::base_score`].
+    ///
+    pub bonuses: Vec<Bonus>,
+
+    /// The base score plus the bonuses.
+    ///
+    pub total: i32,
+}
+
+/// A bonus earned by a matched character, with the points it is worth (see [`ScoringConfig`] for the weights).
+/// A character earns at most one of the bonuses for its position
+/// ([`Bonus::WordStart`], [`Bonus::AfterPathSeparator`], [`Bonus::AfterSeparator`] or [`Bonus::CamelCase`]).
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bonus {
+    /// The character continues an uninterrupted run of matched characters.
+    ///
+    Consecutive(i32),
+
+    /// The case of the character is the same as in the query.
+    ///
+    ExactCase(i32),
+
+    /// The character is the very first one of the target.
+    ///
+    WordStart(i32),
+
+    /// The character follows a separator (`_`, `-`, `.`, space, quotes or `:`).
+    ///
+    AfterSeparator(i32),
+
+    /// The character follows a path separator (`/` or `\`).
+    ///
+    AfterPathSeparator(i32),
+
+    /// The character is an uppercase letter of a camelCase word (and does not continue a run of matches).
+    ///
+    CamelCase(i32),
+}
+
+impl Bonus {
+    /// Returns the points the bonus is worth.
+    ///
+    pub fn points(&self) -> i32 {
+        match *self {
+            Bonus::Consecutive(points)
+            | Bonus::ExactCase(points)
+            | Bonus::WordStart(points)
+            | Bonus::AfterSeparator(points)
+            | Bonus::AfterPathSeparator(points)
+            | Bonus::CamelCase(points) => points,
+        }
+    }
+}
+
+impl MatchExplanation {
+    /// Returns the sum of the points earned by the characters.
+    /// It equals [`MatchExplanation::score`] unless the matcher weights the characters differently
+    /// from the default [`ScoringConfig`].
+    ///
+    pub fn total(&self) -> i32 {
+        self.chars.iter().map(|c| c.total).sum()
+    }
+}
+
+/// One line per query character, e.g. `'t' -> 'T' at 0: 1 + word start 8 = 9`, followed by the total.
+///
+impl fmt::Display for MatchExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in &self.chars {
+            write!(
+                f,
+                "{:?} -> {:?} at {}: {}",
+                c.query_char, c.target_char, c.target_index, c.base_score
+            )?;
+            for bonus in &c.bonuses {
+                write!(f, " + {bonus}")?;
+            }
+            writeln!(f, " = {}", c.total)?;
+        }
+        write!(f, "total {} (score {})", self.total(), self.score)
+    }
+}
+
+impl fmt::Display for Bonus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Bonus::Consecutive(_) => "consecutive",
+            Bonus::ExactCase(_) => "exact case",
+            Bonus::WordStart(_) => "word start",
+            Bonus::AfterSeparator(_) => "after separator",
+            Bonus::AfterPathSeparator(_) => "after path separator",
+            Bonus::CamelCase(_) => "camel case",
+        };
+        write!(f, "{name} {}", self.points())
+    }
+}
+
+/// Matches `query` against `target` and explains the score of the match character by character,
+/// or returns [`None`] if `query` does not match `target`.
+///
+/// The matcher only reports the positions and the total score, so the breakdown is reconstructed
+/// from the positions with the default [`ScoringConfig`] weights, which are the ones the matcher uses.
+/// This is mostly useful for tuning the scoring weights and for debugging unexpected scores.
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::{explain_match, Bonus};
+///
+/// let explanation = explain_match("fb", "foo_bar").unwrap();
+/// assert_eq!(explanation.chars[0].target_index, 0);
+/// assert_eq!(explanation.chars[0].bonuses, [Bonus::ExactCase(1), Bonus::WordStart(8)]);
+/// assert_eq!(explanation.chars[1].target_index, 4);
+/// assert_eq!(explanation.chars[1].bonuses, [Bonus::ExactCase(1), Bonus::AfterSeparator(4)]);
+/// assert_eq!(explanation.total(), explanation.score);
+/// println!("{explanation}");
+/// ```
+///
+pub fn explain_match(query: &str, target: &str) -> Option<MatchExplanation> {
+    let fuzzy_match = vscode_fuzzy_score_rs::fuzzy_match(query, target)?;
+    Some(MatchExplanation {
+        chars: explain_positions(
+            query,
+            target,
+            fuzzy_match.positions(),
+            &ScoringConfig::default(),
+        ),
+        score: fuzzy_match.score(),
+    })
+}
+
+/// Explains the points earned by the characters of `query` matched at `positions` (char indices in `target`)
+/// according to `weights`.
+///
+pub(crate) fn explain_positions(
+    query: &str,
+    target: &str,
+    positions: &[usize],
+    weights: &ScoringConfig,
+) -> Vec<CharExplanation> {
+    let target_chars = target.chars().collect::<Vec<_>>();
+
+    let mut chars = Vec::with_capacity(positions.len());
+    // the number of the matched characters right before the current one
+    let mut run = 0;
+    for (index, (query_char, &target_index)) in query.chars().zip(positions).enumerate() {
+        let target_char = target_chars[target_index];
+        run = match index.checked_sub(1).map(|previous| positions[previous]) {
+            Some(previous) if previous + 1 == target_index => run + 1,
+            _ => 0,
+        };
+
+        let mut bonuses = Vec::new();
+        if run > 0 {
+            bonuses.push(Bonus::Consecutive(weights.consecutive_match * run));
+        }
+        if query_char == target_char {
+            bonuses.push(Bonus::ExactCase(weights.exact_match));
+        }
+        let position_bonus = match target_index
+            .checked_sub(1)
+            .map(|previous| target_chars[previous])
+        {
+            None => Some(Bonus::WordStart(weights.word_start)),
+            Some('/' | '\\') => Some(Bonus::AfterPathSeparator(weights.after_path_separator)),
+            Some('_' | '-' | '.' | ' ' | '\'' | '"' | ':') => {
+                Some(Bonus::AfterSeparator(weights.after_separator))
+            }
+            Some(_) if target_char.is_uppercase() && run == 0 => {
+                Some(Bonus::CamelCase(weights.camel_case))
+            }
+            Some(_) => None,
+        };
+        bonuses.extend(position_bonus);
+
+        let total = weights.regular + bonuses.iter().map(Bonus::points).sum::<i32>();
+        chars.push(CharExplanation {
+            query_char,
+            target_char,
+            target_index,
+            base_score: weights.regular,
+            bonuses,
+            total,
+        });
+    }
+    chars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_match() {
+        assert_eq!(explain_match("xyz", "foo bar"), None);
+    }
+
+    #[test]
+    fn consecutive_run() {
+        let explanation = explain_match("test", "test").unwrap();
+        let bonuses = explanation
+            .chars
+            .iter()
+            .map(|c| c.bonuses.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bonuses,
+            [
+                vec![Bonus::ExactCase(1), Bonus::WordStart(8)],
+                vec![Bonus::Consecutive(5), Bonus::ExactCase(1)],
+                vec![Bonus::Consecutive(10), Bonus::ExactCase(1)],
+                vec![Bonus::Consecutive(15), Bonus::ExactCase(1)],
+            ]
+        );
+        assert_eq!(explanation.total(), explanation.score);
+    }
+
+    #[test]
+    fn position_bonuses() {
+        let explanation = explain_match("abcd", "x/a.b:cxD").unwrap();
+        let bonuses = explanation
+            .chars
+            .iter()
+            .map(|c| (c.target_index, c.bonuses.last().copied()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bonuses,
+            [
+                (2, Some(Bonus::AfterPathSeparator(5))),
+                (4, Some(Bonus::AfterSeparator(4))),
+                (6, Some(Bonus::AfterSeparator(4))),
+                (8, Some(Bonus::CamelCase(2))),
+            ]
+        );
+        // the letter case differs, so the last character has no exact case bonus
+        assert_eq!(explanation.chars[3].query_char, 'd');
+        assert_eq!(explanation.chars[3].target_char, 'D');
+        assert_eq!(explanation.chars[3].total, 3);
+        assert_eq!(explanation.total(), explanation.score);
+    }
+
+    #[test]
+    fn display() {
+        let explanation = explain_match("Ab", "Ab").unwrap();
+        assert_eq!(
+            explanation.to_string(),
+            "'A' -> 'A' at 0: 1 + exact case 1 + word start 8 = 10\n\
+            'b' -> 'b' at 1: 1 + consecutive 5 + exact case 1 = 7\n\
+            total 17 (score 17)"
+        );
+    }
+}
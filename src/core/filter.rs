@@ -0,0 +1,235 @@
+use std::path::Path;
+
+/// Restricts the files searched in the recursive mode by their paths.
+///
+/// A file is searched if its path matches any of the include patterns (or there are none)
+/// and does not match any of the exclude patterns.
+/// The patterns are either globs, which match either the file name or the whole path
+/// (e.g. `*.rs` selects Rust sources, `*/target/*` skips everything under `target` directories),
+/// or regular expressions (the `regex` feature), which are searched for anywhere in the path
+/// (e.g. `\.rs$` selects Rust sources, `(^|/)target/` skips everything under `target` directories).
+///
+#[derive(Clone, Debug, Default)]
+pub struct Filter {
+    /// Glob patterns selecting the files to search.
+    ///
+    pub include_globs: Vec<glob::Pattern>,
+
+    /// Glob patterns selecting the files to skip.
+    ///
+    pub exclude_globs: Vec<glob::Pattern>,
+
+    /// Regular expressions selecting the files to search.
+    ///
+    #[cfg(feature = "regex")]
+    pub include_regexes: Vec<regex::Regex>,
+
+    /// Regular expressions selecting the files to skip.
+    ///
+    #[cfg(feature = "regex")]
+    pub exclude_regexes: Vec<regex::Regex>,
+}
+
+impl Filter {
+    /// Checks whether the file at `path` should be searched.
+    ///
+    pub fn is_allowed(&self, path: &Path) -> bool {
+        let included = (self.include_globs.is_empty() && !self.has_include_regexes())
+            || matches_any_glob(&self.include_globs, path)
+            || self.matches_include_regex(path);
+        included
+            && !matches_any_glob(&self.exclude_globs, path)
+            && !self.matches_exclude_regex(path)
+    }
+
+    #[cfg(feature = "regex")]
+    fn has_include_regexes(&self) -> bool {
+        !self.include_regexes.is_empty()
+    }
+
+    #[cfg(not(feature = "regex"))]
+    fn has_include_regexes(&self) -> bool {
+        false
+    }
+
+    #[cfg(feature = "regex")]
+    fn matches_include_regex(&self, path: &Path) -> bool {
+        let path = path.to_string_lossy();
+        self.include_regexes.iter().any(|re| re.is_match(&path))
+    }
+
+    #[cfg(not(feature = "regex"))]
+    fn matches_include_regex(&self, _path: &Path) -> bool {
+        false
+    }
+
+    #[cfg(feature = "regex")]
+    fn matches_exclude_regex(&self, path: &Path) -> bool {
+        let path = path.to_string_lossy();
+        self.exclude_regexes.iter().any(|re| re.is_match(&path))
+    }
+
+    #[cfg(not(feature = "regex"))]
+    fn matches_exclude_regex(&self, _path: &Path) -> bool {
+        false
+    }
+}
+
+fn matches_any_glob(globs: &[glob::Pattern], path: &Path) -> bool {
+    globs.iter().any(|glob| {
+        glob.matches_path(path)
+            || path
+                .file_name()
+                .is_some_and(|name| glob.matches(&name.to_string_lossy()))
+    })
+}
+
+// `regex::Regex` is not comparable, the regular expressions are compared by the pattern strings instead.
+impl PartialEq for Filter {
+    fn eq(&self, other: &Self) -> bool {
+        self.include_globs == other.include_globs
+            && self.exclude_globs == other.exclude_globs
+            && self.regexes_eq(other)
+    }
+}
+
+impl Filter {
+    #[cfg(feature = "regex")]
+    fn regexes_eq(&self, other: &Self) -> bool {
+        let patterns = |regexes: &[regex::Regex]| {
+            regexes
+                .iter()
+                .map(|re| re.as_str().to_owned())
+                .collect::<Vec<_>>()
+        };
+        patterns(&self.include_regexes) == patterns(&other.include_regexes)
+            && patterns(&self.exclude_regexes) == patterns(&other.exclude_regexes)
+    }
+
+    #[cfg(not(feature = "regex"))]
+    fn regexes_eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glob::Pattern;
+    #[cfg(feature = "regex")]
+    use regex::Regex;
+
+    fn glob_filter(include: &[&str], exclude: &[&str]) -> Filter {
+        let mut filter = Filter::default();
+        filter
+            .include_globs
+            .extend(include.iter().map(|g| Pattern::new(g).unwrap()));
+        filter
+            .exclude_globs
+            .extend(exclude.iter().map(|g| Pattern::new(g).unwrap()));
+        filter
+    }
+
+    #[test]
+    fn globs_include_any() {
+        let filter = glob_filter(&["*.rs", "Cargo.*"], &[]);
+        assert!(filter.is_allowed(Path::new("src/lib.rs")));
+        assert!(filter.is_allowed(Path::new("Cargo.toml")));
+        assert!(!filter.is_allowed(Path::new("README.md")));
+    }
+
+    #[test]
+    fn globs_match_file_name_or_path() {
+        let filter = glob_filter(&[], &["test?.txt", "*/target/*"]);
+        assert!(!filter.is_allowed(Path::new("resources/tests/test2.txt")));
+        assert!(!filter.is_allowed(Path::new("./target/debug/build.rs")));
+        assert!(!filter.is_allowed(Path::new("/home/user/crate/target/lib.rs")));
+        assert!(filter.is_allowed(Path::new("resources/tests/test.txt")));
+        assert!(filter.is_allowed(Path::new("src/targets.rs")));
+    }
+
+    #[test]
+    fn globs_exclude_wins() {
+        let filter = glob_filter(&["*.rs"], &["tests/*"]);
+        assert!(filter.is_allowed(Path::new("src/lib.rs")));
+        assert!(!filter.is_allowed(Path::new("tests/output.rs")));
+    }
+
+    #[test]
+    fn globs_include_exclude_combinations() {
+        let paths = ["src/lib.rs", "tests/output.rs", "README.md"].map(Path::new);
+        let allowed = |filter: Filter| paths.map(|path| filter.is_allowed(path));
+        assert_eq!(allowed(glob_filter(&[], &[])), [true, true, true]);
+        assert_eq!(allowed(glob_filter(&["*.rs"], &[])), [true, true, false]);
+        assert_eq!(allowed(glob_filter(&[], &["tests/*"])), [true, false, true]);
+        assert_eq!(
+            allowed(glob_filter(&["*.rs"], &["tests/*"])),
+            [true, false, false]
+        );
+    }
+
+    #[test]
+    fn globs_equality() {
+        assert_eq!(glob_filter(&["a"], &["b"]), glob_filter(&["a"], &["b"]));
+        assert_ne!(glob_filter(&["a"], &["b"]), glob_filter(&["b"], &["a"]));
+    }
+
+    #[cfg(feature = "regex")]
+    fn filter(include: &[&str], exclude: &[&str]) -> Filter {
+        Filter {
+            include_regexes: include.iter().map(|re| Regex::new(re).unwrap()).collect(),
+            exclude_regexes: exclude.iter().map(|re| Regex::new(re).unwrap()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn empty() {
+        assert!(Filter::default().is_allowed(Path::new("src/lib.rs")));
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn include_any() {
+        let filter = filter(&[r"\.rs$", r"\.toml$"], &[]);
+        assert!(filter.is_allowed(Path::new("src/lib.rs")));
+        assert!(filter.is_allowed(Path::new("Cargo.toml")));
+        assert!(!filter.is_allowed(Path::new("README.md")));
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn exclude_all() {
+        let filter = filter(&[], &[r"(^|/)target/", r"\.lock$"]);
+        assert!(filter.is_allowed(Path::new("src/lib.rs")));
+        assert!(!filter.is_allowed(Path::new("target/debug/build.rs")));
+        assert!(!filter.is_allowed(Path::new("Cargo.lock")));
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn exclude_wins() {
+        let filter = filter(&[r"\.rs$"], &[r"^tests/"]);
+        assert!(filter.is_allowed(Path::new("src/lib.rs")));
+        assert!(!filter.is_allowed(Path::new("tests/output.rs")));
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn equality_by_patterns() {
+        assert_eq!(filter(&["a"], &["b"]), filter(&["a"], &["b"]));
+        assert_ne!(filter(&["a"], &["b"]), filter(&["b"], &["a"]));
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn globs_and_regexes_combined() {
+        let filter = Filter {
+            include_globs: vec![Pattern::new("*.toml").unwrap()],
+            ..filter(&[r"\.rs$"], &[])
+        };
+        assert!(filter.is_allowed(Path::new("src/lib.rs")));
+        assert!(filter.is_allowed(Path::new("Cargo.toml")));
+        assert!(!filter.is_allowed(Path::new("README.md")));
+    }
+}
@@ -0,0 +1,85 @@
+use crate::core::acronym::MASK;
+use vscode_fuzzy_score_rs::FuzzyMatch;
+
+/// Matches `query` against `target` as a literal substring, e.g. `ab` matches `xaby`, but not `axby`.
+/// Letter case is ignored the same way as by the fuzzy matcher, but an occurrence with the exact case is preferred,
+/// so that the case-sensitive check applied afterwards is not fooled by an earlier occurrence differing in case.
+///
+/// Everything but the first suitable occurrence is masked out before running the fuzzy matcher,
+/// so the positions of the returned match are char indices of that occurrence in the original `target`.
+///
+pub(crate) fn fixed_string_match(query: &str, target: &str) -> Option<FuzzyMatch> {
+    if query.contains(MASK) {
+        return None;
+    }
+
+    let start = match target.find(query) {
+        Some(offset) => target[..offset].chars().count(),
+        None => find_ignore_case(query, target)?,
+    };
+    let end = start + query.chars().count();
+    let masked = target
+        .chars()
+        .enumerate()
+        .map(|(index, c)| {
+            if (start..end).contains(&index) {
+                c
+            } else {
+                MASK
+            }
+        })
+        .collect::<String>();
+    vscode_fuzzy_score_rs::fuzzy_match(query, &masked)
+}
+
+/// Returns the char index of the first occurrence of `query` in `target` ignoring letter case.
+///
+fn find_ignore_case(query: &str, target: &str) -> Option<usize> {
+    let query = query.chars().collect::<Vec<_>>();
+    let target = target.chars().collect::<Vec<_>>();
+    (0..=target.len().checked_sub(query.len())?).find(|&start| {
+        query
+            .iter()
+            .zip(&target[start..])
+            .all(|(q, t)| q.to_lowercase().eq(t.to_lowercase()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(query: &str, target: &str) -> Option<Vec<usize>> {
+        fixed_string_match(query, target).map(|m| m.positions().clone())
+    }
+
+    #[test]
+    fn substring() {
+        assert_eq!(positions("test", "a test line"), Some(vec![2, 3, 4, 5]));
+        assert_eq!(positions("line", "line"), Some(vec![0, 1, 2, 3]));
+        assert_eq!(positions("tst", "a test line"), None);
+        assert_eq!(positions("long query", "short"), None);
+    }
+
+    #[test]
+    fn first_occurrence() {
+        assert_eq!(positions("ab", "xabyab"), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn ignore_case() {
+        assert_eq!(positions("TEST", "a test"), Some(vec![2, 3, 4, 5]));
+        assert_eq!(positions("Test", "test Test"), Some(vec![5, 6, 7, 8]));
+    }
+
+    #[test]
+    fn unicode() {
+        assert_eq!(positions("кст", "Текст"), Some(vec![2, 3, 4]));
+        assert_eq!(positions("ТЕКСТ", "це текст"), Some(vec![3, 4, 5, 6, 7]));
+    }
+
+    #[test]
+    fn mask_in_query() {
+        assert_eq!(positions("a\0", "a\0"), None);
+    }
+}
@@ -1,3 +1,21 @@
+pub(crate) mod acronym;
+pub(crate) mod counting_reader;
+pub(crate) mod deadline;
+pub(crate) mod diacritics;
+pub(crate) mod exact_case;
 pub(crate) mod exit_code;
+pub(crate) mod explain;
+pub(crate) mod filter;
+pub(crate) mod fixed_string;
+pub(crate) mod normalization;
+pub(crate) mod path_aware;
+pub(crate) mod query_check;
 pub(crate) mod reader;
 pub mod request;
+pub(crate) mod request_builder;
+pub(crate) mod run_error;
+pub(crate) mod scoring;
+pub(crate) mod scoring_config;
+pub(crate) mod search_stats;
+pub(crate) mod source_plugin;
+pub(crate) mod transcoding_reader;
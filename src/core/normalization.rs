@@ -0,0 +1,82 @@
+use crate::core::acronym::MASK;
+use std::{borrow::Cow, iter};
+use unicode_normalization::{char::canonical_combining_class, is_nfc, UnicodeNormalization};
+
+/// Brings `s` to the Unicode Normalization Form C (it is returned as is if it is already normalized).
+///
+pub(crate) fn normalize(s: String) -> String {
+    if is_nfc(&s) {
+        s
+    } else {
+        s.nfc().collect()
+    }
+}
+
+/// Brings `line` to the Unicode Normalization Form C without changing the number of characters in it:
+/// every character sequence composed into fewer characters is replaced with them, the rest of it is masked out.
+/// This way the positions of a match found in the result are valid for the original `line` as well.
+/// The (rare) sequences that get longer when normalized are left as is.
+///
+pub(crate) fn mask_normalized(line: &str) -> Cow<'_, str> {
+    if is_nfc(line) {
+        return Cow::Borrowed(line);
+    }
+    let mut masked = String::with_capacity(line.len());
+    let mut sequence = String::new();
+    // the mask appended only completes the last sequence, it does not end up in the result
+    for c in line.chars().chain([MASK]) {
+        // a sequence is a character followed by the marks that can be composed with it
+        if canonical_combining_class(c) == 0 && !sequence.is_empty() {
+            mask_sequence(&sequence, &mut masked);
+            sequence.clear();
+        }
+        sequence.push(c);
+    }
+    Cow::Owned(masked)
+}
+
+fn mask_sequence(sequence: &str, masked: &mut String) {
+    let length = sequence.chars().count();
+    let normalized = sequence.nfc().collect::<String>();
+    let normalized_length = normalized.chars().count();
+    if normalized_length <= length {
+        masked.push_str(&normalized);
+        masked.extend(iter::repeat_n(MASK, length - normalized_length));
+    } else {
+        masked.push_str(sequence);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_decomposed() {
+        assert_eq!(normalize(String::from("cafe\u{301}")), "caf\u{e9}");
+        assert_eq!(normalize(String::from("caf\u{e9}")), "caf\u{e9}");
+    }
+
+    #[test]
+    fn mask_normalized_keeps_positions() {
+        assert_eq!(
+            mask_normalized("cafe\u{301} au lait"),
+            "caf\u{e9}\0 au lait"
+        );
+        assert_eq!(
+            mask_normalized("cafe\u{301} au lait").chars().count(),
+            "cafe\u{301} au lait".chars().count()
+        );
+        // a mark that is not composed with its letter stays in place
+        assert_eq!(mask_normalized("q\u{301}e\u{301}"), "q\u{301}\u{e9}\0");
+    }
+
+    #[test]
+    fn mask_normalized_untouched() {
+        assert!(matches!(
+            mask_normalized("caf\u{e9} noir"),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(mask_normalized("한국어"), "한국어");
+    }
+}
@@ -0,0 +1,127 @@
+use crate::core::acronym::MASK;
+use std::ops::Range;
+use vscode_fuzzy_score_rs::FuzzyMatch;
+
+/// Matches a path-like `query` (i.e. one containing `/` or `\`) against `target` component-wise:
+/// every component of the query has to match within a single component of the target,
+/// and the components have to be matched in the same order, e.g. `src/lib` matches `src/core/lib.rs`,
+/// but neither `lib/src.rs` nor `s/rc/lib.rs`. Queries that do not look like paths are matched as usual.
+///
+/// Each query component is assigned to the first suitable target component (after the one assigned to the previous
+/// query component). Everything but the assigned components and the separators in front of them is then masked out
+/// before running the fuzzy matcher, so the query separators can only match the separators of the assigned components.
+/// The positions of the returned match are char indices into the original `target`.
+///
+pub(crate) fn path_aware_match(query: &str, target: &str) -> Option<FuzzyMatch> {
+    if !query.contains(is_path_separator) {
+        return vscode_fuzzy_score_rs::fuzzy_match(query, target);
+    }
+    if query.contains(MASK) {
+        return None;
+    }
+
+    let target_chars = target.chars().collect::<Vec<_>>();
+    let target_components = components(&target_chars);
+    let mut assigned = Vec::new();
+    let mut candidates = target_components.iter();
+    for query_component in query.split(is_path_separator) {
+        let component = candidates.find(|range| {
+            query_component.is_empty()
+                || vscode_fuzzy_score_rs::fuzzy_match(
+                    query_component,
+                    &target_chars[(*range).clone()].iter().collect::<String>(),
+                )
+                .is_some()
+        })?;
+        assigned.push(component.clone());
+    }
+
+    let mut keep = vec![false; target_chars.len()];
+    for (index, component) in assigned.iter().enumerate() {
+        keep[component.clone()].fill(true);
+        if index > 0 {
+            // the separator in front of the component (every component but the first one has it)
+            keep[component.start - 1] = true;
+        }
+    }
+    let masked = target_chars
+        .iter()
+        .zip(keep)
+        .map(|(&c, keep)| if keep { c } else { MASK })
+        .collect::<String>();
+    vscode_fuzzy_score_rs::fuzzy_match(query, &masked)
+}
+
+fn is_path_separator(c: char) -> bool {
+    c == '/' || c == '\\'
+}
+
+/// Splits `chars` on path separators, returning the ranges of char indices of the components (possibly empty ones).
+///
+fn components(chars: &[char]) -> Vec<Range<usize>> {
+    let mut components = Vec::new();
+    let mut start = 0;
+    for (index, &c) in chars.iter().enumerate() {
+        if is_path_separator(c) {
+            components.push(start..index);
+            start = index + 1;
+        }
+    }
+    components.push(start..chars.len());
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(query: &str, target: &str) -> Option<Vec<usize>> {
+        path_aware_match(query, target).map(|m| m.positions().clone())
+    }
+
+    #[test]
+    fn components_ranges() {
+        let chars = "src/core\\lib.rs".chars().collect::<Vec<_>>();
+        assert_eq!(components(&chars), [0..3, 4..8, 9..15]);
+        let chars = "/usr//lib/".chars().collect::<Vec<_>>();
+        assert_eq!(components(&chars), [0..0, 1..4, 5..5, 6..9, 10..10]);
+    }
+
+    #[test]
+    fn not_a_path() {
+        assert_eq!(
+            path_aware_match("test", "a test line").map(|m| m.positions().clone()),
+            vscode_fuzzy_score_rs::fuzzy_match("test", "a test line")
+                .map(|m| m.positions().clone())
+        );
+    }
+
+    #[test]
+    fn components_matched_in_order() {
+        assert_eq!(
+            positions("src/lib", "src/core/lib.rs"),
+            Some(vec![0, 1, 2, 8, 9, 10, 11])
+        );
+        assert_eq!(positions("src/lib", "lib/src.rs"), None);
+    }
+
+    #[test]
+    fn component_spanning_separators() {
+        // a plain fuzzy match picks the characters from different components
+        assert!(vscode_fuzzy_score_rs::fuzzy_match("ab/c", "a/b/c").is_some());
+        assert_eq!(positions("ab/c", "a/b/c"), None);
+        assert_eq!(positions("ab/c", "a/ab/c"), Some(vec![2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn backslashes_and_empty_components() {
+        assert_eq!(
+            positions("core\\lib", "src/core/lib.rs"),
+            Some(vec![4, 5, 6, 7, 8, 9, 10, 11])
+        );
+        assert_eq!(
+            positions("/usr/lib", "/usr/local/lib"),
+            Some(vec![0, 1, 2, 3, 10, 11, 12, 13])
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use crate::core::request::Targets;
+use std::fmt;
+
+/// Queries shorter than this (in characters) are considered too permissive for a large search.
+///
+const MIN_SELECTIVE_LENGTH: usize = 3;
+
+/// A warning about a query that is going to match (almost) every line of a large search.
+///
+#[derive(Debug, PartialEq)]
+pub(crate) struct ShortQueryWarning {
+    query: String,
+}
+
+impl fmt::Display for ShortQueryWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let length = self.query.chars().count();
+        write!(
+            f,
+            "The query '{}' is only {length} character{} long and is likely to match almost every line; \
+            use a longer query or narrow the results with '--top NUM'.",
+            self.query,
+            if length == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// Checks whether `query` is too short for searching `targets`, i.e. whether a huge number of results is to be expected.
+/// Only the searches that may span many files (recursive, glob or several files) are considered large.
+///
+pub(crate) fn validate_query(query: &str, targets: &Targets) -> Option<ShortQueryWarning> {
+    let large = match targets {
+        Targets::Files(files) => files.len() > 1,
+        Targets::RecursiveEntries(_) | Targets::Glob(_) => true,
+        Targets::Stdin => false,
+    };
+    (large && query.chars().count() < MIN_SELECTIVE_LENGTH).then(|| ShortQueryWarning {
+        query: String::from(query),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn short_query_large_targets() {
+        let targets = Targets::RecursiveEntries(vec![PathBuf::from(".")]);
+        assert!(validate_query("a", &targets).is_some());
+        assert!(validate_query("ab", &targets).is_some());
+        assert!(validate_query("abc", &targets).is_none());
+        // the length is counted in characters
+        assert!(validate_query("ая", &targets).is_some());
+        assert!(validate_query("тест", &targets).is_none());
+    }
+
+    #[test]
+    fn short_query_small_targets() {
+        assert!(validate_query("a", &Targets::Stdin).is_none());
+        assert!(validate_query("a", &Targets::Files(vec![PathBuf::from("file")])).is_none());
+        assert!(validate_query(
+            "a",
+            &Targets::Files(vec![PathBuf::from("one"), PathBuf::from("two")])
+        )
+        .is_some());
+        assert!(validate_query(
+            "a",
+            &Targets::Glob(vec![glob::Pattern::new("*.rs").unwrap()])
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn warning_display() {
+        let targets = Targets::RecursiveEntries(vec![PathBuf::from(".")]);
+        assert_eq!(
+            validate_query("a", &targets).unwrap().to_string(),
+            "The query 'a' is only 1 character long and is likely to match almost every line; \
+            use a longer query or narrow the results with '--top NUM'."
+        );
+        assert!(validate_query("ab", &targets)
+            .unwrap()
+            .to_string()
+            .starts_with("The query 'ab' is only 2 characters long"));
+    }
+}
@@ -1,28 +1,112 @@
+use crate::core::{filter::Filter, transcoding_reader::TranscodingReader};
+use encoding_rs::Encoding;
+use log::debug;
 use std::{
+    ffi::OsStr,
     fs,
-    io::{self, BufRead, BufReader},
+    io::{self, BufRead, BufReader, Read},
+    iter,
+    ops::Range,
     path::Path,
 };
 
 pub(crate) struct Reader {
     displayed_name: String,
     source: Box<dyn BufRead>,
+    /// The number of lines that precede the content of `source` (see [`Reader::byte_range`]).
+    lines_skipped: usize,
 }
 
 impl Reader {
-    pub(crate) fn file_reader(path: impl AsRef<Path>) -> Result<Self, io::Error> {
+    /// Creates a reader for the file at `path`.
+    /// If `max_size` is given and the file is larger than that, the file is not opened and [`None`] is returned.
+    ///
+    pub(crate) fn file_reader(
+        path: impl AsRef<Path>,
+        max_size: Option<u64>,
+    ) -> Result<Option<Self>, io::Error> {
+        if let Some(max_size) = max_size {
+            let size = fs::metadata(&path)?.len();
+            if size > max_size {
+                debug!(
+                    "Skipping {} ({size} bytes exceed the limit of {max_size} bytes).",
+                    path.as_ref().display()
+                );
+                return Ok(None);
+            }
+        }
+
         let file = fs::File::open(&path)?;
         let reader = Box::new(BufReader::new(file));
-        Ok(Self {
+        Ok(Some(Self {
             displayed_name: path.as_ref().to_string_lossy().into_owned(),
             source: reader,
-        })
+            lines_skipped: 0,
+        }))
+    }
+
+    /// Same as [`Reader::file_reader`] but if the file is compressed (judging by its extension or its first bytes),
+    /// it is transparently decompressed.
+    /// Gzip and zstd are supported provided that the corresponding features (`gzip` and `zstd`) are enabled.
+    ///
+    pub(crate) fn compressed_reader(
+        path: impl AsRef<Path>,
+        max_size: Option<u64>,
+    ) -> Result<Option<Self>, io::Error> {
+        let Some(Self {
+            displayed_name,
+            mut source,
+            lines_skipped,
+        }) = Self::file_reader(&path, max_size)?
+        else {
+            return Ok(None);
+        };
+
+        let source = match Compression::detect(path.as_ref(), source.fill_buf()?) {
+            Some(Compression::Gzip) => {
+                debug!("Reading {displayed_name} as a gzip archive.");
+                gzip_decoder(source)?
+            }
+            Some(Compression::Zstd) => {
+                debug!("Reading {displayed_name} as a zstd archive.");
+                zstd_decoder(source)?
+            }
+            None => source,
+        };
+        Ok(Some(Self {
+            displayed_name,
+            source,
+            lines_skipped,
+        }))
+    }
+
+    /// Creates a reader for the entry `entry_name` of the zip archive at `archive_path`, `data` being its content.
+    /// The reader is named after both, e.g. `archive.zip:inner/path.txt`.
+    ///
+    #[cfg(feature = "zip-archives")]
+    pub(crate) fn zip_entry_reader(archive_path: &Path, entry_name: &str, data: Vec<u8>) -> Self {
+        Self {
+            displayed_name: format!("{}:{entry_name}", archive_path.to_string_lossy()),
+            source: Box::new(io::Cursor::new(data)),
+            lines_skipped: 0,
+        }
+    }
+
+    /// Creates a reader for `path` with the content provided by a [`crate::SourcePlugin`].
+    ///
+    pub(crate) fn plugin_reader(path: &Path, source: Box<dyn BufRead>) -> Self {
+        Self {
+            displayed_name: path.to_string_lossy().into_owned(),
+            source,
+            lines_skipped: 0,
+        }
     }
 
     pub(crate) fn stdin_reader() -> Self {
         Self {
             displayed_name: String::from("(standard input)"),
             source: Box::new(BufReader::new(io::stdin())),
+            lines_skipped: 0,
         }
     }
 
@@ -30,22 +114,397 @@ impl Reader {
         &self.displayed_name
     }
 
+    /// Returns the number of lines skipped before the content of the reader (see [`Reader::byte_range`]),
+    /// i.e. the offset to add to the line numbers to make them absolute.
+    ///
+    pub(crate) const fn lines_skipped(&self) -> usize {
+        self.lines_skipped
+    }
+
+    /// Restricts the reader to the lines (terminated by `terminator`) that start within `range` (in bytes).
+    /// The beginning of the content is skipped, counting the lines on the way, so that the line numbers
+    /// stay absolute (see [`Reader::lines_skipped`]). The line that straddles `range.start` is skipped too,
+    /// while the one that straddles `range.end` is read to its end.
+    ///
+    pub(crate) fn byte_range(self, range: &Range<u64>, terminator: u8) -> Result<Self, io::Error> {
+        let Self {
+            displayed_name,
+            mut source,
+            mut lines_skipped,
+        } = self;
+        let mut position = 0;
+        let mut at_line_start = true;
+        while position < range.start {
+            let buf = source.fill_buf()?;
+            if buf.is_empty() {
+                break;
+            }
+            let len = buf
+                .len()
+                .min(usize::try_from(range.start - position).unwrap_or(usize::MAX));
+            lines_skipped += buf[..len].iter().filter(|&&b| b == terminator).count();
+            at_line_start = buf[len - 1] == terminator;
+            source.consume(len);
+            position += len as u64;
+        }
+        if !at_line_start {
+            // the line started before the range
+            let mut partial_line = Vec::new();
+            let len = source.read_until(terminator, &mut partial_line)?;
+            position += len as u64;
+            lines_skipped += usize::from(partial_line.last() == Some(&terminator));
+        }
+        debug!(
+            "Reading {displayed_name} from byte {position} (line {}).",
+            lines_skipped + 1
+        );
+        Ok(Self {
+            displayed_name,
+            source: Box::new(BufReader::new(LineBoundedTake {
+                source,
+                remaining: range.end.saturating_sub(position),
+                terminator,
+                at_line_start: true,
+            })),
+            lines_skipped,
+        })
+    }
+
+    /// Makes the reader decode its content from `encoding` into UTF-8 (see [`TranscodingReader`]).
+    ///
+    pub(crate) fn transcoded(self, encoding: &'static Encoding) -> Self {
+        debug!("Decoding {} from {}.", self.displayed_name, encoding.name());
+        Self {
+            displayed_name: self.displayed_name,
+            source: Box::new(BufReader::new(TranscodingReader::new(
+                self.source,
+                encoding,
+            ))),
+            lines_skipped: self.lines_skipped,
+        }
+    }
+
     /// Just a getter that returns the underlying source.
     pub(crate) fn into_source(self) -> Box<dyn BufRead> {
         self.source
     }
+
+    /// Turns the reader into an iterator over its lines terminated by `terminator` (`\n` or `\0`),
+    /// similar to [`BufRead::lines`], except that invalid UTF-8 sequences are replaced with `U+FFFD`
+    /// instead of resulting in an error.
+    ///
+    pub(crate) fn lossy_lines(self, terminator: u8) -> LossyLines {
+        LossyLines {
+            displayed_name: self.displayed_name,
+            source: self.source,
+            terminator,
+            line_number: self.lines_skipped,
+        }
+    }
+
+    /// Turns the reader into an iterator over its NUL-terminated lines, the same way [`BufRead::lines`] does
+    /// for the newline-terminated ones (i.e. invalid UTF-8 results in an error).
+    ///
+    pub(crate) fn split_by_nul(self) -> impl Iterator<Item = Result<String, io::Error>> {
+        self.source.split(b'\0').map(|line| {
+            line.and_then(|bytes| {
+                String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            })
+        })
+    }
+}
+
+/// Compression formats recognized by [`Reader::compressed_reader`].
+///
+#[derive(Debug, PartialEq)]
+enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    const GZIP_MAGIC: &'static [u8] = &[0x1f, 0x8b];
+    const ZSTD_MAGIC: &'static [u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+    /// Detects the compression format of a file based on its `path` extension or, failing that,
+    /// on the first bytes of its content (`header`).
+    ///
+    fn detect(path: &Path, header: &[u8]) -> Option<Self> {
+        match path.extension().and_then(OsStr::to_str) {
+            Some("gz") => Some(Self::Gzip),
+            Some("zst") => Some(Self::Zstd),
+            _ if header.starts_with(Self::GZIP_MAGIC) => Some(Self::Gzip),
+            _ if header.starts_with(Self::ZSTD_MAGIC) => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Checks whether the file at `path` is a zip archive (judging by its extension).
+///
+pub(crate) fn is_zip(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("zip"))
+}
+
+/// Opens the zip archive at `path` and returns a reader for each of its files (see [`Reader::zip_entry_reader`])
+/// whose name is allowed by `filter`. The entries are read one by one, as the iterator advances.
+///
+#[cfg(feature = "zip-archives")]
+pub(crate) fn zip_entry_readers(
+    path: &Path,
+    filter: Filter,
+) -> Box<dyn Iterator<Item = Result<Reader, io::Error>>> {
+    let mut archive = match fs::File::open(path)
+        .and_then(|file| zip::ZipArchive::new(BufReader::new(file)).map_err(io::Error::from))
+    {
+        Ok(archive) => archive,
+        Err(e) => return Box::new(iter::once(Err(e))),
+    };
+    let path = path.to_path_buf();
+    Box::new((0..archive.len()).filter_map(move |index| {
+        let mut entry = match archive.by_index(index) {
+            Ok(entry) => entry,
+            Err(e) => return Some(Err(e.into())),
+        };
+        if entry.is_dir() {
+            return None;
+        }
+        let entry_name = String::from(entry.name());
+        if !filter.is_allowed(Path::new(&entry_name)) {
+            debug!("Skipping {}:{entry_name} (filtered out).", path.display());
+            return None;
+        }
+        let mut data = Vec::new();
+        Some(
+            entry
+                .read_to_end(&mut data)
+                .map(|_| Reader::zip_entry_reader(&path, &entry_name, data)),
+        )
+    }))
+}
+
+#[cfg(not(feature = "zip-archives"))]
+pub(crate) fn zip_entry_readers(
+    _path: &Path,
+    _filter: Filter,
+) -> Box<dyn Iterator<Item = Result<Reader, io::Error>>> {
+    Box::new(iter::once(Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "zip support is not enabled (the `zip-archives` feature)",
+    ))))
+}
+
+#[cfg(feature = "gzip")]
+fn gzip_decoder(source: Box<dyn BufRead>) -> Result<Box<dyn BufRead>, io::Error> {
+    // a multi-member decoder is needed to read files made by concatenating several gzip archives
+    Ok(Box::new(BufReader::new(
+        flate2::bufread::MultiGzDecoder::new(source),
+    )))
+}
+
+#[cfg(not(feature = "gzip"))]
+fn gzip_decoder(_source: Box<dyn BufRead>) -> Result<Box<dyn BufRead>, io::Error> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "gzip support is not enabled (the `gzip` feature)",
+    ))
+}
+
+#[cfg(feature = "zstd")]
+fn zstd_decoder(source: Box<dyn BufRead>) -> Result<Box<dyn BufRead>, io::Error> {
+    Ok(Box::new(BufReader::new(
+        zstd::stream::read::Decoder::with_buffer(source)?,
+    )))
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_decoder(_source: Box<dyn BufRead>) -> Result<Box<dyn BufRead>, io::Error> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "zstd support is not enabled (the `zstd` feature)",
+    ))
+}
+
+/// Reads at most `remaining` bytes of `source` and then the rest of the current line, if any
+/// (see [`Reader::byte_range`]).
+///
+struct LineBoundedTake {
+    source: Box<dyn BufRead>,
+    remaining: u64,
+    terminator: u8,
+    at_line_start: bool,
+}
+
+impl Read for LineBoundedTake {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 && self.at_line_start {
+            return Ok(0);
+        }
+        let buf = self.source.fill_buf()?;
+        let len = if self.remaining > 0 {
+            buf.len()
+                .min(out.len())
+                .min(usize::try_from(self.remaining).unwrap_or(usize::MAX))
+        } else {
+            // past the end of the range, finishing the line that started within it
+            let len = buf.len().min(out.len());
+            buf[..len]
+                .iter()
+                .position(|&b| b == self.terminator)
+                .map_or(len, |index| index + 1)
+        };
+        if len == 0 {
+            return Ok(0);
+        }
+        out[..len].copy_from_slice(&buf[..len]);
+        self.at_line_start = buf[len - 1] == self.terminator;
+        self.remaining = self.remaining.saturating_sub(len as u64);
+        self.source.consume(len);
+        Ok(len)
+    }
+}
+
+/// An iterator over the lines of a [`Reader`] that tolerates invalid UTF-8 (see [`Reader::lossy_lines`]).
+///
+pub(crate) struct LossyLines {
+    displayed_name: String,
+    source: Box<dyn BufRead>,
+    terminator: u8,
+    line_number: usize,
+}
+
+impl Iterator for LossyLines {
+    type Item = Result<String, io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = Vec::new();
+        match self.source.read_until(self.terminator, &mut buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                self.line_number += 1;
+                // strip the line ending the same way `BufRead::lines` does
+                if buf.last() == Some(&self.terminator) {
+                    buf.pop();
+                    if self.terminator == b'\n' && buf.last() == Some(&b'\r') {
+                        buf.pop();
+                    }
+                }
+                for chunk in buf.utf8_chunks() {
+                    if !chunk.invalid().is_empty() {
+                        debug!(
+                            "Replacing invalid UTF-8 sequence {:02x?} in {}, line {}.",
+                            chunk.invalid(),
+                            self.displayed_name,
+                            self.line_number
+                        );
+                    }
+                }
+                Some(Ok(String::from_utf8_lossy(&buf).into_owned()))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Write;
     use tempfile::NamedTempFile;
 
     #[test]
     fn file_constructor() {
         let tmp = NamedTempFile::new().unwrap();
-        let reader = Reader::file_reader(tmp.path()).unwrap();
+        let reader = Reader::file_reader(tmp.path(), None).unwrap().unwrap();
+        assert_eq!(reader.displayed_name, tmp.path().to_string_lossy());
+    }
+
+    #[test]
+    fn file_constructor_within_size_limit() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(b"0123456789").unwrap();
+        let reader = Reader::file_reader(tmp.path(), Some(10)).unwrap();
+        assert!(reader.is_some());
+    }
+
+    #[test]
+    fn file_constructor_above_size_limit() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(b"0123456789").unwrap();
+        let reader = Reader::file_reader(tmp.path(), Some(9)).unwrap();
+        assert!(reader.is_none());
+    }
+
+    #[test]
+    fn compression_detect() {
+        assert_eq!(
+            Compression::detect(Path::new("file.gz"), b""),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::detect(Path::new("file.zst"), b""),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(
+            Compression::detect(Path::new("file"), &[0x1f, 0x8b, 0x08]),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::detect(Path::new("file"), &[0x28, 0xb5, 0x2f, 0xfd, 0x00]),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(Compression::detect(Path::new("file.txt"), b"text"), None);
+        assert_eq!(Compression::detect(Path::new("file"), b""), None);
+    }
+
+    #[test]
+    fn zip_detection() {
+        assert!(is_zip(Path::new("archive.zip")));
+        assert!(is_zip(Path::new("dir/ARCHIVE.ZIP")));
+        assert!(!is_zip(Path::new("archive.zip.txt")));
+        assert!(!is_zip(Path::new("zip")));
+    }
+
+    #[cfg(feature = "zip-archives")]
+    #[test]
+    fn zip_entry_constructor() {
+        let reader = Reader::zip_entry_reader(
+            Path::new("dir/archive.zip"),
+            "inner/file.txt",
+            b"line1\nline2\n".to_vec(),
+        );
+        assert_eq!(reader.displayed_name, "dir/archive.zip:inner/file.txt");
+        let lines = reader
+            .into_source()
+            .lines()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(lines, ["line1", "line2"]);
+    }
+
+    #[test]
+    fn compressed_constructor_plain_file() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(b"line1\nline2\n").unwrap();
+        let reader = Reader::compressed_reader(tmp.path(), None)
+            .unwrap()
+            .unwrap();
         assert_eq!(reader.displayed_name, tmp.path().to_string_lossy());
+        let lines = reader
+            .into_source()
+            .lines()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(lines, ["line1", "line2"]);
+    }
+
+    #[test]
+    fn compressed_constructor_above_size_limit() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(b"0123456789").unwrap();
+        let reader = Reader::compressed_reader(tmp.path(), Some(9)).unwrap();
+        assert!(reader.is_none());
     }
 
     #[test]
@@ -54,10 +513,115 @@ mod tests {
         assert_eq!(reader.displayed_name, "(standard input)");
     }
 
+    #[test]
+    fn lossy_lines() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(b"valid\nin\xffvalid\r\n\xc3\x28\ntrailing")
+            .unwrap();
+        let reader = Reader::file_reader(tmp.path(), None).unwrap().unwrap();
+        let lines = reader
+            .lossy_lines(b'\n')
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(lines, ["valid", "in\u{FFFD}valid", "\u{FFFD}(", "trailing"]);
+    }
+
+    #[test]
+    fn lossy_lines_nul_terminated() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(b"multi\nline\r\n\0in\xffvalid\0").unwrap();
+        let reader = Reader::file_reader(tmp.path(), None).unwrap().unwrap();
+        let lines = reader
+            .lossy_lines(b'\0')
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(lines, ["multi\nline\r\n", "in\u{FFFD}valid"]);
+    }
+
+    #[test]
+    fn split_by_nul() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(b"first\0second\nline\0\0trailing").unwrap();
+        let reader = Reader::file_reader(tmp.path(), None).unwrap().unwrap();
+        let lines = reader
+            .split_by_nul()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(lines, ["first", "second\nline", "", "trailing"]);
+    }
+
+    #[test]
+    fn split_by_nul_invalid_utf8() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(b"valid\0in\xffvalid\0").unwrap();
+        let reader = Reader::file_reader(tmp.path(), None).unwrap().unwrap();
+        let error = reader
+            .split_by_nul()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    fn range_lines(content: &[u8], range: Range<u64>) -> (usize, Vec<String>) {
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(content).unwrap();
+        let reader = Reader::file_reader(tmp.path(), None)
+            .unwrap()
+            .unwrap()
+            .byte_range(&range, b'\n')
+            .unwrap();
+        let lines_skipped = reader.lines_skipped();
+        let lines = reader
+            .into_source()
+            .lines()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        (lines_skipped, lines)
+    }
+
+    #[test]
+    fn byte_range_at_line_boundaries() {
+        // the lines start at 0, 4, 8 and 12
+        let content = b"one\ntwo\nsix\nten\n";
+        assert_eq!(
+            range_lines(content, 4..12),
+            (1, vec![String::from("two"), String::from("six")])
+        );
+        assert_eq!(range_lines(content, 0..4), (0, vec![String::from("one")]));
+        assert_eq!(range_lines(content, 4..4), (1, vec![]));
+    }
+
+    #[test]
+    fn byte_range_within_lines() {
+        let content = b"one\ntwo\nsix\nten";
+        // "one" starts before the range, "six" is read whole
+        assert_eq!(
+            range_lines(content, 2..9),
+            (1, vec![String::from("two"), String::from("six")])
+        );
+        assert_eq!(
+            range_lines(content, 12..100),
+            (3, vec![String::from("ten")])
+        );
+        assert_eq!(range_lines(content, 13..100), (3, vec![]));
+    }
+
+    #[test]
+    fn byte_range_past_the_end() {
+        assert_eq!(range_lines(b"one\ntwo", 100..200), (1, vec![]));
+    }
+
+    #[test]
+    fn lossy_lines_empty() {
+        let tmp = NamedTempFile::new().unwrap();
+        let reader = Reader::file_reader(tmp.path(), None).unwrap().unwrap();
+        assert_eq!(reader.lossy_lines(b'\n').count(), 0);
+    }
+
     #[test]
     fn displayed_name() {
         let tmp = NamedTempFile::new().unwrap();
-        let file_reader = Reader::file_reader(tmp.path()).unwrap();
+        let file_reader = Reader::file_reader(tmp.path(), None).unwrap().unwrap();
         assert_eq!(file_reader.display_name(), &tmp.path().to_string_lossy());
         let stdin_reader = Reader::stdin_reader();
         assert_eq!(stdin_reader.display_name(), "(standard input)");
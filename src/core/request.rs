@@ -1,6 +1,10 @@
-use crate::cli::formatting::Formatting;
+use crate::{
+    cli::formatting::Formatting,
+    core::{filter::Filter, scoring_config::ScoringConfig, source_plugin::SourceRegistry},
+};
+use encoding_rs::Encoding;
 use log::LevelFilter;
-use std::path::PathBuf;
+use std::{collections::BTreeSet, ops::Range, path::PathBuf, time::Duration};
 
 /// Matches collection behavior.
 ///
@@ -17,11 +21,155 @@ pub enum MatchCollectionStrategy {
     /// so it might even turn out to be slower than collecting all matches
     /// if the total number of matches is relatively low.
     ///
-    CollectTop(usize)
+    CollectTop(usize),
+
+    /// Only a number of first matches (in the order they are found, regardless of their scores) should be kept.
+    /// Unlike [`MatchCollectionStrategy::CollectTop`] this does not need to see all the matches,
+    /// the search stops as soon as there are enough of them.
+    ///
+    CollectFirstN(usize),
+}
+
+/// Order of the results in the output.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortOrder {
+    /// By fuzzy score, best matches first.
+    ///
+    Score,
+
+    /// By fuzzy score, best matches last.
+    ///
+    ScoreAsc,
+
+    /// By file name (and then by line number) in ascending order.
+    ///
+    FileName,
+
+    /// By file name (and then by line number) in descending order.
+    ///
+    FileNameDesc,
+
+    /// By line number in ascending order.
+    ///
+    Line,
+
+    /// By line number in descending order.
+    ///
+    LineDesc,
+
+    /// No sorting, the results are kept in the order they were found.
+    /// Note that with [`MatchCollectionStrategy::CollectTop`] the discovery order is not preserved.
+    ///
+    None,
+}
+
+/// Case sensitivity of the matching.
+///
+#[derive(Debug, PartialEq)]
+pub enum CaseSensitivity {
+    /// Letter case is ignored.
+    ///
+    Insensitive,
+
+    /// Letter case is ignored unless the query contains at least one uppercase letter.
+    ///
+    Smart,
+}
+
+/// The way the query is matched against the lines.
+///
+#[derive(Debug, PartialEq)]
+pub enum MatchMode {
+    /// Query characters match anywhere in the line (in order).
+    ///
+    Fuzzy,
+
+    /// Query characters match only the initial characters of the words in the line (in order),
+    /// e.g. `fb` matches `FooBar` or `foo_bar`, but not `foobar`.
+    ///
+    Acronym,
+
+    /// The query matches only as a literal substring of the line (ignoring letter case unless requested otherwise),
+    /// e.g. `ab` matches `xaby`, but not `axby`.
+    ///
+    FixedString,
+}
+
+/// The format of the printed results.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    /// Lines of text (formatted according to [`OutputBehavior`], the field separator, etc.).
+    ///
+    Text,
+
+    /// Newline-delimited JSON: every match is printed as a single line containing a JSON object
+    /// (see [`crate::MatchingResult`] for its structure), the context lines included.
+    /// The formatting options do not apply. Requires the `serde` feature.
+    ///
+    Ndjson,
+}
+
+/// The way the score of every match is printed.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScoreFormat {
+    /// The raw score as a decimal integer.
+    ///
+    Decimal,
+
+    /// The raw score as a lowercase hexadecimal integer.
+    ///
+    Hex,
+
+    /// The score normalized against the best possible score for the query
+    /// (see [`crate::MatchingResult::normalized_score`]), as an integer from 0 to 100.
+    ///
+    Percent,
+}
+
+/// A field of an output line in the regular layout (see [`Request::field_order`]).
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputField {
+    /// The file name (if file names are tracked).
+    ///
+    File,
+
+    /// The line number (if line numbers are tracked), followed by the numbers of context lines if requested.
+    ///
+    Line,
+
+    /// The column of the first matched character (if column numbers are tracked).
+    ///
+    Column,
+
+    /// The score (if requested).
+    ///
+    Score,
+
+    /// The line itself.
+    ///
+    Match,
+}
+
+impl OutputField {
+    /// The order of the fields when no particular order is requested.
+    ///
+    pub const DEFAULT_ORDER: [OutputField; 5] = [
+        OutputField::File,
+        OutputField::Line,
+        OutputField::Column,
+        OutputField::Score,
+        OutputField::Match,
+    ];
 }
 
 /// Behavior of the program with respect to the output
 ///
+// see `Formatting` for why the formatting options are not boxed
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, PartialEq)]
 pub enum OutputBehavior {
     /// Output normally.
@@ -31,6 +179,13 @@ pub enum OutputBehavior {
     /// Output is suppressed, return code can be used to categorize the run results.
     ///
     Quiet,
+
+    /// Instead of the matches, output every file that has any, along with the best score found in it
+    /// (e.g. `path/to/file.rs:87`), the best-matching files first.
+    /// With [`MatchCollectionStrategy::CollectTop`] that many files (rather than matches) are output.
+    /// The file names are always tracked.
+    ///
+    FileScores(Formatting),
 }
 
 /// Possible categories of input targets.
@@ -46,6 +201,12 @@ pub enum Targets {
     ///
     RecursiveEntries(Vec<PathBuf>),
 
+    /// Glob patterns (e.g. `src/**/*.rs`) expanded to the files to process.
+    /// Unlike in [`Targets::RecursiveEntries`], no directories are walked beyond what the patterns require,
+    /// and the matching directories themselves are skipped.
+    ///
+    Glob(Vec<glob::Pattern>),
+
     /// The standard input.
     ///
     Stdin,
@@ -67,10 +228,32 @@ pub struct Request {
     ///
     pub strategy: MatchCollectionStrategy,
 
+    /// An optional time limit of the search.
+    /// Once it has passed, no more files are searched and the matches found so far are output,
+    /// so the results may be incomplete; [`crate::run`] then fails with [`crate::TimeoutError`].
+    /// The limit is checked between the files, i.e. a file being searched is always searched to the end,
+    /// so the search of a single (however large) file or of the standard input is never interrupted.
+    ///
+    pub max_time: Option<Duration>,
+
     /// Additional data about the matches to be collected.
     ///
     pub match_options: MatchOptions,
 
+    /// Order of the results in the output.
+    ///
+    pub sort_order: SortOrder,
+
+    /// Determines whether only one of the matches with the same line (regardless of the file or the line number)
+    /// should be kept: the highest-scoring one or, among the equally scored ones, the first one found.
+    /// The context of that match is kept as well.
+    ///
+    pub unique: bool,
+
+    /// An optional directory the printed file names are made relative to.
+    ///
+    pub relative_paths_root: Option<PathBuf>,
+
     /// Determines the behavior of the program with respect to the output.
     /// [`OutputBehavior::Normal`] means normal output
     /// whereas in case of [`OutputBehavior::Quiet`] the output is fully suppressed
@@ -78,9 +261,130 @@ pub struct Request {
     ///
     pub output_behavior: OutputBehavior,
 
+    /// Determines whether only the names of the files that would be searched are printed (instead of searching them).
+    ///
+    pub dry_run: bool,
+
+    /// Determines whether the file names printed in the dry run mode are followed by the line counts of the files,
+    /// e.g. `file.txt (42 lines)`.
+    ///
+    pub count_lines: bool,
+
+    /// The separator printed between the file name, the line number, the column number and the line itself.
+    ///
+    pub field_separator: String,
+
+    /// The format of the printed results.
+    ///
+    pub output_format: OutputFormat,
+
+    /// Determines whether the numbers of context lines actually collected should be printed with each match.
+    ///
+    pub print_context_count: bool,
+
+    /// Determines whether the positions (char indices) of the matched characters should be printed
+    /// on a separate line after each matching line.
+    ///
+    pub print_match_positions: bool,
+
+    /// Determines whether (and how) the score of every match should be printed before the matching line.
+    ///
+    pub score_format: Option<ScoreFormat>,
+
+    /// Determines whether a line with a caret (`^`) under the column of the match should be printed
+    /// after every matching line (provided the column numbers are tracked).
+    ///
+    pub show_caret: bool,
+
+    /// Determines whether a `==> <file name> <==` header should be printed before the matches from every file
+    /// (instead of the file name on every line). The matches are then grouped by file, the files being ordered
+    /// by their first match according to [`Request::sort_order`].
+    ///
+    pub print_file_headers: bool,
+
+    /// The marker the lines other than the matching ones start with (the matching ones start with `> `)
+    /// when every line is printed (see [`MatchOptions::print_unmatched`]).
+    ///
+    pub unmatched_marker: String,
+
+    /// Determines whether the characters of the context lines that match the query (if the query matches them)
+    /// should be highlighted, the same way the matches in the matching lines are (but in their own style).
+    /// Has no effect unless the output is formatted.
+    ///
+    pub highlight_query: bool,
+
+    /// The fields printed for every line and their order, e.g. the line number followed by the line only.
+    /// [`None`] means [`OutputField::DEFAULT_ORDER`]. Any field is printed only if it is present
+    /// (e.g. the line numbers are tracked).
+    ///
+    pub field_order: Option<Vec<OutputField>>,
+
+    /// The minimum width of the line numbers, the narrower ones are right-aligned (0 means no alignment).
+    ///
+    pub line_number_width: usize,
+
+    /// Determines whether the line numbers are right-aligned to the widest one printed
+    /// (but not narrower than [`Request::line_number_width`]).
+    ///
+    pub auto_align: bool,
+
+    /// The line printed between the groups of lines (matches with their context) that are not adjacent,
+    /// e.g. `--` ([`None`] means no separator). It is printed only if any context is requested.
+    /// `{source}` and `{line}` are replaced with the file name and the line number of the following match.
+    ///
+    pub context_separator: Option<String>,
+
+    /// An optional template of the output lines (one per match), e.g. `{file}:{line}:{match}`.
+    /// When set, it replaces the regular output layout.
+    ///
+    pub output_template: Option<String>,
+
+    /// An optional file to write the output to instead of the standard output.
+    ///
+    pub output_file: Option<PathBuf>,
+
+    /// Determines whether the output should be flushed after every line.
+    ///
+    pub line_buffered: bool,
+
+    /// Determines whether the output lines should be terminated by NUL characters instead of newlines.
+    ///
+    pub zero_terminated: bool,
+
+    /// An optional pager command (e.g. `less -R`) the output is piped through when it does not fit on the terminal.
+    /// Paging is up to the caller, this is only the setting (see [`crate::cli::pager::page`]).
+    ///
+    pub pager: Option<String>,
+
     /// Control the verbosity of the logs.
     ///
     pub log_verbosity: LevelFilter,
+
+    /// Determines whether the search statistics should be printed (to the standard error) after the results.
+    ///
+    pub print_stats: bool,
+
+    /// Determines whether a live progress line (files searched and matches found so far) should be shown on the standard error.
+    ///
+    pub show_progress: bool,
+
+    /// Determines whether error messages should be suppressed.
+    /// Unlike [`OutputBehavior::Quiet`] this affects only the error output, the exit code is still set accordingly.
+    ///
+    pub suppress_errors: bool,
+
+    /// Determines whether the exit code depends only on whether any matches were found,
+    /// i.e. an error while searching results in [`crate::ExitCode::NO_MATCHES`] instead of [`crate::ExitCode::FAILURE`]
+    /// if no matches have been output (and in [`crate::ExitCode::SUCCESS`] otherwise).
+    /// The files that cannot be read are skipped, so they do not stop the search of the others.
+    /// The errors are still reported (unless suppressed).
+    ///
+    pub report_errors_as_matches: bool,
+
+    /// Notices about how the options have been interpreted (e.g. an option implied by another one).
+    /// They are made before the logger can be set up, so it is up to the caller to log them (at the debug level).
+    ///
+    pub notices: Vec<String>,
 }
 
 /// Represents a set of options that control how the additional data about matches is collected.
@@ -90,6 +394,11 @@ pub struct MatchOptions {
     ///
     pub track_line_numbers: bool,
 
+    /// Determines whether the columns of the first matched characters are of interest and should be tracked
+    /// during processing.
+    ///
+    pub track_column_numbers: bool,
+
     /// Determines whether the names of the files containing matching lines are of interest
     /// and should be tracked during processing.
     ///
@@ -98,6 +407,138 @@ pub struct MatchOptions {
     /// Controls the size (numbers of lines before and after) of the context surrounding the matching line.
     ///
     pub context_size: ContextSize,
+
+    /// Controls the minimal number of characters of the context surrounding the matching line.
+    /// Whichever of [`MatchOptions::context_size`] and this gives the larger context takes effect.
+    ///
+    pub context_chars: ContextChars,
+
+    /// An optional file size limit (in bytes). Files larger than this are skipped altogether.
+    ///
+    pub skip_bytes: Option<u64>,
+
+    /// An optional range of bytes to search in every file. Only the lines that start within the range are searched
+    /// (the one that starts within it but ends past its end is searched whole).
+    /// The line numbers are still counted from the beginning of the file.
+    ///
+    pub byte_range: Option<Range<u64>>,
+
+    /// Determines whether compressed files should be transparently decompressed.
+    ///
+    pub decompress: bool,
+
+    /// Determines whether zip archives found in the recursive mode should be searched as if they were directories
+    /// (see [`crate::Targets::RecursiveEntries`]). [`MatchOptions::filter`] applies to the entries of the archives
+    /// rather than to the archives themselves.
+    ///
+    pub search_zip: bool,
+
+    /// Determines whether symbolic links are followed in the recursive mode.
+    ///
+    pub follow_links: bool,
+
+    /// Determines whether files already searched under another name (e.g. hard links, symbolic links
+    /// or overlapping targets) are skipped in the recursive mode. Files are identified by their device and inode
+    /// numbers, so the check is only performed on Unix.
+    ///
+    pub dedup_inodes: bool,
+
+    /// Restricts the files searched in the recursive mode by their paths.
+    ///
+    pub filter: Filter,
+
+    /// Determines whether hidden files and directories (the ones whose names start with `.`) are skipped
+    /// in the recursive mode. The targets given explicitly are never skipped.
+    /// Hidden entries are searched by default for now, but skipping them is intended to become the default.
+    ///
+    pub skip_hidden: bool,
+
+    /// Determines whether a match must start and end at word boundaries.
+    /// Lines where the first matched character is not preceded by a word boundary
+    /// or the last one is not followed by one are not considered matching.
+    ///
+    pub word_boundary_check: bool,
+
+    /// An optional set of the characters considered word characters by [`MatchOptions::word_boundary_check`],
+    /// every other character being a word boundary (e.g. `-` for Lisp identifiers or CSS class names).
+    /// Unless set, word characters are the alphanumeric characters and underscores.
+    ///
+    pub word_chars: Option<BTreeSet<char>>,
+
+    /// Determines whether a match must cover the whole line, i.e. every character of the line must be matched.
+    ///
+    pub whole_line: bool,
+
+    /// Determines whether letter case matters when matching.
+    ///
+    pub case_sensitivity: CaseSensitivity,
+
+    /// Determines how the query is matched against the lines.
+    ///
+    pub match_mode: MatchMode,
+
+    /// Determines whether path-like queries (the ones containing `/` or `\`) are matched component-wise,
+    /// i.e. every component of the query has to match within a single path component of the line, in the same order.
+    /// Only applies to the [`MatchMode::Fuzzy`] mode.
+    ///
+    pub path_aware: bool,
+
+    /// An optional number of points added to the score of a match for every matched character that starts a word
+    /// (see [`MatchMode::Acronym`] for what starts a word). This favors acronym-like matches,
+    /// e.g. `fb` in `foo_bar` over `fb` in `fooby`, without restricting the matches to the word initials.
+    ///
+    pub acronym_bonus: Option<u32>,
+
+    /// Determines whether every line of the files with matches ends up in the results, not only the matching lines
+    /// and their context: the lines between two matches make up the leading context of the latter,
+    /// the ones after the last match the trailing context of it. [`MatchOptions::context_size`]
+    /// and [`MatchOptions::context_chars`] do not apply then. The files without matches are still left out.
+    ///
+    pub print_unmatched: bool,
+
+    /// Determines whether the query and the lines are brought to Unicode Normalization Form C (NFC) before matching,
+    /// so that e.g. a precomposed `é` matches `e` followed by a combining acute accent.
+    /// Only the copies matched against are normalized, the lines end up in the results as they are.
+    ///
+    pub unicode_normalization: bool,
+
+    /// Determines whether diacritical marks are ignored when matching, so that e.g. `cafe` matches `café`
+    /// and vice versa. The match positions still refer to the characters of the original line.
+    ///
+    pub ignore_diacritics: bool,
+
+    /// Weights used by the fuzzy scoring. Custom weights are applied by rescoring the matched positions
+    /// (the difference from the default score goes to [`crate::MatchingResult::score_bonus`]).
+    ///
+    pub scoring: ScoringConfig,
+
+    /// Determines how invalid UTF-8 in the input is handled.
+    ///
+    pub lossy_encoding: LossyEncoding,
+
+    /// An optional character encoding of the input, which is then decoded into UTF-8 before matching.
+    /// Malformed sequences are replaced with `U+FFFD`. [`None`] (as well as UTF-8) means the input is read as UTF-8 as is.
+    ///
+    pub encoding: Option<&'static Encoding>,
+
+    /// Plugins opening the targets that are not regular files (see [`SourcePlugin`](crate::SourcePlugin)).
+    /// The targets no plugin handles are opened as files.
+    ///
+    pub sources: SourceRegistry,
+
+    /// Determines whether the input lines are terminated by NUL characters (`\0`) instead of newlines,
+    /// e.g. the output of `find -print0`.
+    ///
+    pub null_data: bool,
+
+    /// An optional line length limit (in bytes). Longer lines are skipped, unless `truncate_long_lines` is set.
+    ///
+    pub max_line_length: Option<usize>,
+
+    /// Determines whether lines longer than `max_line_length` are truncated (and marked with `…`)
+    /// instead of being skipped.
+    ///
+    pub truncate_long_lines: bool,
 }
 
 /// A thin new-type wrapper that represents a number of lines of text.
@@ -105,6 +546,12 @@ pub struct MatchOptions {
 #[derive(Debug, PartialEq)]
 pub struct Lines(pub usize);
 
+/// A thin new-type wrapper that determines whether invalid UTF-8 sequences in the input
+/// are replaced with `U+FFFD` (if `true`) or cause an error (if `false`).
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LossyEncoding(pub bool);
+
 /// Represents the size of the context surrounding the matching line.
 ///
 #[derive(Debug, PartialEq)]
@@ -117,6 +564,46 @@ pub struct ContextSize {
     pub after: Lines,
 }
 
+/// Represents the size of the context surrounding the matching line in characters rather than lines:
+/// whole lines are collected until they hold at least the given number of characters
+/// (not counting the line terminators), or until the start/end of the file.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ContextChars {
+    /// Minimal number of characters preceding the matching line.
+    ///
+    pub chars_before: usize,
+
+    /// Minimal number of characters following the matching line.
+    ///
+    pub chars_after: usize,
+}
+
+impl SortOrder {
+    /// Returns the order opposite to `self` ([`SortOrder::None`] stays as is).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fzgrep::SortOrder;
+    ///
+    /// assert_eq!(SortOrder::Score.reversed(), SortOrder::ScoreAsc);
+    /// assert_eq!(SortOrder::None.reversed(), SortOrder::None);
+    /// ```
+    ///
+    pub const fn reversed(self) -> Self {
+        match self {
+            SortOrder::Score => SortOrder::ScoreAsc,
+            SortOrder::ScoreAsc => SortOrder::Score,
+            SortOrder::FileName => SortOrder::FileNameDesc,
+            SortOrder::FileNameDesc => SortOrder::FileName,
+            SortOrder::Line => SortOrder::LineDesc,
+            SortOrder::LineDesc => SortOrder::Line,
+            SortOrder::None => SortOrder::None,
+        }
+    }
+}
+
 impl OutputBehavior {
     /// Converts [`OutputBehavior`] to [`Option<Formatting>`].
     /// If `self` is [`OutputBehavior::Normal`] returns [`Some`] with the inner formatting, otherwise [`None`].
@@ -139,7 +626,9 @@ impl OutputBehavior {
     #[cfg(test)]
     pub(crate) const fn formatting(&self) -> Option<Formatting> {
         match self {
-            OutputBehavior::Normal(formatting) => Some(*formatting),
+            OutputBehavior::Normal(formatting) | OutputBehavior::FileScores(formatting) => {
+                Some(*formatting)
+            }
             OutputBehavior::Quiet => None,
         }
     }
@@ -150,6 +639,23 @@ mod test {
     use super::*;
     use crate::cli::formatting::FormattingOptions;
 
+    #[test]
+    fn sort_order_reversed() {
+        for order in [
+            SortOrder::Score,
+            SortOrder::ScoreAsc,
+            SortOrder::FileName,
+            SortOrder::FileNameDesc,
+            SortOrder::Line,
+            SortOrder::LineDesc,
+            SortOrder::None,
+        ] {
+            assert_eq!(order.reversed().reversed(), order);
+        }
+        assert_eq!(SortOrder::FileName.reversed(), SortOrder::FileNameDesc);
+        assert_eq!(SortOrder::LineDesc.reversed(), SortOrder::Line);
+    }
+
     #[test]
     fn output_behavior_to_formatting() {
         let behavior = OutputBehavior::Normal(Formatting::On(FormattingOptions::default()));
@@ -0,0 +1,1223 @@
+use crate::{
+    cli::{args, formatting::Formatting},
+    core::{
+        filter::Filter,
+        request::{
+            CaseSensitivity, ContextChars, ContextSize, Lines, LossyEncoding,
+            MatchCollectionStrategy, MatchMode, MatchOptions, OutputBehavior, OutputField,
+            OutputFormat, Request, ScoreFormat, SortOrder, Targets,
+        },
+        scoring_config::ScoringConfig,
+        source_plugin::{SourcePlugin, SourceRegistry},
+    },
+};
+use encoding_rs::Encoding;
+use log::LevelFilter;
+use std::{
+    collections::BTreeSet,
+    env,
+    error::Error,
+    fmt::Display,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// A builder for [`Request`] that allows specifying only the options that differ from the defaults.
+///
+/// The defaults match the ones of the command line interface, except for the output
+/// which is not formatted (see [`RequestBuilder::output_behavior`]).
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::{ContextSize, Lines, MatchCollectionStrategy, RequestBuilder, Targets};
+/// use std::path::PathBuf;
+///
+/// let request = RequestBuilder::new("query", Targets::Files(vec![PathBuf::from("file")]))
+///     .top(10)
+///     .context(2)
+///     .line_numbers(true)
+///     .build()
+///     .unwrap();
+/// assert_eq!(request.strategy, MatchCollectionStrategy::CollectTop(10));
+/// assert_eq!(
+///     request.match_options.context_size,
+///     ContextSize {
+///         before: Lines(2),
+///         after: Lines(2),
+///     }
+/// );
+/// assert!(request.match_options.track_line_numbers);
+/// ```
+///
+#[derive(Debug)]
+pub struct RequestBuilder {
+    query: String,
+    targets: Targets,
+    top: Option<usize>,
+    max_results: Option<usize>,
+    max_time: Option<Duration>,
+    context: Option<usize>,
+    before_context: Option<usize>,
+    after_context: Option<usize>,
+    context_chars: usize,
+    line_numbers: bool,
+    column_numbers: bool,
+    file_names: bool,
+    max_file_size: Option<u64>,
+    byte_range: Option<Range<u64>>,
+    decompress: bool,
+    search_zip: bool,
+    follow_links: bool,
+    dedup_inodes: bool,
+    filter: Filter,
+    skip_hidden: bool,
+    word_boundary_check: bool,
+    word_chars: Option<BTreeSet<char>>,
+    whole_line: bool,
+    case_sensitivity: CaseSensitivity,
+    match_mode: MatchMode,
+    path_aware: bool,
+    acronym_bonus: Option<u32>,
+    print_unmatched: bool,
+    unicode_normalization: bool,
+    ignore_diacritics: bool,
+    scoring: ScoringConfig,
+    lossy_encoding: LossyEncoding,
+    encoding: Option<&'static Encoding>,
+    sources: SourceRegistry,
+    null_data: bool,
+    max_line_length: Option<usize>,
+    truncate_long_lines: bool,
+    sort_order: SortOrder,
+    unique: bool,
+    relative_paths_root: Option<PathBuf>,
+    output_behavior: OutputBehavior,
+    dry_run: bool,
+    count_lines: bool,
+    field_separator: String,
+    output_format: OutputFormat,
+    print_context_count: bool,
+    print_match_positions: bool,
+    score_format: Option<ScoreFormat>,
+    show_caret: bool,
+    print_file_headers: bool,
+    unmatched_marker: String,
+    highlight_query: bool,
+    field_order: Option<Vec<OutputField>>,
+    line_number_width: usize,
+    auto_align: bool,
+    context_separator: Option<String>,
+    output_template: Option<String>,
+    output_file: Option<PathBuf>,
+    line_buffered: bool,
+    zero_terminated: bool,
+    pager: Option<String>,
+    log_verbosity: LevelFilter,
+    print_stats: bool,
+    show_progress: bool,
+    suppress_errors: bool,
+    report_errors_as_matches: bool,
+    notices: Vec<String>,
+}
+
+/// Errors that can occur when building a [`Request`].
+///
+#[derive(Debug, PartialEq)]
+pub enum RequestBuildError {
+    /// Raised if the (symmetric) context is specified together with the leading and/or trailing one.
+    ///
+    ConflictingContext,
+
+    /// Raised if both the number of top matches and the number of first matches to keep are specified.
+    ///
+    ConflictingStrategy,
+}
+
+impl RequestBuilder {
+    /// Creates a builder for a request to find `query` in `targets`, with all the other options set to their defaults.
+    ///
+    pub fn new(query: impl Into<String>, targets: Targets) -> Self {
+        Self {
+            query: query.into(),
+            targets,
+            top: None,
+            max_results: None,
+            max_time: None,
+            context: None,
+            before_context: None,
+            after_context: None,
+            context_chars: 0,
+            line_numbers: false,
+            column_numbers: false,
+            file_names: false,
+            max_file_size: None,
+            byte_range: None,
+            decompress: false,
+            search_zip: false,
+            follow_links: false,
+            dedup_inodes: false,
+            filter: Filter::default(),
+            skip_hidden: false,
+            word_boundary_check: false,
+            word_chars: None,
+            whole_line: false,
+            case_sensitivity: CaseSensitivity::Insensitive,
+            match_mode: MatchMode::Fuzzy,
+            path_aware: false,
+            acronym_bonus: None,
+            print_unmatched: false,
+            unicode_normalization: false,
+            ignore_diacritics: false,
+            scoring: ScoringConfig::default(),
+            lossy_encoding: LossyEncoding(false),
+            encoding: None,
+            sources: SourceRegistry::default(),
+            null_data: false,
+            max_line_length: None,
+            truncate_long_lines: false,
+            sort_order: SortOrder::Score,
+            unique: false,
+            relative_paths_root: None,
+            output_behavior: OutputBehavior::Normal(Formatting::Off),
+            dry_run: false,
+            count_lines: false,
+            field_separator: String::from(":"),
+            output_format: OutputFormat::Text,
+            print_context_count: false,
+            print_match_positions: false,
+            score_format: None,
+            show_caret: false,
+            print_file_headers: false,
+            unmatched_marker: String::from("  "),
+            highlight_query: false,
+            field_order: None,
+            line_number_width: 0,
+            auto_align: false,
+            context_separator: Some(String::from("--")),
+            output_template: None,
+            output_file: None,
+            line_buffered: false,
+            zero_terminated: false,
+            pager: None,
+            log_verbosity: LevelFilter::Error,
+            print_stats: false,
+            show_progress: false,
+            suppress_errors: false,
+            report_errors_as_matches: false,
+            notices: Vec::new(),
+        }
+    }
+
+    /// Keep only `n` best matches (see [`MatchCollectionStrategy::CollectTop`]).
+    ///
+    pub fn top(mut self, n: usize) -> Self {
+        self.top = Some(n);
+        self
+    }
+
+    /// Keep only `n` first matches and stop searching afterwards (see [`MatchCollectionStrategy::CollectFirstN`]).
+    /// Conflicts with [`RequestBuilder::top`].
+    ///
+    pub fn max_results(mut self, n: usize) -> Self {
+        self.max_results = Some(n);
+        self
+    }
+
+    /// Stop searching once `limit` has passed (see [`Request::max_time`]).
+    ///
+    pub fn max_time(mut self, limit: Duration) -> Self {
+        self.max_time = Some(limit);
+        self
+    }
+
+    /// Collect `lines` lines of context both before and after the matching line.
+    /// Conflicts with [`RequestBuilder::before_context`] and [`RequestBuilder::after_context`].
+    ///
+    pub fn context(mut self, lines: usize) -> Self {
+        self.context = Some(lines);
+        self
+    }
+
+    /// Collect whole lines of context both before and after the matching line until they hold
+    /// at least `chars` characters (see [`MatchOptions::context_chars`]).
+    /// Can be combined with the line-based context, the larger of the two taking effect.
+    ///
+    pub fn context_chars(mut self, chars: usize) -> Self {
+        self.context_chars = chars;
+        self
+    }
+
+    /// Collect `lines` lines of context before the matching line.
+    ///
+    pub fn before_context(mut self, lines: usize) -> Self {
+        self.before_context = Some(lines);
+        self
+    }
+
+    /// Collect `lines` lines of context after the matching line.
+    ///
+    pub fn after_context(mut self, lines: usize) -> Self {
+        self.after_context = Some(lines);
+        self
+    }
+
+    /// Track the numbers of matching lines.
+    ///
+    pub fn line_numbers(mut self, track: bool) -> Self {
+        self.line_numbers = track;
+        self
+    }
+
+    /// Track the columns of the first matched characters.
+    ///
+    pub fn column_numbers(mut self, track: bool) -> Self {
+        self.column_numbers = track;
+        self
+    }
+
+    /// Track the names of the files containing matching lines.
+    ///
+    pub fn file_names(mut self, track: bool) -> Self {
+        self.file_names = track;
+        self
+    }
+
+    /// Skip files larger than `bytes`.
+    ///
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Search only the lines that start within `range` (in bytes) of every file.
+    ///
+    pub fn byte_range(mut self, range: Range<u64>) -> Self {
+        self.byte_range = Some(range);
+        self
+    }
+
+    /// Transparently decompress compressed files.
+    ///
+    pub fn decompress(mut self, decompress: bool) -> Self {
+        self.decompress = decompress;
+        self
+    }
+
+    /// Search inside the zip archives found in the recursive mode (see [`MatchOptions::search_zip`]).
+    ///
+    pub fn search_zip(mut self, search_zip: bool) -> Self {
+        self.search_zip = search_zip;
+        self
+    }
+
+    /// Follow symbolic links in the recursive mode.
+    ///
+    pub fn follow_links(mut self, follow: bool) -> Self {
+        self.follow_links = follow;
+        self
+    }
+
+    /// Skip files already searched under another name in the recursive mode (Unix only).
+    ///
+    pub fn dedup_inodes(mut self, dedup: bool) -> Self {
+        self.dedup_inodes = dedup;
+        self
+    }
+
+    /// Restrict the files searched in the recursive mode with `filter`.
+    ///
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Skip hidden files and directories in the recursive mode.
+    ///
+    pub fn skip_hidden(mut self, skip: bool) -> Self {
+        self.skip_hidden = skip;
+        self
+    }
+
+    /// Require matches to start and end at word boundaries.
+    ///
+    pub fn word_boundary_check(mut self, check: bool) -> Self {
+        self.word_boundary_check = check;
+        self
+    }
+
+    /// Set the characters considered word characters by [`RequestBuilder::word_boundary_check`],
+    /// every other character being a word boundary.
+    /// Unless set, word characters are the alphanumeric characters and underscores.
+    ///
+    pub fn word_chars(mut self, chars: impl IntoIterator<Item = char>) -> Self {
+        self.word_chars = Some(chars.into_iter().collect());
+        self
+    }
+
+    /// Require matches to cover the whole line.
+    ///
+    pub fn whole_line(mut self, check: bool) -> Self {
+        self.whole_line = check;
+        self
+    }
+
+    /// Set the case sensitivity of the matching.
+    ///
+    pub fn case_sensitivity(mut self, case_sensitivity: CaseSensitivity) -> Self {
+        self.case_sensitivity = case_sensitivity;
+        self
+    }
+
+    /// Set the way the query is matched against the lines.
+    ///
+    pub fn match_mode(mut self, mode: MatchMode) -> Self {
+        self.match_mode = mode;
+        self
+    }
+
+    /// Match path-like queries component-wise.
+    ///
+    pub fn path_aware(mut self, path_aware: bool) -> Self {
+        self.path_aware = path_aware;
+        self
+    }
+
+    /// Add `bonus` points to the score of a match for every matched character that starts a word.
+    ///
+    pub fn acronym_bonus(mut self, bonus: u32) -> Self {
+        self.acronym_bonus = Some(bonus);
+        self
+    }
+
+    /// Bring the query and the lines to the Unicode Normalization Form C before matching.
+    ///
+    pub fn unicode_normalization(mut self, normalize: bool) -> Self {
+        self.unicode_normalization = normalize;
+        self
+    }
+
+    /// Ignore diacritical marks when matching.
+    ///
+    pub fn ignore_diacritics(mut self, ignore: bool) -> Self {
+        self.ignore_diacritics = ignore;
+        self
+    }
+
+    /// Set the weights of the fuzzy scoring.
+    ///
+    pub fn scoring(mut self, scoring: ScoringConfig) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
+    /// Replace invalid UTF-8 sequences in the input with `U+FFFD` instead of failing.
+    ///
+    pub fn lossy_encoding(mut self, lossy: bool) -> Self {
+        self.lossy_encoding = LossyEncoding(lossy);
+        self
+    }
+
+    /// Decode the input from `encoding` into UTF-8 before matching (see [`MatchOptions::encoding`]).
+    ///
+    pub fn encoding(mut self, encoding: &'static Encoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Register `plugin` for opening the targets that are not regular files (see [`MatchOptions::sources`]).
+    ///
+    pub fn source_plugin(mut self, plugin: impl SourcePlugin + 'static) -> Self {
+        self.sources.register(plugin);
+        self
+    }
+
+    /// Treat the input as NUL-terminated lines instead of newline-terminated ones.
+    ///
+    pub fn null_data(mut self, null_data: bool) -> Self {
+        self.null_data = null_data;
+        self
+    }
+
+    /// Skip lines longer than `bytes`.
+    ///
+    pub fn max_line_length(mut self, bytes: usize) -> Self {
+        self.max_line_length = Some(bytes);
+        self
+    }
+
+    /// Truncate lines longer than the maximum line length instead of skipping them.
+    ///
+    pub fn truncate_long_lines(mut self, truncate: bool) -> Self {
+        self.truncate_long_lines = truncate;
+        self
+    }
+
+    /// Set the order of the results.
+    ///
+    pub fn sort_order(mut self, order: SortOrder) -> Self {
+        self.sort_order = order;
+        self
+    }
+
+    /// Keep only the best (or, among the equally good ones, the first found) of the matches with the same line.
+    ///
+    pub fn unique(mut self, unique: bool) -> Self {
+        self.unique = unique;
+        self
+    }
+
+    /// Print file names relative to `root`.
+    ///
+    pub fn relative_paths_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.relative_paths_root = Some(root.into());
+        self
+    }
+
+    /// Set the output behavior. By default the output is not formatted.
+    ///
+    pub fn output_behavior(mut self, behavior: OutputBehavior) -> Self {
+        self.output_behavior = behavior;
+        self
+    }
+
+    /// Only list the files that would be searched instead of searching them.
+    ///
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Follow the file names printed in the dry run mode by the line counts of the files.
+    ///
+    pub fn count_lines(mut self, count_lines: bool) -> Self {
+        self.count_lines = count_lines;
+        self
+    }
+
+    /// Set the separator between the file name, the line number, etc. and the line itself. By default it is `:`.
+    ///
+    pub fn field_separator(mut self, separator: impl Into<String>) -> Self {
+        self.field_separator = separator.into();
+        self
+    }
+
+    /// Set the format of the printed results. By default it is [`OutputFormat::Text`].
+    ///
+    pub fn output_format(mut self, format: OutputFormat) -> Self {
+        self.output_format = format;
+        self
+    }
+
+    /// Print the numbers of context lines actually collected with each match.
+    ///
+    pub fn print_context_count(mut self, print: bool) -> Self {
+        self.print_context_count = print;
+        self
+    }
+
+    /// Print the positions of the matched characters after each matching line.
+    ///
+    pub fn print_match_positions(mut self, print: bool) -> Self {
+        self.print_match_positions = print;
+        self
+    }
+
+    /// Print the score of every match in the given `format`.
+    ///
+    pub fn score_format(mut self, format: ScoreFormat) -> Self {
+        self.score_format = Some(format);
+        self
+    }
+
+    /// Print a caret under the column of every match (when the column numbers are tracked).
+    ///
+    pub fn show_caret(mut self, show: bool) -> Self {
+        self.show_caret = show;
+        self
+    }
+
+    /// Print a `==> <file name> <==` header before the matches from every file, instead of the file name
+    /// on every line. Implies [`RequestBuilder::file_names`].
+    ///
+    pub fn print_file_headers(mut self, print: bool) -> Self {
+        self.print_file_headers = print;
+        self
+    }
+
+    /// Print every line of the files with matches, marking the matching ones (see [`MatchOptions::print_unmatched`]).
+    /// The results are kept in the order they are found in, regardless of [`RequestBuilder::sort_order`].
+    ///
+    pub fn print_unmatched(mut self, print: bool) -> Self {
+        self.print_unmatched = print;
+        self
+    }
+
+    /// Set the marker of the lines other than the matching ones when every line is printed
+    /// (see [`RequestBuilder::print_unmatched`]).
+    ///
+    pub fn unmatched_marker(mut self, marker: &str) -> Self {
+        self.unmatched_marker = String::from(marker);
+        self
+    }
+
+    /// Highlight the characters of the context lines that match the query (when the output is formatted).
+    ///
+    pub fn highlight_query(mut self, highlight: bool) -> Self {
+        self.highlight_query = highlight;
+        self
+    }
+
+    /// Print only the given fields of every line, in the given order.
+    ///
+    pub fn field_order(mut self, order: impl Into<Vec<OutputField>>) -> Self {
+        self.field_order = Some(order.into());
+        self
+    }
+
+    /// Right-align the line numbers to at least `width` digits.
+    ///
+    pub fn line_number_width(mut self, width: usize) -> Self {
+        self.line_number_width = width;
+        self
+    }
+
+    /// Right-align the line numbers to the widest one printed.
+    ///
+    pub fn auto_align(mut self, auto_align: bool) -> Self {
+        self.auto_align = auto_align;
+        self
+    }
+
+    /// Print `separator` between the groups of lines that are not adjacent ([`None`] for no separator).
+    /// The default is `--`.
+    ///
+    pub fn context_separator(mut self, separator: Option<impl Into<String>>) -> Self {
+        self.context_separator = separator.map(Into::into);
+        self
+    }
+
+    /// Print every match according to `template` instead of the regular output layout.
+    ///
+    pub fn output_template(mut self, template: impl Into<String>) -> Self {
+        self.output_template = Some(template.into());
+        self
+    }
+
+    /// Write the output to `file` instead of the standard output.
+    ///
+    pub fn output_file(mut self, file: impl Into<PathBuf>) -> Self {
+        self.output_file = Some(file.into());
+        self
+    }
+
+    /// Flush the output after every line.
+    ///
+    pub fn line_buffered(mut self, line_buffered: bool) -> Self {
+        self.line_buffered = line_buffered;
+        self
+    }
+
+    /// Terminate the output lines with NUL characters instead of newlines.
+    ///
+    pub fn zero_terminated(mut self, zero_terminated: bool) -> Self {
+        self.zero_terminated = zero_terminated;
+        self
+    }
+
+    /// Pipe the output through `pager` when it does not fit on the terminal.
+    ///
+    pub fn pager(mut self, pager: impl Into<String>) -> Self {
+        self.pager = Some(pager.into());
+        self
+    }
+
+    /// Set the verbosity of the logs.
+    ///
+    pub fn log_verbosity(mut self, verbosity: LevelFilter) -> Self {
+        self.log_verbosity = verbosity;
+        self
+    }
+
+    /// Print search statistics after the results.
+    ///
+    pub fn print_stats(mut self, print: bool) -> Self {
+        self.print_stats = print;
+        self
+    }
+
+    /// Show the search progress on the standard error while searching.
+    ///
+    pub fn show_progress(mut self, show: bool) -> Self {
+        self.show_progress = show;
+        self
+    }
+
+    /// Suppress error messages.
+    ///
+    pub fn suppress_errors(mut self, suppress: bool) -> Self {
+        self.suppress_errors = suppress;
+        self
+    }
+
+    /// Let the exit code depend only on whether any matches were found, not on the errors.
+    ///
+    pub fn report_errors_as_matches(mut self, report: bool) -> Self {
+        self.report_errors_as_matches = report;
+        self
+    }
+
+    /// Add a notice about how the options have been interpreted (see [`Request::notices`]).
+    ///
+    pub fn notice(mut self, notice: impl Into<String>) -> Self {
+        self.notices.push(notice.into());
+        self
+    }
+
+    /// Makes a [`Request`] out of the command line arguments of the process, exactly the way the `fzgrep`
+    /// binary does (see [`RequestBuilder::from_args`]).
+    ///
+    /// # Errors
+    ///
+    ///   * [`clap::Error`] if the arguments are invalid.
+    ///
+    pub fn from_cli_args() -> Result<Request, clap::Error> {
+        Self::from_args(env::args())
+    }
+
+    /// Makes a [`Request`] out of `args` (the first one being the program name), exactly the way the `fzgrep`
+    /// binary does, including `FZGREP_DEFAULT_OPTIONS` (see [`args::DEFAULT_OPTIONS_VAR`]) and the config file
+    /// at the default location (see [`args::default_config_path`]).
+    /// Use [`RequestBuilder::from_args_with`] to pass them explicitly.
+    ///
+    /// Just like the binary, this **exits the process** after printing the help (`--help`), the version (`--version`)
+    /// or whatever else is requested instead of a search (e.g. `--generate-completions` or `--dump-config`).
+    /// It exits as well if the config file cannot be loaded or if the query is shorter than `--min-query-length`.
+    ///
+    /// # Errors
+    ///
+    ///   * [`clap::Error`] if the arguments are invalid (e.g. an unknown option, an invalid value or a missing query).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fzgrep::{RequestBuilder, Targets};
+    /// use std::path::PathBuf;
+    ///
+    /// let args = ["fzgrep", "--line-number", "query", "file"].map(String::from);
+    /// let request = RequestBuilder::from_args(args).unwrap();
+    /// assert_eq!(request.query, "query");
+    /// assert_eq!(request.targets, Targets::Files(vec![PathBuf::from("file")]));
+    /// assert!(request.match_options.track_line_numbers);
+    ///
+    /// let args = ["fzgrep", "--no-such-option", "query"].map(String::from);
+    /// assert!(RequestBuilder::from_args(args).is_err());
+    /// ```
+    ///
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Request, clap::Error> {
+        let config = args::default_config_path();
+        let default_options = env::var(args::DEFAULT_OPTIONS_VAR).ok();
+        Self::from_args_with(args, config.as_deref(), default_options.as_deref())
+    }
+
+    /// Same as [`RequestBuilder::from_args`], except that the config file and the default options are the ones given
+    /// (see [`args::make_request_with`]) rather than the ones the `fzgrep` binary reads.
+    ///
+    /// # Errors
+    ///
+    ///   * [`clap::Error`] if the arguments are invalid (e.g. an unknown option, an invalid value or a missing query).
+    ///
+    pub fn from_args_with(
+        args: impl IntoIterator<Item = String>,
+        config: Option<&Path>,
+        default_options: Option<&str>,
+    ) -> Result<Request, clap::Error> {
+        args::try_make_request(args.into_iter(), config, default_options)
+    }
+
+    /// Builds the [`Request`].
+    ///
+    /// # Errors
+    ///
+    ///   * [`RequestBuildError::ConflictingContext`] if the context is specified together with the leading
+    ///     and/or trailing context.
+    ///   * [`RequestBuildError::ConflictingStrategy`] if both [`RequestBuilder::top`]
+    ///     and [`RequestBuilder::max_results`] are specified.
+    ///
+    pub fn build(self) -> Result<Request, RequestBuildError> {
+        let context_size = match (self.context, self.before_context, self.after_context) {
+            (Some(_), Some(_), _) | (Some(_), _, Some(_)) => {
+                return Err(RequestBuildError::ConflictingContext)
+            }
+            (Some(lines), None, None) => ContextSize {
+                before: Lines(lines),
+                after: Lines(lines),
+            },
+            (None, before, after) => ContextSize {
+                before: Lines(before.unwrap_or(0)),
+                after: Lines(after.unwrap_or(0)),
+            },
+        };
+
+        let strategy = match (self.top, self.max_results) {
+            (Some(_), Some(_)) => return Err(RequestBuildError::ConflictingStrategy),
+            (Some(n), None) => MatchCollectionStrategy::CollectTop(n),
+            (None, Some(n)) => MatchCollectionStrategy::CollectFirstN(n),
+            (None, None) => MatchCollectionStrategy::CollectAll,
+        };
+
+        Ok(Request {
+            query: self.query,
+            targets: self.targets,
+            strategy,
+            max_time: self.max_time,
+            match_options: MatchOptions {
+                track_line_numbers: self.line_numbers,
+                track_column_numbers: self.column_numbers,
+                track_file_names: self.file_names
+                    || self.print_file_headers
+                    || matches!(self.output_behavior, OutputBehavior::FileScores(_)),
+                context_size,
+                context_chars: ContextChars {
+                    chars_before: self.context_chars,
+                    chars_after: self.context_chars,
+                },
+                skip_bytes: self.max_file_size,
+                byte_range: self.byte_range,
+                decompress: self.decompress,
+                search_zip: self.search_zip,
+                follow_links: self.follow_links,
+                dedup_inodes: self.dedup_inodes,
+                filter: self.filter,
+                skip_hidden: self.skip_hidden,
+                word_boundary_check: self.word_boundary_check,
+                word_chars: self.word_chars,
+                whole_line: self.whole_line,
+                case_sensitivity: self.case_sensitivity,
+                match_mode: self.match_mode,
+                path_aware: self.path_aware,
+                acronym_bonus: self.acronym_bonus,
+                print_unmatched: self.print_unmatched,
+                unicode_normalization: self.unicode_normalization,
+                ignore_diacritics: self.ignore_diacritics,
+                scoring: self.scoring,
+                lossy_encoding: self.lossy_encoding,
+                encoding: self.encoding,
+                sources: self.sources,
+                null_data: self.null_data,
+                max_line_length: self.max_line_length,
+                truncate_long_lines: self.truncate_long_lines,
+            },
+            // the lines have to stay in the file order
+            sort_order: if self.print_unmatched {
+                SortOrder::None
+            } else {
+                self.sort_order
+            },
+            unique: self.unique,
+            relative_paths_root: self.relative_paths_root,
+            output_behavior: self.output_behavior,
+            dry_run: self.dry_run,
+            count_lines: self.count_lines,
+            field_separator: self.field_separator,
+            output_format: self.output_format,
+            print_context_count: self.print_context_count,
+            print_match_positions: self.print_match_positions,
+            score_format: self.score_format,
+            show_caret: self.show_caret,
+            print_file_headers: self.print_file_headers,
+            unmatched_marker: self.unmatched_marker,
+            highlight_query: self.highlight_query,
+            field_order: self.field_order,
+            line_number_width: self.line_number_width,
+            auto_align: self.auto_align,
+            context_separator: self.context_separator,
+            output_template: self.output_template,
+            output_file: self.output_file,
+            line_buffered: self.line_buffered,
+            zero_terminated: self.zero_terminated,
+            pager: self.pager,
+            log_verbosity: self.log_verbosity,
+            print_stats: self.print_stats,
+            show_progress: self.show_progress,
+            suppress_errors: self.suppress_errors,
+            report_errors_as_matches: self.report_errors_as_matches,
+            notices: self.notices,
+        })
+    }
+}
+
+impl Display for RequestBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ConflictingContext => write!(
+                f,
+                "Context cannot be specified together with leading or trailing context"
+            ),
+            Self::ConflictingStrategy => write!(
+                f,
+                "The number of top matches cannot be specified together with the maximum number of results"
+            ),
+        }
+    }
+}
+
+impl Error for RequestBuildError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let request = RequestBuilder::new("query", Targets::Stdin)
+            .build()
+            .unwrap();
+        assert_eq!(
+            request,
+            Request {
+                query: String::from("query"),
+                targets: Targets::Stdin,
+                strategy: MatchCollectionStrategy::CollectAll,
+                max_time: None,
+                match_options: MatchOptions {
+                    track_line_numbers: false,
+                    track_column_numbers: false,
+                    track_file_names: false,
+                    context_size: ContextSize {
+                        before: Lines(0),
+                        after: Lines(0),
+                    },
+                    context_chars: ContextChars {
+                        chars_before: 0,
+                        chars_after: 0,
+                    },
+                    skip_bytes: None,
+                    byte_range: None,
+                    decompress: false,
+                    search_zip: false,
+                    follow_links: false,
+                    dedup_inodes: false,
+                    filter: Filter::default(),
+                    skip_hidden: false,
+                    word_boundary_check: false,
+                    word_chars: None,
+                    whole_line: false,
+                    case_sensitivity: CaseSensitivity::Insensitive,
+                    match_mode: MatchMode::Fuzzy,
+                    path_aware: false,
+                    acronym_bonus: None,
+                    print_unmatched: false,
+                    unicode_normalization: false,
+                    ignore_diacritics: false,
+                    scoring: ScoringConfig::default(),
+                    lossy_encoding: LossyEncoding(false),
+                    encoding: None,
+                    sources: SourceRegistry::default(),
+                    null_data: false,
+                    max_line_length: None,
+                    truncate_long_lines: false,
+                },
+                sort_order: SortOrder::Score,
+                unique: false,
+                relative_paths_root: None,
+                output_behavior: OutputBehavior::Normal(Formatting::Off),
+                dry_run: false,
+                count_lines: false,
+                field_separator: String::from(":"),
+                output_format: OutputFormat::Text,
+                print_context_count: false,
+                print_match_positions: false,
+                score_format: None,
+                show_caret: false,
+                print_file_headers: false,
+                unmatched_marker: String::from("  "),
+                highlight_query: false,
+                field_order: None,
+                line_number_width: 0,
+                auto_align: false,
+                context_separator: Some(String::from("--")),
+                output_template: None,
+                output_file: None,
+                line_buffered: false,
+                zero_terminated: false,
+                pager: None,
+                log_verbosity: LevelFilter::Error,
+                print_stats: false,
+                show_progress: false,
+                suppress_errors: false,
+                report_errors_as_matches: false,
+                notices: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn all_options() {
+        let request = RequestBuilder::new("query", Targets::Files(vec![PathBuf::from("file")]))
+            .top(5)
+            .before_context(1)
+            .after_context(2)
+            .line_numbers(true)
+            .column_numbers(true)
+            .file_names(true)
+            .max_file_size(1024)
+            .decompress(true)
+            .search_zip(true)
+            .follow_links(true)
+            .dedup_inodes(true)
+            .filter(Filter::default())
+            .skip_hidden(true)
+            .word_boundary_check(true)
+            .word_chars(['-'])
+            .whole_line(true)
+            .case_sensitivity(CaseSensitivity::Smart)
+            .match_mode(MatchMode::Acronym)
+            .path_aware(true)
+            .acronym_bonus(3)
+            .unicode_normalization(true)
+            .ignore_diacritics(true)
+            .scoring(ScoringConfig {
+                camel_case: 0,
+                ..Default::default()
+            })
+            .lossy_encoding(true)
+            .null_data(true)
+            .max_line_length(80)
+            .truncate_long_lines(true)
+            .sort_order(SortOrder::Line)
+            .unique(true)
+            .relative_paths_root("root")
+            .output_behavior(OutputBehavior::Quiet)
+            .dry_run(true)
+            .count_lines(true)
+            .field_separator("\t")
+            .print_context_count(true)
+            .print_match_positions(true)
+            .score_format(ScoreFormat::Percent)
+            .show_caret(true)
+            .unmatched_marker("- ")
+            .print_file_headers(true)
+            .highlight_query(true)
+            .field_order([OutputField::Line, OutputField::Match])
+            .line_number_width(4)
+            .auto_align(true)
+            .context_separator(Some("== {source} =="))
+            .output_template("{file}:{line}:{match}")
+            .output_file("out.txt")
+            .line_buffered(true)
+            .zero_terminated(true)
+            .pager("less -R")
+            .log_verbosity(LevelFilter::Debug)
+            .print_stats(true)
+            .show_progress(true)
+            .suppress_errors(true)
+            .report_errors_as_matches(true)
+            .build()
+            .unwrap();
+        assert_eq!(
+            request,
+            Request {
+                query: String::from("query"),
+                targets: Targets::Files(vec![PathBuf::from("file")]),
+                strategy: MatchCollectionStrategy::CollectTop(5),
+                max_time: None,
+                match_options: MatchOptions {
+                    track_line_numbers: true,
+                    track_column_numbers: true,
+                    track_file_names: true,
+                    context_size: ContextSize {
+                        before: Lines(1),
+                        after: Lines(2),
+                    },
+                    context_chars: ContextChars {
+                        chars_before: 0,
+                        chars_after: 0,
+                    },
+                    skip_bytes: Some(1024),
+                    byte_range: None,
+                    decompress: true,
+                    search_zip: true,
+                    follow_links: true,
+                    dedup_inodes: true,
+                    filter: Filter::default(),
+                    skip_hidden: true,
+                    word_boundary_check: true,
+                    word_chars: Some(BTreeSet::from(['-'])),
+                    whole_line: true,
+                    case_sensitivity: CaseSensitivity::Smart,
+                    match_mode: MatchMode::Acronym,
+                    path_aware: true,
+                    acronym_bonus: Some(3),
+                    print_unmatched: false,
+                    unicode_normalization: true,
+                    ignore_diacritics: true,
+                    scoring: ScoringConfig {
+                        camel_case: 0,
+                        ..Default::default()
+                    },
+                    lossy_encoding: LossyEncoding(true),
+                    encoding: None,
+                    sources: SourceRegistry::default(),
+                    null_data: true,
+                    max_line_length: Some(80),
+                    truncate_long_lines: true,
+                },
+                sort_order: SortOrder::Line,
+                unique: true,
+                relative_paths_root: Some(PathBuf::from("root")),
+                output_behavior: OutputBehavior::Quiet,
+                dry_run: true,
+                count_lines: true,
+                field_separator: String::from("\t"),
+                output_format: OutputFormat::Text,
+                print_context_count: true,
+                print_match_positions: true,
+                score_format: Some(ScoreFormat::Percent),
+                show_caret: true,
+                print_file_headers: true,
+                unmatched_marker: String::from("- "),
+                highlight_query: true,
+                field_order: Some(vec![OutputField::Line, OutputField::Match]),
+                line_number_width: 4,
+                auto_align: true,
+                context_separator: Some(String::from("== {source} ==")),
+                output_template: Some(String::from("{file}:{line}:{match}")),
+                output_file: Some(PathBuf::from("out.txt")),
+                line_buffered: true,
+                zero_terminated: true,
+                pager: Some(String::from("less -R")),
+                log_verbosity: LevelFilter::Debug,
+                print_stats: true,
+                show_progress: true,
+                suppress_errors: true,
+                report_errors_as_matches: true,
+                notices: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn print_unmatched_keeps_file_order() {
+        let request = RequestBuilder::new("query", Targets::Stdin)
+            .sort_order(SortOrder::Line)
+            .print_unmatched(true)
+            .build()
+            .unwrap();
+        assert!(request.match_options.print_unmatched);
+        assert_eq!(request.sort_order, SortOrder::None);
+        assert_eq!(request.unmatched_marker, "  ");
+    }
+
+    #[test]
+    fn context() {
+        let request = RequestBuilder::new("query", Targets::Stdin)
+            .context(3)
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.match_options.context_size,
+            ContextSize {
+                before: Lines(3),
+                after: Lines(3),
+            }
+        );
+    }
+
+    #[test]
+    fn context_chars() {
+        let request = RequestBuilder::new("query", Targets::Stdin)
+            .context(1)
+            .context_chars(80)
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.match_options.context_chars,
+            ContextChars {
+                chars_before: 80,
+                chars_after: 80,
+            }
+        );
+        assert_eq!(
+            request.match_options.context_size,
+            ContextSize {
+                before: Lines(1),
+                after: Lines(1),
+            }
+        );
+    }
+
+    #[test]
+    fn conflicting_context() {
+        let result = RequestBuilder::new("query", Targets::Stdin)
+            .context(3)
+            .before_context(1)
+            .build();
+        assert_eq!(result, Err(RequestBuildError::ConflictingContext));
+
+        let result = RequestBuilder::new("query", Targets::Stdin)
+            .context(3)
+            .after_context(1)
+            .build();
+        assert_eq!(result, Err(RequestBuildError::ConflictingContext));
+    }
+    #[test]
+    fn max_results() {
+        let request = RequestBuilder::new("query", Targets::Stdin)
+            .max_results(3)
+            .build()
+            .unwrap();
+        assert_eq!(request.strategy, MatchCollectionStrategy::CollectFirstN(3));
+    }
+
+    #[test]
+    fn max_time() {
+        let request = RequestBuilder::new("query", Targets::Stdin)
+            .max_time(Duration::from_secs(3))
+            .build()
+            .unwrap();
+        assert_eq!(request.max_time, Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn conflicting_strategy() {
+        let result = RequestBuilder::new("query", Targets::Stdin)
+            .top(3)
+            .max_results(1)
+            .build();
+        assert_eq!(result, Err(RequestBuildError::ConflictingStrategy));
+    }
+
+    #[test]
+    fn from_args_with() {
+        let args = ["fzgrep", "--top", "3", "-C", "1", "query", "file"];
+        assert_eq!(
+            RequestBuilder::from_args_with(args.map(String::from), None, None).unwrap(),
+            args::make_request(args.into_iter().map(String::from))
+        );
+
+        let args = ["fzgrep", "query", "file"];
+        let request =
+            RequestBuilder::from_args_with(args.map(String::from), None, Some("--top 5")).unwrap();
+        assert_eq!(request.strategy, MatchCollectionStrategy::CollectTop(5));
+    }
+
+    #[test]
+    fn from_args_errors() {
+        let args = ["fzgrep", "--top"];
+        assert_eq!(
+            RequestBuilder::from_args_with(args.map(String::from), None, None)
+                .unwrap_err()
+                .kind(),
+            clap::error::ErrorKind::InvalidValue
+        );
+        let args = ["fzgrep"];
+        assert_eq!(
+            RequestBuilder::from_args_with(args.map(String::from), None, None)
+                .unwrap_err()
+                .kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        );
+    }
+}
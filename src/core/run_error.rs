@@ -0,0 +1,55 @@
+use crate::matching_results::result::MatchingResult;
+use std::{error, fmt};
+
+/// An error that stopped [`crate::run`], along with the matches that had been output before it happened
+/// (e.g. the ones found before [`crate::TimeoutError`] or streamed as NDJSON before a failure).
+///
+/// # Fields
+///   * `matches` - the matches output before the error, empty if nothing has been output
+///   * `source` - the error itself
+///
+#[derive(Debug)]
+pub struct RunError {
+    pub matches: Vec<MatchingResult>,
+    pub source: Box<dyn error::Error>,
+}
+
+impl RunError {
+    /// Creates an error with no matches output before it.
+    ///
+    pub(crate) fn without_matches(source: impl Into<Box<dyn error::Error>>) -> Self {
+        Self {
+            matches: Vec::new(),
+            source: source.into(),
+        }
+    }
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl error::Error for RunError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::deadline::TimeoutError;
+    use std::time::Duration;
+
+    #[test]
+    fn run_error_display() {
+        let error = RunError::without_matches(TimeoutError(Duration::from_secs(2)));
+        assert!(error.matches.is_empty());
+        assert_eq!(
+            error.to_string(),
+            "the search was stopped after 2 s, the results may be incomplete"
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use crate::core::{
+    acronym, explain, path_aware,
+    request::{CaseSensitivity, MatchMode},
+    scoring_config::ScoringConfig,
+};
+use std::collections::BTreeSet;
+use vscode_fuzzy_score_rs::FuzzyMatch;
+
+/// Options of [`batch_score`], i.e. the subset of [`crate::MatchOptions`] that affects matching a single line.
+///
+#[derive(Debug, PartialEq)]
+pub struct BatchMatchOptions {
+    /// Determines whether letter case matters when matching.
+    ///
+    pub case_sensitivity: CaseSensitivity,
+
+    /// Determines how the queries are matched against the target.
+    ///
+    pub match_mode: MatchMode,
+
+    /// Determines whether path-like queries are matched component-wise (see [`crate::MatchOptions::path_aware`]).
+    ///
+    pub path_aware: bool,
+
+    /// Determines whether a match must start and end at word boundaries.
+    ///
+    pub word_boundary_check: bool,
+
+    /// An optional set of the characters considered word characters by [`BatchMatchOptions::word_boundary_check`].
+    /// Unless set, word characters are the alphanumeric characters and underscores.
+    ///
+    pub word_chars: Option<BTreeSet<char>>,
+
+    /// Determines whether a match must cover the whole target.
+    ///
+    pub whole_line: bool,
+}
+
+impl Default for BatchMatchOptions {
+    /// Plain case-insensitive fuzzy matching without additional constraints.
+    ///
+    fn default() -> Self {
+        Self {
+            case_sensitivity: CaseSensitivity::Insensitive,
+            match_mode: MatchMode::Fuzzy,
+            path_aware: false,
+            word_boundary_check: false,
+            word_chars: None,
+            whole_line: false,
+        }
+    }
+}
+
+/// Matches each of `queries` against `target` in a single pass, e.g. to rank the candidates of an autocompletion.
+/// The `n`-th element of the result is the match of the `n`-th query, or [`None`] if it does not match
+/// (or the match does not satisfy the constraints from `options`).
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::{batch_score, BatchMatchOptions};
+///
+/// let matches = batch_score(&["tst", "xyz", "test"], "a test", &BatchMatchOptions::default());
+/// assert_eq!(matches.len(), 3);
+/// assert!(matches[0].is_some());
+/// assert!(matches[1].is_none());
+/// assert!(matches[2].as_ref().unwrap().score() > matches[0].as_ref().unwrap().score());
+/// ```
+///
+pub fn batch_score(
+    queries: &[&str],
+    target: &str,
+    options: &BatchMatchOptions,
+) -> Vec<Option<FuzzyMatch>> {
+    let fuzzy_match = crate::line_matcher(&options.match_mode, options.path_aware);
+    queries
+        .iter()
+        .map(|query| {
+            let case_sensitive = crate::is_case_sensitive(query, &options.case_sensitivity);
+            crate::match_line(fuzzy_match, query, target, case_sensitive).filter(|m| {
+                crate::satisfies_constraints(
+                    target,
+                    m.positions(),
+                    options
+                        .word_boundary_check
+                        .then_some(options.word_chars.as_ref()),
+                    options.whole_line,
+                )
+            })
+        })
+        .collect()
+}
+
+/// Matches a path-like `query` against the path `target` component-wise: the query is split on `/` and `\`,
+/// and every component has to match within a single component of the target, in the same order
+/// (the fuzzy matcher favors the characters right after the separators, i.e. aligned components).
+/// Queries that do not look like paths are matched as usual. The positions cover the whole `target`.
+///
+/// This is what [`crate::MatchOptions::path_aware`] matches the lines with.
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::path_fuzzy_match;
+///
+/// let m = path_fuzzy_match("src/lib", "src/core/lib.rs").unwrap();
+/// assert_eq!(m.positions(), &vec![0, 1, 2, 8, 9, 10, 11]);
+/// assert!(path_fuzzy_match("src/lib", "lib/src.rs").is_none());
+/// ```
+///
+pub fn path_fuzzy_match(query: &str, target: &str) -> Option<FuzzyMatch> {
+    path_aware::path_aware_match(query, target)
+}
+
+/// Returns `bonus` points for every one of `positions` (char indices in `target`) that starts a word
+/// (the same word starts the acronym mode matches, see [`crate::MatchMode::Acronym`]).
+///
+pub(crate) fn acronym_bonus(target: &str, positions: &[usize], bonus: u32) -> i32 {
+    let starts = acronym::word_starts(target);
+    let initials = positions
+        .iter()
+        .filter(|position| starts.binary_search(position).is_ok())
+        .count();
+    i32::try_from(bonus)
+        .unwrap_or(i32::MAX)
+        .saturating_mul(i32::try_from(initials).unwrap_or(i32::MAX))
+}
+
+/// Returns the points to add to the score of a match of `query` at `positions` (char indices in `target`)
+/// so that it is scored with `weights` rather than with the default weights the matcher uses.
+///
+/// The matcher only reports the positions, so both scores are reconstructed from them (see [`crate::explain_match`]).
+///
+pub(crate) fn scoring_adjustment(
+    query: &str,
+    target: &str,
+    positions: &[usize],
+    weights: &ScoringConfig,
+) -> i32 {
+    let score = |weights| {
+        explain::explain_positions(query, target, positions, weights)
+            .iter()
+            .map(|c| c.total)
+            .sum::<i32>()
+    };
+    score(weights) - score(&ScoringConfig::default())
+}
+
+/// Returns the score of `fuzzy_match` scaled to the `[0.0, 1.0]` range, which (unlike the raw score)
+/// is comparable across queries of different lengths.
+///
+/// The raw score is divided by the best possible score for a query of `query_len` characters:
+/// the one of an uninterrupted run of exact-case matches at the start of the target.
+/// Since the run cannot be longer than the target, only `min(query_len, target_len)` characters are taken into account.
+/// The query itself can earn slightly more if it contains separators (that give bonuses to the following characters),
+/// so the result is clamped to `1.0`.
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::normalized_score;
+///
+/// let perfect = vscode_fuzzy_score_rs::fuzzy_match("test", "test").unwrap();
+/// assert_eq!(normalized_score(&perfect, 4, 4), 1.0);
+///
+/// let partial = vscode_fuzzy_score_rs::fuzzy_match("tst", "a test").unwrap();
+/// assert!(normalized_score(&partial, 3, 6) < 1.0);
+/// ```
+///
+pub fn normalized_score(fuzzy_match: &FuzzyMatch, query_len: usize, target_len: usize) -> f64 {
+    normalize_score(fuzzy_match.score(), query_len, target_len)
+}
+
+/// Same as [`normalized_score`] but scales an arbitrary `score` (e.g. one including a score bonus).
+///
+pub(crate) fn normalize_score(score: i32, query_len: usize, target_len: usize) -> f64 {
+    let max = max_score(query_len.min(target_len));
+    if max <= 0 {
+        return 0.0;
+    }
+    (f64::from(score) / f64::from(max)).clamp(0.0, 1.0)
+}
+
+/// The score of `len` consecutive exact-case matches starting at the beginning of the target.
+///
+fn max_score(len: usize) -> i32 {
+    if len == 0 {
+        return 0;
+    }
+    let weights = ScoringConfig::default();
+    // every preceding character of the run adds a consecutive match bonus
+    let per_character = (0..len).map(|preceding| {
+        weights.regular + weights.exact_match + weights.consecutive_match * preceding as i32
+    });
+    weights.word_start + per_character.sum::<i32>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(matches: &[Option<FuzzyMatch>]) -> Vec<Option<Vec<usize>>> {
+        matches
+            .iter()
+            .map(|m| m.as_ref().map(|m| m.positions().clone()))
+            .collect()
+    }
+
+    #[test]
+    fn batch_score_default() {
+        let matches = batch_score(
+            &["foo", "Bar", "xyz"],
+            "foo bar",
+            &BatchMatchOptions::default(),
+        );
+        assert_eq!(
+            positions(&matches),
+            [Some(vec![0, 1, 2]), Some(vec![4, 5, 6]), None]
+        );
+    }
+
+    #[test]
+    fn batch_score_empty() {
+        assert!(batch_score(&[], "foo bar", &BatchMatchOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn batch_score_options() {
+        let options = BatchMatchOptions {
+            case_sensitivity: CaseSensitivity::Smart,
+            ..Default::default()
+        };
+        let matches = batch_score(&["foo", "Foo"], "foo bar", &options);
+        assert_eq!(positions(&matches), [Some(vec![0, 1, 2]), None]);
+
+        let options = BatchMatchOptions {
+            match_mode: MatchMode::Acronym,
+            ..Default::default()
+        };
+        let matches = batch_score(&["fb", "oo"], "foo bar", &options);
+        assert_eq!(positions(&matches), [Some(vec![0, 4]), None]);
+
+        let options = BatchMatchOptions {
+            word_boundary_check: true,
+            word_chars: Some(BTreeSet::from(['-'])),
+            ..Default::default()
+        };
+        let matches = batch_score(&["bar", "foo"], "foo_bar-baz", &options);
+        assert_eq!(positions(&matches), [None, Some(vec![0, 1, 2])]);
+
+        let options = BatchMatchOptions {
+            whole_line: true,
+            ..Default::default()
+        };
+        let matches = batch_score(&["foobar", "foo bar"], "foo bar", &options);
+        assert_eq!(positions(&matches), [None, Some(vec![0, 1, 2, 3, 4, 5, 6])]);
+    }
+
+    #[test]
+    fn path_fuzzy_match_same_as_path_aware_batch() {
+        let options = BatchMatchOptions {
+            path_aware: true,
+            ..Default::default()
+        };
+        for (query, target) in [
+            ("src/lib", "src/core/lib.rs"),
+            ("src/lib", "lib/src.rs"),
+            ("lib", "src/core/lib.rs"),
+        ] {
+            assert_eq!(
+                path_fuzzy_match(query, target).map(|m| m.positions().clone()),
+                positions(&batch_score(&[query], target, &options))[0],
+                "{query} in {target}"
+            );
+        }
+    }
+
+    #[test]
+    fn acronym_bonus_counts_word_initials() {
+        assert_eq!(acronym_bonus("FooBar baz", &[0, 3, 7], 10), 30);
+        assert_eq!(acronym_bonus("FooBar baz", &[0, 1, 2], 10), 10);
+        assert_eq!(acronym_bonus("foobar", &[1, 2], 10), 0);
+        assert_eq!(acronym_bonus("foo_bar", &[0, 4], 0), 0);
+        assert_eq!(acronym_bonus("a b", &[0, 2], u32::MAX), i32::MAX);
+    }
+
+    #[test]
+    fn max_score_values() {
+        assert_eq!(max_score(0), 0);
+        assert_eq!(max_score(1), 10);
+        assert_eq!(max_score(4), 46);
+    }
+
+    #[test]
+    fn max_score_is_reachable() {
+        for query in ["a", "ab", "test", "contiguous"] {
+            let fuzzy_match = vscode_fuzzy_score_rs::fuzzy_match(query, query).unwrap();
+            assert_eq!(fuzzy_match.score(), max_score(query.chars().count()));
+        }
+    }
+
+    #[test]
+    fn normalized_score_perfect() {
+        let fuzzy_match = vscode_fuzzy_score_rs::fuzzy_match("test", "test and more").unwrap();
+        assert_eq!(normalized_score(&fuzzy_match, 4, 13), 1.0);
+    }
+
+    #[test]
+    fn normalized_score_ordering() {
+        let start = vscode_fuzzy_score_rs::fuzzy_match("con", "contiguous").unwrap();
+        let middle = vscode_fuzzy_score_rs::fuzzy_match("con", "a contiguous").unwrap();
+        let scattered = vscode_fuzzy_score_rs::fuzzy_match("con", "cabbage on").unwrap();
+        let start = normalized_score(&start, 3, 10);
+        let middle = normalized_score(&middle, 3, 12);
+        let scattered = normalized_score(&scattered, 3, 10);
+        assert!(start > middle);
+        assert!(middle > scattered);
+        assert!(scattered > 0.0);
+    }
+
+    #[test]
+    fn normalized_score_clamped() {
+        // the separators give the following characters extra bonuses
+        let fuzzy_match = vscode_fuzzy_score_rs::fuzzy_match("a/b", "a/b").unwrap();
+        assert!(fuzzy_match.score() > max_score(3));
+        assert_eq!(normalized_score(&fuzzy_match, 3, 3), 1.0);
+    }
+
+    #[test]
+    fn scoring_adjustment_default() {
+        assert_eq!(
+            scoring_adjustment("fb", "foo_bar", &[0, 4], &ScoringConfig::default()),
+            0
+        );
+    }
+
+    #[test]
+    fn scoring_adjustment_custom() {
+        let weights = ScoringConfig {
+            after_separator: 100,
+            word_start: 0,
+            ..Default::default()
+        };
+        // `f` loses the word start bonus (8), `b` gets 96 more for following the separator
+        assert_eq!(scoring_adjustment("fb", "foo_bar", &[0, 4], &weights), 88);
+    }
+}
@@ -0,0 +1,109 @@
+use serde::Deserialize;
+
+/// Weights used by the fuzzy scoring.
+///
+/// The defaults are the constants used by the matcher (which follows VS Code fuzzy scoring):
+/// every matched character is worth `regular` points, plus `exact_match` if its case matches,
+/// plus `consecutive_match` for each preceding character of the uninterrupted run of matches it belongs to,
+/// plus one of the bonuses for its position in the line:
+/// `word_start` (the very first character), `after_path_separator` (after `/` or `\`),
+/// `after_separator` (after `_`, `-`, `.`, space, quotes or `:`) or `camel_case` (an uppercase letter in a camelCase word).
+///
+/// The matcher itself does not accept custom weights, so it still picks the matched characters with the default ones;
+/// the custom weights only change the score of the match, which is recomputed from the matched positions.
+///
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoringConfig {
+    /// Score of a matched character.
+    ///
+    pub regular: i32,
+
+    /// Bonus per preceding character of an uninterrupted run of matched characters.
+    ///
+    pub consecutive_match: i32,
+
+    /// Bonus for a matched character whose case matches the query.
+    ///
+    pub exact_match: i32,
+
+    /// Bonus for a match at the start of the line.
+    ///
+    pub word_start: i32,
+
+    /// Bonus for a match right after a separator (`_`, `-`, `.`, space, quotes or `:`).
+    ///
+    pub after_separator: i32,
+
+    /// Bonus for a match right after a path separator (`/` or `\`).
+    ///
+    pub after_path_separator: i32,
+
+    /// Bonus for a match at an uppercase letter in a camelCase word.
+    ///
+    pub camel_case: i32,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self {
+            regular: 1,
+            consecutive_match: 5,
+            exact_match: 1,
+            word_start: 8,
+            after_separator: 4,
+            after_path_separator: 5,
+            camel_case: 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_full() {
+        let config: ScoringConfig = serde_json::from_str(
+            r#"{
+                "regular": 2,
+                "consecutive_match": 3,
+                "exact_match": 4,
+                "word_start": 5,
+                "after_separator": 6,
+                "after_path_separator": 7,
+                "camel_case": 8
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            ScoringConfig {
+                regular: 2,
+                consecutive_match: 3,
+                exact_match: 4,
+                word_start: 5,
+                after_separator: 6,
+                after_path_separator: 7,
+                camel_case: 8,
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_partial() {
+        let config: ScoringConfig = serde_json::from_str(r#"{"camel_case": 10}"#).unwrap();
+        assert_eq!(
+            config,
+            ScoringConfig {
+                camel_case: 10,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_unknown_field() {
+        assert!(serde_json::from_str::<ScoringConfig>(r#"{"bonus": 10}"#).is_err());
+    }
+}
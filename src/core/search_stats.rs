@@ -0,0 +1,82 @@
+use std::{fmt, time::Duration};
+
+/// Statistics of a search run.
+///
+/// Displayed as a single line of space-separated `key=value` pairs so that it is easy to parse by scripts, e.g.
+/// ```text
+/// files=3 lines=120 matches=4 elapsed=0.001234
+/// ```
+/// where `elapsed` is in seconds.
+///
+#[derive(Debug, Default, PartialEq)]
+pub struct SearchStats {
+    /// The number of files (or the standard input) searched.
+    ///
+    pub files_searched: usize,
+
+    /// The total number of lines processed.
+    ///
+    pub lines_processed: usize,
+
+    /// The total number of matches found (including the ones that did not make it to the top, if any).
+    ///
+    pub matches_found: usize,
+
+    /// Time spent on the search.
+    ///
+    pub elapsed: Duration,
+}
+
+/// A summary of a search whose results were handed over to a callback
+/// (see [`crate::collect_matches_with_callback`]).
+///
+#[derive(Debug, Default, PartialEq)]
+pub struct CallbackResult {
+    /// The total number of matches passed to the callback.
+    ///
+    pub total_matches: usize,
+
+    /// The number of files (or the standard input) searched.
+    ///
+    pub files_searched: usize,
+}
+
+impl fmt::Display for SearchStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "files={} lines={} matches={} elapsed={:.6}",
+            self.files_searched,
+            self.lines_processed,
+            self.matches_found,
+            self.elapsed.as_secs_f64()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let stats = SearchStats {
+            files_searched: 3,
+            lines_processed: 120,
+            matches_found: 4,
+            elapsed: Duration::from_micros(1234),
+        };
+        assert_eq!(
+            stats.to_string(),
+            "files=3 lines=120 matches=4 elapsed=0.001234"
+        );
+    }
+
+    #[test]
+    fn display_default() {
+        assert_eq!(
+            SearchStats::default().to_string(),
+            "files=0 lines=0 matches=0 elapsed=0.000000"
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    sync::Arc,
+};
+
+/// A source of content to search other than a regular file, e.g. an object in a remote storage or a database blob.
+///
+/// The plugins are registered in a [`SourceRegistry`] (see [`crate::MatchOptions::sources`]), which is consulted
+/// for every target before it is opened as a regular file. The first plugin that can handle the target
+/// (see [`SourcePlugin::can_handle`]) opens it, the content is then searched the same way a file is
+/// (decoded from [`crate::MatchOptions::encoding`], split into lines, etc.), and the matches are reported
+/// under the name of the target. In the recursive mode the plugins are consulted for the files found
+/// while walking the directories.
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::{RequestBuilder, SourcePlugin, Targets};
+/// use std::{
+///     io::{self, BufRead, Cursor},
+///     path::{Path, PathBuf},
+/// };
+///
+/// // serves the content of `mem:<text>` targets from memory
+/// struct MemoryPlugin;
+///
+/// impl SourcePlugin for MemoryPlugin {
+///     fn can_handle(&self, path: &Path) -> bool {
+///         path.to_str().is_some_and(|path| path.starts_with("mem:"))
+///     }
+///
+///     fn open(&self, path: &Path) -> io::Result<Box<dyn BufRead>> {
+///         let text = path.to_str().and_then(|path| path.strip_prefix("mem:")).unwrap_or_default();
+///         Ok(Box::new(Cursor::new(text.replace(';', "\n"))))
+///     }
+/// }
+///
+/// let request = RequestBuilder::new("contigous", Targets::Files(vec![PathBuf::from("mem:text;contiguous;more text")]))
+///     .file_names(true)
+///     .source_plugin(MemoryPlugin)
+///     .build()
+///     .unwrap();
+/// let results = fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options).unwrap();
+/// assert_eq!(results[0].matching_line, "contiguous");
+/// assert_eq!(results[0].file_name.as_deref(), Some("mem:text;contiguous;more text"));
+/// ```
+///
+pub trait SourcePlugin: Send + Sync {
+    /// Checks whether the plugin is able to open `path` (which does not have to exist in the file system).
+    /// This is called for every target, so it is expected to be cheap, e.g. to check a prefix or an extension.
+    ///
+    fn can_handle(&self, path: &Path) -> bool;
+
+    /// Opens `path` for reading. Only called for the paths accepted by [`SourcePlugin::can_handle`].
+    ///
+    /// # Errors
+    ///
+    /// Any [`io::Error`] preventing the content from being read, it is reported like a failure to open a file.
+    ///
+    fn open(&self, path: &Path) -> io::Result<Box<dyn BufRead>>;
+}
+
+/// An ordered collection of [`SourcePlugin`]s. The registry is cheap to clone, the plugins are shared.
+///
+#[derive(Clone, Default)]
+pub struct SourceRegistry {
+    plugins: Vec<Arc<dyn SourcePlugin>>,
+}
+
+impl SourceRegistry {
+    /// Creates an empty registry, i.e. one that leaves all the targets to be opened as regular files.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `plugin` to the registry. The plugins are consulted in the order they are registered.
+    ///
+    pub fn register(&mut self, plugin: impl SourcePlugin + 'static) {
+        self.plugins.push(Arc::new(plugin));
+    }
+
+    /// Returns the number of the registered plugins.
+    ///
+    pub fn len(&self) -> usize {
+        self.plugins.len()
+    }
+
+    /// Checks whether no plugins are registered.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Opens `path` with the first plugin that can handle it, or returns [`None`] if there is no such plugin.
+    ///
+    pub(crate) fn open(&self, path: &Path) -> Option<io::Result<Box<dyn BufRead>>> {
+        self.plugins
+            .iter()
+            .find(|plugin| plugin.can_handle(path))
+            .map(|plugin| plugin.open(path))
+    }
+}
+
+impl fmt::Debug for SourceRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SourceRegistry")
+            .field("plugins", &self.plugins.len())
+            .finish()
+    }
+}
+
+/// Two registries are equal if they share the same plugins, in the same order.
+///
+impl PartialEq for SourceRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.plugins.len() == other.plugins.len()
+            && self
+                .plugins
+                .iter()
+                .zip(&other.plugins)
+                .all(|(a, b)| Arc::ptr_eq(a, b))
+    }
+}
+
+/// Opens the regular files, the same way the targets are opened when no plugin handles them
+/// (except that the size limit and the decompression do not apply).
+///
+#[derive(Debug, Default)]
+pub struct FilePlugin;
+
+impl SourcePlugin for FilePlugin {
+    fn can_handle(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn BufRead>> {
+        Ok(Box::new(BufReader::new(fs::File::open(path)?)))
+    }
+}
+
+/// Reads the standard input for the `-` target, the way many command line tools do.
+///
+#[derive(Debug, Default)]
+pub struct StdinPlugin;
+
+impl SourcePlugin for StdinPlugin {
+    fn can_handle(&self, path: &Path) -> bool {
+        path == Path::new("-")
+    }
+
+    fn open(&self, _path: &Path) -> io::Result<Box<dyn BufRead>> {
+        Ok(Box::new(BufReader::new(io::stdin())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    struct PrefixPlugin(&'static str);
+
+    impl SourcePlugin for PrefixPlugin {
+        fn can_handle(&self, path: &Path) -> bool {
+            path.to_string_lossy().starts_with(self.0)
+        }
+
+        fn open(&self, _path: &Path) -> io::Result<Box<dyn BufRead>> {
+            Ok(Box::new(Cursor::new(self.0)))
+        }
+    }
+
+    fn content(source: Box<dyn BufRead>) -> String {
+        source
+            .lines()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+            .concat()
+    }
+
+    #[test]
+    fn registry_empty() {
+        let registry = SourceRegistry::new();
+        assert!(registry.is_empty());
+        assert!(registry
+            .open(Path::new("resources/tests/test.txt"))
+            .is_none());
+    }
+
+    #[test]
+    fn registry_first_plugin_wins() {
+        let mut registry = SourceRegistry::new();
+        registry.register(PrefixPlugin("mem"));
+        registry.register(PrefixPlugin("me"));
+        assert_eq!(registry.len(), 2);
+        assert_eq!(
+            content(registry.open(Path::new("memory")).unwrap().unwrap()),
+            "mem"
+        );
+        assert_eq!(
+            content(registry.open(Path::new("metal")).unwrap().unwrap()),
+            "me"
+        );
+        assert!(registry.open(Path::new("other")).is_none());
+    }
+
+    #[test]
+    fn registry_eq() {
+        let mut registry = SourceRegistry::new();
+        assert_eq!(registry, SourceRegistry::new());
+        registry.register(PrefixPlugin("mem"));
+        assert_eq!(registry, registry.clone());
+        let mut other = SourceRegistry::new();
+        other.register(PrefixPlugin("mem"));
+        assert_ne!(registry, other);
+    }
+
+    #[test]
+    fn file_plugin() {
+        assert!(FilePlugin.can_handle(Path::new("resources/tests/test.txt")));
+        assert!(!FilePlugin.can_handle(Path::new("resources/tests")));
+        assert!(!FilePlugin.can_handle(Path::new("resources/tests/no_such_file.txt")));
+        let source = FilePlugin
+            .open(Path::new("resources/tests/test.txt"))
+            .unwrap();
+        assert_eq!(source.lines().count(), 6);
+    }
+
+    #[test]
+    fn stdin_plugin() {
+        assert!(StdinPlugin.can_handle(Path::new("-")));
+        assert!(!StdinPlugin.can_handle(Path::new("-file")));
+        assert!(!StdinPlugin.can_handle(Path::new("file")));
+    }
+}
@@ -0,0 +1,127 @@
+use encoding_rs::{Decoder, Encoding};
+use std::io::{self, BufRead, Read};
+
+/// A wrapper around a [`BufRead`] that decodes its content from `encoding` into UTF-8 on the fly,
+/// so that the lines can be split and matched as usual.
+/// Malformed sequences are replaced with `U+FFFD`. A byte order mark, if any, takes precedence over `encoding`.
+///
+pub(crate) struct TranscodingReader<R: BufRead> {
+    inner: R,
+    decoder: Decoder,
+    decoded: Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+impl<R: BufRead> TranscodingReader<R> {
+    /// Wraps `inner` into a reader decoding it from `encoding`.
+    ///
+    pub(crate) fn new(inner: R, encoding: &'static Encoding) -> Self {
+        Self {
+            inner,
+            decoder: encoding.new_decoder(),
+            decoded: Vec::new(),
+            position: 0,
+            finished: false,
+        }
+    }
+
+    /// Decodes the next chunk of `inner` into the internal buffer.
+    /// An empty chunk means the end of `inner`, which flushes the decoder.
+    ///
+    fn decode_chunk(&mut self) -> io::Result<()> {
+        let input = self.inner.fill_buf()?;
+        let last = input.is_empty();
+        // the buffer is large enough for the whole chunk, so it is always consumed completely
+        let capacity = self
+            .decoder
+            .max_utf8_buffer_length(input.len())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::OutOfMemory, "decoded chunk too long"))?;
+        self.decoded.resize(capacity, 0);
+        let (_, read, written, _) = self.decoder.decode_to_utf8(input, &mut self.decoded, last);
+        self.inner.consume(read);
+        self.decoded.truncate(written);
+        self.position = 0;
+        self.finished = last;
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for TranscodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // a chunk ending in the middle of a character may not produce any output on its own
+        while self.position == self.decoded.len() {
+            if self.finished {
+                return Ok(0);
+            }
+            self.decode_chunk()?;
+        }
+        let pending = &self.decoded[self.position..];
+        let length = pending.len().min(buf.len());
+        buf[..length].copy_from_slice(&pending[..length]);
+        self.position += length;
+        Ok(length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    fn transcode(bytes: &[u8], encoding: &'static Encoding) -> String {
+        let mut decoded = String::new();
+        TranscodingReader::new(bytes, encoding)
+            .read_to_string(&mut decoded)
+            .unwrap();
+        decoded
+    }
+
+    #[test]
+    fn latin1() {
+        assert_eq!(
+            transcode(b"caf\xe9\nma\xf1ana\n", encoding_rs::WINDOWS_1252),
+            "café\nmañana\n"
+        );
+    }
+
+    #[test]
+    fn shift_jis() {
+        assert_eq!(
+            transcode(b"\x83\x65\x83\x4c\x83\x58\x83\x67", encoding_rs::SHIFT_JIS),
+            "テキスト"
+        );
+    }
+
+    #[test]
+    fn utf16_bom() {
+        assert_eq!(
+            transcode(b"\xff\xfeh\0i\0", encoding_rs::WINDOWS_1252),
+            "hi"
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        // a lead byte cut off by the end of the input
+        assert_eq!(transcode(b"ab\x82", encoding_rs::SHIFT_JIS), "ab\u{fffd}");
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(transcode(b"", encoding_rs::SHIFT_JIS), "");
+    }
+
+    #[test]
+    fn split_character() {
+        // a buffer of a single byte splits every double-byte character across two chunks
+        let mut decoded = String::new();
+        TranscodingReader::new(
+            BufReader::with_capacity(1, &b"\x83\x65\x83\x4c"[..]),
+            encoding_rs::SHIFT_JIS,
+        )
+        .read_to_string(&mut decoded)
+        .unwrap();
+        assert_eq!(decoded, "テキ");
+    }
+}
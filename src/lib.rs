@@ -4,33 +4,66 @@ mod matching_results;
 
 pub use crate::{
     core::{
+        deadline::TimeoutError,
         exit_code::ExitCode,
+        explain::{explain_match, Bonus, CharExplanation, MatchExplanation},
+        filter::Filter,
         request::{
-            ContextSize, Lines, MatchCollectionStrategy, MatchOptions, OutputBehavior, Request,
-            Targets,
+            CaseSensitivity, ContextChars, ContextSize, Lines, LossyEncoding,
+            MatchCollectionStrategy, MatchMode, MatchOptions, OutputBehavior, OutputField,
+            OutputFormat, Request, ScoreFormat, SortOrder, Targets,
         },
+        request_builder::{RequestBuildError, RequestBuilder},
+        run_error::RunError,
+        scoring::{batch_score, normalized_score, path_fuzzy_match, BatchMatchOptions},
+        scoring_config::ScoringConfig,
+        search_stats::{CallbackResult, SearchStats},
+        source_plugin::{FilePlugin, SourcePlugin, SourceRegistry, StdinPlugin},
+    },
+    matching_results::{
+        context_accumulators::SlidingAccumulator,
+        result::{group_indices, Context, ContextLine, MatchingResult},
+        result_collection::ResultCollection,
+        top_bracket::TopBracket,
     },
-    matching_results::result::MatchingResult,
 };
 
 use crate::{
-    cli::output,
-    core::reader::Reader,
+    cli::output::{
+        self,
+        ndjson::{self, NdjsonStream},
+        template::Template,
+        OutputOptions,
+    },
+    cli::progress::{Progress, ProgressCounters},
+    core::{
+        acronym::{self, MASK},
+        counting_reader::CountingReader,
+        deadline::Deadline,
+        diacritics, exact_case, fixed_string, normalization, path_aware, query_check,
+        reader::{self, Reader},
+        scoring,
+    },
     matching_results::{
-        context_accumulators::SlidingAccumulator,
+        context_accumulators::SaturatingAccumulator,
         result::{MatchingResultState, PartialMatchingResult},
-        result_collection::ResultCollection,
-        top_bracket::TopBracket,
+        result_collection::{Callback, FirstN},
     },
 };
-use log::debug;
+use log::{debug, error, warn};
+#[cfg(feature = "channel")]
+use std::sync::mpsc::SyncSender;
 use std::{
-    collections::VecDeque,
-    error,
+    borrow::Cow,
+    cmp,
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
+    error, fs,
     io::{self, BufRead, Write},
     iter, mem,
     path::Path,
+    time::Instant,
 };
+use vscode_fuzzy_score_rs::FuzzyMatch;
 use walkdir::WalkDir;
 
 /// This function handles all the application logic.
@@ -42,37 +75,205 @@ use walkdir::WalkDir;
 ///
 /// # Errors
 ///
+/// A [`RunError`] carrying the matches output before the failure and one of the following:
 ///   * [`std::fmt::Error`] if encounters any formatting related issues.
 ///   * [`std::io::Error`] if encounters any I/O related issues.
 ///   * [`walkdir::Error`] if any errors related to recursive processing occur
+///   * [`TimeoutError`] if the search is stopped by [`Request::max_time`] (the matches found so far are output nonetheless)
+///
+/// With [`Request::report_errors_as_matches`], the errors reading a file are only logged and the search goes on.
 ///
 pub fn run(
     request: &Request,
     output_dest: &mut impl Write,
-) -> Result<Vec<MatchingResult>, Box<dyn error::Error>> {
+) -> Result<Vec<MatchingResult>, RunError> {
     debug!("Running with the following configuration: {:?}", request);
 
-    let results = match request.strategy {
+    if request.dry_run {
+        list_files(request, output_dest).map_err(RunError::without_matches)?;
+        return Ok(Vec::new());
+    }
+
+    if let Some(warning) = query_check::validate_query(&request.query, &request.targets) {
+        warn!("{warning}");
+    }
+
+    let start = Instant::now();
+    let mut stats = SearchStats::default();
+    let progress = request.show_progress.then(Progress::start);
+    let deadline = request.max_time.map(Deadline::start);
+    // the errors are still reported, but only the matches decide the exit code, so the rest of the files is searched
+    let report_error = |err: Box<dyn error::Error>| {
+        if !request.suppress_errors {
+            error!("Error: {err}");
+        }
+    };
+    let on_error = request
+        .report_errors_as_matches
+        .then_some(&report_error as &dyn Fn(Box<dyn error::Error>));
+    // the collection depends on the strategy, but there is no need for a copy of the search for each of them
+    let mut collect = |dest: &mut dyn ResultCollection| {
+        collect_matches_common(
+            &request.query,
+            &request.targets,
+            &request.match_options,
+            dest,
+            &mut stats,
+            progress.as_ref().map(Progress::counters),
+            deadline.as_ref(),
+            on_error,
+        )
+    };
+    // without sorting, the results can be printed as soon as they are found
+    let streamed = request.output_format == OutputFormat::Ndjson
+        && request.sort_order == SortOrder::None
+        && !request.unique
+        && matches!(request.output_behavior, OutputBehavior::Normal(_));
+    // the best files (rather than matches) are selected once all the matches are known
+    let file_scores = matches!(request.output_behavior, OutputBehavior::FileScores(_));
+    let (mut results, timed_out) = match request.strategy {
+        MatchCollectionStrategy::CollectAll if streamed => {
+            let mut stream = NdjsonStream::new(output_dest, request.relative_paths_root.as_deref());
+            let timed_out = collect(&mut stream);
+            let results = stream.finish().map_err(RunError::without_matches)?;
+            // the matches streamed before the error have already been output
+            match timed_out {
+                Ok(timed_out) => (results, timed_out),
+                Err(source) => {
+                    return Err(RunError {
+                        matches: results,
+                        source,
+                    })
+                }
+            }
+        }
+        MatchCollectionStrategy::CollectTop(_) if file_scores => {
+            let mut results = Vec::new();
+            let timed_out = collect(&mut results).map_err(RunError::without_matches)?;
+            (results, timed_out)
+        }
         MatchCollectionStrategy::CollectAll => {
-            collect_all_matches(&request.query, &request.targets, &request.match_options)
+            // the results are sorted below, so that the discovery order can be preserved if requested
+            let mut results = Vec::new();
+            let timed_out = collect(&mut results).map_err(RunError::without_matches)?;
+            (results, timed_out)
         }
         MatchCollectionStrategy::CollectTop(n) => {
-            collect_top_matches(&request.query, &request.targets, &request.match_options, n)
+            if request.sort_order == SortOrder::ScoreAsc {
+                warn!(
+                    "Note: --top selects the {n} highest-scoring results; --sort score-asc then displays them lowest-first"
+                );
+            }
+            let mut results = TopBracket::new(n);
+            let timed_out = collect(&mut results).map_err(RunError::without_matches)?;
+            (results.into_sorted_vec(), timed_out)
+        }
+        MatchCollectionStrategy::CollectFirstN(n) => {
+            let mut results = FirstN::new(n);
+            let timed_out = collect(&mut results).map_err(RunError::without_matches)?;
+            (results.into_vec(), timed_out)
         }
-    }?;
+    };
+    if request.unique {
+        dedup_matching_lines(&mut results);
+    }
+    sort_results(&mut results, request.sort_order);
+    // every file gets a single header, so its matches have to be printed together
+    if request.print_file_headers {
+        group_by_file(&mut results);
+    }
+    // every JSON object is self-contained, so the shared context lines are kept in both
+    if request.match_options.track_line_numbers && request.output_format == OutputFormat::Text {
+        trim_context_overlaps(&mut results);
+    }
+    stats.elapsed = start.elapsed();
+    // the progress line has to be cleared before the results are printed
+    drop(progress);
 
     match request.output_behavior {
+        OutputBehavior::Normal(_) if streamed => {}
         OutputBehavior::Normal(formatting) => {
+            // the results handed back keep the file names as found, only the printed ones are made relative
+            let relative_paths_root = request.relative_paths_root.as_deref();
+            match &request.output_template {
+                _ if request.output_format == OutputFormat::Ndjson => ndjson::write_results(
+                    output_dest,
+                    &output::with_relative_file_names(&results, relative_paths_root),
+                )
+                .map_err(RunError::without_matches)?,
+                Some(template) => Template::parse(template)
+                    .write_results(
+                        output_dest,
+                        &output::with_relative_file_names(&results, relative_paths_root),
+                        &formatting,
+                    )
+                    .map_err(RunError::without_matches)?,
+                None => output::format_results(
+                    output_dest,
+                    &results,
+                    &formatting,
+                    &request.field_separator,
+                    OutputOptions {
+                        context_count: request.print_context_count,
+                        match_positions: request.print_match_positions,
+                        score_format: request.score_format,
+                        caret: request.show_caret,
+                        file_headers: request.print_file_headers,
+                        highlighted_query: request
+                            .highlight_query
+                            .then_some(request.query.as_str()),
+                        field_order: request.field_order.as_deref(),
+                        unmatched_marker: request
+                            .match_options
+                            .print_unmatched
+                            .then_some(request.unmatched_marker.as_str()),
+                        line_number_width: request.line_number_width,
+                        auto_align: request.auto_align,
+                        relative_paths_root,
+                        // like `grep`, the groups are only separated when there is any context
+                        context_separator: request.context_separator.as_deref().filter(|_| {
+                            request.match_options.context_size
+                                != ContextSize {
+                                    before: Lines(0),
+                                    after: Lines(0),
+                                }
+                                || request.match_options.context_chars != ContextChars::default()
+                        }),
+                    },
+                )
+                .map_err(RunError::without_matches)?,
+            }
+        }
+        OutputBehavior::FileScores(formatting) => {
+            let results =
+                output::with_relative_file_names(&results, request.relative_paths_root.as_deref());
+            let limit = match request.strategy {
+                MatchCollectionStrategy::CollectTop(n) => Some(n),
+                _ => None,
+            };
             write!(
                 output_dest,
                 "{}",
-                output::format_results(&results, &formatting)
-            )?;
+                output::format_file_scores(&results, &formatting, &request.field_separator, limit)
+            )
+            .map_err(RunError::without_matches)?;
         }
         OutputBehavior::Quiet => {}
     }
 
-    Ok(results)
+    if request.print_stats {
+        // the stats go to the standard error so that they do not get mixed with the results
+        eprintln!("{stats}");
+    }
+
+    match request.max_time {
+        // the matches found in time are output anyway, the error only reports that there might be more
+        Some(limit) if timed_out => Err(RunError {
+            matches: results,
+            source: Box::new(TimeoutError(limit)),
+        }),
+        _ => Ok(results),
+    }
 }
 
 /// Find fuzzy matches of `query` in `targets` using the configuration supplied `options`.
@@ -88,7 +289,16 @@ pub fn collect_all_matches(
     options: &MatchOptions,
 ) -> Result<Vec<MatchingResult>, Box<dyn error::Error>> {
     let mut result = Vec::new();
-    collect_matches_common(query, targets, options, &mut result)?;
+    collect_matches_common(
+        query,
+        targets,
+        options,
+        &mut result,
+        &mut SearchStats::default(),
+        None,
+        None,
+        None,
+    )?;
     result.sort_by(|a, b| b.cmp(a));
     Ok(result)
 }
@@ -107,39 +317,398 @@ pub fn collect_top_matches(
     top: usize,
 ) -> Result<Vec<MatchingResult>, Box<dyn error::Error>> {
     let mut result = TopBracket::new(top);
-    collect_matches_common(query, targets, options, &mut result)?;
-    Ok(result.into_vec())
+    collect_matches_common(
+        query,
+        targets,
+        options,
+        &mut result,
+        &mut SearchStats::default(),
+        None,
+        None,
+        None,
+    )?;
+    Ok(result.into_sorted_vec())
 }
 
-fn collect_matches_common(
+/// Same as [`collect_all_matches`] but puts the matches into `dest`, so that the collection can be chosen at runtime
+/// (e.g. a [`Vec`] for all the matches or a [`TopBracket`] for the best ones only).
+/// The matches are pushed in the order they are found in the targets,
+/// the search stops once `dest` does not need any more of them
+/// (see [`ResultCollection::stops_search`]).
+///
+/// # Errors
+///
+///   * [`io::Error`] if encounters any I/O related issues.
+///   * [`walkdir::Error`] if any errors related to recursive processing occur
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::{RequestBuilder, ResultCollection, Targets, TopBracket};
+/// use std::path::PathBuf;
+///
+/// let request = RequestBuilder::new(
+///     "contig",
+///     Targets::Files(vec![PathBuf::from("resources/tests/test.txt")]),
+/// )
+/// .build()
+/// .unwrap();
+/// let mut all = Vec::new();
+/// let mut top = TopBracket::new(1);
+/// for keep_all in [true, false] {
+///     let dest: &mut dyn ResultCollection = if keep_all { &mut all } else { &mut top };
+///     fzgrep::collect_matches_into_dyn(&request.query, &request.targets, &request.match_options, dest)
+///         .unwrap();
+/// }
+/// assert_eq!(all.len(), 2);
+/// assert_eq!(top.into_sorted_vec().len(), 1);
+/// ```
+///
+pub fn collect_matches_into_dyn(
     query: &str,
     targets: &Targets,
     options: &MatchOptions,
-    dest: &mut impl ResultCollection,
+    dest: &mut dyn ResultCollection,
 ) -> Result<(), Box<dyn error::Error>> {
-    for reader in make_readers(targets) {
-        let reader = reader?;
-        debug!("Processing {}.", reader.display_name());
-        merge_target_matches(query, reader, options, dest)?;
+    collect_matches_common(
+        query,
+        targets,
+        options,
+        dest,
+        &mut SearchStats::default(),
+        None,
+        None,
+        None,
+    )?;
+    Ok(())
+}
+
+/// Finds fuzzy matches of the query in the targets of `request` and passes each of them to `on_match`
+/// as soon as it is complete (i.e. once its trailing context, if any, is collected).
+///
+/// Nothing is accumulated, so the matches arrive in the order they are found in the targets rather than by score.
+/// Only the query, the targets and the match options of `request` are taken into account.
+/// The callback only gets a reference to the match, so it has to clone the match if it wants to keep it.
+///
+/// # Errors
+///
+///   * [`io::Error`] if encounters any I/O related issues.
+///   * [`walkdir::Error`] if any errors related to recursive processing occur
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::{CallbackResult, RequestBuilder, Targets};
+/// use std::path::PathBuf;
+///
+/// let request = RequestBuilder::new(
+///     "contig",
+///     Targets::Files(vec![PathBuf::from("resources/tests/test.txt")]),
+/// )
+/// .build()
+/// .unwrap();
+/// let mut lines = Vec::new();
+/// let summary =
+///     fzgrep::collect_matches_with_callback(&request, |m| lines.push(m.matching_line.clone()))
+///         .unwrap();
+/// assert_eq!(summary.total_matches, lines.len());
+/// assert_eq!(summary.files_searched, 1);
+/// ```
+///
+pub fn collect_matches_with_callback(
+    request: &Request,
+    on_match: impl FnMut(&MatchingResult),
+) -> Result<CallbackResult, Box<dyn error::Error>> {
+    let mut stats = SearchStats::default();
+    collect_matches_common(
+        &request.query,
+        &request.targets,
+        &request.match_options,
+        &mut Callback(on_match),
+        &mut stats,
+        None,
+        None,
+        None,
+    )?;
+    Ok(CallbackResult {
+        total_matches: stats.matches_found,
+        files_searched: stats.files_searched,
+    })
+}
+
+/// Finds fuzzy matches of the query in the targets of `request` and sends each of them over `dest`
+/// as soon as it is complete (i.e. once its trailing context, if any, is collected).
+///
+/// This lets another thread consume (e.g. format) the matches while the search is still going on.
+/// As with [`collect_matches_with_callback`], the matches arrive in the order they are found in the targets
+/// and only the query, the targets and the match options of `request` are taken into account.
+/// The sender is dropped when the search is over, so the receiving end sees the channel closed after the last match.
+/// Sending blocks while the channel is full; if the receiver is gone, the remaining matches are discarded.
+///
+/// # Errors
+///
+///   * [`io::Error`] if encounters any I/O related issues.
+///   * [`walkdir::Error`] if any errors related to recursive processing occur
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::{MatchingResult, RequestBuilder, Targets};
+/// use std::{path::PathBuf, sync::mpsc, thread};
+///
+/// let request = RequestBuilder::new(
+///     "contig",
+///     Targets::Files(vec![PathBuf::from("resources/tests/test.txt")]),
+/// )
+/// .line_numbers(true)
+/// .build()
+/// .unwrap();
+/// let (sender, receiver) = mpsc::sync_channel::<MatchingResult>(16);
+/// let consumer = thread::spawn(move || {
+///     receiver
+///         .iter()
+///         .map(|m| format!("{}:{}", m.line_number.unwrap(), m.matching_line))
+///         .collect::<Vec<_>>()
+/// });
+/// let summary = fzgrep::collect_matches_into(&request, sender).unwrap();
+/// let lines = consumer.join().unwrap();
+/// assert_eq!(lines, ["2:contiguous", "3:Contiguous"]);
+/// assert_eq!(summary.total_matches, 2);
+/// ```
+///
+#[cfg(feature = "channel")]
+pub fn collect_matches_into(
+    request: &Request,
+    mut dest: SyncSender<MatchingResult>,
+) -> Result<CallbackResult, Box<dyn error::Error>> {
+    let mut stats = SearchStats::default();
+    collect_matches_common(
+        &request.query,
+        &request.targets,
+        &request.match_options,
+        &mut dest,
+        &mut stats,
+        None,
+        None,
+        None,
+    )?;
+    Ok(CallbackResult {
+        total_matches: stats.matches_found,
+        files_searched: stats.files_searched,
+    })
+}
+
+/// Prints the names of the files that would be searched according to `request`, without searching them.
+///
+fn list_files(
+    request: &Request,
+    output_dest: &mut impl Write,
+) -> Result<(), Box<dyn error::Error>> {
+    let terminator = if request.match_options.null_data {
+        b'\0'
+    } else {
+        b'\n'
+    };
+    let mut files = make_readers(&request.targets, &request.match_options)
+        .map(|reader| {
+            let reader = reader?;
+            let file_name = reader.display_name().clone();
+            let line_count = if request.count_lines {
+                Some(CountingReader::new(reader.into_source(), terminator).count_lines()?)
+            } else {
+                None
+            };
+            Ok((file_name, line_count))
+        })
+        .collect::<Result<Vec<_>, Box<dyn error::Error>>>()?;
+    match request.output_behavior {
+        OutputBehavior::Normal(formatting) | OutputBehavior::FileScores(formatting) => {
+            if let Some(root) = &request.relative_paths_root {
+                output::make_paths_relative(files.iter_mut().map(|(file_name, _)| file_name), root);
+            }
+            write!(
+                output_dest,
+                "{}",
+                output::format_file_names(&files, &formatting)
+            )?;
+        }
+        OutputBehavior::Quiet => {}
     }
     Ok(())
 }
 
+fn sort_results(results: &mut [MatchingResult], order: SortOrder) {
+    let by_file_name = |a: &MatchingResult, b: &MatchingResult| {
+        a.file_name
+            .cmp(&b.file_name)
+            .then(a.line_number.cmp(&b.line_number))
+    };
+    // the sort is stable, so the results that compare equal keep the discovery order
+    match order {
+        SortOrder::Score => results.sort_by(|a, b| b.cmp(a)),
+        SortOrder::ScoreAsc => results.sort(),
+        SortOrder::FileName => results.sort_by(by_file_name),
+        SortOrder::FileNameDesc => results.sort_by(|a, b| by_file_name(b, a)),
+        SortOrder::Line => results.sort_by_key(|r| r.line_number),
+        SortOrder::LineDesc => results.sort_by_key(|r| cmp::Reverse(r.line_number)),
+        SortOrder::None => {}
+    }
+}
+
+/// Moves the `results` from the same file next to each other. The files are ordered by their first result
+/// and the results of every file keep their order (e.g. the best match of the best file comes first when sorting by score).
+///
+fn group_by_file(results: &mut [MatchingResult]) {
+    let mut first_results = HashMap::new();
+    for (index, result) in results.iter().enumerate() {
+        first_results
+            .entry(result.file_name.clone())
+            .or_insert(index);
+    }
+    // the sort is stable, so the results of a file keep their order
+    results.sort_by_key(|result| first_results[&result.file_name]);
+}
+
+/// Keeps only one of the `results` with the same matching line: the highest-scoring one
+/// or, among the equally scored ones, the first one. The remaining results keep their order.
+///
+fn dedup_matching_lines(results: &mut Vec<MatchingResult>) {
+    let mut by_score = (0..results.len()).collect::<Vec<_>>();
+    // the sort is stable, so the equally scored results keep their order
+    by_score.sort_by(|&a, &b| results[b].cmp(&results[a]));
+    let mut seen_lines = HashSet::new();
+    let mut kept = vec![false; results.len()];
+    for index in by_score {
+        kept[index] = seen_lines.insert(results[index].matching_line.as_str());
+    }
+    let mut kept = kept.into_iter();
+    results.retain(|_| kept.next().unwrap_or_default());
+}
+
+/// Trims the context lines shared by adjacent `results` from the same file so that they are printed only once.
+/// Only the pairs following each other in the file are considered (which is always the case when sorting by line).
+///
+fn trim_context_overlaps(results: &mut [MatchingResult]) {
+    for index in 1..results.len() {
+        let (head, tail) = results.split_at_mut(index);
+        let (previous, next) = (&mut head[index - 1], &mut tail[0]);
+        if previous.file_name != next.file_name {
+            continue;
+        }
+        if let (Some(previous_line), Some(next_line)) = (previous.line_number, next.line_number) {
+            if previous_line < next_line {
+                previous
+                    .context
+                    .trim_overlap(&mut next.context, next_line - previous_line - 1);
+            }
+        }
+    }
+}
+
+/// Searches `targets` for `query` and puts the matches into `dest`.
+/// Returns whether the search was stopped by `deadline` while some targets were still left to search.
+/// An error reading a target is passed to `on_error` and the target skipped, or, without it, stops the search.
+///
+#[allow(clippy::too_many_arguments)]
+fn collect_matches_common(
+    query: &str,
+    targets: &Targets,
+    options: &MatchOptions,
+    dest: &mut (impl ResultCollection + ?Sized),
+    stats: &mut SearchStats,
+    progress: Option<&ProgressCounters>,
+    deadline: Option<&Deadline>,
+    on_error: Option<&dyn Fn(Box<dyn error::Error>)>,
+) -> Result<bool, Box<dyn error::Error>> {
+    let query = if options.unicode_normalization {
+        normalization::normalize(String::from(query))
+    } else {
+        String::from(query)
+    };
+    let query = if options.ignore_diacritics {
+        diacritics::strip_diacritics(&query)
+    } else {
+        query
+    };
+    for reader in make_readers(targets, options) {
+        // there is a target left, so stopping here may miss some matches
+        if deadline.is_some_and(Deadline::is_expired) {
+            debug!("Out of time, stopping the search.");
+            return Ok(true);
+        }
+        if dest.stops_search() {
+            debug!("Enough matches collected, stopping the search.");
+            break;
+        }
+        let result = reader.and_then(|reader| {
+            debug!("Processing {}.", reader.display_name());
+            stats.files_searched += 1;
+            Ok(merge_target_matches(&query, reader, options, dest, stats)?)
+        });
+        match (result, on_error) {
+            (Ok(()), _) => {}
+            (Err(err), Some(on_error)) => on_error(err),
+            (Err(err), None) => return Err(err),
+        }
+        if let Some(progress) = progress {
+            progress.file_searched(stats.matches_found);
+        }
+    }
+    Ok(false)
+}
+
 fn merge_target_matches(
     query: &str,
     target: Reader,
     options: &MatchOptions,
-    dest: &mut impl ResultCollection,
+    dest: &mut (impl ResultCollection + ?Sized),
+    stats: &mut SearchStats,
 ) -> Result<(), io::Error> {
     let display_name = target.display_name().clone();
+    let lines_skipped = target.lines_skipped();
     let ContextSize {
         before: Lines(lines_before),
         after: Lines(lines_after),
     } = options.context_size;
-    let mut context_before = SlidingAccumulator::new(lines_before);
+    let ContextChars {
+        chars_before,
+        chars_after,
+    } = options.context_chars;
+    let case_sensitive = is_case_sensitive(query, &options.case_sensitivity);
+    let fuzzy_match = line_matcher(&options.match_mode, options.path_aware);
+    let mut context_before = SlidingAccumulator::with_min_chars(lines_before, chars_before);
     let mut pending_results: VecDeque<PartialMatchingResult> = VecDeque::new();
-    for (index, line) in target.into_source().lines().enumerate() {
+    // When every line is printed, the lines since the previous match make up the leading context of the next one,
+    // and the last match is held back until the end of the file, which makes up its trailing context.
+    let mut unmatched = Vec::new();
+    let mut last_match: Option<MatchingResult> = None;
+    let lines: Box<dyn Iterator<Item = io::Result<String>>> =
+        match (&options.lossy_encoding, options.null_data) {
+            (LossyEncoding(true), false) => Box::new(target.lossy_lines(b'\n')),
+            (LossyEncoding(true), true) => Box::new(target.lossy_lines(b'\0')),
+            (LossyEncoding(false), false) => Box::new(target.into_source().lines()),
+            (LossyEncoding(false), true) => Box::new(target.split_by_nul()),
+        };
+    for (index, line) in lines.enumerate() {
+        if dest.stops_search() {
+            break;
+        }
         let line = line?;
+        stats.lines_processed += 1;
+        let line = match options.max_line_length {
+            Some(max_length) if line.len() > max_length => {
+                if !options.truncate_long_lines {
+                    debug!(
+                        "Skipping line {} in {display_name}: {} bytes long.",
+                        lines_skipped + index + 1,
+                        line.len()
+                    );
+                    continue;
+                }
+                truncate_line(line, max_length)
+            }
+            _ => line,
+        };
 
         // Feed the current line to the results that are waiting for their post-contexts to fill up (if there are any).
         for partial_result in mem::take(&mut pending_results) {
@@ -151,31 +720,90 @@ fn merge_target_matches(
             }
         }
 
-        if let Some(m) = vscode_fuzzy_score_rs::fuzzy_match(query, &line) {
-            let line_number = index + 1;
+        // Normalized and without the diacritics the line has exactly the same number of characters,
+        // so the positions of a match are valid for the original line too.
+        let mut matched_line = Cow::Borrowed(line.as_str());
+        if options.unicode_normalization {
+            matched_line = normalization::mask_normalized(&line);
+        }
+        if options.ignore_diacritics {
+            matched_line = Cow::Owned(diacritics::mask_diacritics(&matched_line));
+        }
+        if let Some(m) = match_line(fuzzy_match, query, &matched_line, case_sensitive).filter(|m| {
+            satisfies_constraints(
+                &matched_line,
+                m.positions(),
+                options
+                    .word_boundary_check
+                    .then_some(options.word_chars.as_ref()),
+                options.whole_line,
+            )
+        }) {
+            stats.matches_found += 1;
+            let line_number = lines_skipped + index + 1;
             debug!(
                 "Found a match in {display_name}, line {line_number}, positions {:?}",
                 m.positions()
             );
 
+            // the column is 1-based and counted in characters, just like the positions reported by the matcher
+            let column_number = options
+                .track_column_numbers
+                .then(|| m.positions().first().map_or(1, |position| position + 1));
+
+            let mut score_bonus = options.acronym_bonus.map_or(0, |bonus| {
+                scoring::acronym_bonus(&matched_line, m.positions(), bonus)
+            });
+            if options.scoring != ScoringConfig::default() {
+                score_bonus = score_bonus.saturating_add(scoring::scoring_adjustment(
+                    query,
+                    &matched_line,
+                    m.positions(),
+                    &options.scoring,
+                ));
+            }
+
+            let (before, after) = if options.print_unmatched {
+                (mem::take(&mut unmatched), SaturatingAccumulator::new(0, 0))
+            } else {
+                (
+                    context_before.snapshot(),
+                    SaturatingAccumulator::new(lines_after, chars_after),
+                )
+            };
             match MatchingResultState::new(
                 line.clone(),
                 m,
+                score_bonus,
                 options.track_file_names.then_some(display_name.clone()),
                 options.track_line_numbers.then_some(line_number),
-                context_before.snapshot(),
-                lines_after,
+                column_number,
+                before,
+                after,
             ) {
+                MatchingResultState::Complete(matching_result) if options.print_unmatched => {
+                    if let Some(previous) = last_match.replace(matching_result) {
+                        dest.push(previous);
+                    }
+                }
                 MatchingResultState::Complete(matching_result) => dest.push(matching_result),
                 MatchingResultState::Incomplete(partial_matching_result) => {
                     pending_results.push_back(partial_matching_result)
                 }
             }
+        } else if options.print_unmatched {
+            unmatched.push(line);
+            continue;
         }
 
         context_before.feed(line);
     }
 
+    if let Some(mut last_match) = last_match {
+        last_match.context.after = unmatched;
+        dest.push(last_match);
+    }
+
     // It is possible that the end of the file was reached when some matches were still waiting
     // for their post-context to fill up. In such case we just add what we have to `result`.
     for partial_result in pending_results {
@@ -185,27 +813,198 @@ fn merge_target_matches(
     Ok(())
 }
 
-fn make_readers(
-    targets: &Targets,
-) -> Box<dyn Iterator<Item = Result<Reader, Box<dyn error::Error>>> + '_> {
+/// Cuts `line` down to at most `max_length` bytes (without splitting a character) and appends `…` to it.
+///
+fn truncate_line(mut line: String, max_length: usize) -> String {
+    let end = (0..=max_length)
+        .rev()
+        .find(|&index| line.is_char_boundary(index))
+        .unwrap_or(0);
+    line.truncate(end);
+    line.push('…');
+    line
+}
+
+/// Selects the function matching a query against a line according to `match_mode`
+/// (`path_aware` only makes a difference for [`MatchMode::Fuzzy`]).
+///
+fn line_matcher(match_mode: &MatchMode, path_aware: bool) -> fn(&str, &str) -> Option<FuzzyMatch> {
+    match match_mode {
+        MatchMode::Fuzzy if path_aware => path_aware::path_aware_match,
+        MatchMode::Fuzzy => vscode_fuzzy_score_rs::fuzzy_match,
+        MatchMode::Acronym => acronym::acronym_match,
+        MatchMode::FixedString => fixed_string::fixed_string_match,
+    }
+}
+
+/// Matches `query` against `line` with `fuzzy_match`, requiring the matched characters to have
+/// the same letter case as the ones of the query if `case_sensitive`.
+///
+fn match_line(
+    fuzzy_match: fn(&str, &str) -> Option<FuzzyMatch>,
+    query: &str,
+    line: &str,
+    case_sensitive: bool,
+) -> Option<FuzzyMatch> {
+    if case_sensitive {
+        exact_case::exact_case_match(fuzzy_match, query, line)
+    } else {
+        fuzzy_match(query, line)
+    }
+}
+
+/// Checks whether `query` is to be matched case-sensitively according to `case_sensitivity`.
+///
+fn is_case_sensitive(query: &str, case_sensitivity: &CaseSensitivity) -> bool {
+    match case_sensitivity {
+        CaseSensitivity::Insensitive => false,
+        CaseSensitivity::Smart => query.chars().any(char::is_uppercase),
+    }
+}
+
+/// Checks whether a match at `positions` (char indices in `line`) satisfies the requested constraints
+/// the fuzzy matcher itself knows nothing about.
+/// `word_boundary_check` is [`None`] if the boundaries are not checked,
+/// otherwise it holds the custom word characters, if any.
+///
+fn satisfies_constraints(
+    line: &str,
+    positions: &[usize],
+    word_boundary_check: Option<Option<&BTreeSet<char>>>,
+    whole_line: bool,
+) -> bool {
+    word_boundary_check.is_none_or(|word_chars| is_at_word_boundaries(line, positions, word_chars))
+        && (!whole_line || is_whole_line(line, positions))
+}
+
+/// Checks whether the first of `positions` (char indices in `line`) is preceded by a word boundary
+/// and the last one is followed by a word boundary.
+/// Word characters are `word_chars` if given, otherwise the same as `\w` in regular expressions,
+/// i.e. alphanumeric characters and underscores.
+///
+fn is_at_word_boundaries(
+    line: &str,
+    positions: &[usize],
+    word_chars: Option<&BTreeSet<char>>,
+) -> bool {
+    let (Some(&first), Some(&last)) = (positions.first(), positions.last()) else {
+        return true;
+    };
+    let is_word_char = |c: char| match word_chars {
+        Some(word_chars) => word_chars.contains(&c),
+        None => c.is_alphanumeric() || c == '_',
+    };
+    // the masked out characters belong to the ones preceding them
+    let preceded_by_boundary = !line
+        .chars()
+        .take(first)
+        .filter(|&c| c != MASK)
+        .last()
+        .is_some_and(is_word_char);
+    let followed_by_boundary = !line
+        .chars()
+        .skip(last + 1)
+        .find(|&c| c != MASK)
+        .is_some_and(is_word_char);
+    preceded_by_boundary && followed_by_boundary
+}
+
+/// Checks whether `positions` (distinct char indices in `line`) account for every character of `line`
+/// (except for the masked out ones).
+///
+fn is_whole_line(line: &str, positions: &[usize]) -> bool {
+    positions.len() == line.chars().filter(|&c| c != MASK).count()
+}
+
+fn make_readers<'a>(
+    targets: &'a Targets,
+    options: &MatchOptions,
+) -> Box<dyn Iterator<Item = Result<Reader, Box<dyn error::Error>>> + 'a> {
+    let readers = make_raw_readers(targets, options);
+    let readers = match options.byte_range.clone() {
+        Some(range) => {
+            let terminator = if options.null_data { b'\0' } else { b'\n' };
+            Box::new(readers.map(move |reader| {
+                reader.and_then(|r| r.byte_range(&range, terminator).map_err(|e| e.into()))
+            }))
+        }
+        None => readers,
+    };
+    // UTF-8 is read as is, so that `--lossy-encoding` still decides what happens to invalid sequences
+    match options
+        .encoding
+        .filter(|&encoding| encoding != encoding_rs::UTF_8)
+    {
+        Some(encoding) => {
+            Box::new(readers.map(move |reader| reader.map(|r| r.transcoded(encoding))))
+        }
+        None => readers,
+    }
+}
+
+fn make_raw_readers<'a>(
+    targets: &'a Targets,
+    options: &MatchOptions,
+) -> Box<dyn Iterator<Item = Result<Reader, Box<dyn error::Error>>> + 'a> {
+    let max_file_size = options.skip_bytes;
+    let open_file: fn(&Path, Option<u64>) -> Result<Option<Reader>, io::Error> =
+        if options.decompress {
+            |path, max_size| Reader::compressed_reader(path, max_size)
+        } else {
+            |path, max_size| Reader::file_reader(path, max_size)
+        };
+    let sources = options.sources.clone();
     match targets {
         Targets::Files(files) => {
             debug!(
                 "*Non*-recursive mode; using the following input files: {:?}",
                 files
             );
-            Box::new(
-                files
-                    .iter()
-                    .map(|p| Reader::file_reader(p).map_err(|e| e.into())),
-            )
+            Box::new(files.iter().filter_map(move |p| {
+                if let Some(source) = sources.open(p) {
+                    return Some(
+                        source
+                            .map(|s| Reader::plugin_reader(p, s))
+                            .map_err(|e| e.into()),
+                    );
+                }
+                // the same as `grep` does, rather than failing to read it
+                if p.is_dir() {
+                    warn!(
+                        "Skipping directory '{}'; use -r to search recursively",
+                        p.display()
+                    );
+                    return None;
+                }
+                open_file(p, max_file_size)
+                    .map_err(|e| e.into())
+                    .transpose()
+            }))
         }
         Targets::RecursiveEntries(entries) => {
             debug!(
                 "Recursive mode; using the following input targets: {:?}",
                 entries
             );
-            make_recursive_reader_iterator(entries.iter())
+            make_recursive_reader_iterator(entries.iter(), open_file, options)
+        }
+        Targets::Glob(patterns) => {
+            debug!("Glob mode; using the following patterns: {:?}", patterns);
+            Box::new(
+                patterns
+                    .iter()
+                    // the patterns are already valid, so the expansion itself cannot fail
+                    .flat_map(|pattern| glob::glob(pattern.as_str()).into_iter().flatten())
+                    .filter_map(move |item| match item {
+                        Ok(path) if path.is_file() => {
+                            open_target(&path, max_file_size, open_file, &sources)
+                                .map_err(|e| e.into())
+                                .transpose()
+                        }
+                        Ok(_) => None,
+                        Err(e) => Some(Err(e.into())),
+                    }),
+            )
         }
         Targets::Stdin => {
             debug!("*Non*-recursive mode; using STDIN.");
@@ -216,23 +1015,121 @@ fn make_readers(
 
 fn make_recursive_reader_iterator<'item>(
     targets: impl Iterator<Item = impl AsRef<Path> + 'item> + 'item,
+    open_file: fn(&Path, Option<u64>) -> Result<Option<Reader>, io::Error>,
+    options: &MatchOptions,
 ) -> Box<dyn Iterator<Item = Result<Reader, Box<dyn error::Error>>> + 'item> {
+    let MatchOptions {
+        skip_bytes: max_file_size,
+        follow_links,
+        dedup_inodes,
+        skip_hidden,
+        search_zip,
+        ..
+    } = *options;
+    let filter = options.filter.clone();
+    let sources = options.sources.clone();
+    let mut seen_files = HashSet::new();
+    // the archives themselves are not filtered, their entries are
+    let entry_filter = filter.clone();
+    let is_archive = move |path: &Path| search_zip && reader::is_zip(path);
     Box::new(
         targets
-            .flat_map(|target| WalkDir::new(target).sort_by_file_name())
-            .filter_map(|item| {
+            .flat_map(move |target| {
+                WalkDir::new(target)
+                    .follow_links(follow_links)
+                    .sort_by_file_name()
+                    .into_iter()
+                    // the targets themselves are searched even if hidden (e.g. `.` or `.config`)
+                    .filter_entry(move |e| !skip_hidden || e.depth() == 0 || !is_hidden(e))
+            })
+            .filter_map(move |item| {
                 item.map_or_else(
                     |e| Some(Err(e.into())),
                     |d| {
                         d.metadata().map_or_else(
                             |e| Some(Err(e.into())),
                             |m| {
-                                m.is_file()
-                                    .then_some(Reader::file_reader(d.path()).map_err(|e| e.into()))
+                                if !m.is_file() {
+                                    return None;
+                                }
+                                if !is_archive(d.path()) && !filter.is_allowed(d.path()) {
+                                    debug!("Skipping {} (filtered out).", d.path().display());
+                                    return None;
+                                }
+                                if max_file_size.is_some_and(|max| m.len() > max) {
+                                    debug!(
+                                        "Skipping {} ({} bytes exceed the size limit).",
+                                        d.path().display(),
+                                        m.len()
+                                    );
+                                    return None;
+                                }
+                                if dedup_inodes
+                                    && file_id(&m).is_some_and(|id| !seen_files.insert(id))
+                                {
+                                    debug!(
+                                        "Skipping {} (already searched under another name).",
+                                        d.path().display()
+                                    );
+                                    return None;
+                                }
+                                Some(Ok(d.into_path()))
                             },
                         )
                     },
                 )
-            }),
+            })
+            .flat_map(
+                move |item| -> Box<dyn Iterator<Item = Result<Reader, Box<dyn error::Error>>>> {
+                    match item {
+                        Ok(path) if is_archive(&path) => Box::new(
+                            reader::zip_entry_readers(&path, entry_filter.clone())
+                                .map(|reader| reader.map_err(|e| e.into())),
+                        ),
+                        Ok(path) => Box::new(
+                            open_target(&path, None, open_file, &sources)
+                                .map_err(|e| e.into())
+                                .transpose()
+                                .into_iter(),
+                        ),
+                        Err(e) => Box::new(iter::once(Err(e))),
+                    }
+                },
+            ),
     )
 }
+
+/// Opens `path` with the first of `sources` that can handle it, or with `open_file` if none can.
+///
+fn open_target(
+    path: &Path,
+    max_size: Option<u64>,
+    open_file: fn(&Path, Option<u64>) -> Result<Option<Reader>, io::Error>,
+    sources: &SourceRegistry,
+) -> Result<Option<Reader>, io::Error> {
+    match sources.open(path) {
+        Some(source) => source.map(|s| Some(Reader::plugin_reader(path, s))),
+        None => open_file(path, max_size),
+    }
+}
+
+/// Checks whether the name of the file or directory described by `entry` starts with `.`.
+///
+fn is_hidden(entry: &walkdir::DirEntry) -> bool {
+    entry.file_name().to_string_lossy().starts_with('.')
+}
+
+/// Returns the device and inode numbers identifying the file described by `metadata`.
+///
+#[cfg(unix)]
+fn file_id(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Files cannot be reliably identified on this platform, so no file is considered a duplicate.
+///
+#[cfg(not(unix))]
+fn file_id(_metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
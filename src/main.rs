@@ -1,25 +1,83 @@
-use fzgrep::cli::args;
-use log::error;
-use std::{env, io, process};
+use fzgrep::cli::{
+    args, line_buffered_writer::LineBufferedWriter, nul_terminated_writer::NulTerminatedWriter,
+    pager,
+};
+use log::{debug, error};
+use std::{
+    env,
+    fs::File,
+    io::{self, Write},
+    process,
+};
 
 fn main() -> process::ExitCode {
-    let request = args::make_request(env::args());
+    let config = args::default_config_path();
+    let default_options = env::var(args::DEFAULT_OPTIONS_VAR).ok();
+    let request =
+        args::make_request_with(env::args(), config.as_deref(), default_options.as_deref());
     // initialize logger
     env_logger::Builder::new()
         .filter_level(request.log_verbosity)
         .init();
+    for notice in &request.notices {
+        debug!("{notice}");
+    }
+
+    let mut output: Box<dyn Write> = match &request.output_file {
+        Some(path) => match File::create(path) {
+            Ok(file) => Box::new(file),
+            Err(err) => {
+                if !request.suppress_errors {
+                    error!("Error: cannot open {}: {err}", path.display());
+                }
+                return process::ExitCode::from(fzgrep::ExitCode::FAILURE);
+            }
+        },
+        None => Box::new(io::stdout()),
+    };
+    if request.zero_terminated {
+        output = Box::new(NulTerminatedWriter::new(output));
+    }
+    // flushing is triggered by the newlines, so they have to be seen before they are replaced
+    if request.line_buffered {
+        output = Box::new(LineBufferedWriter::new(output));
+    }
 
-    match fzgrep::run(&request, &mut io::stdout()) {
+    let result = match &request.pager {
+        Some(pager) => {
+            // whether the output fits on the terminal is only known once it is complete
+            let mut buffer = Vec::new();
+            let result = fzgrep::run(&request, &mut buffer);
+            if let Err(err) = pager::page(&buffer, pager) {
+                if !request.suppress_errors {
+                    error!("Error: {err}");
+                }
+                return process::ExitCode::from(fzgrep::ExitCode::FAILURE);
+            }
+            result
+        }
+        None => fzgrep::run(&request, &mut output),
+    };
+    match result {
         Ok(matches) => {
-            if !matches.is_empty() {
+            if !matches.is_empty() || request.dry_run {
                 process::ExitCode::from(fzgrep::ExitCode::SUCCESS)
             } else {
                 process::ExitCode::from(fzgrep::ExitCode::NO_MATCHES)
             }
         }
         Err(err) => {
-            error!("Error: {err}");
-            process::ExitCode::from(fzgrep::ExitCode::FAILURE)
+            if !request.suppress_errors {
+                error!("Error: {err}");
+            }
+            if !request.report_errors_as_matches {
+                process::ExitCode::from(fzgrep::ExitCode::FAILURE)
+            } else if !err.matches.is_empty() {
+                // e.g. a timeout only stops the search after some matches have been printed
+                process::ExitCode::from(fzgrep::ExitCode::SUCCESS)
+            } else {
+                process::ExitCode::from(fzgrep::ExitCode::NO_MATCHES)
+            }
         }
     }
 }
@@ -3,9 +3,25 @@ use std::collections::VecDeque;
 /// A FIFO-like context accumulator: when at capacity every new line that is fed
 /// will pop the oldest line stored in the accumulator.
 ///
+/// Can be used to keep a streaming window of the last few lines, e.g. for a custom leading context.
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::SlidingAccumulator;
+///
+/// let mut acc = SlidingAccumulator::new(2);
+/// acc.feed(String::from("one"));
+/// acc.feed(String::from("two"));
+/// acc.feed(String::from("three"));
+/// assert_eq!(acc.snapshot(), ["two", "three"]);
+/// ```
+///
 #[derive(Debug, PartialEq)]
-pub(crate) struct SlidingAccumulator {
+pub struct SlidingAccumulator {
     capacity: usize,
+    min_chars: usize,
+    chars: usize,
     data: VecDeque<String>,
 }
 
@@ -14,50 +30,144 @@ pub(crate) struct SlidingAccumulator {
 #[derive(Debug, PartialEq)]
 pub(crate) struct SaturatingAccumulator {
     capacity: usize,
+    min_chars: usize,
+    chars: usize,
     data: Vec<String>,
 }
 
 impl SlidingAccumulator {
     /// Creates a new [`SlidingAccumulator`] with capacity `capacity`.
-    /// `capacity` can be 0, in which case [`feed`] does nothing.
+    /// `capacity` can be 0, in which case [`SlidingAccumulator::feed`] does nothing.
     ///
-    pub(crate) fn new(capacity: usize) -> Self {
+    /// # Examples
+    ///
+    /// ```
+    /// use fzgrep::SlidingAccumulator;
+    ///
+    /// let acc = SlidingAccumulator::new(3);
+    /// assert_eq!(acc.capacity(), 3);
+    /// assert!(acc.snapshot().is_empty());
+    /// ```
+    ///
+    pub fn new(capacity: usize) -> Self {
+        Self::with_min_chars(capacity, 0)
+    }
+
+    /// Creates a new [`SlidingAccumulator`] that keeps (at least) `capacity` lines
+    /// and, on top of that, as many of the most recent lines as needed to hold at least `min_chars` characters
+    /// (not counting the line terminators), whichever is more.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fzgrep::SlidingAccumulator;
+    ///
+    /// let mut acc = SlidingAccumulator::with_min_chars(1, 5);
+    /// acc.feed(String::from("one"));
+    /// acc.feed(String::from("two"));
+    /// acc.feed(String::from("three"));
+    /// // "three" alone is long enough
+    /// assert_eq!(acc.snapshot(), ["three"]);
+    /// acc.feed(String::from("four"));
+    /// assert_eq!(acc.snapshot(), ["three", "four"]);
+    /// ```
+    ///
+    pub fn with_min_chars(capacity: usize, min_chars: usize) -> Self {
         Self {
             capacity,
+            min_chars,
+            chars: 0,
             data: VecDeque::with_capacity(capacity),
         }
     }
 
+    /// Returns the maximum number of lines the accumulator holds.
+    ///
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
     /// Pushes a line into the accumulator.
     /// If the accumulator is at capacity, the oldest stored line is popped.
     /// If the capacity is zero does nothing.
     ///
-    pub(crate) fn feed(&mut self, line: String) {
-        if self.capacity == 0 {
+    /// # Examples
+    ///
+    /// ```
+    /// use fzgrep::SlidingAccumulator;
+    ///
+    /// let mut acc = SlidingAccumulator::new(0);
+    /// acc.feed(String::from("one"));
+    /// assert!(acc.snapshot().is_empty());
+    /// ```
+    ///
+    pub fn feed(&mut self, line: String) {
+        if self.capacity == 0 && self.min_chars == 0 {
             return;
         }
 
-        if self.data.len() == self.capacity {
-            self.data.pop_front();
-        }
-
+        self.chars += line.chars().count();
         self.data.push_back(line);
+        self.trim();
     }
 
-    /// Returns the accumulated lines as a [`Vec<String>`].
+    /// Returns the accumulated lines (from the oldest to the newest) as a [`Vec<String>`].
     ///
-    pub(crate) fn snapshot(&self) -> Vec<String> {
+    pub fn snapshot(&self) -> Vec<String> {
         self.data.iter().cloned().collect()
     }
+
+    /// Changes the capacity of the accumulator to `new_capacity`.
+    /// When growing, all the stored lines are kept; when shrinking, the oldest lines
+    /// that do not fit anymore are dropped, so that the `new_capacity` most recent ones are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fzgrep::SlidingAccumulator;
+    ///
+    /// let mut acc = SlidingAccumulator::new(3);
+    /// acc.feed(String::from("one"));
+    /// acc.feed(String::from("two"));
+    /// acc.feed(String::from("three"));
+    /// acc.resize(2);
+    /// assert_eq!(acc.snapshot(), ["two", "three"]);
+    /// acc.resize(3);
+    /// acc.feed(String::from("four"));
+    /// assert_eq!(acc.snapshot(), ["two", "three", "four"]);
+    /// ```
+    ///
+    pub fn resize(&mut self, new_capacity: usize) {
+        self.capacity = new_capacity;
+        self.trim();
+        self.data
+            .reserve(new_capacity.saturating_sub(self.data.len()));
+    }
+
+    /// Drops the oldest lines that are neither needed to keep `capacity` lines nor `min_chars` characters.
+    ///
+    fn trim(&mut self) {
+        while self.data.len() > self.capacity {
+            let oldest = self.data.front().map_or(0, |line| line.chars().count());
+            if self.chars - oldest < self.min_chars {
+                break;
+            }
+            self.data.pop_front();
+            self.chars -= oldest;
+        }
+    }
 }
 
 impl SaturatingAccumulator {
-    /// Creates a new [`SaturatingAccumulator`] with capacity `capacity`.
-    /// `capacity` can be 0, in which case [`feed`] does nothing.
+    /// Creates a new [`SaturatingAccumulator`] with capacity `capacity`, which is only saturated
+    /// once the lines also hold at least `min_chars` characters (not counting the line terminators).
+    /// Both can be 0, in which case [`feed`] does nothing.
     ///
-    pub(crate) fn new(capacity: usize) -> Self {
+    pub(crate) fn new(capacity: usize, min_chars: usize) -> Self {
         Self {
             capacity,
+            min_chars,
+            chars: 0,
             data: Vec::with_capacity(capacity),
         }
     }
@@ -71,13 +181,14 @@ impl SaturatingAccumulator {
             return;
         }
 
+        self.chars += line.chars().count();
         self.data.push(line);
     }
 
     /// Returns whether the accumulator is completely filled up.
     ///
     pub(crate) fn is_saturated(&self) -> bool {
-        self.data.len() == self.capacity
+        self.data.len() >= self.capacity && self.chars >= self.min_chars
     }
 
     /// Turns the accumulator into a [`Vec<String>`] of accumulated lines.
@@ -98,6 +209,8 @@ mod test {
             acc,
             SlidingAccumulator {
                 capacity: 3,
+                min_chars: 0,
+                chars: 0,
                 data: VecDeque::new(),
             }
         );
@@ -142,6 +255,43 @@ mod test {
         assert_eq!(acc.data, VecDeque::from([]));
     }
 
+    #[test]
+    fn sliding_accumulator_capacity() {
+        assert_eq!(SlidingAccumulator::new(0).capacity(), 0);
+        assert_eq!(SlidingAccumulator::new(1).capacity(), 1);
+        let mut acc = SlidingAccumulator::new(3);
+        acc.feed(String::from("one"));
+        assert_eq!(acc.capacity(), 3);
+    }
+
+    #[test]
+    fn sliding_accumulator_snapshot_zero_capacity() {
+        let mut acc = SlidingAccumulator::new(0);
+        assert_eq!(acc.snapshot(), [""; 0]);
+        acc.feed(String::from("one"));
+        assert_eq!(acc.snapshot(), [""; 0]);
+        acc.feed(String::from("two"));
+        assert_eq!(acc.snapshot(), [""; 0]);
+    }
+
+    #[test]
+    fn sliding_accumulator_snapshot_capacity_one() {
+        let mut acc = SlidingAccumulator::new(1);
+        assert_eq!(acc.snapshot(), [""; 0]);
+        acc.feed(String::from("one"));
+        assert_eq!(acc.snapshot(), ["one"]);
+        acc.feed(String::from("two"));
+        assert_eq!(acc.snapshot(), ["two"]);
+    }
+
+    #[test]
+    fn sliding_accumulator_snapshot_below_capacity() {
+        let mut acc = SlidingAccumulator::new(5);
+        acc.feed(String::from("one"));
+        acc.feed(String::from("two"));
+        assert_eq!(acc.snapshot(), ["one", "two"]);
+    }
+
     #[test]
     fn sliding_accumulator_snapshot() {
         let mut acc = SlidingAccumulator::new(3);
@@ -156,13 +306,118 @@ mod test {
         assert_eq!(acc.snapshot(), ["two", "three", "four"]);
     }
 
+    #[test]
+    fn sliding_accumulator_resize_grow() {
+        let mut acc = SlidingAccumulator::new(2);
+        acc.feed(String::from("one"));
+        acc.feed(String::from("two"));
+        acc.resize(4);
+        assert_eq!(acc.capacity(), 4);
+        assert_eq!(acc.snapshot(), ["one", "two"]);
+        acc.feed(String::from("three"));
+        acc.feed(String::from("four"));
+        assert_eq!(acc.snapshot(), ["one", "two", "three", "four"]);
+        acc.feed(String::from("five"));
+        assert_eq!(acc.snapshot(), ["two", "three", "four", "five"]);
+    }
+
+    #[test]
+    fn sliding_accumulator_resize_shrink() {
+        let mut acc = SlidingAccumulator::new(4);
+        for line in ["one", "two", "three", "four"] {
+            acc.feed(String::from(line));
+        }
+        acc.resize(2);
+        assert_eq!(acc.capacity(), 2);
+        assert_eq!(acc.snapshot(), ["three", "four"]);
+        acc.feed(String::from("five"));
+        assert_eq!(acc.snapshot(), ["four", "five"]);
+    }
+
+    #[test]
+    fn sliding_accumulator_resize_shrink_below_capacity() {
+        let mut acc = SlidingAccumulator::new(4);
+        acc.feed(String::from("one"));
+        acc.resize(2);
+        assert_eq!(acc.snapshot(), ["one"]);
+        acc.resize(0);
+        assert!(acc.snapshot().is_empty());
+        acc.feed(String::from("two"));
+        assert!(acc.snapshot().is_empty());
+    }
+
+    #[test]
+    fn sliding_accumulator_min_chars() {
+        let mut acc = SlidingAccumulator::with_min_chars(0, 6);
+        acc.feed(String::from("one"));
+        assert_eq!(acc.snapshot(), ["one"]);
+        acc.feed(String::from("two"));
+        assert_eq!(acc.snapshot(), ["one", "two"]);
+        acc.feed(String::from("three"));
+        assert_eq!(acc.snapshot(), ["two", "three"]);
+        acc.feed(String::from("sixsix"));
+        assert_eq!(acc.snapshot(), ["sixsix"]);
+    }
+
+    #[test]
+    fn sliding_accumulator_min_chars_and_capacity() {
+        // the larger of the two windows wins
+        let mut acc = SlidingAccumulator::with_min_chars(2, 4);
+        acc.feed(String::from("a"));
+        acc.feed(String::from("b"));
+        acc.feed(String::from("c"));
+        assert_eq!(acc.snapshot(), ["a", "b", "c"]);
+        acc.feed(String::from("long line"));
+        assert_eq!(acc.snapshot(), ["c", "long line"]);
+    }
+
+    #[test]
+    fn sliding_accumulator_min_chars_counts_characters() {
+        let mut acc = SlidingAccumulator::with_min_chars(0, 4);
+        acc.feed(String::from("тест"));
+        acc.feed(String::from("test"));
+        assert_eq!(acc.snapshot(), ["test"]);
+    }
+
+    #[test]
+    fn sliding_accumulator_resize_min_chars() {
+        let mut acc = SlidingAccumulator::with_min_chars(3, 3);
+        for line in ["one", "two", "three"] {
+            acc.feed(String::from(line));
+        }
+        acc.resize(0);
+        assert_eq!(acc.snapshot(), ["three"]);
+    }
+
+    #[test]
+    fn saturating_accumulator_min_chars() {
+        let mut acc = SaturatingAccumulator::new(1, 5);
+        assert!(!acc.is_saturated());
+        acc.feed(String::from("one"));
+        assert!(!acc.is_saturated());
+        acc.feed(String::from("two"));
+        assert!(acc.is_saturated());
+        acc.feed(String::from("three"));
+        assert_eq!(acc.consume(), ["one", "two"]);
+    }
+
+    #[test]
+    fn saturating_accumulator_min_chars_zero_capacity() {
+        let mut acc = SaturatingAccumulator::new(0, 3);
+        assert!(!acc.is_saturated());
+        acc.feed(String::from("line"));
+        assert!(acc.is_saturated());
+    }
+
     #[test]
     fn saturating_accumulator_constructor() {
-        let acc = SaturatingAccumulator::new(3);
+        let acc = SaturatingAccumulator::new(3, 0);
         assert_eq!(
             acc,
             SaturatingAccumulator {
                 capacity: 3,
+                min_chars: 0,
+                chars: 0,
                 data: Vec::new(),
             }
         );
@@ -170,7 +425,7 @@ mod test {
 
     #[test]
     fn saturating_accumulator_feed() {
-        let mut acc = SaturatingAccumulator::new(3);
+        let mut acc = SaturatingAccumulator::new(3, 0);
         assert_eq!(acc.data, [""; 0]);
         acc.feed(String::from("one"));
         assert_eq!(acc.data, [String::from("one")]);
@@ -198,7 +453,7 @@ mod test {
 
     #[test]
     fn saturating_accumulator_feed_zero_capacity() {
-        let mut acc = SaturatingAccumulator::new(3);
+        let mut acc = SaturatingAccumulator::new(3, 0);
         assert!(!acc.is_saturated());
         acc.feed(String::from("one"));
         assert!(!acc.is_saturated());
@@ -212,7 +467,7 @@ mod test {
 
     #[test]
     fn saturating_accumulator_is_saturated() {
-        let mut acc = SaturatingAccumulator::new(0);
+        let mut acc = SaturatingAccumulator::new(0, 0);
         assert_eq!(acc.data, [""; 0]);
         acc.feed(String::from("something"));
         assert_eq!(acc.data, [""; 0]);
@@ -220,7 +475,7 @@ mod test {
 
     #[test]
     fn saturating_accumulator_consume() {
-        let mut acc = SaturatingAccumulator::new(3);
+        let mut acc = SaturatingAccumulator::new(3, 0);
         acc.feed(String::from("one"));
         acc.feed(String::from("two"));
         acc.feed(String::from("three"));
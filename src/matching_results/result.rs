@@ -1,10 +1,27 @@
-use crate::matching_results::context_accumulators::SaturatingAccumulator;
-use std::cmp::Ordering;
+use crate::{core::scoring, matching_results::context_accumulators::SaturatingAccumulator};
+use log::debug;
+#[cfg(feature = "json-schema")]
+use schemars::JsonSchema;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize, Serializer};
+use std::{cmp::Ordering, fmt, iter, ops::Range};
 use vscode_fuzzy_score_rs::FuzzyMatch;
 
 /// Stores a full result of matching.
 ///
+/// With the `serde` feature enabled it can be serialized, the fuzzy match being represented as
+/// `{ "score": ..., "positions": [...] }`.
+/// Deserialization is not supported because a [`FuzzyMatch`] can only be obtained from the matcher.
+/// With the `context-line-numbers` feature enabled, the context lines are serialized as [`ContextLine`]s
+/// rather than bare strings, so that it is known which lines of the file they are.
+/// With the `json-schema` feature enabled the JSON Schema of the serialized form is available (see `--print-json-schema`).
+///
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    all(feature = "serde", not(feature = "context-line-numbers")),
+    derive(Serialize)
+)]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
 pub struct MatchingResult {
     /// The line that contains the match.
     ///
@@ -12,8 +29,18 @@ pub struct MatchingResult {
 
     /// The properties of the match.
     ///
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_fuzzy_match"))]
+    #[cfg_attr(feature = "json-schema", schemars(with = "FuzzyMatchSchema"))]
     pub fuzzy_match: FuzzyMatch,
 
+    /// Extra points on top of the score of the fuzzy match (see [`crate::MatchOptions::acronym_bonus`]
+    /// and [`crate::MatchOptions::scoring`]); negative if custom scoring weights are lower than the default ones.
+    /// Only serialized if not zero.
+    ///
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_zero"))]
+    #[cfg_attr(feature = "json-schema", schemars(default))]
+    pub score_bonus: i32,
+
     /// An optional file name (if file names tracking was requested).
     ///
     pub file_name: Option<String>,
@@ -22,14 +49,25 @@ pub struct MatchingResult {
     ///
     pub line_number: Option<usize>,
 
+    /// An optional 1-based column (in characters) of the first matched character
+    /// (if column numbers tracking was requested).
+    ///
+    pub column_number: Option<usize>,
+
     /// Context surrounding the match.
     ///
+    #[cfg_attr(
+        all(feature = "json-schema", feature = "context-line-numbers"),
+        schemars(with = "NumberedContext")
+    )]
     pub context: Context,
 }
 
 /// Context (surrounding lines) around a match
 ///
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, PartialEq))]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
 pub struct Context {
     /// Lines preceding the matching line.
     ///
@@ -40,6 +78,21 @@ pub struct Context {
     pub after: Vec<String>,
 }
 
+/// A line of the context annotated with its line number (see [`MatchingResult::before_context_lines`]).
+///
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+pub struct ContextLine {
+    /// The content of the line.
+    ///
+    pub text: String,
+
+    /// The (1-based) number of the line in the file, if line numbers tracking was requested.
+    ///
+    pub line_number: Option<usize>,
+}
+
 /// Represents possible states of a matching result.
 ///
 pub(crate) enum MatchingResultState {
@@ -64,6 +117,10 @@ pub(crate) struct PartialMatchingResult {
     ///
     fuzzy_match: FuzzyMatch,
 
+    /// Extra points on top of the score of the fuzzy match.
+    ///
+    score_bonus: i32,
+
     /// An optional file name (if file names tracking was requested).
     ///
     file_name: Option<String>,
@@ -72,6 +129,10 @@ pub(crate) struct PartialMatchingResult {
     ///
     line_number: Option<usize>,
 
+    /// An optional column number (if column numbers tracking was requested).
+    ///
+    column_number: Option<usize>,
+
     /// Partial context (the trailing context is not fully accumulated).
     ///
     partial_context: PartialContext,
@@ -88,31 +149,184 @@ struct PartialContext {
     after_accumulator: SaturatingAccumulator,
 }
 
+impl MatchingResult {
+    /// Returns the score of the match, i.e. the score of the fuzzy match plus [`MatchingResult::score_bonus`].
+    /// This is what the results are ranked by.
+    ///
+    pub fn score(&self) -> i32 {
+        self.fuzzy_match.score().saturating_add(self.score_bonus)
+    }
+
+    /// Returns [`MatchingResult::score`] scaled to the `[0.0, 1.0]` range (see [`crate::normalized_score`]),
+    /// so a score bonus counts here as well (and a high one takes the result to `1.0`).
+    /// Every character of the query is matched, so the query length is the number of the match positions.
+    ///
+    pub fn normalized_score(&self) -> f64 {
+        scoring::normalize_score(
+            self.score(),
+            self.fuzzy_match.positions().len(),
+            self.matching_line.chars().count(),
+        )
+    }
+
+    /// Returns the matched parts of the line as ranges of character (codepoint) indices,
+    /// adjacent matched characters being merged into a single range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fzgrep::{RequestBuilder, Targets};
+    /// use std::path::PathBuf;
+    ///
+    /// let request = RequestBuilder::new("екс", Targets::Files(vec![PathBuf::from("resources/tests/test.txt")]))
+    ///     .build()
+    ///     .unwrap();
+    /// let results = fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options).unwrap();
+    /// assert_eq!(results[0].matching_line, "Текст");
+    /// assert_eq!(results[0].match_char_ranges(), [1..4]);
+    /// ```
+    ///
+    pub fn match_char_ranges(&self) -> Vec<Range<usize>> {
+        group_indices(self.fuzzy_match.positions())
+    }
+
+    /// Returns the lines preceding the matching line, each with its line number
+    /// (provided that [`MatchingResult::line_number`] is known).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fzgrep::{ContextLine, RequestBuilder, Targets};
+    /// use std::path::PathBuf;
+    ///
+    /// let request = RequestBuilder::new("contigous", Targets::Files(vec![PathBuf::from("resources/tests/test.txt")]))
+    ///     .line_numbers(true)
+    ///     .context(1)
+    ///     .build()
+    ///     .unwrap();
+    /// let results = fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options).unwrap();
+    /// assert_eq!(results[0].line_number, Some(2));
+    /// assert_eq!(
+    ///     results[0].before_context_lines(),
+    ///     [ContextLine { text: String::from("打电动"), line_number: Some(1) }]
+    /// );
+    /// ```
+    ///
+    pub fn before_context_lines(&self) -> Vec<ContextLine> {
+        // the before-context always ends right before the matching line, even if trimmed
+        let first = self
+            .line_number
+            .map(|line_number| line_number - self.context.before.len());
+        number_lines(&self.context.before, first)
+    }
+
+    /// Returns the lines following the matching line, each with its line number
+    /// (provided that [`MatchingResult::line_number`] is known).
+    ///
+    pub fn after_context_lines(&self) -> Vec<ContextLine> {
+        // the after-context always starts right after the matching line, even if trimmed
+        let first = self.line_number.map(|line_number| line_number + 1);
+        number_lines(&self.context.after, first)
+    }
+
+    /// Returns the same result with the context removed, e.g. when only the match itself is of interest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fzgrep::{RequestBuilder, Targets};
+    /// use std::path::PathBuf;
+    ///
+    /// let request = RequestBuilder::new("contigous", Targets::Files(vec![PathBuf::from("resources/tests/test.txt")]))
+    ///     .context(1)
+    ///     .build()
+    ///     .unwrap();
+    /// let results = fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options).unwrap();
+    /// let result = results[0].clone().without_context();
+    /// assert_eq!(result.matching_line, "contiguous");
+    /// assert!(result.context.before.is_empty());
+    /// assert!(result.context.after.is_empty());
+    /// ```
+    ///
+    pub fn without_context(self) -> MatchingResult {
+        self.with_context(Vec::new(), Vec::new())
+    }
+
+    /// Returns the same result with the context replaced by `before` and `after`.
+    ///
+    pub fn with_context(self, before: Vec<String>, after: Vec<String>) -> MatchingResult {
+        MatchingResult {
+            context: Context { before, after },
+            ..self
+        }
+    }
+
+    /// Returns the matched parts of the line as ranges of byte offsets into [`MatchingResult::matching_line`],
+    /// adjacent matched characters being merged into a single range.
+    /// This is what most editors and other tools (e.g. LSP clients) expect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fzgrep::{RequestBuilder, Targets};
+    /// use std::path::PathBuf;
+    ///
+    /// let request = RequestBuilder::new("екс", Targets::Files(vec![PathBuf::from("resources/tests/test.txt")]))
+    ///     .build()
+    ///     .unwrap();
+    /// let results = fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options).unwrap();
+    /// assert_eq!(results[0].matching_line, "Текст");
+    /// assert_eq!(results[0].match_byte_ranges(), [2..8]);
+    /// ```
+    ///
+    pub fn match_byte_ranges(&self) -> Vec<Range<usize>> {
+        // byte offsets of all the characters, plus the end of the line (for ranges ending at the last character)
+        let offsets = self
+            .matching_line
+            .char_indices()
+            .map(|(offset, _)| offset)
+            .chain(iter::once(self.matching_line.len()))
+            .collect::<Vec<_>>();
+        self.match_char_ranges()
+            .into_iter()
+            .map(|range| offsets[range.start]..offsets[range.end])
+            .collect()
+    }
+}
+
 impl MatchingResultState {
     /// Creates a result state based on the parameters.
-    /// Effectively the only case when it can return [`MatchingResultState::Complete`] is `after_context_size` being `0`.
+    /// Effectively the only case when it can return [`MatchingResultState::Complete`] is
+    /// `after_context` being saturated right away (i.e. no trailing context is requested).
     ///
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         matching_line: String,
         fuzzy_match: FuzzyMatch,
+        score_bonus: i32,
         file_name: Option<String>,
         line_number: Option<usize>,
+        column_number: Option<usize>,
         before_context: Vec<String>,
-        after_context_size: usize,
+        after_context: SaturatingAccumulator,
     ) -> Self {
-        match ContextState::new(before_context, after_context_size) {
+        match ContextState::new(before_context, after_context) {
             ContextState::Complete(context) => Self::Complete(MatchingResult {
                 matching_line,
                 fuzzy_match,
+                score_bonus,
                 file_name,
                 line_number,
+                column_number,
                 context,
             }),
             ContextState::Incomplete(partial_context) => Self::Incomplete(PartialMatchingResult {
                 matching_line,
                 fuzzy_match,
+                score_bonus,
                 file_name,
                 line_number,
+                column_number,
                 partial_context,
             }),
         }
@@ -128,16 +342,20 @@ impl PartialMatchingResult {
             ContextState::Complete(context) => MatchingResultState::Complete(MatchingResult {
                 matching_line: self.matching_line,
                 fuzzy_match: self.fuzzy_match,
+                score_bonus: self.score_bonus,
                 file_name: self.file_name,
                 line_number: self.line_number,
+                column_number: self.column_number,
                 context,
             }),
             ContextState::Incomplete(partial_context) => {
                 MatchingResultState::Incomplete(PartialMatchingResult {
                     matching_line: self.matching_line,
                     fuzzy_match: self.fuzzy_match,
+                    score_bonus: self.score_bonus,
                     file_name: self.file_name,
                     line_number: self.line_number,
+                    column_number: self.column_number,
                     partial_context,
                 })
             }
@@ -151,8 +369,10 @@ impl PartialMatchingResult {
         MatchingResult {
             matching_line: self.matching_line,
             fuzzy_match: self.fuzzy_match,
+            score_bonus: self.score_bonus,
             file_name: self.file_name,
             line_number: self.line_number,
+            column_number: self.column_number,
             context: self.partial_context.complete(),
         }
     }
@@ -160,7 +380,7 @@ impl PartialMatchingResult {
 
 impl PartialEq for MatchingResult {
     fn eq(&self, other: &Self) -> bool {
-        self.fuzzy_match.eq(&other.fuzzy_match)
+        self.cmp(other) == Ordering::Equal
     }
 }
 
@@ -172,15 +392,189 @@ impl PartialOrd for MatchingResult {
 
 impl Eq for MatchingResult {}
 
+/// Results are compared by their scores (see [`MatchingResult::score`]) first.
+/// Results with equal scores are ordered so that the ones with lower line numbers,
+/// and then the ones with alphabetically lesser file names, are considered *greater*
+/// (i.e. come first when the results are sorted from the best to the worst).
+///
 impl Ord for MatchingResult {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.fuzzy_match.cmp(&other.fuzzy_match)
+        self.score()
+            .cmp(&other.score())
+            .then_with(|| other.line_number.cmp(&self.line_number))
+            .then_with(|| other.file_name.cmp(&self.file_name))
+    }
+}
+
+/// Plain text (never colored) representation of the result in the form of `[<file-name>:][<line-number>:]<matching-line>`,
+/// the absent fields being omitted. The column number and the context are not included.
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::{RequestBuilder, Targets};
+/// use std::path::PathBuf;
+///
+/// let request = RequestBuilder::new(
+///     "contigous",
+///     Targets::Files(vec![PathBuf::from("resources/tests/test.txt")]),
+/// )
+/// .line_numbers(true)
+/// .build()
+/// .unwrap();
+/// let results =
+///     fzgrep::collect_top_matches(&request.query, &request.targets, &request.match_options, 1)
+///         .unwrap();
+/// assert_eq!(results[0].to_string(), "2:contiguous");
+/// ```
+///
+impl fmt::Display for MatchingResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file_name) = &self.file_name {
+            write!(f, "{file_name}:")?;
+        }
+        if let Some(line_number) = self.line_number {
+            write!(f, "{line_number}:")?;
+        }
+        write!(f, "{}", self.matching_line)
+    }
+}
+
+/// Plain text representation of the context: the lines preceding the match followed by the lines following it,
+/// one per line (the matching line itself is not a part of the context).
+///
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, line) in self.before.iter().chain(&self.after).enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The serialized form of a [`FuzzyMatch`] (see [`serialize_fuzzy_match`]), used only to describe it in the schema.
+///
+#[cfg(feature = "json-schema")]
+#[derive(JsonSchema)]
+#[schemars(rename = "FuzzyMatch")]
+#[allow(dead_code)]
+struct FuzzyMatchSchema {
+    /// The fuzzy score (the greater the better).
+    ///
+    score: i32,
+
+    /// The positions (0-based character indices) of the matched characters.
+    ///
+    positions: Vec<usize>,
+}
+
+/// Annotates consecutive `lines` with their line numbers, starting from `first` (if known).
+///
+fn number_lines(lines: &[String], first: Option<usize>) -> Vec<ContextLine> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(index, text)| ContextLine {
+            text: text.clone(),
+            line_number: first.map(|first| first + index),
+        })
+        .collect()
+}
+
+/// The serialized form of a [`Context`] with the `context-line-numbers` feature enabled.
+///
+#[cfg(feature = "context-line-numbers")]
+#[derive(Serialize)]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+#[cfg_attr(feature = "json-schema", schemars(rename = "Context"))]
+struct NumberedContext {
+    /// Lines preceding the matching line.
+    ///
+    before: Vec<ContextLine>,
+
+    /// Lines following the matching line.
+    ///
+    after: Vec<ContextLine>,
+}
+
+/// Same as the derived implementation, except for the context lines, which are serialized with their line numbers.
+///
+#[cfg(feature = "context-line-numbers")]
+impl Serialize for MatchingResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Repr<'a> {
+            matching_line: &'a str,
+            #[serde(serialize_with = "serialize_fuzzy_match")]
+            fuzzy_match: &'a FuzzyMatch,
+            #[serde(skip_serializing_if = "is_zero")]
+            score_bonus: i32,
+            file_name: &'a Option<String>,
+            line_number: Option<usize>,
+            column_number: Option<usize>,
+            context: NumberedContext,
+        }
+
+        Repr {
+            matching_line: &self.matching_line,
+            fuzzy_match: &self.fuzzy_match,
+            score_bonus: self.score_bonus,
+            file_name: &self.file_name,
+            line_number: self.line_number,
+            column_number: self.column_number,
+            context: NumberedContext {
+                before: self.before_context_lines(),
+                after: self.after_context_lines(),
+            },
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn is_zero(value: &i32) -> bool {
+    *value == 0
+}
+
+#[cfg(feature = "serde")]
+fn serialize_fuzzy_match<S: Serializer>(
+    fuzzy_match: &FuzzyMatch,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct Repr<'a> {
+        score: i32,
+        positions: &'a [usize],
+    }
+
+    Repr {
+        score: fuzzy_match.score(),
+        positions: fuzzy_match.positions(),
+    }
+    .serialize(serializer)
+}
+
+impl Context {
+    /// Trims the lines shared by this context (of a match) and `other` (the context of the next match in the same file),
+    /// `gap` being the number of lines between the two matching lines.
+    ///
+    /// The after-context of this match is cut so that it does not reach the next matching line
+    /// (the lines past it are the next match and its own after-context), and the lines already covered by it
+    /// are removed from the beginning of the before-context of `other`.
+    /// This way every line is printed only once when the two results are printed one after another.
+    ///
+    pub fn trim_overlap(&mut self, other: &mut Context, gap: usize) {
+        self.after.truncate(gap);
+        let overlap = (self.after.len() + other.before.len()).saturating_sub(gap);
+        other.before.drain(..overlap.min(other.before.len()));
     }
 }
 
 impl ContextState {
-    fn new(before: Vec<String>, after_size: usize) -> ContextState {
-        let accumulator = SaturatingAccumulator::new(after_size);
+    fn new(before: Vec<String>, accumulator: SaturatingAccumulator) -> ContextState {
         if accumulator.is_saturated() {
             Self::Complete(Context {
                 before,
@@ -216,39 +610,395 @@ impl PartialContext {
     }
 }
 
+/// Merges sorted `indices` into ranges of consecutive indices, e.g. `[0, 1, 2, 5, 7, 8]` -> `[0..3, 5..6, 7..9]`.
+///
+/// This is how the match positions (see [`MatchingResult::fuzzy_match`]) are turned into the parts of the line to highlight.
+/// The indices are expected to be sorted in ascending order and not to contain duplicates,
+/// which is always the case for the positions reported by the matcher.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(fzgrep::group_indices(&[1, 2, 3, 6]), [1..4, 6..7]);
+/// assert!(fzgrep::group_indices(&[]).is_empty());
+/// ```
+///
+pub fn group_indices(indices: &[usize]) -> Vec<Range<usize>> {
+    if indices.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ret = Vec::new();
+    let mut itr = indices.iter();
+    // we've already handled the case of an empty input, it is safe to unwrap
+    let mut start = *itr.next().unwrap();
+
+    for (i, x) in itr.enumerate() {
+        if x - indices[i] != 1 {
+            let end = indices[i];
+            ret.push(Range {
+                start,
+                end: end + 1,
+            });
+            start = *x;
+        }
+    }
+    // again, the case of an empty input is already handled so it is safe to unwrap here too
+    ret.push(Range {
+        start,
+        end: indices.last().unwrap() + 1,
+    });
+
+    debug!("Match indices {:?} -> ranges {:?}", indices, ret);
+
+    ret
+}
+
+/// A builder for [`MatchingResult`] test fixtures.
+/// The fuzzy match is computed from the query and the matching line, everything else is empty unless set.
+///
+#[cfg(test)]
+pub(crate) struct MatchingResultBuilder {
+    matching_line: String,
+    fuzzy_match: FuzzyMatch,
+    score_bonus: i32,
+    file_name: Option<String>,
+    line_number: Option<usize>,
+    column_number: Option<usize>,
+    context: Context,
+}
+
+#[cfg(test)]
+impl MatchingResultBuilder {
+    /// Creates a builder for the result of matching `query` against `line`.
+    ///
+    /// # Panics
+    ///
+    /// If `query` does not match `line`.
+    ///
+    pub(crate) fn new(query: &str, line: &str) -> Self {
+        Self {
+            matching_line: String::from(line),
+            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match(query, line)
+                .expect("The query of a test fixture should match its line"),
+            score_bonus: 0,
+            file_name: None,
+            line_number: None,
+            column_number: None,
+            context: Context {
+                before: vec![],
+                after: vec![],
+            },
+        }
+    }
+
+    /// Replaces the computed fuzzy match with a pre-computed one.
+    ///
+    pub(crate) fn fuzzy_match(mut self, fuzzy_match: FuzzyMatch) -> Self {
+        self.fuzzy_match = fuzzy_match;
+        self
+    }
+
+    pub(crate) fn score_bonus(mut self, score_bonus: i32) -> Self {
+        self.score_bonus = score_bonus;
+        self
+    }
+
+    pub(crate) fn file_name(mut self, file_name: &str) -> Self {
+        self.file_name = Some(String::from(file_name));
+        self
+    }
+
+    pub(crate) fn line_number(mut self, line_number: usize) -> Self {
+        self.line_number = Some(line_number);
+        self
+    }
+
+    pub(crate) fn column_number(mut self, column_number: usize) -> Self {
+        self.column_number = Some(column_number);
+        self
+    }
+
+    pub(crate) fn before_context(mut self, lines: &[&str]) -> Self {
+        self.context.before = lines.iter().copied().map(String::from).collect();
+        self
+    }
+
+    pub(crate) fn after_context(mut self, lines: &[&str]) -> Self {
+        self.context.after = lines.iter().copied().map(String::from).collect();
+        self
+    }
+
+    pub(crate) fn build(self) -> MatchingResult {
+        MatchingResult {
+            matching_line: self.matching_line,
+            fuzzy_match: self.fuzzy_match,
+            score_bonus: self.score_bonus,
+            file_name: self.file_name,
+            line_number: self.line_number,
+            column_number: self.column_number,
+            context: self.context,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn group_indices_empty() {
+        assert!(group_indices(&[]).is_empty());
+    }
+
+    #[test]
+    fn group_indices_non_empty() {
+        assert_eq!(group_indices(&[3]), [Range { start: 3, end: 4 }]);
+        assert_eq!(group_indices(&[0, 1, 2]), [Range { start: 0, end: 3 }]);
+        assert_eq!(group_indices(&[0, 1, 2, 5, 7, 8]), [0..3, 5..6, 7..9]);
+    }
+
+    #[test]
+    fn group_indices_single() {
+        assert_eq!(group_indices(&[0]), [Range { start: 0, end: 1 }]);
+        assert_eq!(group_indices(&[42]), [Range { start: 42, end: 43 }]);
+    }
+
+    #[test]
+    fn group_indices_all_consecutive() {
+        assert_eq!(
+            group_indices(&[4, 5, 6, 7, 8]),
+            [Range { start: 4, end: 9 }]
+        );
+    }
+
+    #[test]
+    fn group_indices_none_consecutive() {
+        assert_eq!(group_indices(&[0, 2, 4, 9]), [0..1, 2..3, 4..5, 9..10]);
+    }
+
+    #[test]
+    fn group_indices_largest_index() {
+        // the range end is one past the last index, so it just fits
+        assert_eq!(
+            group_indices(&[usize::MAX - 2, usize::MAX - 1]),
+            [Range {
+                start: usize::MAX - 2,
+                end: usize::MAX
+            }]
+        );
+        assert_eq!(
+            group_indices(&[0, usize::MAX - 1]),
+            [0..1, usize::MAX - 1..usize::MAX]
+        );
+    }
+
+    #[test]
+    fn context_lines_numbered() {
+        let result = MatchingResultBuilder::new("test", "test")
+            .line_number(5)
+            .before_context(&["three", "four"])
+            .after_context(&["six"])
+            .build();
+        assert_eq!(
+            result.before_context_lines(),
+            [
+                ContextLine {
+                    text: String::from("three"),
+                    line_number: Some(3)
+                },
+                ContextLine {
+                    text: String::from("four"),
+                    line_number: Some(4)
+                }
+            ]
+        );
+        assert_eq!(
+            result.after_context_lines(),
+            [ContextLine {
+                text: String::from("six"),
+                line_number: Some(6)
+            }]
+        );
+    }
+
+    #[test]
+    fn clone_equals_original() {
+        let result = MatchingResultBuilder::new("test", "a test")
+            .file_name("file")
+            .line_number(2)
+            .column_number(3)
+            .before_context(&["before"])
+            .after_context(&["after"])
+            .build();
+        let clone = result.clone();
+        assert_eq!(clone.matching_line, result.matching_line);
+        assert_eq!(clone.fuzzy_match.score(), result.fuzzy_match.score());
+        assert_eq!(
+            clone.fuzzy_match.positions(),
+            result.fuzzy_match.positions()
+        );
+        assert_eq!(clone.file_name, result.file_name);
+        assert_eq!(clone.line_number, result.line_number);
+        assert_eq!(clone.column_number, result.column_number);
+        assert_eq!(clone.context.before, result.context.before);
+        assert_eq!(clone.context.after, result.context.after);
+    }
+
+    #[test]
+    fn score_bonus() {
+        let plain = MatchingResultBuilder::new("test", "a test").build();
+        let with_bonus = MatchingResultBuilder::new("test", "a test")
+            .score_bonus(3)
+            .build();
+        assert_eq!(plain.score(), plain.fuzzy_match.score());
+        assert_eq!(with_bonus.score(), plain.fuzzy_match.score() + 3);
+        assert!(with_bonus > plain);
+        // the bonus can outweigh a better fuzzy match
+        let better = MatchingResultBuilder::new("test", "test").build();
+        let boosted = MatchingResultBuilder::new("test", "a test")
+            .score_bonus(better.score() - plain.score() + 1)
+            .build();
+        assert!(boosted > better);
+    }
+
+    #[test]
+    fn score_bonus_tie() {
+        // the total scores are equal, so the line number decides regardless of the bonus
+        let better = MatchingResultBuilder::new("test", "test")
+            .line_number(2)
+            .build();
+        let plain = MatchingResultBuilder::new("test", "a test").build();
+        let boosted = MatchingResultBuilder::new("test", "a test")
+            .score_bonus(better.score() - plain.score())
+            .line_number(1)
+            .build();
+        assert_eq!(boosted.score(), better.score());
+        assert!(boosted > better);
+        let mut results = [better, boosted];
+        results.sort_by(|a, b| b.cmp(a));
+        assert_eq!(
+            results.iter().map(|r| r.line_number).collect::<Vec<_>>(),
+            [Some(1), Some(2)]
+        );
+    }
+
+    #[test]
+    fn without_context() {
+        let result = MatchingResultBuilder::new("test", "a test")
+            .file_name("file")
+            .line_number(2)
+            .column_number(3)
+            .before_context(&["before"])
+            .after_context(&["after"])
+            .build();
+        let score = result.fuzzy_match.score();
+        let positions = result.fuzzy_match.positions().to_vec();
+        let result = result.without_context();
+        assert_eq!(result.matching_line, "a test");
+        assert_eq!(result.fuzzy_match.score(), score);
+        assert_eq!(*result.fuzzy_match.positions(), positions);
+        assert_eq!(result.file_name.as_deref(), Some("file"));
+        assert_eq!(result.line_number, Some(2));
+        assert_eq!(result.column_number, Some(3));
+        assert!(result.context.before.is_empty());
+        assert!(result.context.after.is_empty());
+    }
+
+    #[test]
+    fn with_context() {
+        let result = MatchingResultBuilder::new("test", "a test")
+            .file_name("file")
+            .line_number(2)
+            .before_context(&["before"])
+            .build()
+            .with_context(vec![String::from("one")], vec![String::from("three")]);
+        assert_eq!(result.matching_line, "a test");
+        assert_eq!(result.file_name.as_deref(), Some("file"));
+        assert_eq!(result.line_number, Some(2));
+        assert_eq!(result.column_number, None);
+        assert_eq!(result.context.before, ["one"]);
+        assert_eq!(result.context.after, ["three"]);
+    }
+
+    #[test]
+    fn context_lines_without_line_numbers() {
+        let result = MatchingResultBuilder::new("test", "test")
+            .before_context(&["before"])
+            .build();
+        assert_eq!(
+            result.before_context_lines(),
+            [ContextLine {
+                text: String::from("before"),
+                line_number: None
+            }]
+        );
+        assert!(result.after_context_lines().is_empty());
+    }
+
+    #[test]
+    fn normalized_score() {
+        let result = MatchingResultBuilder::new("test", "test").build();
+        assert_eq!(result.normalized_score(), 1.0);
+
+        let result = MatchingResultBuilder::new("tst", "a test").build();
+        assert!(result.normalized_score() > 0.0);
+        assert!(result.normalized_score() < 1.0);
+
+        // the bonus is a part of the score
+        let with_bonus = MatchingResultBuilder::new("tst", "a test")
+            .score_bonus(5)
+            .build();
+        assert!(with_bonus.normalized_score() > result.normalized_score());
+        let with_bonus = MatchingResultBuilder::new("tst", "a test")
+            .score_bonus(1000)
+            .build();
+        assert_eq!(with_bonus.normalized_score(), 1.0);
+    }
+
+    #[test]
+    fn match_ranges_ascii() {
+        let result = MatchingResultBuilder::new("tst", "test").build();
+        assert_eq!(result.match_char_ranges(), [0..1, 2..4]);
+        assert_eq!(result.match_byte_ranges(), [0..1, 2..4]);
+    }
+
+    #[test]
+    fn match_ranges_multibyte() {
+        let result = MatchingResultBuilder::new("ab", "a😀b").build();
+        assert_eq!(result.match_char_ranges(), [0..1, 2..3]);
+        assert_eq!(result.match_byte_ranges(), [0..1, 5..6]);
+
+        let result = MatchingResultBuilder::new("кст", "Текст").build();
+        assert_eq!(result.match_char_ranges(), [Range { start: 2, end: 5 }]);
+        assert_eq!(result.match_byte_ranges(), [Range { start: 4, end: 10 }]);
+    }
+
     #[test]
     fn matching_result_state_constructor_complete() {
         let matching_line = String::from("test");
         let fuzzy_match = vscode_fuzzy_score_rs::fuzzy_match("test", "test").unwrap();
         let file_name = None;
         let line_number = None;
+        let column_number = None;
         let before_context = vec![String::from("line1"), String::from("line2")];
 
         match MatchingResultState::new(
             matching_line,
             fuzzy_match,
+            0,
             file_name,
             line_number,
+            column_number,
             before_context,
-            0,
+            SaturatingAccumulator::new(0, 0),
         ) {
             MatchingResultState::Complete(result) => {
                 assert_eq!(
                     result,
-                    MatchingResult {
-                        matching_line: String::from("test"),
-                        fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test", "test").unwrap(),
-                        file_name: None,
-                        line_number: None,
-                        context: Context {
-                            before: vec![String::from("line1"), String::from("line2")],
-                            after: vec![],
-                        },
-                    }
+                    MatchingResultBuilder::new("test", "test")
+                        .before_context(&["line1", "line2"])
+                        .build()
                 )
             }
             MatchingResultState::Incomplete(_) => unreachable!(),
@@ -261,15 +1011,18 @@ mod test {
         let fuzzy_match = vscode_fuzzy_score_rs::fuzzy_match("test", "test").unwrap();
         let file_name = None;
         let line_number = None;
+        let column_number = None;
         let before_context = vec![String::from("line1"), String::from("line2")];
 
         match MatchingResultState::new(
             matching_line,
             fuzzy_match,
+            0,
             file_name,
             line_number,
+            column_number,
             before_context,
-            2,
+            SaturatingAccumulator::new(2, 0),
         ) {
             MatchingResultState::Complete(_) => unreachable!(),
             MatchingResultState::Incomplete(partial_result) => {
@@ -278,11 +1031,13 @@ mod test {
                     PartialMatchingResult {
                         matching_line: String::from("test"),
                         fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test", "test").unwrap(),
+                        score_bonus: 0,
                         file_name: None,
                         line_number: None,
+                        column_number: None,
                         partial_context: PartialContext {
                             before: vec![String::from("line1"), String::from("line2")],
-                            after_accumulator: SaturatingAccumulator::new(2)
+                            after_accumulator: SaturatingAccumulator::new(2, 0)
                         },
                     }
                 )
@@ -296,14 +1051,17 @@ mod test {
         let fuzzy_match = vscode_fuzzy_score_rs::fuzzy_match("test", "test").unwrap();
         let file_name = None;
         let line_number = None;
+        let column_number = None;
         let before_context = vec![String::from("line1"), String::from("line2")];
         match MatchingResultState::new(
             matching_line,
             fuzzy_match,
+            0,
             file_name,
             line_number,
+            column_number,
             before_context,
-            2,
+            SaturatingAccumulator::new(2, 0),
         ) {
             MatchingResultState::Incomplete(partial_result) => {
                 match partial_result.feed(String::from("line3")) {
@@ -312,25 +1070,10 @@ mod test {
                             MatchingResultState::Complete(result) => {
                                 assert_eq!(
                                     result,
-                                    MatchingResult {
-                                        matching_line: String::from("test"),
-                                        fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match(
-                                            "test", "test"
-                                        )
-                                        .unwrap(),
-                                        file_name: None,
-                                        line_number: None,
-                                        context: Context {
-                                            before: vec![
-                                                String::from("line1"),
-                                                String::from("line2")
-                                            ],
-                                            after: vec![
-                                                String::from("line3"),
-                                                String::from("line4")
-                                            ],
-                                        },
-                                    }
+                                    MatchingResultBuilder::new("test", "test")
+                                        .before_context(&["line1", "line2"])
+                                        .after_context(&["line3", "line4"])
+                                        .build()
                                 );
                             }
                             MatchingResultState::Incomplete(_) => unreachable!(),
@@ -348,11 +1091,13 @@ mod test {
         let mut partial_result = PartialMatchingResult {
             matching_line: String::from("test"),
             fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test", "test").unwrap(),
+            score_bonus: 0,
             file_name: None,
             line_number: None,
+            column_number: None,
             partial_context: PartialContext {
                 before: vec![String::from("line1"), String::from("line2")],
-                after_accumulator: SaturatingAccumulator::new(2),
+                after_accumulator: SaturatingAccumulator::new(2, 0),
             },
         };
         partial_result
@@ -362,166 +1107,292 @@ mod test {
         let result = partial_result.complete();
         assert_eq!(
             result,
-            MatchingResult {
-                matching_line: String::from("test"),
-                fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test", "test").unwrap(),
-                file_name: None,
-                line_number: None,
-                context: Context {
-                    before: vec![String::from("line1"), String::from("line2")],
-                    after: vec![String::from("line3")],
-                },
-            }
+            MatchingResultBuilder::new("test", "test")
+                .before_context(&["line1", "line2"])
+                .after_context(&["line3"])
+                .build()
         )
     }
 
     #[test]
     fn matching_result_comparisons_ne() {
-        let m1 = MatchingResult {
-            matching_line: String::from("test"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test", "test").unwrap(),
-            file_name: None,
-            line_number: Some(42),
-            context: Context {
-                before: vec![String::from("before")],
-                after: vec![String::from("after")],
-            },
-        };
-        let m2 = MatchingResult {
-            matching_line: String::from("test"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("tes", "test").unwrap(),
-            file_name: None,
-            line_number: Some(42),
-            context: Context {
-                before: vec![String::from("before")],
-                after: vec![String::from("after")],
-            },
-        };
+        let m1 = MatchingResultBuilder::new("test", "test")
+            .line_number(42)
+            .before_context(&["before"])
+            .after_context(&["after"])
+            .build();
+        let m2 = MatchingResultBuilder::new("tes", "test")
+            .line_number(42)
+            .before_context(&["before"])
+            .after_context(&["after"])
+            .build();
         assert_ne!(m1, m2);
     }
 
     #[test]
     fn matching_result_comparisons_eq() {
-        let m1 = MatchingResult {
-            matching_line: String::from("test1"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test1", "test1").unwrap(),
-            file_name: Some(String::from("test.txt")),
-            line_number: None,
-            context: Context {
-                before: vec![String::from("before1")],
-                after: vec![String::from("after1")],
-            },
-        };
-        let m2 = MatchingResult {
-            matching_line: String::from("test2"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test2", "test2").unwrap(),
-            file_name: None,
-            line_number: Some(42),
-            context: Context {
-                before: vec![String::from("before2")],
-                after: vec![String::from("after2")],
-            },
-        };
+        let m1 = MatchingResultBuilder::new("test1", "test1")
+            .file_name("test.txt")
+            .line_number(42)
+            .before_context(&["before1"])
+            .after_context(&["after1"])
+            .build();
+        let m2 = MatchingResultBuilder::new("test2", "test2")
+            .file_name("test.txt")
+            .line_number(42)
+            .before_context(&["before2"])
+            .after_context(&["after2"])
+            .build();
         assert_eq!(m1, m2);
     }
 
+    #[test]
+    fn matching_result_builder_defaults() {
+        let result = MatchingResultBuilder::new("tst", "test").build();
+        assert_eq!(result.matching_line, "test");
+        assert_eq!(
+            result.fuzzy_match,
+            vscode_fuzzy_score_rs::fuzzy_match("tst", "test").unwrap()
+        );
+        assert_eq!(result.score_bonus, 0);
+        assert_eq!(result.file_name, None);
+        assert_eq!(result.line_number, None);
+        assert_eq!(result.column_number, None);
+        assert!(result.context.before.is_empty());
+        assert!(result.context.after.is_empty());
+    }
+
+    #[test]
+    fn matching_result_builder_all_fields() {
+        let result = MatchingResultBuilder::new("tst", "test")
+            .fuzzy_match(vscode_fuzzy_score_rs::fuzzy_match("test", "test").unwrap())
+            .score_bonus(5)
+            .file_name("file.txt")
+            .line_number(7)
+            .column_number(3)
+            .before_context(&["line1", "line2"])
+            .after_context(&["line4"])
+            .build();
+        assert_eq!(
+            result.fuzzy_match,
+            vscode_fuzzy_score_rs::fuzzy_match("test", "test").unwrap()
+        );
+        assert_eq!(result.score_bonus, 5);
+        assert_eq!(result.file_name, Some(String::from("file.txt")));
+        assert_eq!(result.line_number, Some(7));
+        assert_eq!(result.column_number, Some(3));
+        assert_eq!(result.context.before, ["line1", "line2"]);
+        assert_eq!(result.context.after, ["line4"]);
+    }
+
+    #[test]
+    fn matching_result_display() {
+        let result = MatchingResultBuilder::new("tst", "test").build();
+        assert_eq!(result.to_string(), "test");
+
+        let result = MatchingResultBuilder::new("tst", "test")
+            .file_name("file.txt")
+            .build();
+        assert_eq!(result.to_string(), "file.txt:test");
+
+        let result = MatchingResultBuilder::new("tst", "test")
+            .line_number(7)
+            .build();
+        assert_eq!(result.to_string(), "7:test");
+
+        let result = MatchingResultBuilder::new("tst", "test")
+            .file_name("file.txt")
+            .line_number(7)
+            .build();
+        assert_eq!(result.to_string(), "file.txt:7:test");
+    }
+
+    #[test]
+    fn matching_result_display_omits_column_and_context() {
+        let result = MatchingResultBuilder::new("tst", "test")
+            .file_name("file.txt")
+            .line_number(7)
+            .column_number(3)
+            .before_context(&["before"])
+            .after_context(&["after"])
+            .build();
+        assert_eq!(result.to_string(), "file.txt:7:test");
+    }
+
+    #[test]
+    fn context_display_empty() {
+        assert_eq!(context(&[], &[]).to_string(), "");
+    }
+
+    #[test]
+    fn context_display_before_only() {
+        assert_eq!(context(&["1", "2"], &[]).to_string(), "1\n2");
+    }
+
+    #[test]
+    fn context_display_after_only() {
+        assert_eq!(context(&[], &["4", "5"]).to_string(), "4\n5");
+    }
+
+    #[test]
+    fn context_display_before_and_after() {
+        assert_eq!(context(&["1", "2"], &["4"]).to_string(), "1\n2\n4");
+    }
+
+    #[test]
+    fn matching_result_comparisons_equal_scores_line_numbers() {
+        let m1 = MatchingResultBuilder::new("test", "test")
+            .file_name("b.txt")
+            .line_number(1)
+            .build();
+        let m2 = MatchingResultBuilder::new("test", "test")
+            .file_name("a.txt")
+            .line_number(2)
+            .build();
+        assert_ne!(m1, m2);
+        assert!(m1 > m2);
+    }
+
+    #[test]
+    fn matching_result_comparisons_equal_scores_file_names() {
+        let m1 = MatchingResultBuilder::new("test", "test")
+            .file_name("a.txt")
+            .build();
+        let m2 = MatchingResultBuilder::new("test", "test")
+            .file_name("b.txt")
+            .build();
+        assert_ne!(m1, m2);
+        assert!(m1 > m2);
+
+        let m1 = MatchingResultBuilder::new("test", "test")
+            .file_name("a.txt")
+            .line_number(5)
+            .build();
+        let m2 = MatchingResultBuilder::new("test", "test")
+            .file_name("b.txt")
+            .line_number(5)
+            .build();
+        assert!(m1 > m2);
+    }
+
+    #[test]
+    fn matching_result_sort_equal_scores() {
+        let mut results = [
+            MatchingResultBuilder::new("test", "test")
+                .file_name("b.txt")
+                .line_number(3)
+                .build(),
+            MatchingResultBuilder::new("test", "test")
+                .file_name("b.txt")
+                .line_number(1)
+                .build(),
+            MatchingResultBuilder::new("test", "test")
+                .file_name("a.txt")
+                .line_number(3)
+                .build(),
+            MatchingResultBuilder::new("test", "test")
+                .file_name("a.txt")
+                .line_number(2)
+                .build(),
+        ];
+        results.sort_by(|a, b| b.cmp(a));
+        let locations = results
+            .iter()
+            .map(|r| (r.file_name.as_deref().unwrap(), r.line_number.unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locations,
+            [("b.txt", 1), ("a.txt", 2), ("a.txt", 3), ("b.txt", 3)]
+        );
+    }
+
     #[test]
     fn matching_result_comparisons_lt() {
-        let m1 = MatchingResult {
-            matching_line: String::from("test1"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test", "test1").unwrap(),
-            file_name: Some(String::from("test.txt")),
-            line_number: None,
-            context: Context {
-                before: vec![String::from("before1")],
-                after: vec![String::from("after1")],
-            },
-        };
-        let m2 = MatchingResult {
-            matching_line: String::from("test2"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test2", "test2").unwrap(),
-            file_name: None,
-            line_number: Some(42),
-            context: Context {
-                before: vec![String::from("before2")],
-                after: vec![String::from("after2")],
-            },
-        };
+        let m1 = MatchingResultBuilder::new("test", "test1")
+            .file_name("test.txt")
+            .before_context(&["before1"])
+            .after_context(&["after1"])
+            .build();
+        let m2 = MatchingResultBuilder::new("test2", "test2")
+            .line_number(42)
+            .before_context(&["before2"])
+            .after_context(&["after2"])
+            .build();
         assert!(m1 < m2);
     }
 
     #[test]
     fn matching_result_comparisons_gt() {
-        let m1 = MatchingResult {
-            matching_line: String::from("test1"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test1", "test1").unwrap(),
-            file_name: Some(String::from("test1.txt")),
-            line_number: Some(41),
-            context: Context {
-                before: vec![String::from("before1")],
-                after: vec![String::from("after1")],
-            },
-        };
-        let m2 = MatchingResult {
-            matching_line: String::from("test2"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test", "test2").unwrap(),
-            file_name: Some(String::from("test2.txt")),
-            line_number: Some(42),
-            context: Context {
-                before: vec![String::from("before2")],
-                after: vec![String::from("after2")],
-            },
-        };
+        let m1 = MatchingResultBuilder::new("test1", "test1")
+            .file_name("test1.txt")
+            .line_number(41)
+            .before_context(&["before1"])
+            .after_context(&["after1"])
+            .build();
+        let m2 = MatchingResultBuilder::new("test", "test2")
+            .file_name("test2.txt")
+            .line_number(42)
+            .before_context(&["before2"])
+            .after_context(&["after2"])
+            .build();
         assert!(m1 > m2);
     }
 
+    fn context(before: &[&str], after: &[&str]) -> Context {
+        Context {
+            before: before.iter().map(|s| s.to_string()).collect(),
+            after: after.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn context_trim_overlap_disjoint() {
+        let mut first = context(&["1"], &["3", "4"]);
+        let mut second = context(&["6", "7"], &["9"]);
+        first.trim_overlap(&mut second, 4);
+        assert_eq!(first.after, ["3", "4"]);
+        assert_eq!(second.before, ["6", "7"]);
+    }
+
+    #[test]
+    fn context_trim_overlap_partial() {
+        let mut first = context(&["1"], &["3", "4"]);
+        let mut second = context(&["4", "5"], &["7"]);
+        first.trim_overlap(&mut second, 3);
+        assert_eq!(first.after, ["3", "4"]);
+        assert_eq!(second.before, ["5"]);
+    }
+
+    #[test]
+    fn context_trim_overlap_past_next_match() {
+        let mut first = context(&["1"], &["3", "4", "5"]);
+        let mut second = context(&["2", "3"], &["5", "6", "7"]);
+        first.trim_overlap(&mut second, 1);
+        assert_eq!(first.after, ["3"]);
+        assert!(second.before.is_empty());
+        assert_eq!(second.after, ["5", "6", "7"]);
+    }
+
+    #[test]
+    fn context_trim_overlap_adjacent() {
+        let mut first = context(&["1"], &["3"]);
+        let mut second = context(&["2"], &["4"]);
+        first.trim_overlap(&mut second, 0);
+        assert!(first.after.is_empty());
+        assert!(second.before.is_empty());
+    }
+
     #[test]
     fn matching_result_comparisons_le() {
-        let m1 = MatchingResult {
-            matching_line: String::from("test1"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test1", "test1").unwrap(),
-            file_name: None,
-            line_number: None,
-            context: Context {
-                before: vec![],
-                after: vec![],
-            },
-        };
-        let m2 = MatchingResult {
-            matching_line: String::from("test2"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test2", "test2").unwrap(),
-            file_name: None,
-            line_number: None,
-            context: Context {
-                before: vec![],
-                after: vec![],
-            },
-        };
+        let m1 = MatchingResultBuilder::new("test1", "test1").build();
+        let m2 = MatchingResultBuilder::new("test2", "test2").build();
         assert!(m1 <= m2);
     }
 
     #[test]
     fn matching_result_comparisons_ge() {
-        let m1 = MatchingResult {
-            matching_line: String::from("test1"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test1", "test1").unwrap(),
-            file_name: None,
-            line_number: None,
-            context: Context {
-                before: vec![],
-                after: vec![],
-            },
-        };
-        let m2 = MatchingResult {
-            matching_line: String::from("test2"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test2", "test2").unwrap(),
-            file_name: None,
-            line_number: None,
-            context: Context {
-                before: vec![],
-                after: vec![],
-            },
-        };
+        let m1 = MatchingResultBuilder::new("test1", "test1").build();
+        let m2 = MatchingResultBuilder::new("test2", "test2").build();
         assert!(m1 >= m2);
     }
 }
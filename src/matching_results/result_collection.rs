@@ -1,11 +1,26 @@
 use crate::{matching_results::top_bracket::TopBracket, MatchingResult};
+#[cfg(feature = "channel")]
+use std::sync::mpsc::SyncSender;
 
 /// A trait that generalizes interface between possible results containers
 /// As it currently stands, only one method is required to be provided -
 /// the one that adds an item into the container.
 ///
-pub(crate) trait ResultCollection {
+/// The trait is kept object safe, so that the container can be chosen at runtime (as `&mut dyn ResultCollection`,
+/// see [`crate::collect_matches_into_dyn`]).
+///
+pub trait ResultCollection {
+    /// Adds `result` into the container.
+    ///
     fn push(&mut self, result: MatchingResult);
+
+    /// Checks whether the collection does not need any more results, i.e. the search can be stopped.
+    /// A [`TopBracket`] never stops the search, even when it is full: a better match found later can still
+    /// replace one of the results it holds.
+    ///
+    fn stops_search(&self) -> bool {
+        false
+    }
 }
 
 impl ResultCollection for Vec<MatchingResult> {
@@ -20,10 +35,63 @@ impl ResultCollection for TopBracket<MatchingResult> {
     }
 }
 
+/// A collection that keeps only the first `capacity` results pushed into it (ignoring the rest).
+///
+pub(crate) struct FirstN {
+    capacity: usize,
+    data: Vec<MatchingResult>,
+}
+
+impl FirstN {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            data: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub(crate) fn into_vec(self) -> Vec<MatchingResult> {
+        self.data
+    }
+}
+
+impl ResultCollection for FirstN {
+    fn push(&mut self, result: MatchingResult) {
+        if !self.stops_search() {
+            self.data.push(result);
+        }
+    }
+
+    fn stops_search(&self) -> bool {
+        self.data.len() >= self.capacity
+    }
+}
+
+/// A "collection" that does not store anything but hands every result over to the wrapped callback.
+///
+pub(crate) struct Callback<F: FnMut(&MatchingResult)>(pub(crate) F);
+
+impl<F: FnMut(&MatchingResult)> ResultCollection for Callback<F> {
+    fn push(&mut self, result: MatchingResult) {
+        (self.0)(&result);
+    }
+}
+
+/// Sends every result to the receiving end of the channel as soon as it is found.
+/// Once the receiver is gone, the results are simply dropped.
+///
+#[cfg(feature = "channel")]
+impl ResultCollection for SyncSender<MatchingResult> {
+    fn push(&mut self, result: MatchingResult) {
+        // the receiver hanging up only means that nobody is interested in the rest of the results
+        let _ = self.send(result);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::matching_results::result::Context;
+    use crate::matching_results::result::MatchingResultBuilder;
 
     fn do_push<T: ResultCollection>(tested: &mut T, item: MatchingResult) -> &T {
         tested.push(item);
@@ -32,26 +100,8 @@ mod tests {
 
     #[test]
     fn push_vec() {
-        let mut v = vec![MatchingResult {
-            matching_line: String::from("test_vec"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test_vec", "test_vec").unwrap(),
-            file_name: None,
-            line_number: None,
-            context: Context {
-                before: Vec::new(),
-                after: Vec::new(),
-            },
-        }];
-        let item = MatchingResult {
-            matching_line: String::from("test"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test", "test").unwrap(),
-            file_name: None,
-            line_number: None,
-            context: Context {
-                before: Vec::new(),
-                after: Vec::new(),
-            },
-        };
+        let mut v = vec![MatchingResultBuilder::new("test_vec", "test_vec").build()];
+        let item = MatchingResultBuilder::new("test", "test").build();
         let expected = {
             let mut v = v.clone();
             v.push(item.clone());
@@ -64,27 +114,8 @@ mod tests {
     #[test]
     fn push_top_bracket() {
         let mut tb = TopBracket::new(1);
-        tb.push(MatchingResult {
-            matching_line: String::from("test_top_bracket"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test_top_bracket", "test_top_bracket")
-                .unwrap(),
-            file_name: None,
-            line_number: None,
-            context: Context {
-                before: Vec::new(),
-                after: Vec::new(),
-            },
-        });
-        let item = MatchingResult {
-            matching_line: String::from("test"),
-            fuzzy_match: vscode_fuzzy_score_rs::fuzzy_match("test", "test").unwrap(),
-            file_name: None,
-            line_number: None,
-            context: Context {
-                before: Vec::new(),
-                after: Vec::new(),
-            },
-        };
+        tb.push(MatchingResultBuilder::new("test_top_bracket", "test_top_bracket").build());
+        let item = MatchingResultBuilder::new("test", "test").build();
         let expected = {
             let mut tb = tb.clone();
             tb.push(item.clone());
@@ -93,4 +124,69 @@ mod tests {
 
         assert_eq!(*do_push(&mut tb, item.clone()), expected);
     }
+
+    #[test]
+    fn push_dyn() {
+        let mut all = Vec::new();
+        let mut top = TopBracket::new(1);
+        for keep_all in [true, false] {
+            let collection: &mut dyn ResultCollection = if keep_all { &mut all } else { &mut top };
+            for s in ["one", "three"] {
+                collection.push(MatchingResultBuilder::new(s, s).build());
+            }
+            assert!(!collection.stops_search());
+        }
+        assert_eq!(all.len(), 2);
+        assert_eq!(top.into_sorted_vec().len(), 1);
+    }
+
+    #[test]
+    fn push_first_n() {
+        let mut first = FirstN::new(2);
+        assert!(!first.stops_search());
+        let items = ["one", "two", "three"].map(|s| MatchingResultBuilder::new(s, s).build());
+        for item in items.clone() {
+            first.push(item);
+        }
+        assert!(first.stops_search());
+        assert_eq!(first.into_vec(), items[..2]);
+    }
+
+    #[test]
+    fn first_n_zero() {
+        assert!(FirstN::new(0).stops_search());
+    }
+
+    #[test]
+    fn push_callback() {
+        let mut seen = Vec::new();
+        let mut callback = Callback(|result: &MatchingResult| seen.push(result.clone()));
+        let item = MatchingResultBuilder::new("test", "test").build();
+        do_push(&mut callback, item.clone());
+        assert_eq!(seen, [item]);
+    }
+
+    #[cfg(feature = "channel")]
+    #[test]
+    fn push_sync_sender() {
+        let (mut sender, receiver) = std::sync::mpsc::sync_channel(2);
+        let items = ["one", "two"].map(|s| MatchingResultBuilder::new(s, s).build());
+        for item in items.clone() {
+            ResultCollection::push(&mut sender, item);
+        }
+        assert!(!sender.stops_search());
+        drop(sender);
+        assert_eq!(receiver.iter().collect::<Vec<_>>(), items);
+    }
+
+    #[cfg(feature = "channel")]
+    #[test]
+    fn push_sync_sender_disconnected() {
+        let (mut sender, receiver) = std::sync::mpsc::sync_channel(1);
+        drop(receiver);
+        ResultCollection::push(
+            &mut sender,
+            MatchingResultBuilder::new("test", "test").build(),
+        );
+    }
 }
@@ -1,41 +1,168 @@
+use crate::MatchingResult;
+use std::mem;
+
+/// A container that keeps only a fixed number of the greatest items pushed into it,
+/// sorted from the greatest to the least.
+///
+/// Can be used for streaming top-N collection of matching results.
+///
+/// # Examples
+///
+/// ```
+/// use fzgrep::TopBracket;
+///
+/// let mut top = TopBracket::new(2);
+/// top.extend([3, 1, 4, 1, 5]);
+/// assert!(top.is_full());
+/// assert_eq!(top.into_sorted_vec(), [5, 4]);
+/// ```
+///
 #[derive(Clone, Debug, PartialEq)]
-pub(crate) struct TopBracket<T> {
+pub struct TopBracket<T> {
     capacity: usize,
     data: Vec<T>,
 }
 
 impl<T> TopBracket<T> {
-    pub(crate) fn new(capacity: usize) -> Self {
+    /// Creates an empty [`TopBracket`] that holds at most `capacity` items.
+    ///
+    pub fn new(capacity: usize) -> Self {
         Self {
             capacity,
             data: Vec::with_capacity(capacity),
         }
     }
 
-    pub(crate) fn into_vec(self) -> Vec<T> {
+    /// Returns the number of items currently held.
+    ///
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns whether no items are held.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns whether the container holds as many items as its capacity allows,
+    /// i.e. any further item has to beat the weakest one to get in.
+    ///
+    pub fn is_full(&self) -> bool {
+        self.data.len() == self.capacity
+    }
+
+    /// Turns the container into a [`Vec`] of the held items, sorted from the greatest to the least.
+    ///
+    pub fn into_sorted_vec(self) -> Vec<T> {
         self.data
     }
+
+    /// Removes all the held items, yielding them from the greatest to the least.
+    /// The container stays usable afterwards (with the same capacity).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fzgrep::TopBracket;
+    ///
+    /// let mut top = TopBracket::new(3);
+    /// top.extend([1, 2]);
+    /// assert_eq!(top.drain_sorted().collect::<Vec<_>>(), [2, 1]);
+    /// assert!(top.is_empty());
+    /// ```
+    ///
+    pub fn drain_sorted(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.data.drain(..)
+    }
 }
 
 impl<T: Ord> TopBracket<T> {
-    pub(crate) fn push(&mut self, item: T) -> bool {
-        if self.data.len() == self.capacity {
-            if item <= *self.data.last().unwrap() {
-                return false;
+    /// Adds `item` to the container if there is still room for it or if it is greater than the weakest item held
+    /// (which is then dropped). Returns whether `item` has been added.
+    ///
+    pub fn push(&mut self, item: T) -> bool {
+        if self.is_full() {
+            match self.data.last() {
+                Some(weakest) if item > *weakest => {
+                    self.data.pop();
+                }
+                // either the item is not good enough or the capacity is zero
+                _ => return false,
             }
-
-            self.data.pop();
         }
 
         self.data.push(item);
         self.data.sort_by(|a, b| b.cmp(a));
         true
     }
+
+    /// Moves the items of `other` into this container, keeping only the greatest ones that fit into its capacity.
+    /// Useful for reducing the brackets collected independently (e.g. by several threads) into a single one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fzgrep::TopBracket;
+    ///
+    /// let mut top = TopBracket::new(3);
+    /// top.extend([1, 5, 3]);
+    /// let mut other = TopBracket::new(3);
+    /// other.extend([4, 2]);
+    /// top.absorb(other);
+    /// assert_eq!(top.into_sorted_vec(), [5, 4, 3]);
+    /// ```
+    ///
+    pub fn absorb(&mut self, other: TopBracket<T>) {
+        self.extend(other.data);
+    }
+
+    /// Same as [`TopBracket::absorb`] but consumes both containers, returning the merged one
+    /// (with the capacity of `self`). The smaller container is drained into the larger one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fzgrep::TopBracket;
+    ///
+    /// let mut top = TopBracket::new(2);
+    /// top.push(1);
+    /// let mut other = TopBracket::new(2);
+    /// other.extend([3, 2]);
+    /// assert_eq!(top.merge(other).into_sorted_vec(), [3, 2]);
+    /// ```
+    ///
+    pub fn merge(mut self, mut other: TopBracket<T>) -> TopBracket<T> {
+        if other.len() > self.len() {
+            mem::swap(&mut self.data, &mut other.data);
+            // `other` may have had a greater capacity, the items are sorted so the greatest ones are kept
+            self.data.truncate(self.capacity);
+        }
+        self.absorb(other);
+        self
+    }
+}
+
+impl TopBracket<MatchingResult> {
+    /// Returns the score of the weakest result currently held, [`None`] if the container is empty.
+    ///
+    pub fn min_score(&self) -> Option<i32> {
+        self.data.last().map(MatchingResult::score)
+    }
+}
+
+impl<T: Ord> Extend<T> for TopBracket<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::matching_results::result::MatchingResultBuilder;
 
     #[test]
     fn constructor() {
@@ -84,15 +211,15 @@ mod tests {
     }
 
     #[test]
-    fn into_vec() {
+    fn into_sorted_vec() {
         let capacity = 4;
         let container = TopBracket::<i32>::new(capacity);
-        assert_eq!(container.into_vec(), []);
+        assert_eq!(container.into_sorted_vec(), Vec::<i32>::new());
 
         let mut container = TopBracket::new(capacity);
         container.push(1);
         container.push(2);
-        assert_eq!(container.into_vec(), [2, 1]);
+        assert_eq!(container.into_sorted_vec(), [2, 1]);
 
         let mut container = TopBracket::new(capacity);
         container.push(1);
@@ -100,6 +227,105 @@ mod tests {
         container.push(0);
         container.push(1);
         container.push(2);
-        assert_eq!(container.into_vec(), [2, 2, 1, 1]);
+        assert_eq!(container.into_sorted_vec(), [2, 2, 1, 1]);
+    }
+
+    #[test]
+    fn len_and_is_full() {
+        let mut container = TopBracket::new(2);
+        assert_eq!(container.len(), 0);
+        assert!(container.is_empty());
+        assert!(!container.is_full());
+        container.push(1);
+        assert_eq!(container.len(), 1);
+        assert!(!container.is_empty());
+        assert!(!container.is_full());
+        container.push(2);
+        container.push(3);
+        assert_eq!(container.len(), 2);
+        assert!(container.is_full());
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut container = TopBracket::new(0);
+        assert!(container.is_full());
+        assert!(!container.push(1));
+        assert!(container.is_empty());
+    }
+
+    #[test]
+    fn drain_sorted() {
+        let mut container = TopBracket::new(3);
+        container.extend([2, 3, 1, 4]);
+        assert_eq!(container.drain_sorted().collect::<Vec<_>>(), [4, 3, 2]);
+        assert!(container.is_empty());
+        container.push(5);
+        assert_eq!(container.into_sorted_vec(), [5]);
+    }
+
+    #[test]
+    fn extend() {
+        let mut container = TopBracket::new(3);
+        container.extend([1, 5, 2]);
+        container.extend(vec![4, 0]);
+        assert_eq!(container.into_sorted_vec(), [5, 4, 2]);
+    }
+
+    #[test]
+    fn absorb() {
+        let mut container = TopBracket::new(3);
+        container.extend([1, 6]);
+        let mut other = TopBracket::new(3);
+        other.extend([5, 0, 2]);
+        container.absorb(other);
+        assert_eq!(container.into_sorted_vec(), [6, 5, 2]);
+
+        let mut container = TopBracket::<i32>::new(2);
+        container.absorb(TopBracket::new(2));
+        assert!(container.is_empty());
+    }
+
+    #[test]
+    fn merge() {
+        let mut smaller = TopBracket::new(3);
+        smaller.push(4);
+        let mut larger = TopBracket::new(3);
+        larger.extend([1, 5, 3]);
+        assert_eq!(
+            smaller.clone().merge(larger.clone()).into_sorted_vec(),
+            [5, 4, 3]
+        );
+        assert_eq!(larger.merge(smaller).into_sorted_vec(), [5, 4, 3]);
+    }
+
+    #[test]
+    fn merge_different_capacities() {
+        let mut container = TopBracket::new(2);
+        container.push(3);
+        let mut other = TopBracket::new(4);
+        other.extend([1, 2, 4, 5]);
+        let merged = container.merge(other);
+        assert_eq!(merged.capacity, 2);
+        assert_eq!(merged.into_sorted_vec(), [5, 4]);
+
+        let mut container = TopBracket::new(4);
+        container.extend([1, 2]);
+        let mut other = TopBracket::new(1);
+        other.push(3);
+        assert_eq!(container.merge(other).into_sorted_vec(), [3, 2, 1]);
+    }
+
+    #[test]
+    fn min_score() {
+        let mut container = TopBracket::new(2);
+        assert_eq!(container.min_score(), None);
+        let best = MatchingResultBuilder::new("test", "test").build();
+        let worst = MatchingResultBuilder::new("tst", "test").build();
+        let (best_score, worst_score) = (best.fuzzy_match.score(), worst.fuzzy_match.score());
+        container.push(best);
+        assert_eq!(container.min_score(), Some(best_score));
+        container.push(worst);
+        assert_eq!(container.min_score(), Some(worst_score));
     }
 }
\ No newline at end of file
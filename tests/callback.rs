@@ -0,0 +1,99 @@
+use fzgrep::{CallbackResult, RequestBuilder, Targets};
+use std::path::PathBuf;
+
+#[test]
+fn discovery_order() {
+    let request = RequestBuilder::new(
+        "contigous",
+        Targets::Files(vec![
+            PathBuf::from("resources/tests/test.txt"),
+            PathBuf::from("resources/tests/тест.txt"),
+        ]),
+    )
+    .file_names(true)
+    .line_numbers(true)
+    .build()
+    .unwrap();
+
+    let mut found = Vec::new();
+    let summary = fzgrep::collect_matches_with_callback(&request, |m| {
+        found.push((m.file_name.clone().unwrap(), m.line_number.unwrap()))
+    })
+    .unwrap();
+    assert_eq!(
+        summary,
+        CallbackResult {
+            total_matches: 4,
+            files_searched: 2,
+        }
+    );
+    assert_eq!(
+        found,
+        [
+            (String::from("resources/tests/test.txt"), 2),
+            (String::from("resources/tests/test.txt"), 3),
+            (String::from("resources/tests/тест.txt"), 5),
+            (String::from("resources/tests/тест.txt"), 6),
+        ]
+    );
+}
+
+#[test]
+fn trailing_context() {
+    let request = RequestBuilder::new(
+        "contigous",
+        Targets::Files(vec![PathBuf::from("resources/tests/test.txt")]),
+    )
+    .after_context(2)
+    .build()
+    .unwrap();
+
+    let mut found = Vec::new();
+    fzgrep::collect_matches_with_callback(&request, |m| found.push(m.clone())).unwrap();
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].matching_line, "contiguous");
+    assert_eq!(found[0].context.after, ["Contiguous", "Текст"]);
+    assert_eq!(found[1].matching_line, "Contiguous");
+    assert_eq!(found[1].context.after, ["Текст", "тестування"]);
+}
+
+#[test]
+fn context_chars() {
+    let request = RequestBuilder::new(
+        "contigous",
+        Targets::Files(vec![PathBuf::from("resources/tests/test.txt")]),
+    )
+    .context_chars(6)
+    .build()
+    .unwrap();
+
+    let mut found = Vec::new();
+    fzgrep::collect_matches_with_callback(&request, |m| found.push(m.clone())).unwrap();
+    assert_eq!(found.len(), 2);
+    // the file starts before the leading context can reach 6 characters
+    assert_eq!(found[0].context.before, ["打电动"]);
+    assert_eq!(found[0].context.after, ["Contiguous"]);
+    assert_eq!(found[1].context.before, ["contiguous"]);
+    assert_eq!(found[1].context.after, ["Текст", "тестування"]);
+}
+
+#[test]
+fn no_matches() {
+    let request = RequestBuilder::new(
+        "nothing",
+        Targets::Files(vec![PathBuf::from("resources/tests/test.txt")]),
+    )
+    .build()
+    .unwrap();
+
+    let mut calls = 0;
+    let summary = fzgrep::collect_matches_with_callback(&request, |_| calls += 1).unwrap();
+    assert_eq!(calls, 0);
+    assert_eq!(
+        summary,
+        CallbackResult {
+            total_matches: 0,
+            files_searched: 1,
+        }
+    );
+}
@@ -0,0 +1,12 @@
+use fzgrep::cli::args;
+
+/// Runs the search `cmd` describes and returns the matching lines (the best ones first).
+///
+pub fn matching_lines(cmd: &[&str]) -> Vec<String> {
+    let request = args::make_request(cmd.iter().copied().map(String::from));
+    fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+        .unwrap()
+        .into_iter()
+        .map(|m| m.matching_line)
+        .collect()
+}
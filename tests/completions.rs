@@ -0,0 +1,34 @@
+use std::{process::Command, str};
+
+#[test]
+fn bash_completions() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["--generate-completions", "bash"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(str::from_utf8(&output.stdout)
+        .unwrap()
+        .starts_with("_fzgrep()"));
+}
+
+#[test]
+fn all_shells_completions() {
+    for shell in ["bash", "zsh", "fish", "powershell", "elvish"] {
+        let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+            .args(["--generate-completions", shell])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert!(!output.stdout.is_empty());
+    }
+}
+
+#[test]
+fn completions_are_exclusive() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["--generate-completions", "bash", "query"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
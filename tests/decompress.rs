@@ -0,0 +1,102 @@
+mod common;
+
+use common::matching_lines;
+use fzgrep::cli::args;
+
+#[test]
+fn plain_file() {
+    assert_eq!(
+        matching_lines(&["fzgrep", "-z", "contigous", "resources/tests/test.txt"]),
+        ["contiguous", "Contiguous"]
+    );
+}
+
+#[test]
+fn compressed_file_without_flag() {
+    let cmd = ["fzgrep", "contigous", "resources/compressed/test.txt.gz"];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    // the compressed content is not valid UTF-8
+    assert!(
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .is_err()
+    );
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn gzip() {
+    assert_eq!(
+        matching_lines(&[
+            "fzgrep",
+            "--decompress",
+            "contigous",
+            "resources/compressed/test.txt.gz"
+        ]),
+        ["contiguous", "Contiguous"]
+    );
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn gzip_detected_by_content() {
+    assert_eq!(
+        matching_lines(&[
+            "fzgrep",
+            "--decompress",
+            "contigous",
+            "resources/compressed/gzip_without_extension"
+        ]),
+        ["contiguous", "Contiguous"]
+    );
+}
+
+#[cfg(not(feature = "gzip"))]
+#[test]
+fn gzip_not_supported() {
+    let cmd = [
+        "fzgrep",
+        "--decompress",
+        "contigous",
+        "resources/compressed/test.txt.gz",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    assert!(
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .is_err()
+    );
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd() {
+    assert_eq!(
+        matching_lines(&[
+            "fzgrep",
+            "--decompress",
+            "contigous",
+            "resources/compressed/test.txt.zst"
+        ]),
+        ["contiguous", "Contiguous"]
+    );
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd_detected_by_content() {
+    assert_eq!(
+        matching_lines(&[
+            "fzgrep",
+            "--decompress",
+            "contigous",
+            "resources/compressed/zstd_without_extension"
+        ]),
+        ["contiguous", "Contiguous"]
+    );
+}
+
+#[cfg(all(feature = "gzip", feature = "zstd"))]
+#[test]
+fn recursive() {
+    let cmd = ["fzgrep", "-z", "-r", "contigous", "resources/compressed"];
+    assert_eq!(matching_lines(&cmd).len(), 8);
+}
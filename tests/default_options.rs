@@ -0,0 +1,90 @@
+use fzgrep::{cli::args, MatchCollectionStrategy};
+use std::{io::Write, path::Path, process::Command, str};
+use tempfile::NamedTempFile;
+
+fn make_request(cmd: &[&str], default_options: &str) -> fzgrep::Request {
+    args::make_request_with(
+        cmd.iter().copied().map(String::from),
+        None,
+        Some(default_options),
+    )
+}
+
+#[test]
+fn default_options() {
+    let request = make_request(&["fzgrep", "query", "file"], "--line-number --top 5");
+    assert!(request.match_options.track_line_numbers);
+    assert_eq!(request.strategy, MatchCollectionStrategy::CollectTop(5));
+
+    let request = make_request(&["fzgrep", "query", "file"], "-vv --include *.rs");
+    assert_eq!(request.log_verbosity, log::LevelFilter::Info);
+    assert!(request.match_options.filter.is_allowed(Path::new("lib.rs")));
+    assert!(!request.match_options.filter.is_allowed(Path::new("lib.c")));
+}
+
+#[test]
+fn default_options_overridden() {
+    let request = make_request(&["fzgrep", "--top", "10", "query", "file"], "--top 5");
+    assert_eq!(request.strategy, MatchCollectionStrategy::CollectTop(10));
+
+    let request = make_request(
+        &["fzgrep", "--no-filename", "query", "file1", "file2"],
+        "--with-filename",
+    );
+    assert!(!request.match_options.track_file_names);
+
+    let request = make_request(&["fzgrep", "--verbose", "query", "file"], "--quiet");
+    assert_ne!(request.output_behavior, fzgrep::OutputBehavior::Quiet);
+}
+
+#[test]
+fn default_options_over_config() {
+    let mut config = NamedTempFile::new().unwrap();
+    writeln!(config, "top = 5\nline-number = true").unwrap();
+    let request = args::make_request_with(
+        ["fzgrep", "query", "file"].into_iter().map(String::from),
+        Some(config.path()),
+        Some("--top 7"),
+    );
+    assert_eq!(request.strategy, MatchCollectionStrategy::CollectTop(7));
+    assert!(request.match_options.track_line_numbers);
+}
+
+#[test]
+fn default_options_invalid() {
+    // unrecognized options make the whole value ignored
+    let request = make_request(
+        &["fzgrep", "query", "file"],
+        "--line-number --no-such-option",
+    );
+    assert!(!request.match_options.track_line_numbers);
+
+    // so does a (self-)reference or any other positional argument
+    let request = make_request(
+        &["fzgrep", "query", "file"],
+        "--line-number $FZGREP_DEFAULT_OPTIONS",
+    );
+    assert!(!request.match_options.track_line_numbers);
+    assert_eq!(request.query, "query");
+}
+
+#[test]
+fn default_options_not_read_by_make_request() {
+    let request = args::make_request(["fzgrep", "query", "file"].into_iter().map(String::from));
+    assert!(!request.match_options.track_line_numbers);
+    assert_eq!(request.strategy, MatchCollectionStrategy::CollectAll);
+}
+
+#[test]
+fn default_options_env_var() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .env(args::DEFAULT_OPTIONS_VAR, "--line-number --top 5")
+        .args(["--no-config", "--top", "7", "--dump-config"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let dump = str::from_utf8(&output.stdout).unwrap();
+    let lines = dump.lines().collect::<Vec<_>>();
+    assert!(lines.contains(&"line-number = true"));
+    assert!(lines.contains(&"top = 7"));
+}
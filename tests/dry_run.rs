@@ -0,0 +1,84 @@
+use fzgrep::{RequestBuilder, Targets};
+use std::{io::Write, path::PathBuf, process::Command, str};
+
+#[test]
+fn dry_run_recursive() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["--dry-run", "-r", "no such query", "resources/tests/nested"])
+        .output()
+        .unwrap();
+    // listing the files is a success even though nothing is matched
+    assert!(output.status.success());
+    assert_eq!(
+        str::from_utf8(&output.stdout).unwrap(),
+        "resources/tests/nested/more_nested/test.txt\n\
+        resources/tests/nested/test.txt\n\
+        resources/tests/nested/test2.txt\n"
+    );
+}
+
+#[test]
+fn dry_run_relative_paths() {
+    let request = RequestBuilder::new(
+        "query",
+        Targets::RecursiveEntries(vec![PathBuf::from("resources/tests/nested")]),
+    )
+    .dry_run(true)
+    .relative_paths_root(PathBuf::from("resources/tests").canonicalize().unwrap())
+    .build()
+    .unwrap();
+    let mut output = Vec::new();
+    let results = fzgrep::run(&request, &mut output).unwrap();
+    assert!(results.is_empty());
+    assert_eq!(
+        str::from_utf8(&output).unwrap(),
+        "nested/more_nested/test.txt\nnested/test.txt\nnested/test2.txt\n"
+    );
+}
+
+#[test]
+fn dry_run_missing_file() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["--dry-run", "query", "resources/tests/no_such_file.txt"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn list_files_line_counts() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args([
+            "--list-files",
+            "-r",
+            "--exclude",
+            "test2.txt",
+            "no such query",
+            "resources/tests/nested",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        str::from_utf8(&output.stdout).unwrap(),
+        "resources/tests/nested/more_nested/test.txt (1 line)\n\
+        resources/tests/nested/test.txt (1 line)\n"
+    );
+}
+
+#[test]
+fn list_files_unterminated_last_line() {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    write!(file, "one\ntwo\nthree").unwrap();
+    let request = RequestBuilder::new("query", Targets::Files(vec![file.path().to_path_buf()]))
+        .dry_run(true)
+        .count_lines(true)
+        .build()
+        .unwrap();
+    let mut output = Vec::new();
+    fzgrep::run(&request, &mut output).unwrap();
+    assert_eq!(
+        str::from_utf8(&output).unwrap(),
+        format!("{} (3 lines)\n", file.path().display())
+    );
+}
@@ -0,0 +1,52 @@
+use std::{io::Write, process::Command, str};
+use tempfile::NamedTempFile;
+
+#[test]
+fn dump_config() {
+    let mut config = NamedTempFile::new().unwrap();
+    writeln!(config, "top = 4\ncolor-theme = \"light\"").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .env_remove("FZGREP_DEFAULT_OPTIONS")
+        .args(["--config", config.path().to_str().unwrap()])
+        .args(["--line-number", "--top", "7", "--dump-config"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let dump = str::from_utf8(&output.stdout).unwrap();
+    let lines = dump.lines().collect::<Vec<_>>();
+    // the command line overrides the config file, which overrides the defaults
+    assert!(lines.contains(&"line-number = true"));
+    assert!(lines.contains(&"top = 7"));
+    assert!(lines.contains(&"color-theme = \"light\""));
+    assert!(lines.contains(&"color = \"auto\""));
+
+    // the dump is a valid config file itself
+    let mut dumped = NamedTempFile::new().unwrap();
+    write!(dumped, "{dump}").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .env_remove("FZGREP_DEFAULT_OPTIONS")
+        .args(["--config", dumped.path().to_str().unwrap(), "--dump-config"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(str::from_utf8(&output.stdout).unwrap(), dump);
+}
+
+#[test]
+fn dump_default_config() {
+    let config_home = tempfile::tempdir().unwrap();
+    std::fs::create_dir(config_home.path().join("fzgrep")).unwrap();
+    std::fs::write(config_home.path().join("fzgrep/config.toml"), "top = 4\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .env_remove("FZGREP_DEFAULT_OPTIONS")
+        .env("XDG_CONFIG_HOME", config_home.path())
+        .arg("--dump-config")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    // the binary reads the config file at the default location
+    assert!(str::from_utf8(&output.stdout)
+        .unwrap()
+        .lines()
+        .any(|line| line == "top = 4"));
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use common::matching_lines;
+use fzgrep::cli::args;
+
+#[test]
+fn latin1_without_encoding() {
+    let cmd = ["fzgrep", "café", "resources/encodings/latin1.txt"];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    // the accented characters are not valid UTF-8
+    assert!(
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .is_err()
+    );
+}
+
+#[test]
+fn latin1() {
+    assert_eq!(
+        matching_lines(&[
+            "fzgrep",
+            "--encoding",
+            "latin1",
+            "café",
+            "resources/encodings/latin1.txt"
+        ]),
+        ["café au lait"]
+    );
+    assert_eq!(
+        matching_lines(&[
+            "fzgrep",
+            "--encoding",
+            "ISO-8859-1",
+            "mañ",
+            "resources/encodings/latin1.txt"
+        ]),
+        ["mañana"]
+    );
+}
+
+#[test]
+fn shift_jis() {
+    assert_eq!(
+        matching_lines(&[
+            "fzgrep",
+            "--encoding",
+            "shift_jis",
+            "キスト",
+            "resources/encodings/shift_jis.txt"
+        ]),
+        ["テキスト"]
+    );
+}
+
+#[test]
+fn utf8_explicit() {
+    assert_eq!(
+        matching_lines(&[
+            "fzgrep",
+            "--encoding",
+            "utf-8",
+            "contigous",
+            "resources/tests/test.txt"
+        ]),
+        ["contiguous", "Contiguous"]
+    );
+}
+
+fn lossy_matches(query: &str, file: &str) -> Vec<(String, Vec<usize>)> {
+    let cmd = ["fzgrep", "--lossy-encoding", query, file];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+        .unwrap()
+        .into_iter()
+        .map(|m| (m.matching_line, m.fuzzy_match.positions().clone()))
+        .collect()
+}
+
+/// Checks that every position points at the character of `line` matching the next character of `query`.
+///
+fn positions_match(query: &str, line: &str, positions: &[usize]) -> bool {
+    let line = line.chars().collect::<Vec<_>>();
+    positions.len() == query.chars().count()
+        && positions
+            .iter()
+            .zip(query.chars())
+            .all(|(&position, c)| line[position].eq_ignore_ascii_case(&c))
+}
+
+#[test]
+fn latin1_lossy() {
+    let matches = lossy_matches("aulait", "resources/encodings/latin1.txt");
+    assert_eq!(matches.len(), 1);
+    let (line, positions) = &matches[0];
+    assert_eq!(line, "caf\u{FFFD} au lait");
+    assert!(positions_match("aulait", line, positions));
+
+    let matches = lossy_matches("mana", "resources/encodings/latin1.txt");
+    assert_eq!(matches.len(), 1);
+    let (line, positions) = &matches[0];
+    assert_eq!(line, "ma\u{FFFD}ana");
+    // the replacement is a single character, so the positions after it are not shifted
+    assert!(positions_match("mana", line, positions));
+}
+
+#[test]
+fn cp1252_lossy() {
+    let matches = lossy_matches("quoted", "resources/encodings/cp1252.txt");
+    assert_eq!(matches.len(), 1);
+    let (line, positions) = &matches[0];
+    assert_eq!(line, "\u{FFFD}quoted\u{FFFD} text");
+    assert!(positions_match("quoted", line, positions));
+
+    let matches = lossy_matches("emdash", "resources/encodings/cp1252.txt");
+    assert_eq!(matches.len(), 1);
+    let (line, positions) = &matches[0];
+    assert_eq!(line, "em\u{FFFD}dash");
+    assert!(positions_match("emdash", line, positions));
+}
+
+#[test]
+fn cp1252() {
+    assert_eq!(
+        matching_lines(&[
+            "fzgrep",
+            "--encoding",
+            "windows-1252",
+            "quoted",
+            "resources/encodings/cp1252.txt"
+        ]),
+        ["\u{201C}quoted\u{201D} text"]
+    );
+    assert_eq!(
+        matching_lines(&[
+            "fzgrep",
+            "--encoding",
+            "windows-1252",
+            "price",
+            "resources/encodings/cp1252.txt"
+        ]),
+        ["price: 5\u{20AC}"]
+    );
+}
@@ -0,0 +1,31 @@
+use fzgrep::{cli::args, MatchCollectionStrategy, RequestBuilder};
+use std::{env, fs};
+
+// The environment is shared by all the tests of a binary, hence the only test here.
+#[test]
+fn from_args_reads_default_options_and_config() {
+    let config_home = tempfile::tempdir().unwrap();
+    fs::create_dir(config_home.path().join("fzgrep")).unwrap();
+    fs::write(
+        config_home.path().join("fzgrep/config.toml"),
+        "line-number = true\ntop = 5\n",
+    )
+    .unwrap();
+    env::set_var("XDG_CONFIG_HOME", config_home.path());
+    env::set_var(args::DEFAULT_OPTIONS_VAR, "--top 7");
+
+    let cmd = ["fzgrep", "query", "file"].map(String::from);
+    let request = RequestBuilder::from_args(cmd).unwrap();
+    // the config file is read...
+    assert!(request.match_options.track_line_numbers);
+    // ...and the default options take precedence over it
+    assert_eq!(request.strategy, MatchCollectionStrategy::CollectTop(7));
+
+    // the same as the binary would make out of the same arguments
+    let cmd = ["fzgrep", "--top", "3", "query", "file"].map(String::from);
+    let config = args::default_config_path();
+    assert_eq!(
+        RequestBuilder::from_args(cmd.clone()).unwrap(),
+        args::make_request_with(cmd.into_iter(), config.as_deref(), Some("--top 7"))
+    );
+}
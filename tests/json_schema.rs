@@ -0,0 +1,58 @@
+use std::process::Command;
+
+#[cfg(feature = "json-schema")]
+#[test]
+fn print_json_schema() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .arg("--print-json-schema")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(schema["$schema"], "http://json-schema.org/draft-07/schema#");
+    assert_eq!(schema["title"], "MatchingResult");
+    let mut required = schema["required"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| field.as_str().unwrap())
+        .collect::<Vec<_>>();
+    required.sort_unstable();
+    assert_eq!(required, ["context", "fuzzy_match", "matching_line"]);
+    for field in [
+        "matching_line",
+        "fuzzy_match",
+        "score_bonus",
+        "file_name",
+        "line_number",
+        "column_number",
+        "context",
+    ] {
+        assert!(
+            schema["properties"][field]["description"].is_string(),
+            "{field}"
+        );
+    }
+    for field in ["score", "positions"] {
+        assert!(schema["definitions"]["FuzzyMatch"]["properties"][field].is_object());
+    }
+    for field in ["before", "after"] {
+        assert!(schema["definitions"]["Context"]["properties"][field].is_object());
+    }
+    #[cfg(feature = "context-line-numbers")]
+    for field in ["text", "line_number"] {
+        assert!(schema["definitions"]["ContextLine"]["properties"][field].is_object());
+    }
+}
+
+#[cfg(not(feature = "json-schema"))]
+#[test]
+fn print_json_schema_not_enabled() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .arg("--print-json-schema")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
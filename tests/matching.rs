@@ -1,5 +1,6 @@
-use fzgrep::{cli::args, Targets};
-use std::path::PathBuf;
+use fzgrep::{cli::args, MatchingResult, Targets};
+use std::{io::Write, path::PathBuf};
+use tempfile::NamedTempFile;
 
 #[test]
 fn ascii_query() {
@@ -34,9 +35,9 @@ fn ascii_query() {
 
     assert_eq!(
         results[0].file_name.as_ref().unwrap(),
-        "resources/tests/👨‍🔬.txt"
+        "resources/tests/test.txt"
     );
-    assert_eq!(results[0].line_number.unwrap(), 6);
+    assert_eq!(results[0].line_number.unwrap(), 2);
     assert_eq!(results[0].matching_line, String::from("contiguous"));
     assert_eq!(results[0].fuzzy_match.score(), 116);
     assert_eq!(
@@ -46,9 +47,9 @@ fn ascii_query() {
 
     assert_eq!(
         results[1].file_name.as_ref().unwrap(),
-        "resources/tests/name with spaces.txt"
+        "resources/tests/测试.txt"
     );
-    assert_eq!(results[1].line_number.unwrap(), 5);
+    assert_eq!(results[1].line_number.unwrap(), 3);
     assert_eq!(results[1].matching_line, String::from("contiguous"));
     assert_eq!(results[1].fuzzy_match.score(), 116);
     assert_eq!(
@@ -58,9 +59,9 @@ fn ascii_query() {
 
     assert_eq!(
         results[2].file_name.as_ref().unwrap(),
-        "resources/tests/test.txt"
+        "resources/tests/name with spaces.txt"
     );
-    assert_eq!(results[2].line_number.unwrap(), 2);
+    assert_eq!(results[2].line_number.unwrap(), 5);
     assert_eq!(results[2].matching_line, String::from("contiguous"));
     assert_eq!(results[2].fuzzy_match.score(), 116);
     assert_eq!(
@@ -82,9 +83,9 @@ fn ascii_query() {
 
     assert_eq!(
         results[4].file_name.as_ref().unwrap(),
-        "resources/tests/测试.txt"
+        "resources/tests/👨‍🔬.txt"
     );
-    assert_eq!(results[4].line_number.unwrap(), 3);
+    assert_eq!(results[4].line_number.unwrap(), 6);
     assert_eq!(results[4].matching_line, String::from("contiguous"));
     assert_eq!(results[4].fuzzy_match.score(), 116);
     assert_eq!(
@@ -94,7 +95,7 @@ fn ascii_query() {
 
     assert_eq!(
         results[5].file_name.as_ref().unwrap(),
-        "resources/tests/👨‍🔬.txt"
+        "resources/tests/测试.txt"
     );
     assert_eq!(results[5].line_number.unwrap(), 2);
     assert_eq!(results[5].matching_line, String::from("Contiguous"));
@@ -106,9 +107,9 @@ fn ascii_query() {
 
     assert_eq!(
         results[6].file_name.as_ref().unwrap(),
-        "resources/tests/name with spaces.txt"
+        "resources/tests/👨‍🔬.txt"
     );
-    assert_eq!(results[6].line_number.unwrap(), 3);
+    assert_eq!(results[6].line_number.unwrap(), 2);
     assert_eq!(results[6].matching_line, String::from("Contiguous"));
     assert_eq!(results[6].fuzzy_match.score(), 115);
     assert_eq!(
@@ -118,7 +119,7 @@ fn ascii_query() {
 
     assert_eq!(
         results[7].file_name.as_ref().unwrap(),
-        "resources/tests/test.txt"
+        "resources/tests/name with spaces.txt"
     );
     assert_eq!(results[7].line_number.unwrap(), 3);
     assert_eq!(results[7].matching_line, String::from("Contiguous"));
@@ -130,9 +131,9 @@ fn ascii_query() {
 
     assert_eq!(
         results[8].file_name.as_ref().unwrap(),
-        "resources/tests/тест.txt"
+        "resources/tests/test.txt"
     );
-    assert_eq!(results[8].line_number.unwrap(), 6);
+    assert_eq!(results[8].line_number.unwrap(), 3);
     assert_eq!(results[8].matching_line, String::from("Contiguous"));
     assert_eq!(results[8].fuzzy_match.score(), 115);
     assert_eq!(
@@ -142,9 +143,9 @@ fn ascii_query() {
 
     assert_eq!(
         results[9].file_name.as_ref().unwrap(),
-        "resources/tests/测试.txt"
+        "resources/tests/тест.txt"
     );
-    assert_eq!(results[9].line_number.unwrap(), 2);
+    assert_eq!(results[9].line_number.unwrap(), 6);
     assert_eq!(results[9].matching_line, String::from("Contiguous"));
     assert_eq!(results[9].fuzzy_match.score(), 115);
     assert_eq!(
@@ -195,36 +196,36 @@ fn emoji_query() {
 
     assert_eq!(
         results[1].file_name.as_ref().unwrap(),
-        "resources/tests/test.txt"
+        "resources/tests/тест.txt"
     );
-    assert_eq!(results[1].line_number.unwrap(), 6);
+    assert_eq!(results[1].line_number.unwrap(), 1);
     assert_eq!(results[1].matching_line, String::from("🐲🐣🐼🦀🦞🦠"));
     assert_eq!(results[1].fuzzy_match.score(), 4);
     assert_eq!(results[1].fuzzy_match.positions(), &vec![1, 3]);
 
     assert_eq!(
         results[2].file_name.as_ref().unwrap(),
-        "resources/tests/👨‍🔬.txt"
+        "resources/tests/测试.txt"
     );
-    assert_eq!(results[2].line_number.unwrap(), 5);
+    assert_eq!(results[2].line_number.unwrap(), 4);
     assert_eq!(results[2].matching_line, String::from("🐲🐣🐼🦀🦞🦠"));
     assert_eq!(results[2].fuzzy_match.score(), 4);
     assert_eq!(results[2].fuzzy_match.positions(), &vec![1, 3]);
 
     assert_eq!(
         results[3].file_name.as_ref().unwrap(),
-        "resources/tests/тест.txt"
+        "resources/tests/👨‍🔬.txt"
     );
-    assert_eq!(results[3].line_number.unwrap(), 1);
+    assert_eq!(results[3].line_number.unwrap(), 5);
     assert_eq!(results[3].matching_line, String::from("🐲🐣🐼🦀🦞🦠"));
     assert_eq!(results[3].fuzzy_match.score(), 4);
     assert_eq!(results[3].fuzzy_match.positions(), &vec![1, 3]);
 
     assert_eq!(
         results[4].file_name.as_ref().unwrap(),
-        "resources/tests/测试.txt"
+        "resources/tests/test.txt"
     );
-    assert_eq!(results[4].line_number.unwrap(), 4);
+    assert_eq!(results[4].line_number.unwrap(), 6);
     assert_eq!(results[4].matching_line, String::from("🐲🐣🐼🦀🦞🦠"));
     assert_eq!(results[4].fuzzy_match.score(), 4);
     assert_eq!(results[4].fuzzy_match.positions(), &vec![1, 3]);
@@ -272,16 +273,16 @@ fn cyrillic_query() {
 
     assert_eq!(
         results[1].file_name.as_ref().unwrap(),
-        "resources/tests/test.txt"
+        "resources/tests/тест.txt"
     );
-    assert_eq!(results[1].line_number.unwrap(), 5);
+    assert_eq!(results[1].line_number.unwrap(), 4);
     assert_eq!(results[1].matching_line, String::from("тестування"));
     assert_eq!(results[1].fuzzy_match.score(), 46);
     assert_eq!(results[1].fuzzy_match.positions(), &vec![0, 1, 2, 3]);
 
     assert_eq!(
         results[2].file_name.as_ref().unwrap(),
-        "resources/tests/тест.txt"
+        "resources/tests/👨‍🔬.txt"
     );
     assert_eq!(results[2].line_number.unwrap(), 4);
     assert_eq!(results[2].matching_line, String::from("тестування"));
@@ -290,9 +291,9 @@ fn cyrillic_query() {
 
     assert_eq!(
         results[3].file_name.as_ref().unwrap(),
-        "resources/tests/👨‍🔬.txt"
+        "resources/tests/test.txt"
     );
-    assert_eq!(results[3].line_number.unwrap(), 4);
+    assert_eq!(results[3].line_number.unwrap(), 5);
     assert_eq!(results[3].matching_line, String::from("тестування"));
     assert_eq!(results[3].fuzzy_match.score(), 46);
     assert_eq!(results[3].fuzzy_match.positions(), &vec![0, 1, 2, 3]);
@@ -308,36 +309,36 @@ fn cyrillic_query() {
 
     assert_eq!(
         results[5].file_name.as_ref().unwrap(),
-        "resources/tests/name with spaces.txt"
+        "resources/tests/👨‍🔬.txt"
     );
-    assert_eq!(results[5].line_number.unwrap(), 4);
+    assert_eq!(results[5].line_number.unwrap(), 1);
     assert_eq!(results[5].matching_line, String::from("Текст"));
     assert_eq!(results[5].fuzzy_match.score(), 25);
     assert_eq!(results[5].fuzzy_match.positions(), &vec![0, 1, 3, 4]);
 
     assert_eq!(
         results[6].file_name.as_ref().unwrap(),
-        "resources/tests/test.txt"
+        "resources/tests/тест.txt"
     );
-    assert_eq!(results[6].line_number.unwrap(), 4);
+    assert_eq!(results[6].line_number.unwrap(), 2);
     assert_eq!(results[6].matching_line, String::from("Текст"));
     assert_eq!(results[6].fuzzy_match.score(), 25);
     assert_eq!(results[6].fuzzy_match.positions(), &vec![0, 1, 3, 4]);
 
     assert_eq!(
         results[7].file_name.as_ref().unwrap(),
-        "resources/tests/тест.txt"
+        "resources/tests/name with spaces.txt"
     );
-    assert_eq!(results[7].line_number.unwrap(), 2);
+    assert_eq!(results[7].line_number.unwrap(), 4);
     assert_eq!(results[7].matching_line, String::from("Текст"));
     assert_eq!(results[7].fuzzy_match.score(), 25);
     assert_eq!(results[7].fuzzy_match.positions(), &vec![0, 1, 3, 4]);
 
     assert_eq!(
         results[8].file_name.as_ref().unwrap(),
-        "resources/tests/👨‍🔬.txt"
+        "resources/tests/test.txt"
     );
-    assert_eq!(results[8].line_number.unwrap(), 1);
+    assert_eq!(results[8].line_number.unwrap(), 4);
     assert_eq!(results[8].matching_line, String::from("Текст"));
     assert_eq!(results[8].fuzzy_match.score(), 25);
     assert_eq!(results[8].fuzzy_match.positions(), &vec![0, 1, 3, 4]);
@@ -385,16 +386,16 @@ fn chinese_query() {
 
     assert_eq!(
         results[0].file_name.as_ref().unwrap(),
-        "resources/tests/name with spaces.txt"
+        "resources/tests/test.txt"
     );
-    assert_eq!(results[0].line_number.unwrap(), 6);
+    assert_eq!(results[0].line_number.unwrap(), 1);
     assert_eq!(results[0].matching_line, String::from("打电动"));
     assert_eq!(results[0].fuzzy_match.score(), 17);
     assert_eq!(results[0].fuzzy_match.positions(), &vec![0, 1]);
 
     assert_eq!(
         results[1].file_name.as_ref().unwrap(),
-        "resources/tests/test.txt"
+        "resources/tests/测试.txt"
     );
     assert_eq!(results[1].line_number.unwrap(), 1);
     assert_eq!(results[1].matching_line, String::from("打电动"));
@@ -412,19 +413,666 @@ fn chinese_query() {
 
     assert_eq!(
         results[3].file_name.as_ref().unwrap(),
-        "resources/tests/测试.txt"
+        "resources/tests/👨‍🔬.txt"
     );
-    assert_eq!(results[3].line_number.unwrap(), 1);
+    assert_eq!(results[3].line_number.unwrap(), 3);
     assert_eq!(results[3].matching_line, String::from("打电动"));
     assert_eq!(results[3].fuzzy_match.score(), 17);
     assert_eq!(results[3].fuzzy_match.positions(), &vec![0, 1]);
 
     assert_eq!(
         results[4].file_name.as_ref().unwrap(),
-        "resources/tests/👨‍🔬.txt"
+        "resources/tests/name with spaces.txt"
     );
-    assert_eq!(results[4].line_number.unwrap(), 3);
+    assert_eq!(results[4].line_number.unwrap(), 6);
     assert_eq!(results[4].matching_line, String::from("打电动"));
     assert_eq!(results[4].fuzzy_match.score(), 17);
     assert_eq!(results[4].fuzzy_match.positions(), &vec![0, 1]);
 }
+
+fn make_words_file() -> NamedTempFile {
+    let mut tmp = NamedTempFile::new().unwrap();
+    writeln!(
+        tmp,
+        "fooBar\nfoo_bar\nfoo bar\nbar\nbarBaz\nbar_baz\nbar-baz"
+    )
+    .unwrap();
+    tmp
+}
+
+#[test]
+fn no_word_boundary_check() {
+    let tmp = make_words_file();
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "bar",
+        tmp.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    assert_eq!(results.len(), 7);
+}
+
+#[test]
+fn word_boundary_check() {
+    let tmp = make_words_file();
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "--word-regexp",
+        "bar",
+        tmp.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    let mut line_numbers = results
+        .iter()
+        .map(|r| r.line_number.unwrap())
+        .collect::<Vec<_>>();
+    line_numbers.sort();
+    // camelCase and underscore-separated words are single words
+    assert_eq!(line_numbers, vec![3, 4, 7]);
+}
+
+#[test]
+fn word_boundary_check_custom_word_chars() {
+    let tmp = make_words_file();
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "--word-regexp",
+        "--word-chars",
+        "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ-",
+        "bar",
+        tmp.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    let mut line_numbers = results
+        .iter()
+        .map(|r| r.line_number.unwrap())
+        .collect::<Vec<_>>();
+    line_numbers.sort();
+    // now dash-separated words are single words, and underscores separate them
+    assert_eq!(line_numbers, vec![2, 3, 4, 6]);
+}
+
+#[test]
+fn whole_line() {
+    let tmp = make_words_file();
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "--whole-line",
+        "bar",
+        tmp.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].line_number.unwrap(), 4);
+
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "-x",
+        "foobar",
+        tmp.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    // the separators in "foo_bar" and "foo bar" are left unmatched
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].matching_line, "fooBar");
+}
+
+#[test]
+fn acronym() {
+    let tmp = make_words_file();
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "--acronym",
+        "bb",
+        tmp.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    let mut line_numbers = results
+        .iter()
+        .map(|r| r.line_number.unwrap())
+        .collect::<Vec<_>>();
+    line_numbers.sort();
+    // "bar" has just one word to match the query against
+    assert_eq!(line_numbers, vec![5, 6, 7]);
+    assert_eq!(results[0].matching_line, "barBaz");
+    assert_eq!(results[0].fuzzy_match.positions(), &vec![0, 3]);
+}
+
+#[test]
+fn fixed_string() {
+    let mut tmp = NamedTempFile::new().unwrap();
+    write!(tmp, "contiguous\ncontinuous\nCONTINUE\n").unwrap();
+    let run = |options: &[&str], query: &str| {
+        let cmd = ["fzgrep", "--line-number", "--fixed-string"]
+            .into_iter()
+            .chain(options.iter().copied())
+            .chain([query, tmp.path().to_str().unwrap()]);
+        let request = args::make_request(cmd.map(String::from));
+        let mut results =
+            fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+                .unwrap()
+                .into_iter()
+                .map(|r| (r.line_number.unwrap(), r.fuzzy_match.positions().clone()))
+                .collect::<Vec<_>>();
+        results.sort();
+        results
+    };
+    assert_eq!(
+        run(&[], "contin"),
+        [(2, vec![0, 1, 2, 3, 4, 5]), (3, vec![0, 1, 2, 3, 4, 5])]
+    );
+    assert_eq!(
+        run(&["--smart-case"], "CONTIN"),
+        [(3, vec![0, 1, 2, 3, 4, 5])]
+    );
+}
+
+#[test]
+fn path_aware() {
+    let mut tmp = NamedTempFile::new().unwrap();
+    write!(tmp, "src/core/lib.rs\nlib/src.rs\ns/rc/lib.rs\n").unwrap();
+    let run = |path_aware: bool| {
+        let mut cmd = vec!["fzgrep", "--line-number"];
+        if path_aware {
+            cmd.push("--path-aware");
+        }
+        cmd.extend(["src/lib", tmp.path().to_str().unwrap()]);
+        let request = args::make_request(cmd.into_iter().map(String::from));
+        let mut line_numbers =
+            fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+                .unwrap()
+                .iter()
+                .map(|r| r.line_number.unwrap())
+                .collect::<Vec<_>>();
+        line_numbers.sort();
+        line_numbers
+    };
+    assert_eq!(run(false), vec![1, 3]);
+    assert_eq!(run(true), vec![1]);
+}
+
+#[test]
+fn smart_case_lowercase_query() {
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "--smart-case",
+        "contig",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    let mut line_numbers = results
+        .iter()
+        .map(|r| r.line_number.unwrap())
+        .collect::<Vec<_>>();
+    line_numbers.sort();
+    assert_eq!(line_numbers, vec![2, 3]);
+}
+
+#[test]
+fn smart_case_uppercase_query() {
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "--smart-case",
+        "Contig",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].line_number.unwrap(), 3);
+    assert_eq!(results[0].matching_line, String::from("Contiguous"));
+}
+
+#[test]
+fn smart_case_exact_case_after_different_case() {
+    let mut tmp = NamedTempFile::new().unwrap();
+    writeln!(tmp, "foo Foo\nfoo FOO").unwrap();
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "--smart-case",
+        "Foo",
+        tmp.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    // the matcher alone prefers the first `foo`, which differs in case
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].line_number.unwrap(), 1);
+    assert_eq!(results[0].fuzzy_match.positions(), &vec![4, 5, 6]);
+}
+
+#[test]
+fn smart_case_uppercase_query_cyrillic() {
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "--smart-case",
+        "Тек",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].line_number.unwrap(), 4);
+}
+
+fn make_invalid_utf8_file() -> NamedTempFile {
+    let mut tmp = NamedTempFile::new().unwrap();
+    tmp.write_all(b"contiguous\nconti\xffguous\n").unwrap();
+    tmp
+}
+
+#[test]
+fn invalid_utf8_strict() {
+    let tmp = make_invalid_utf8_file();
+    let cmd = ["fzgrep", "contigous", tmp.path().to_str().unwrap()];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    assert!(
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .is_err()
+    );
+}
+
+#[test]
+fn invalid_utf8_lossy() {
+    let tmp = make_invalid_utf8_file();
+    let cmd = [
+        "fzgrep",
+        "--lossy-encoding",
+        "--line-number",
+        "contigous",
+        tmp.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut output = Vec::new();
+    let results = fzgrep::run(&request, &mut output).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[1].matching_line, "conti\u{FFFD}guous");
+    assert_eq!(results[1].line_number.unwrap(), 2);
+    assert!(String::from_utf8(output).unwrap().contains('\u{FFFD}'));
+}
+
+#[test]
+fn null_data() {
+    let mut tmp = NamedTempFile::new().unwrap();
+    write!(tmp, "not this one\0a test\nspanning lines\0test\0").unwrap();
+    let cmd = [
+        "fzgrep",
+        "--null-data",
+        "--line-number",
+        "test",
+        tmp.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.line_number.unwrap(), m.matching_line))
+            .collect::<Vec<_>>();
+    results.sort();
+    assert_eq!(
+        results,
+        [
+            (2, String::from("a test\nspanning lines")),
+            (3, String::from("test"))
+        ]
+    );
+}
+
+#[test]
+fn column_number() {
+    let cmd = [
+        "fzgrep",
+        "--column-number",
+        "tig",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].matching_line, "contiguous");
+    assert_eq!(results[0].column_number, Some(4));
+    // `--column-number` implies `--line-number`
+    assert_eq!(results[0].line_number, Some(2));
+    assert_eq!(results[1].matching_line, "Contiguous");
+    assert_eq!(results[1].column_number, Some(4));
+    assert_eq!(results[1].line_number, Some(3));
+}
+
+#[test]
+fn column_number_multibyte() {
+    let cmd = [
+        "fzgrep",
+        "--column-number",
+        "ування",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    assert_eq!(results.len(), 1);
+    // the column is counted in characters, not bytes
+    assert_eq!(results[0].column_number, Some(5));
+}
+
+fn make_long_line_file() -> NamedTempFile {
+    let mut tmp = NamedTempFile::new().unwrap();
+    writeln!(tmp, "short foo").unwrap();
+    writeln!(tmp, "foo{}", "x".repeat(1024 * 1024)).unwrap();
+    writeln!(tmp, "Текст foo").unwrap();
+    tmp
+}
+
+#[test]
+fn long_lines_skipped() {
+    let tmp = make_long_line_file();
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "--max-line-length",
+        "64",
+        "foo",
+        tmp.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].matching_line, "short foo");
+    assert_eq!(results[0].line_number, Some(1));
+    assert_eq!(results[1].matching_line, "Текст foo");
+    assert_eq!(results[1].line_number, Some(3));
+}
+
+#[test]
+fn long_lines_truncated() {
+    let tmp = make_long_line_file();
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "--max-line-length",
+        "7",
+        "--truncate-long-lines",
+        "o",
+        tmp.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    // the multibyte line is cut at a character boundary ("Тек" is 6 bytes long) and does not match anymore
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].matching_line, "short f…");
+    assert_eq!(results[0].line_number, Some(1));
+    assert_eq!(results[1].matching_line, "fooxxxx…");
+    assert_eq!(results[1].line_number, Some(2));
+}
+
+fn make_mixed_normalization_file() -> NamedTempFile {
+    let mut tmp = NamedTempFile::new().unwrap();
+    // decomposed (NFD) and precomposed (NFC) 'é' respectively
+    writeln!(tmp, "cafe\u{301} au lait\ncaf\u{e9} noir").unwrap();
+    tmp
+}
+
+#[test]
+fn unicode_normalization_off() {
+    let tmp = make_mixed_normalization_file();
+    for (query, expected) in [
+        ("caf\u{e9}", "caf\u{e9} noir"),
+        ("cafe\u{301}", "cafe\u{301} au lait"),
+    ] {
+        let cmd = ["fzgrep", query, tmp.path().to_str().unwrap()];
+        let request = args::make_request(cmd.into_iter().map(String::from));
+        let results =
+            fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+                .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].matching_line, expected);
+    }
+}
+
+#[test]
+fn unicode_normalization_on() {
+    let tmp = make_mixed_normalization_file();
+    for query in ["caf\u{e9}", "cafe\u{301}"] {
+        let cmd = [
+            "fzgrep",
+            "--normalize-unicode",
+            query,
+            tmp.path().to_str().unwrap(),
+        ];
+        let request = args::make_request(cmd.into_iter().map(String::from));
+        let results =
+            fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+                .unwrap();
+        // the lines are reported as they are in the file, with the match positions consistent with them
+        let mut lines = results
+            .iter()
+            .map(|r| {
+                let line = r.matching_line.as_str();
+                let matched = r
+                    .match_byte_ranges()
+                    .into_iter()
+                    .map(|range| &line[range])
+                    .collect::<String>();
+                (line, matched)
+            })
+            .collect::<Vec<_>>();
+        lines.sort();
+        assert_eq!(
+            lines,
+            [
+                ("cafe\u{301} au lait", String::from("cafe")),
+                ("caf\u{e9} noir", String::from("caf\u{e9}")),
+            ]
+        );
+    }
+}
+
+fn make_accented_file() -> NamedTempFile {
+    let mut tmp = NamedTempFile::new().unwrap();
+    writeln!(
+        tmp,
+        "crème brûlée\nStraßenbahn Müller\nel niño\ncafe\u{301} au lait\ncafe noir"
+    )
+    .unwrap();
+    tmp
+}
+
+fn ignoring_diacritics(query: &str, file: &NamedTempFile) -> Vec<MatchingResult> {
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "--ignore-diacritics",
+        query,
+        file.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options).unwrap()
+}
+
+#[test]
+fn ignore_diacritics() {
+    let tmp = make_accented_file();
+
+    let results = ignoring_diacritics("brulee", &tmp);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].matching_line, "crème brûlée");
+    // the positions refer to the characters of the original line
+    assert_eq!(
+        results[0].fuzzy_match.positions(),
+        &vec![6, 7, 8, 9, 10, 11]
+    );
+
+    let results = ignoring_diacritics("muller", &tmp);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].line_number, Some(2));
+
+    let results = ignoring_diacritics("nino", &tmp);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].line_number, Some(3));
+}
+
+#[test]
+fn ignore_diacritics_accented_query() {
+    let tmp = make_accented_file();
+    let mut line_numbers = ignoring_diacritics("café", &tmp)
+        .iter()
+        .map(|r| r.line_number.unwrap())
+        .collect::<Vec<_>>();
+    line_numbers.sort();
+    // both the decomposed and the plain spelling match
+    assert_eq!(line_numbers, vec![4, 5]);
+}
+
+#[test]
+fn diacritics_respected_by_default() {
+    let tmp = make_accented_file();
+    let cmd = ["fzgrep", "brulee", tmp.path().to_str().unwrap()];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    assert!(results.is_empty());
+}
+
+#[test]
+fn byte_range() {
+    // "contiguous" starts at the byte 13, "Contiguous" at the byte 24
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "--byte-range",
+        "20-30",
+        "contigous",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].matching_line, "Contiguous");
+    assert_eq!(results[0].line_number.unwrap(), 3);
+}
+
+#[test]
+fn acronym_bonus() {
+    let mut tmp = NamedTempFile::new().unwrap();
+    writeln!(tmp, "xfoobar\nfoo_bar").unwrap();
+    let cmd = [
+        "fzgrep",
+        "--line-number",
+        "--acronym-bonus",
+        "100",
+        "fb",
+        tmp.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    assert_eq!(results.len(), 2);
+    let result = |line: &str| {
+        results
+            .iter()
+            .find(|result| result.matching_line == line)
+            .unwrap()
+    };
+    // neither matched character starts a word in `xfoobar`, both do in `foo_bar`
+    assert_eq!(result("xfoobar").score_bonus, 0);
+    assert_eq!(result("foo_bar").score_bonus, 200);
+    assert_eq!(
+        result("foo_bar").score(),
+        result("foo_bar").fuzzy_match.score() + 200
+    );
+    assert!(result("foo_bar") > result("xfoobar"));
+}
+
+#[test]
+fn scoring_config() {
+    let mut tmp = NamedTempFile::new().unwrap();
+    writeln!(tmp, "foo_bar").unwrap();
+    let cmd = [
+        "fzgrep",
+        "--scoring-config",
+        r#"{"after_separator": 100}"#,
+        "fb",
+        tmp.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    assert_eq!(results.len(), 1);
+    // `b` follows a separator, which is worth 96 more points than by default
+    assert_eq!(results[0].score_bonus, 96);
+    assert_eq!(results[0].score(), results[0].fuzzy_match.score() + 96);
+}
+
+#[test]
+fn print_unmatched() {
+    let mut tmp = NamedTempFile::new().unwrap();
+    writeln!(tmp, "alpha\ntest one\nbeta\ngamma\ntest two\ndelta").unwrap();
+    let cmd = [
+        "fzgrep",
+        "--no-filename",
+        "--print-unmatched",
+        "test",
+        tmp.path().to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut output = Vec::new();
+    let results = fzgrep::run(&request, &mut output).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].context.before, ["alpha"]);
+    assert!(results[0].context.after.is_empty());
+    assert_eq!(results[1].context.before, ["beta", "gamma"]);
+    assert_eq!(results[1].context.after, ["delta"]);
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "  alpha\n> test one\n  beta\n  gamma\n> test two\n  delta\n"
+    );
+}
@@ -0,0 +1,216 @@
+use std::process::Command;
+
+#[test]
+fn error_message() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["query", "resources/tests/no_such_file.txt"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!output.stderr.is_empty());
+}
+
+#[test]
+fn no_messages() {
+    for flag in ["-s", "--no-messages"] {
+        let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+            .args([flag, "query", "resources/tests/no_such_file.txt"])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(2));
+        assert!(output.stderr.is_empty());
+    }
+}
+
+#[test]
+fn report_errors_as_matches() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args([
+            "--report-errors-as-matches",
+            "query",
+            "resources/tests/no_such_file.txt",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!output.stderr.is_empty());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args([
+            "--report-errors-as-matches",
+            "-s",
+            "query",
+            "resources/tests/no_such_file.txt",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn report_errors_as_matches_unreadable_file() {
+    // the file that cannot be read is skipped, the one next to it is still searched
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args([
+            "--report-errors-as-matches",
+            "contigous",
+            "resources/tests/no_such_file.txt",
+            "resources/tests/test.txt",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "resources/tests/test.txt:contiguous\nresources/tests/test.txt:Contiguous\n"
+    );
+    assert!(!output.stderr.is_empty());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args([
+            "contigous",
+            "resources/tests/no_such_file.txt",
+            "resources/tests/test.txt",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn report_errors_as_matches_timeout() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args([
+            "--report-errors-as-matches",
+            "--max-time",
+            "0",
+            "contigous",
+            "resources/tests/test.txt",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("the search was stopped"));
+}
+
+#[test]
+fn directory_target_skipped() {
+    // warnings are printed starting from `-v`
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args([
+            "-v",
+            "contigous",
+            "resources/tests/nested",
+            "resources/tests/test.txt",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "resources/tests/test.txt:contiguous\nresources/tests/test.txt:Contiguous\n"
+    );
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Skipping directory 'resources/tests/nested'; use -r to search recursively"));
+}
+
+#[test]
+fn max_time_exceeded() {
+    // a zero limit is exceeded before the first file is searched
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["--max-time", "0", "contigous", "resources/tests/test.txt"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("the search was stopped after 0 s, the results may be incomplete"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args([
+            "--max-time",
+            "3600",
+            "contigous",
+            "resources/tests/test.txt",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "contiguous\nContiguous\n"
+    );
+}
+
+#[test]
+fn max_time_exceeded_with_nothing_left() {
+    // the limit has passed, but there is no file the search would have to skip
+    let dir = tempfile::tempdir().unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["--max-time", "0", "--recursive", "contigous"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("the search was stopped"));
+}
+
+#[test]
+fn short_query_warning() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["-v", "--recursive", "c", "resources/tests"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("The query 'c' is only 1 character long"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["-v", "c", "resources/tests/test.txt"])
+        .output()
+        .unwrap();
+    assert!(!String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("The query 'c'"));
+}
+
+#[test]
+fn column_number_notice() {
+    // debug messages are printed starting from `-vvv`
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["-vvv", "-k", "contigous", "resources/tests/test.txt"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("--column-number implies --line-number"));
+}
+
+#[test]
+fn min_query_length() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["--min-query-length", "3", "co", "resources/tests/test.txt"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("shorter than the minimum of 3"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["--min-query-length", "3", "con", "resources/tests/test.txt"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "serde")]
+
+use fzgrep::cli::args;
+use std::str;
+
+fn run(cmd: &[&str]) -> Vec<serde_json::Value> {
+    let request = args::make_request(cmd.iter().copied().map(String::from));
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf).unwrap();
+    str::from_utf8(&buf)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+// with `context-line-numbers` the context lines are objects carrying the text and the line number
+fn context_text(line: &serde_json::Value) -> &str {
+    line.as_str()
+        .unwrap_or_else(|| line["text"].as_str().unwrap())
+}
+
+#[test]
+fn streamed() {
+    let results = run(&[
+        "fzgrep",
+        "--output-format",
+        "ndjson",
+        "--line-number",
+        "contigous",
+        "resources/tests/test.txt",
+    ]);
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["matching_line"], "contiguous");
+    assert_eq!(results[0]["line_number"], 2);
+    assert_eq!(results[1]["matching_line"], "Contiguous");
+    assert_eq!(results[1]["line_number"], 3);
+}
+
+#[test]
+fn sorted() {
+    let results = run(&[
+        "fzgrep",
+        "--output-format",
+        "ndjson",
+        "--with-filename",
+        "--sort",
+        "score",
+        "contigous",
+        "resources/tests/test.txt",
+    ]);
+    assert_eq!(results.len(), 2);
+    assert!(
+        results[0]["fuzzy_match"]["score"].as_i64() >= results[1]["fuzzy_match"]["score"].as_i64()
+    );
+    assert_eq!(results[0]["file_name"], "resources/tests/test.txt");
+}
+
+#[test]
+fn context_kept_in_every_result() {
+    let results = run(&[
+        "fzgrep",
+        "--output-format",
+        "ndjson",
+        "--line-number",
+        "--context",
+        "1",
+        "contigous",
+        "resources/tests/test.txt",
+    ]);
+    assert_eq!(results.len(), 2);
+    // the line shared by both contexts is not trimmed from the second one
+    assert_eq!(
+        context_text(&results[0]["context"]["after"][0]),
+        "Contiguous"
+    );
+    assert_eq!(
+        context_text(&results[1]["context"]["before"][0]),
+        "contiguous"
+    );
+}
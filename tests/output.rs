@@ -1,5 +1,5 @@
 use atty::Stream;
-use fzgrep::cli::args;
+use fzgrep::cli::{args, nul_terminated_writer::NulTerminatedWriter};
 use std::str;
 use yansi::{Condition, Paint};
 
@@ -335,6 +335,8 @@ fn before_context_short() {
             "contig".red().bold(),
             "ous".red().bold()
         ),
+        // the groups of lines are separated, as without line numbers they cannot be known to be adjacent
+        format!("{}\n", "--".cyan()),
         format!(
             "打电动\n\
             contiguous\n\
@@ -368,6 +370,7 @@ fn before_context_long() {
             "contig".red().bold(),
             "ous".red().bold()
         ),
+        format!("{}\n", "--".cyan()),
         format!(
             "打电动\n\
             contiguous\n\
@@ -402,6 +405,7 @@ fn after_context_short() {
             "contig".red().bold(),
             "ous".red().bold()
         ),
+        format!("{}\n", "--".cyan()),
         format!(
             "{}u{}\n\
             Текст\n\
@@ -436,6 +440,7 @@ fn after_context_long() {
             "contig".red().bold(),
             "ous".red().bold()
         ),
+        format!("{}\n", "--".cyan()),
         format!(
             "{}u{}\n\
             Текст\n\
@@ -728,6 +733,7 @@ fn formatting_override_context() {
             "Contiguous".rgb(192, 255, 238).dim(),
             "Текст".rgb(192, 255, 238).dim(),
         ),
+        format!("{}\n", "--".cyan()),
         format!(
             "{}\n\
             {}u{}\n\
@@ -746,6 +752,50 @@ fn formatting_override_context() {
     assert_eq!(str::from_utf8(&buf).unwrap(), expected);
 }
 
+#[test]
+fn hyperlinks() {
+    let cmd = [
+        "fzgrep",
+        "--hyperlinks",
+        "--color",
+        "always",
+        "--line-number",
+        "--with-filename",
+        "contigous",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf).unwrap();
+    let output = str::from_utf8(&buf).unwrap();
+    let url = format!(
+        "file://{}#2",
+        std::fs::canonicalize("resources/tests/test.txt")
+            .unwrap()
+            .display()
+    );
+    assert!(output.starts_with(&format!("\x1b]8;;{url}\x1b\\")));
+    assert!(output.contains("\x1b]8;;\x1b\\"));
+}
+
+#[test]
+fn hyperlinks_color_never() {
+    let cmd = [
+        "fzgrep",
+        "--hyperlinks",
+        "--color",
+        "never",
+        "--line-number",
+        "--with-filename",
+        "contigous",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf).unwrap();
+    assert!(!str::from_utf8(&buf).unwrap().contains("\x1b]8;;"));
+}
+
 #[test]
 fn all_options_short() {
     let cmd = [
@@ -762,9 +812,7 @@ fn all_options_short() {
     let expected = [
         format!(
             "{}{}{}{}打电动\n\
-            {}{}{}{}{}u{}\n\
-            {}{}{}{}Contiguous\n\
-            {}{}{}{}Текст\n",
+            {}{}{}{}{}u{}\n",
             "resources/tests/test.txt".magenta().whenever(Condition::cached(atty::is(Stream::Stdout))),
             ':'.cyan().whenever(Condition::cached(atty::is(Stream::Stdout))),
             '1'.green().whenever(Condition::cached(atty::is(Stream::Stdout))),
@@ -775,26 +823,13 @@ fn all_options_short() {
             ':'.cyan().whenever(Condition::cached(atty::is(Stream::Stdout))),
             "contig".red().bold().whenever(Condition::cached(atty::is(Stream::Stdout))),
             "ous".red().bold().whenever(Condition::cached(atty::is(Stream::Stdout))),
-            "resources/tests/test.txt".magenta().whenever(Condition::cached(atty::is(Stream::Stdout))),
-            ':'.cyan().whenever(Condition::cached(atty::is(Stream::Stdout))),
-            '3'.green().whenever(Condition::cached(atty::is(Stream::Stdout))),
-            ':'.cyan().whenever(Condition::cached(atty::is(Stream::Stdout))),
-            "resources/tests/test.txt".magenta().whenever(Condition::cached(atty::is(Stream::Stdout))),
-            ':'.cyan().whenever(Condition::cached(atty::is(Stream::Stdout))),
-            '4'.green().whenever(Condition::cached(atty::is(Stream::Stdout))),
-            ':'.cyan().whenever(Condition::cached(atty::is(Stream::Stdout))),
         ),
         format!(
-            "{}{}{}{}contiguous\n\
-            {}{}{}{}{}u{}\n\
+            "{}{}{}{}{}u{}\n\
             {}{}{}{}Текст\n\
             {}{}{}{}тестування\n",
             "resources/tests/test.txt".magenta().whenever(Condition::cached(atty::is(Stream::Stdout))),
             ':'.cyan().whenever(Condition::cached(atty::is(Stream::Stdout))),
-            '2'.green().whenever(Condition::cached(atty::is(Stream::Stdout))),
-            ':'.cyan().whenever(Condition::cached(atty::is(Stream::Stdout))),
-            "resources/tests/test.txt".magenta().whenever(Condition::cached(atty::is(Stream::Stdout))),
-            ':'.cyan().whenever(Condition::cached(atty::is(Stream::Stdout))),
             '3'.green().whenever(Condition::cached(atty::is(Stream::Stdout))),
             ':'.cyan().whenever(Condition::cached(atty::is(Stream::Stdout))),
             "Contig".red().bold().whenever(Condition::cached(atty::is(Stream::Stdout))),
@@ -836,9 +871,7 @@ fn all_options_long() {
     let expected = [
         format!(
             "{}{}{}{}{}\n\
-            {}{}{}{}{}{}{}\n\
-            {}{}{}{}{}\n\
-            {}{}{}{}{}\n",
+            {}{}{}{}{}{}{}\n",
             "resources/tests/test.txt".rgb(192, 255, 238).italic(),
             ':'.magenta(),
             '1'.rgb(192, 255, 238).italic(),
@@ -851,29 +884,13 @@ fn all_options_long() {
             "contig".on_yellow(),
             'u'.fixed(245).dim(),
             "ous".on_yellow(),
-            "resources/tests/test.txt".rgb(192, 255, 238).italic(),
-            ':'.magenta(),
-            '3'.rgb(192, 255, 238).italic(),
-            ':'.magenta(),
-            "Contiguous".fixed(245).dim(),
-            "resources/tests/test.txt".rgb(192, 255, 238).italic(),
-            ':'.magenta(),
-            '4'.rgb(192, 255, 238).italic(),
-            ':'.magenta(),
-            "Текст".fixed(245).dim(),
         ),
         format!(
-            "{}{}{}{}{}\n\
-            {}{}{}{}{}{}{}\n\
+            "{}{}{}{}{}{}{}\n\
             {}{}{}{}{}\n\
             {}{}{}{}{}\n",
             "resources/tests/test.txt".rgb(192, 255, 238).italic(),
             ':'.magenta(),
-            '2'.rgb(192, 255, 238).italic(),
-            ':'.magenta(),
-            "contiguous".fixed(245).dim(),
-            "resources/tests/test.txt".rgb(192, 255, 238).italic(),
-            ':'.magenta(),
             '3'.rgb(192, 255, 238).italic(),
             ':'.magenta(),
             "Contig".on_yellow(),
@@ -897,6 +914,197 @@ fn all_options_long() {
     assert_eq!(str::from_utf8(&buf).unwrap(), expected);
 }
 
+#[test]
+fn format_template() {
+    let cmd = [
+        "fzgrep",
+        "--color",
+        "never",
+        "--format",
+        "{file}:{line}:{unknown}:{match}",
+        "contigous",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf).unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        "resources/tests/test.txt:2:{unknown}:contiguous\n\
+        resources/tests/test.txt:3:{unknown}:Contiguous\n"
+    );
+}
+
+#[test]
+fn format_template_colored() {
+    let cmd = [
+        "fzgrep",
+        "--color",
+        "always",
+        "--format",
+        "{line} {match}",
+        "contigous",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let expected = [
+        format!(
+            "{} {}u{}\n",
+            '2'.green(),
+            "contig".red().bold(),
+            "ous".red().bold()
+        ),
+        format!(
+            "{} {}u{}\n",
+            '3'.green(),
+            "Contig".red().bold(),
+            "ous".red().bold()
+        ),
+    ]
+    .concat();
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf).unwrap();
+    assert_eq!(str::from_utf8(&buf).unwrap(), expected);
+}
+
+/// Splits the output of `--print-match-positions` into pairs of a matching line and its parsed positions.
+///
+fn parse_match_positions(output: &str) -> Vec<(&str, Vec<usize>)> {
+    let lines = output.lines().collect::<Vec<_>>();
+    lines
+        .chunks(2)
+        .map(|pair| {
+            let positions = pair[1]
+                .strip_prefix('[')
+                .and_then(|s| s.strip_suffix(']'))
+                .unwrap()
+                .split(',')
+                .map(|p| p.parse().unwrap())
+                .collect();
+            (pair[0], positions)
+        })
+        .collect()
+}
+
+#[test]
+fn print_match_positions() {
+    let cmd = [
+        "fzgrep",
+        "--print-match-positions",
+        "contigous",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf).unwrap();
+    assert_eq!(
+        parse_match_positions(str::from_utf8(&buf).unwrap()),
+        [
+            ("contiguous", vec![0, 1, 2, 3, 4, 5, 7, 8, 9]),
+            ("Contiguous", vec![0, 1, 2, 3, 4, 5, 7, 8, 9]),
+        ]
+    );
+}
+
+#[test]
+fn print_match_positions_not_colored() {
+    let cmd = [
+        "fzgrep",
+        "--color",
+        "always",
+        "--line-number",
+        "--print-match-positions",
+        "contigous",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf).unwrap();
+    let output = str::from_utf8(&buf).unwrap();
+    let positions_lines = output.lines().skip(1).step_by(2).collect::<Vec<_>>();
+    assert_eq!(
+        positions_lines,
+        ["[0,1,2,3,4,5,7,8,9]", "[0,1,2,3,4,5,7,8,9]"]
+    );
+}
+
+#[test]
+fn zero_terminated() {
+    let cmd = [
+        "fzgrep",
+        "--with-filename",
+        "--line-number",
+        "contigous",
+        "resources/tests/name with spaces.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut plain = Vec::new();
+    fzgrep::run(&request, &mut plain).unwrap();
+    let mut writer = NulTerminatedWriter::new(Vec::new());
+    fzgrep::run(&request, &mut writer).unwrap();
+
+    let plain = String::from_utf8(plain).unwrap();
+    let zero_terminated = String::from_utf8(writer.into_inner()).unwrap();
+    assert!(!zero_terminated.contains('\n'));
+    assert_eq!(
+        zero_terminated
+            .strip_suffix('\0')
+            .unwrap()
+            .split('\0')
+            .collect::<Vec<_>>(),
+        plain.lines().collect::<Vec<_>>()
+    );
+}
+
+/// Extracts the scores printed with `--show-score` (the field preceding the line itself).
+///
+fn printed_scores(output: &[u8]) -> Vec<&str> {
+    str::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| line.split(':').next().unwrap())
+        .collect()
+}
+
+#[test]
+fn show_score_formats() {
+    let fuzzy_match = vscode_fuzzy_score_rs::fuzzy_match("contigous", "contiguous").unwrap();
+    let expected_percent = (fzgrep::normalized_score(&fuzzy_match, 9, 10) * 100.0).round() as u32;
+
+    let run = |format: &str| {
+        let cmd = [
+            "fzgrep",
+            "--show-score",
+            "--score-format",
+            format,
+            "contigous",
+            "resources/tests/test.txt",
+        ];
+        let request = args::make_request(cmd.into_iter().map(String::from));
+        let mut buf = Vec::new();
+        fzgrep::run(&request, &mut buf).unwrap();
+        buf
+    };
+
+    let decimal = run("decimal");
+    let decimal = printed_scores(&decimal);
+    assert_eq!(decimal.len(), 2);
+    assert_eq!(decimal[0].parse::<i32>().unwrap(), fuzzy_match.score());
+
+    let hex = run("hex");
+    let hex = printed_scores(&hex);
+    assert_eq!(
+        i32::from_str_radix(hex[0], 16).unwrap(),
+        fuzzy_match.score()
+    );
+    assert_eq!(hex[0], hex[0].to_lowercase());
+
+    let percent = run("percent");
+    let percent = printed_scores(&percent);
+    assert_eq!(percent[0].parse::<u32>().unwrap(), expected_percent);
+    assert!(percent.iter().all(|s| s.parse::<u32>().unwrap() <= 100));
+}
+
 #[test]
 fn no_matches_default_single_file() {
     let cmd = ["fzgrep", "nomatch", "resources/tests/test.txt"];
@@ -924,3 +1132,197 @@ fn no_matches_all_options_long() {
     fzgrep::run(&request, &mut buf).unwrap();
     assert_eq!(str::from_utf8(&buf).unwrap(), "");
 }
+
+#[test]
+fn field_order() {
+    let cmd = [
+        "fzgrep",
+        "--with-filename",
+        "--field-order",
+        "line,match",
+        "contigous",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf).unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        "2:contiguous\n3:Contiguous\n"
+    );
+
+    let cmd = [
+        "fzgrep",
+        "--field-order",
+        "match,file",
+        "contigous",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf).unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        "contiguous:resources/tests/test.txt\nContiguous:resources/tests/test.txt\n"
+    );
+}
+
+#[test]
+fn context_separator() {
+    let run = |separator_args: &[&str]| {
+        let cmd = ["fzgrep", "-n", "-A", "1", "--sort", "filename"]
+            .into_iter()
+            .chain(separator_args.iter().copied())
+            .chain([
+                "contigous",
+                "resources/tests/test.txt",
+                "resources/tests/тест.txt",
+            ]);
+        let request = args::make_request(cmd.map(String::from));
+        let mut buf = Vec::new();
+        fzgrep::run(&request, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    };
+
+    let first_file = "resources/tests/test.txt:2:contiguous\n\
+        resources/tests/test.txt:3:Contiguous\n\
+        resources/tests/test.txt:4:Текст\n";
+    let second_file = "resources/tests/тест.txt:5:contiguous\n\
+        resources/tests/тест.txt:6:Contiguous\n";
+    assert_eq!(run(&[]), format!("{first_file}--\n{second_file}"));
+    assert_eq!(
+        run(&["--context-separator", "== {source}:{line} =="]),
+        format!("{first_file}== resources/tests/тест.txt:5 ==\n{second_file}")
+    );
+    assert_eq!(
+        run(&["--no-context-separator"]),
+        format!("{first_file}{second_file}")
+    );
+}
+
+#[test]
+fn only_filenames_with_score() {
+    let run = |top_args: &[&str]| {
+        let cmd = ["fzgrep", "--color", "never", "--only-filenames-with-score"]
+            .into_iter()
+            .chain(top_args.iter().copied())
+            .chain([
+                "contigous",
+                "resources/tests/test.txt",
+                "resources/tests/тест.txt",
+                "resources/tests/nested/test2.txt",
+            ]);
+        let request = args::make_request(cmd.map(String::from));
+        let mut buf = Vec::new();
+        fzgrep::run(&request, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    };
+
+    let output = run(&[]);
+    let files = output
+        .lines()
+        .map(|line| {
+            let (file_name, score) = line.rsplit_once(':').unwrap();
+            (file_name, score.parse::<i32>().unwrap())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        files
+            .iter()
+            .map(|(file_name, _)| *file_name)
+            .collect::<Vec<_>>(),
+        ["resources/tests/test.txt", "resources/tests/тест.txt"]
+    );
+    assert!(files[0].1 >= files[1].1);
+
+    assert_eq!(
+        run(&["--top", "1"]),
+        format!("{}:{}\n", files[0].0, files[0].1)
+    );
+}
+
+#[test]
+fn print_file_headers() {
+    let cmd = [
+        "fzgrep",
+        "--color",
+        "never",
+        "--print-file-headers",
+        "--line-number",
+        "--sort",
+        "filename",
+        "contigous",
+        "resources/tests/test.txt",
+        "resources/tests/nested/test2.txt",
+        "resources/tests/тест.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf).unwrap();
+    let output = String::from_utf8(buf).unwrap();
+    let headers = output
+        .lines()
+        .filter(|line| line.starts_with("==> "))
+        .collect::<Vec<_>>();
+    // nothing matches in `nested/test2.txt`
+    assert_eq!(
+        headers,
+        [
+            "==> resources/tests/test.txt <==",
+            "==> resources/tests/тест.txt <=="
+        ]
+    );
+    assert!(output
+        .lines()
+        .filter(|line| !line.starts_with("==> "))
+        .all(|line| !line.contains("resources/tests")));
+}
+
+#[test]
+fn print_file_headers_interleaved_scores() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = dir.path().join("first.txt");
+    let second = dir.path().join("second.txt");
+    std::fs::write(&first, "test\nxtxexsxt\n").unwrap();
+    std::fs::write(&second, "tes_t\nt_e_s_t_\n").unwrap();
+    let cmd = [
+        "fzgrep",
+        "--color",
+        "never",
+        "--print-file-headers",
+        "test",
+        first.to_str().unwrap(),
+        second.to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf).unwrap();
+    // by score alone, the lines of the two files alternate
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        format!(
+            "==> {} <==\ntest\nxtxexsxt\n==> {} <==\ntes_t\nt_e_s_t_\n",
+            first.display(),
+            second.display()
+        )
+    );
+}
+
+#[test]
+fn column_number_with_line_number() {
+    let cmd = [
+        "fzgrep",
+        "--color",
+        "never",
+        "--column-number",
+        "tig",
+        "resources/tests/test.txt",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf).unwrap();
+    let mut lines = str::from_utf8(&buf).unwrap().lines().collect::<Vec<_>>();
+    lines.sort();
+    // the line numbers are printed even though only the column numbers are asked for
+    assert_eq!(lines, ["2:4:contiguous", "3:4:Contiguous"]);
+}
@@ -0,0 +1,45 @@
+use std::{fs, process::Command};
+
+#[test]
+fn output_to_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.txt");
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["-n", "--output", path.to_str().unwrap()])
+        .args(["contigous", "resources/tests/test.txt"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    // not colored by default
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "2:contiguous\n3:Contiguous\n"
+    );
+}
+
+#[test]
+fn output_to_file_colored() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.txt");
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["-o", path.to_str().unwrap(), "--color", "always"])
+        .args(["contigous", "resources/tests/test.txt"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(fs::read_to_string(&path).unwrap().contains('\x1b'));
+}
+
+#[test]
+fn output_to_file_cannot_open() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("no_such_dir").join("out.txt");
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["-o", path.to_str().unwrap()])
+        .args(["contigous", "resources/tests/test.txt"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!output.stderr.is_empty());
+}
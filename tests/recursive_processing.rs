@@ -1,5 +1,5 @@
 use fzgrep::{cli::args, Targets};
-use std::path::PathBuf;
+use std::{io::Write, path::PathBuf};
 
 #[test]
 fn basic_usage() {
@@ -141,3 +141,283 @@ fn files_and_dirs_mixed() {
         ]
     );
 }
+
+fn matching_files(options: &[&str], dir: &std::path::Path) -> Vec<String> {
+    let cmd = ["fzgrep", "--with-filename", "--recursive"]
+        .into_iter()
+        .chain(options.iter().copied())
+        .chain(["contigous", dir.to_str().unwrap()]);
+    let request = args::make_request(cmd.map(String::from));
+    let mut results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap()
+            .into_iter()
+            .map(|x| x.file_name.unwrap())
+            .collect::<Vec<_>>();
+    results.sort();
+    results.dedup();
+    results
+}
+
+#[cfg(unix)]
+#[test]
+fn hard_links() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::copy("resources/tests/test.txt", dir.path().join("a.txt")).unwrap();
+    std::fs::hard_link(dir.path().join("a.txt"), dir.path().join("b.txt")).unwrap();
+    let a = dir.path().join("a.txt").to_str().unwrap().to_string();
+    let b = dir.path().join("b.txt").to_str().unwrap().to_string();
+
+    assert_eq!(matching_files(&[], dir.path()), [a.clone(), b]);
+    assert_eq!(
+        matching_files(&["--no-dereference-unique"], dir.path()),
+        [a]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn symbolic_links() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::copy("resources/tests/test.txt", dir.path().join("a.txt")).unwrap();
+    std::os::unix::fs::symlink(dir.path().join("a.txt"), dir.path().join("b.txt")).unwrap();
+    let other = tempfile::tempdir().unwrap();
+    std::fs::copy("resources/tests/test.txt", other.path().join("c.txt")).unwrap();
+    std::os::unix::fs::symlink(other.path(), dir.path().join("linked")).unwrap();
+    let a = dir.path().join("a.txt").to_str().unwrap().to_string();
+    let c = dir
+        .path()
+        .join("linked/c.txt")
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    // the linked file is found only once, but the linked directory is traversed
+    assert_eq!(
+        matching_files(&["--dereference"], dir.path()),
+        [a.clone(), c]
+    );
+    // symbolic links are not followed by default
+    assert_eq!(matching_files(&[], dir.path()), [a]);
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn regex_filter() {
+    let cmd = [
+        "fzgrep",
+        "--with-filename",
+        "--recursive",
+        "--include-regex",
+        r"test\.txt$",
+        "--exclude-regex",
+        "more_nested",
+        "recursive",
+        "resources/tests/",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap()
+            .into_iter()
+            .map(|x| x.file_name.unwrap())
+            .collect::<Vec<_>>();
+    results.sort();
+    assert_eq!(results, ["resources/tests/nested/test.txt"]);
+}
+
+#[test]
+fn glob_filter() {
+    let dir = tempfile::tempdir().unwrap();
+    let sub = dir.path().join("sub");
+    std::fs::create_dir(&sub).unwrap();
+    std::fs::copy("resources/tests/test.txt", dir.path().join("a.txt")).unwrap();
+    std::fs::copy("resources/tests/test.txt", dir.path().join("b.log")).unwrap();
+    std::fs::copy("resources/tests/test.txt", sub.join("c.txt")).unwrap();
+    let a = dir.path().join("a.txt").to_str().unwrap().to_string();
+    let c = sub.join("c.txt").to_str().unwrap().to_string();
+
+    let mut list = tempfile::NamedTempFile::new().unwrap();
+    writeln!(list, "# text files only\n\n*.txt").unwrap();
+    let list = list.path().to_str().unwrap();
+
+    assert_eq!(
+        matching_files(&["--include-from", list], dir.path()),
+        [a.clone(), c]
+    );
+    assert_eq!(
+        matching_files(
+            &["--include-from", list, "--exclude", "*/sub/*"],
+            dir.path()
+        ),
+        [a]
+    );
+}
+
+#[test]
+fn hidden() {
+    let dir = tempfile::tempdir().unwrap();
+    let hidden_dir = dir.path().join(".hidden");
+    std::fs::create_dir(&hidden_dir).unwrap();
+    std::fs::copy("resources/tests/test.txt", dir.path().join("a.txt")).unwrap();
+    std::fs::copy("resources/tests/test.txt", dir.path().join(".b.txt")).unwrap();
+    std::fs::copy("resources/tests/test.txt", hidden_dir.join("c.txt")).unwrap();
+    let a = dir.path().join("a.txt").to_str().unwrap().to_string();
+    let b = dir.path().join(".b.txt").to_str().unwrap().to_string();
+    let c = hidden_dir.join("c.txt").to_str().unwrap().to_string();
+
+    let mut all = vec![a.clone(), b, c.clone()];
+    all.sort();
+    assert_eq!(matching_files(&[], dir.path()), all);
+    assert_eq!(matching_files(&["--hidden"], dir.path()), all);
+    assert_eq!(matching_files(&["--no-hidden"], dir.path()), [a]);
+    // a hidden target is searched anyway
+    assert_eq!(matching_files(&["--no-hidden"], &hidden_dir), [c]);
+}
+
+#[test]
+fn max_results() {
+    let cmd = [
+        "fzgrep",
+        "--with-filename",
+        "--recursive",
+        "--max-results",
+        "2",
+        "--sort",
+        "none",
+        "recursive",
+        "resources/tests/",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut buf = Vec::new();
+    let results = fzgrep::run(&request, &mut buf)
+        .unwrap()
+        .into_iter()
+        .map(|x| x.file_name.unwrap())
+        .collect::<Vec<_>>();
+    // the search stops before the last file
+    assert_eq!(
+        results,
+        [
+            "resources/tests/nested/more_nested/test.txt",
+            "resources/tests/nested/test.txt",
+        ]
+    );
+}
+
+#[test]
+fn target_glob() {
+    let cmd = [
+        "fzgrep",
+        "--target-glob",
+        "resources/tests/**/test.txt",
+        "recursive",
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut buf = Vec::new();
+    let mut results = fzgrep::run(&request, &mut buf)
+        .unwrap()
+        .into_iter()
+        .map(|x| x.file_name.unwrap())
+        .collect::<Vec<_>>();
+    results.sort();
+    // `resources/tests/nested/test2.txt` matches as well, but its name does not match the pattern
+    assert_eq!(
+        results,
+        [
+            "resources/tests/nested/more_nested/test.txt",
+            "resources/tests/nested/test.txt",
+        ]
+    );
+}
+
+#[test]
+fn extension_filter() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::copy("resources/tests/test.txt", dir.path().join("a.txt")).unwrap();
+    std::fs::copy("resources/tests/test.txt", dir.path().join("b.min.js")).unwrap();
+    std::fs::copy("resources/tests/test.txt", dir.path().join("c.js")).unwrap();
+    let a = dir.path().join("a.txt").to_str().unwrap().to_string();
+    let c = dir.path().join("c.js").to_str().unwrap().to_string();
+
+    assert_eq!(
+        matching_files(&["--exclude-extension", ".min.js"], dir.path()),
+        [a.clone(), c.clone()]
+    );
+    assert_eq!(
+        matching_files(&["--include-extension", "txt"], dir.path()),
+        [a]
+    );
+    assert_eq!(
+        matching_files(
+            &[
+                "--include-extension",
+                ".js",
+                "--exclude-extension",
+                "min.js"
+            ],
+            dir.path()
+        ),
+        [c]
+    );
+}
+
+/// Runs a recursive search in `dir` keeping the discovery order and returns the output,
+/// with the file names relative to `dir` (so that the outputs for different directories can be compared).
+///
+fn discovery_order_output(dir: &std::path::Path) -> String {
+    let relative_paths = format!("--relative-paths={}", dir.to_str().unwrap());
+    let cmd = [
+        "fzgrep",
+        "--color",
+        "never",
+        "--with-filename",
+        "--line-number",
+        "--recursive",
+        "--sort",
+        "none",
+        &relative_paths,
+        "contigous",
+        dir.to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf).unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+#[test]
+fn deterministic_order() {
+    let dir = std::path::Path::new("resources/tests");
+    let output = discovery_order_output(dir);
+    assert!(!output.is_empty());
+    assert_eq!(discovery_order_output(dir), output);
+}
+
+#[test]
+fn order_independent_of_creation() {
+    let names = ["b.txt", "a.txt", "nested/d.txt", "c.txt", "nested/a.txt"];
+    let create = |dir: &std::path::Path, names: &mut dyn Iterator<Item = &&str>| {
+        std::fs::create_dir(dir.join("nested")).unwrap();
+        for name in names {
+            std::fs::copy("resources/tests/test.txt", dir.join(name)).unwrap();
+        }
+    };
+    let first = tempfile::tempdir().unwrap();
+    create(first.path(), &mut names.iter());
+    let second = tempfile::tempdir().unwrap();
+    create(second.path(), &mut names.iter().rev());
+
+    let output = discovery_order_output(first.path());
+    assert_eq!(discovery_order_output(second.path()), output);
+    // the directory entries are visited in the order of their names
+    let mut files = output
+        .lines()
+        .map(|line| line.split(':').next().unwrap())
+        .collect::<Vec<_>>();
+    files.dedup();
+    assert_eq!(
+        files,
+        ["a.txt", "b.txt", "c.txt", "nested/a.txt", "nested/d.txt"]
+    );
+}
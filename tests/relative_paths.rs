@@ -0,0 +1,98 @@
+use fzgrep::cli::args;
+use std::{fs, str};
+use tempfile::TempDir;
+
+// <tmp>/project/src/deep/a.txt, <tmp>/project/b.txt and <tmp>/other/c.txt
+fn make_tree() -> TempDir {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::create_dir_all(tmp.path().join("project/src/deep")).unwrap();
+    fs::create_dir_all(tmp.path().join("other")).unwrap();
+    fs::write(tmp.path().join("project/src/deep/a.txt"), "test\n").unwrap();
+    fs::write(tmp.path().join("project/b.txt"), "test\n").unwrap();
+    fs::write(tmp.path().join("other/c.txt"), "test\n").unwrap();
+    tmp
+}
+
+fn run(cmd: &[&str]) -> String {
+    let request = args::make_request(cmd.iter().copied().map(String::from));
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf).unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+#[test]
+fn relative_paths() {
+    let tmp = make_tree();
+    let root = format!("--relative-paths={}", tmp.path().join("project").display());
+    let output = run(&[
+        "fzgrep",
+        "--color",
+        "never",
+        "--with-filename",
+        "--sort",
+        "filename",
+        &root,
+        "--recursive",
+        "test",
+        tmp.path().join("project").to_str().unwrap(),
+    ]);
+    assert_eq!(output, "b.txt:test\nsrc/deep/a.txt:test\n");
+}
+
+#[test]
+fn relative_paths_outside_of_root() {
+    let tmp = make_tree();
+    let root = format!(
+        "--relative-paths={}",
+        tmp.path().join("project/src").display()
+    );
+    let output = run(&[
+        "fzgrep",
+        "--color",
+        "never",
+        "--with-filename",
+        "--sort",
+        "filename",
+        &root,
+        "--recursive",
+        "test",
+        tmp.path().to_str().unwrap(),
+    ]);
+    // the results are sorted by the full file names
+    let expected = format!(
+        "{}:test\n{}:test\ndeep/a.txt:test\n",
+        tmp.path().join("other/c.txt").display(),
+        tmp.path().join("project/b.txt").display()
+    );
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn relative_paths_hyperlinks() {
+    let tmp = make_tree();
+    let project = tmp.path().join("project");
+    let file = project.join("b.txt");
+    let root = format!("--relative-paths={}", project.display());
+    let cmd = [
+        "fzgrep",
+        "--hyperlinks",
+        "--color",
+        "always",
+        "--line-number",
+        "--with-filename",
+        &root,
+        "test",
+        file.to_str().unwrap(),
+    ];
+    let request = args::make_request(cmd.into_iter().map(String::from));
+    let mut buf = Vec::new();
+    let results = fzgrep::run(&request, &mut buf).unwrap();
+    // only the printed file name is relative, the link and the returned result keep the actual path
+    let url = format!("file://{}#1", fs::canonicalize(&file).unwrap().display());
+    let output = str::from_utf8(&buf).unwrap();
+    let (link, text) = output.split_once("\x1b\\").unwrap();
+    assert_eq!(link, format!("\x1b]8;;{url}"));
+    assert!(text.contains("b.txt"));
+    assert!(!text.contains(project.to_str().unwrap()));
+    assert_eq!(results[0].file_name.as_deref(), file.to_str());
+}
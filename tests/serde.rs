@@ -0,0 +1,86 @@
+#![cfg(feature = "serde")]
+
+use fzgrep::{Context, RequestBuilder, Targets};
+use std::path::PathBuf;
+
+#[test]
+fn matching_result_to_json() {
+    let request = RequestBuilder::new(
+        "contigous",
+        Targets::Files(vec![PathBuf::from("resources/tests/test.txt")]),
+    )
+    .file_names(true)
+    .line_numbers(true)
+    .build()
+    .unwrap();
+    let results =
+        fzgrep::collect_top_matches(&request.query, &request.targets, &request.match_options, 1)
+            .unwrap();
+    let result = &results[0];
+
+    assert_eq!(
+        serde_json::to_value(result).unwrap(),
+        serde_json::json!({
+            "matching_line": "contiguous",
+            "fuzzy_match": {
+                "score": result.fuzzy_match.score(),
+                "positions": result.fuzzy_match.positions(),
+            },
+            "file_name": "resources/tests/test.txt",
+            "line_number": 2,
+            "column_number": null,
+            "context": {
+                "before": [],
+                "after": [],
+            },
+        })
+    );
+}
+
+#[test]
+fn context_round_trip() {
+    let request = RequestBuilder::new(
+        "contigous",
+        Targets::Files(vec![PathBuf::from("resources/tests/test.txt")]),
+    )
+    .context(1)
+    .build()
+    .unwrap();
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    let context = &results[0].context;
+    assert_eq!(context.before, ["打电动"]);
+    assert_eq!(context.after, ["Contiguous"]);
+
+    let json = serde_json::to_string(context).unwrap();
+    assert_eq!(serde_json::from_str::<Context>(&json).unwrap(), *context);
+}
+
+#[cfg(feature = "context-line-numbers")]
+#[test]
+fn context_line_numbers_to_json() {
+    let request = RequestBuilder::new(
+        "contigous",
+        Targets::Files(vec![PathBuf::from("resources/tests/test.txt")]),
+    )
+    .line_numbers(true)
+    .context(1)
+    .build()
+    .unwrap();
+    let results =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)
+            .unwrap();
+    let result = results
+        .iter()
+        .find(|result| result.line_number == Some(2))
+        .unwrap();
+
+    assert_eq!(
+        serde_json::to_value(result).unwrap()["context"],
+        serde_json::json!({
+            "before": [{ "text": "打电动", "line_number": 1 }],
+            "after": [{ "text": "Contiguous", "line_number": 3 }],
+        })
+    );
+}
@@ -0,0 +1,112 @@
+use fzgrep::cli::args;
+
+fn run(extra_args: &[&str]) -> Vec<(String, usize)> {
+    let cmd = ["fzgrep", "--quiet", "--with-filename", "--line-number"]
+        .into_iter()
+        .chain(extra_args.iter().copied())
+        .chain([
+            "contigous",
+            "resources/tests/test.txt",
+            "resources/tests/тест.txt",
+        ]);
+    let request = args::make_request(cmd.map(String::from));
+    let mut buf = Vec::new();
+    fzgrep::run(&request, &mut buf)
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.file_name.unwrap(), r.line_number.unwrap()))
+        .collect()
+}
+
+fn locations(expected: &[(&str, usize)]) -> Vec<(String, usize)> {
+    expected
+        .iter()
+        .map(|(file, line)| (String::from(*file), *line))
+        .collect()
+}
+
+#[test]
+fn sort_by_score() {
+    let expected = locations(&[
+        ("resources/tests/test.txt", 2),
+        ("resources/tests/тест.txt", 5),
+        ("resources/tests/test.txt", 3),
+        ("resources/tests/тест.txt", 6),
+    ]);
+    assert_eq!(run(&[]), expected);
+    assert_eq!(run(&["--sort", "score"]), expected);
+
+    // the exact reverse, including the matches with equal scores
+    let mut reversed = expected.clone();
+    reversed.reverse();
+    assert_eq!(run(&["--sort", "score-asc"]), reversed);
+}
+
+#[test]
+fn sort_by_file_name() {
+    let expected = locations(&[
+        ("resources/tests/test.txt", 2),
+        ("resources/tests/test.txt", 3),
+        ("resources/tests/тест.txt", 5),
+        ("resources/tests/тест.txt", 6),
+    ]);
+    assert_eq!(run(&["--sort", "filename"]), expected);
+
+    let mut reversed = expected.clone();
+    reversed.reverse();
+    assert_eq!(run(&["--sort", "filename-desc"]), reversed);
+    assert_eq!(run(&["--sort", "filename", "--reverse-sort"]), reversed);
+}
+
+#[test]
+fn sort_by_line() {
+    assert_eq!(
+        run(&["--sort", "line"]),
+        locations(&[
+            ("resources/tests/test.txt", 2),
+            ("resources/tests/test.txt", 3),
+            ("resources/tests/тест.txt", 5),
+            ("resources/tests/тест.txt", 6),
+        ])
+    );
+    assert_eq!(
+        run(&["--sort", "line-desc"]),
+        locations(&[
+            ("resources/tests/тест.txt", 6),
+            ("resources/tests/тест.txt", 5),
+            ("resources/tests/test.txt", 3),
+            ("resources/tests/test.txt", 2),
+        ])
+    );
+}
+
+#[test]
+fn sort_none() {
+    assert_eq!(
+        run(&["--sort", "none"]),
+        locations(&[
+            ("resources/tests/test.txt", 2),
+            ("resources/tests/test.txt", 3),
+            ("resources/tests/тест.txt", 5),
+            ("resources/tests/тест.txt", 6),
+        ])
+    );
+}
+
+#[test]
+fn unique_lines() {
+    // both files have the same lines, the first file searched wins the ties
+    let expected = locations(&[
+        ("resources/tests/test.txt", 2),
+        ("resources/tests/test.txt", 3),
+    ]);
+    assert_eq!(run(&["--unique"]), expected);
+    assert_eq!(run(&["-u", "--sort", "line"]), expected);
+    assert_eq!(
+        run(&["-u", "--sort", "filename-desc"]),
+        locations(&[
+            ("resources/tests/test.txt", 3),
+            ("resources/tests/test.txt", 2),
+        ])
+    );
+}
@@ -0,0 +1,93 @@
+use fzgrep::{FilePlugin, RequestBuilder, SourcePlugin, Targets};
+use std::{
+    io::{self, BufRead, Cursor},
+    path::{Path, PathBuf},
+};
+
+/// Serves `mem:<lines separated by ';'>` targets from memory.
+struct MemoryPlugin;
+
+impl SourcePlugin for MemoryPlugin {
+    fn can_handle(&self, path: &Path) -> bool {
+        path.to_str().is_some_and(|p| p.starts_with("mem:"))
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn BufRead>> {
+        let content = path.to_str().unwrap().strip_prefix("mem:").unwrap();
+        Ok(Box::new(Cursor::new(content.replace(';', "\n"))))
+    }
+}
+
+/// Refuses to open anything with the `.secret` extension.
+struct DenyPlugin;
+
+impl SourcePlugin for DenyPlugin {
+    fn can_handle(&self, path: &Path) -> bool {
+        path.extension().is_some_and(|e| e == "secret")
+    }
+
+    fn open(&self, _path: &Path) -> io::Result<Box<dyn BufRead>> {
+        Err(io::Error::new(io::ErrorKind::PermissionDenied, "denied"))
+    }
+}
+
+fn run(builder: RequestBuilder) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let request = builder.file_names(true).build()?;
+    let mut found =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)?
+            .into_iter()
+            .map(|m| (m.file_name.unwrap(), m.matching_line))
+            .collect::<Vec<_>>();
+    found.sort();
+    Ok(found)
+}
+
+#[test]
+fn custom_plugin() {
+    let targets = Targets::Files(vec![
+        PathBuf::from("mem:first;contiguous;last"),
+        PathBuf::from("resources/tests/test.txt"),
+    ]);
+    assert_eq!(
+        run(RequestBuilder::new("contigous", targets).source_plugin(MemoryPlugin)).unwrap(),
+        [
+            (
+                String::from("mem:first;contiguous;last"),
+                String::from("contiguous")
+            ),
+            (
+                String::from("resources/tests/test.txt"),
+                String::from("Contiguous")
+            ),
+            (
+                String::from("resources/tests/test.txt"),
+                String::from("contiguous")
+            ),
+        ]
+    );
+}
+
+#[test]
+fn unhandled_target() {
+    let targets = Targets::Files(vec![PathBuf::from("mem:contiguous")]);
+    assert!(run(RequestBuilder::new("contigous", targets)).is_err());
+}
+
+#[test]
+fn plugin_error() {
+    let targets = Targets::Files(vec![PathBuf::from("resources/tests/test.secret")]);
+    let error = run(RequestBuilder::new("contigous", targets).source_plugin(DenyPlugin))
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("denied"), "{error}");
+}
+
+#[test]
+fn plugins_in_recursive_mode() {
+    let targets = || Targets::RecursiveEntries(vec![PathBuf::from("resources/tests/nested")]);
+    let plain = run(RequestBuilder::new("recursive", targets())).unwrap();
+    let with_plugin =
+        run(RequestBuilder::new("recursive", targets()).source_plugin(FilePlugin)).unwrap();
+    assert!(!plain.is_empty());
+    assert_eq!(plain, with_plugin);
+}
@@ -0,0 +1,46 @@
+use std::{process::Command, str};
+
+#[test]
+fn stats() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["--stats", "contigous"])
+        .args(["resources/tests/test.txt", "resources/tests/тест.txt"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        stderr.starts_with("files=2 lines=12 matches=4 elapsed="),
+        "{stderr}"
+    );
+    assert_eq!(stderr.lines().count(), 1);
+}
+
+#[test]
+fn stats_top() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args(["--stats", "--top", "1", "contigous"])
+        .args(["resources/tests/test.txt", "resources/tests/тест.txt"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    // all the matches are counted, not only the top ones
+    assert!(str::from_utf8(&output.stderr)
+        .unwrap()
+        .starts_with("files=2 lines=12 matches=4 elapsed="));
+}
+
+#[test]
+fn stats_quiet() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fzgrep"))
+        .args([
+            "--stats",
+            "--quiet",
+            "contigous",
+            "resources/tests/test.txt",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+}
@@ -31,19 +31,19 @@ fn top_five() {
 
     assert_eq!(
         results[1].file_name.as_ref().unwrap(),
-        "resources/tests/top_matches/1.txt"
+        "resources/tests/top_matches/2.txt"
     );
-    assert_eq!(results[1].line_number.unwrap(), 5);
-    assert_eq!(results[1].matching_line, String::from("tests"));
+    assert_eq!(results[1].line_number.unwrap(), 4);
+    assert_eq!(results[1].matching_line, String::from("test"));
     assert_eq!(results[1].fuzzy_match.score(), 46);
     assert_eq!(results[1].fuzzy_match.positions(), &vec![0, 1, 2, 3,]);
 
     assert_eq!(
         results[2].file_name.as_ref().unwrap(),
-        "resources/tests/top_matches/2.txt"
+        "resources/tests/top_matches/1.txt"
     );
-    assert_eq!(results[2].line_number.unwrap(), 4);
-    assert_eq!(results[2].matching_line, String::from("test"));
+    assert_eq!(results[2].line_number.unwrap(), 5);
+    assert_eq!(results[2].matching_line, String::from("tests"));
     assert_eq!(results[2].fuzzy_match.score(), 46);
     assert_eq!(results[2].fuzzy_match.positions(), &vec![0, 1, 2, 3,]);
 
@@ -0,0 +1,83 @@
+use fzgrep::cli::args;
+
+fn run(cmd: &[&str]) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let request = args::make_request(cmd.iter().copied().map(String::from));
+    let mut found =
+        fzgrep::collect_all_matches(&request.query, &request.targets, &request.match_options)?
+            .into_iter()
+            .map(|m| (m.file_name.unwrap(), m.matching_line))
+            .collect::<Vec<_>>();
+    // the results are sorted by score, the order of the files is of more interest here
+    found.sort();
+    Ok(found)
+}
+
+#[cfg(feature = "zip-archives")]
+#[test]
+fn archive_entries() {
+    assert_eq!(
+        run(&[
+            "fzgrep",
+            "--recursive",
+            "--search-zip",
+            "--with-filename",
+            "contigous",
+            "resources/archives",
+        ])
+        .unwrap(),
+        [
+            (
+                String::from("resources/archives/archive.zip:docs/test.txt"),
+                String::from("Contiguous")
+            ),
+            (
+                String::from("resources/archives/archive.zip:docs/test.txt"),
+                String::from("contiguous")
+            ),
+            (
+                String::from("resources/archives/archive.zip:notes.md"),
+                String::from("a contiguous note")
+            ),
+            (
+                String::from("resources/archives/plain.txt"),
+                String::from("contiguous plain")
+            ),
+        ]
+    );
+}
+
+#[cfg(feature = "zip-archives")]
+#[test]
+fn filter_applies_to_entries() {
+    let found = run(&[
+        "fzgrep",
+        "--recursive",
+        "--search-zip",
+        "--with-filename",
+        "--include",
+        "*.md",
+        "contigous",
+        "resources/archives",
+    ])
+    .unwrap();
+    assert_eq!(
+        found,
+        [(
+            String::from("resources/archives/archive.zip:notes.md"),
+            String::from("a contiguous note")
+        )]
+    );
+}
+
+#[cfg(not(feature = "zip-archives"))]
+#[test]
+fn archive_entries_unsupported() {
+    assert!(run(&[
+        "fzgrep",
+        "--recursive",
+        "--search-zip",
+        "contigous",
+        "resources/archives",
+    ])
+    .is_err());
+}